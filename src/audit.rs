@@ -0,0 +1,115 @@
+//! Audit log of tool calls.
+//!
+//! When enabled with `audit_log = true` (under `[workspace]` in
+//! `workspace.toml` or `[jumble]` in `~/.jumble/jumble.toml`), every
+//! `tools/call` is appended to `<root>/.jumble/audit.log` as one JSON object
+//! per line, so a team can review what context agents pulled and what they
+//! wrote back.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Longest result text kept per entry, in characters.
+pub const MAX_RESULT_CHARS: usize = 500;
+
+/// One line of the audit log.
+#[derive(Debug, Serialize)]
+pub struct AuditEntry<'a> {
+    /// ISO 8601 timestamp of the call.
+    pub timestamp: String,
+    pub tool: &'a str,
+    pub arguments: &'a Value,
+    pub ok: bool,
+    /// The response text (or error message), cut to [`MAX_RESULT_CHARS`].
+    pub result: String,
+}
+
+impl<'a> AuditEntry<'a> {
+    pub fn new(tool: &'a str, arguments: &'a Value, result: &Result<String, String>) -> Self {
+        let (ok, text) = match result {
+            Ok(text) => (true, text),
+            Err(message) => (false, message),
+        };
+        AuditEntry {
+            timestamp: crate::memory::current_timestamp(),
+            tool,
+            arguments,
+            ok,
+            result: truncate(text, MAX_RESULT_CHARS),
+        }
+    }
+}
+
+/// Audit log location for a workspace root.
+pub fn audit_log_path(root: &Path) -> PathBuf {
+    root.join(".jumble").join("audit.log")
+}
+
+/// Appends `entry` as a JSON line to `path`, creating the file if needed.
+pub fn append(path: &Path, entry: &impl Serialize) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    // One write per entry keeps lines from concurrent requests intact.
+    file.write_all(format!("{}\n", line).as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => format!("{}...", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_writes_json_lines() {
+        let temp = TempDir::new().unwrap();
+        let path = audit_log_path(temp.path());
+        let args = json!({"project": "app", "key": "notes"});
+
+        append(
+            &path,
+            &AuditEntry::new("get_memory", &args, &Ok("x".repeat(600))),
+        )
+        .unwrap();
+        append(
+            &path,
+            &AuditEntry::new("get_commands", &args, &Err("Project not found".to_string())),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["tool"], "get_memory");
+        assert_eq!(lines[0]["arguments"]["key"], "notes");
+        assert_eq!(lines[0]["ok"], true);
+        assert_eq!(
+            lines[0]["result"].as_str().unwrap().len(),
+            MAX_RESULT_CHARS + 3
+        );
+        assert_eq!(lines[1]["ok"], false);
+        assert_eq!(lines[1]["result"], "Project not found");
+    }
+}
@@ -0,0 +1,254 @@
+//! `draft_project_config`: drafts `.jumble/project.toml` for a project with
+//! no Jumble context by asking the client's model through MCP sampling.
+//!
+//! The request carries a listing of the project and its manifests and
+//! README, so the model needs no tools of its own. The reply must parse as a
+//! project config before it is offered for review; nothing is written until
+//! the caller confirms.
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::config::{self, ProjectConfig};
+
+/// Files quoted in full (up to [`MAX_FILE_CHARS`]) in the sampling request.
+const CONTEXT_FILES: &[&str] = &[
+    "README.md",
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "Makefile",
+    "justfile",
+];
+
+/// Longest excerpt of any one context file.
+const MAX_FILE_CHARS: usize = 4000;
+
+/// Most paths listed from the project tree.
+const MAX_LISTED_PATHS: usize = 200;
+
+/// Directories left out of the listing.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// Tokens the client's model may spend on the draft.
+const MAX_DRAFT_TOKENS: u64 = 2000;
+
+const SYSTEM_PROMPT: &str = "You write Jumble project context files. Reply with only the TOML \
+for .jumble/project.toml: a [project] table with name, description, and language; a \
+[commands] table with exact build/test/lint/run commands; an [entry_points] table; and \
+3-5 [concepts.<name>] tables, each with a one-line summary and a files list. Only \
+reference files that appear in the listing.";
+
+/// The project directory named by the `directory` argument, relative to the
+/// workspace `root` unless absolute. It must exist inside the workspace and
+/// have no Jumble context yet.
+pub fn draft_target(root: &Path, args: &Value) -> Result<PathBuf, String> {
+    let directory = args
+        .get("directory")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'directory' argument")?;
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve workspace root: {}", e))?;
+    let dir = root
+        .join(directory)
+        .canonicalize()
+        .map_err(|_| format!("'{}' is not a directory", directory))?;
+    if !dir.starts_with(&root) {
+        return Err(format!("'{}' is outside the workspace", directory));
+    }
+    if !dir.is_dir() {
+        return Err(format!("'{}' is not a directory", dir.display()));
+    }
+    if let Some(existing) = config::find_project_config(&dir) {
+        return Err(format!(
+            "'{}' already has Jumble context in {}",
+            dir.display(),
+            existing.display()
+        ));
+    }
+    Ok(dir)
+}
+
+/// Parameters for the `sampling/createMessage` request drafting `dir`'s config.
+pub fn sampling_request(dir: &Path) -> Value {
+    json!({
+        "messages": [{
+            "role": "user",
+            "content": {"type": "text", "text": draft_prompt(dir)}
+        }],
+        "systemPrompt": SYSTEM_PROMPT,
+        "includeContext": "none",
+        "maxTokens": MAX_DRAFT_TOKENS
+    })
+}
+
+fn draft_prompt(dir: &Path) -> String {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut prompt = format!(
+        "Draft .jumble/project.toml for the project in '{}'.\n\n## Files\n\n",
+        name
+    );
+    let entries = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(3)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .flatten();
+    for (listed, entry) in entries.enumerate() {
+        if listed == MAX_LISTED_PATHS {
+            prompt.push_str("- ...\n");
+            break;
+        }
+        let relative = entry.path().strip_prefix(dir).unwrap_or(entry.path());
+        let suffix = if entry.file_type().is_dir() { "/" } else { "" };
+        prompt.push_str(&format!("- {}{}\n", relative.display(), suffix));
+    }
+    for file in CONTEXT_FILES {
+        let Ok(content) = std::fs::read_to_string(dir.join(file)) else {
+            continue;
+        };
+        let excerpt: String = content.chars().take(MAX_FILE_CHARS).collect();
+        prompt.push_str(&format!(
+            "\n## {}\n\n```\n{}\n```\n",
+            file,
+            excerpt.trim_end()
+        ));
+    }
+    prompt
+}
+
+/// The TOML in a `sampling/createMessage` result, checked to be a valid
+/// project config. A surrounding code fence is removed.
+pub fn parse_draft(result: &Value) -> Result<String, String> {
+    let text = result
+        .get("content")
+        .and_then(|c| c.get("text"))
+        .and_then(|t| t.as_str())
+        .ok_or("The client's model returned no text")?;
+    let text = text.trim();
+    let draft = match text.strip_prefix("```") {
+        Some(fenced) => {
+            let body = fenced.split_once('\n').map_or("", |(_, body)| body);
+            body.trim_end().trim_end_matches("```").trim_end()
+        }
+        None => text,
+    };
+    toml::from_str::<ProjectConfig>(draft)
+        .map_err(|e| format!("The drafted config is not a valid project.toml: {}", e))?;
+    Ok(format!("{}\n", draft))
+}
+
+/// Writes a reviewed draft to `dir/.jumble/project.toml`.
+pub fn write_draft(dir: &Path, draft: &str) -> Result<PathBuf, String> {
+    let jumble_dir = dir.join(".jumble");
+    let file = jumble_dir.join("project.toml");
+    if file.exists() {
+        return Err(format!("{} already exists", file.display()));
+    }
+    std::fs::create_dir_all(&jumble_dir)
+        .map_err(|e| format!("Failed to create {}: {}", jumble_dir.display(), e))?;
+    std::fs::write(&file, draft)
+        .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_draft_target_requires_missing_context() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("app/src")).unwrap();
+        let args = json!({"directory": "app"});
+        assert_eq!(
+            draft_target(temp.path(), &args).unwrap(),
+            temp.path().canonicalize().unwrap().join("app")
+        );
+
+        std::fs::write(
+            temp.path().join("app/jumble.toml"),
+            "[project]\nname = \"app\"\ndescription = \"x\"\n",
+        )
+        .unwrap();
+        let err = draft_target(temp.path(), &args).unwrap_err();
+        assert!(err.contains("already has Jumble context"));
+        assert!(draft_target(temp.path(), &json!({"directory": "missing"})).is_err());
+    }
+
+    #[test]
+    fn test_draft_target_stays_in_workspace() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("workspace");
+        std::fs::create_dir_all(root.join("app")).unwrap();
+        std::fs::create_dir_all(temp.path().join("outside")).unwrap();
+
+        for directory in ["../outside", "app/../../outside"] {
+            let err = draft_target(&root, &json!({"directory": directory})).unwrap_err();
+            assert!(err.contains("outside the workspace"), "{}", err);
+        }
+        let outside = temp.path().join("outside");
+        let err = draft_target(&root, &json!({"directory": outside})).unwrap_err();
+        assert!(err.contains("outside the workspace"));
+        assert!(draft_target(&root, &json!({"directory": "app/../app"})).is_ok());
+    }
+
+    #[test]
+    fn test_sampling_request_describes_project() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("app");
+        for (file, content) in [
+            ("src/main.rs", "fn main() {}"),
+            ("Cargo.toml", "[package]\nname = \"app\"\n"),
+            ("target/debug/app", ""),
+            (".git/HEAD", ""),
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let request = sampling_request(&dir);
+        let text = request["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.contains("- src/main.rs\n"));
+        assert!(text.contains("## Cargo.toml\n\n```\n[package]\nname = \"app\"\n```"));
+        assert!(!text.contains("target"));
+        assert!(!text.contains(".git"));
+        assert_eq!(request["maxTokens"], MAX_DRAFT_TOKENS);
+    }
+
+    #[test]
+    fn test_parse_draft() {
+        let result = json!({"content": {"type": "text", "text":
+            "```toml\n[project]\nname = \"app\"\ndescription = \"An app\"\n```"}});
+        assert_eq!(
+            parse_draft(&result).unwrap(),
+            "[project]\nname = \"app\"\ndescription = \"An app\"\n"
+        );
+
+        let result = json!({"content": {"type": "text", "text": "[project]\nname = \"app\""}});
+        assert!(parse_draft(&result)
+            .unwrap_err()
+            .contains("not a valid project.toml"));
+        assert!(parse_draft(&json!({"content": {"type": "image"}})).is_err());
+    }
+
+    #[test]
+    fn test_write_draft() {
+        let temp = TempDir::new().unwrap();
+        let file = write_draft(temp.path(), "[project]\n").unwrap();
+        assert_eq!(file, temp.path().join(".jumble/project.toml"));
+        assert!(write_draft(temp.path(), "[project]\n").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Release sections of a markdown changelog, for `get_changelog`.
+//!
+//! Release headings are the headings at the level of the first one that
+//! names a version or `Unreleased`, in the styles Keep a Changelog and most
+//! release tools write: `## [1.2.0] - 2024-05-01`, `## v1.2.0 (2024-05-01)`,
+//! `# 1.2.0`, or `## [Unreleased]`.
+
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::format::markdown_headings;
+
+/// Changelog names looked for when `[releases]` does not name one.
+pub const CHANGELOG_FILES: &[&str] = &["CHANGELOG.md", "CHANGES.md", "HISTORY.md", "RELEASES.md"];
+
+static VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[?v?(\d+(?:\.\d+)*(?:[-+][0-9A-Za-z.-]+)?)\]?").expect("valid regex")
+});
+static DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d{4}-\d{2}-\d{2}").expect("valid regex"));
+
+/// One release section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// The version without brackets or a leading `v`, or `Unreleased`.
+    pub version: String,
+    pub date: Option<String>,
+    /// The section, heading included.
+    pub text: String,
+}
+
+impl Release {
+    pub fn is_unreleased(&self) -> bool {
+        self.version.eq_ignore_ascii_case("unreleased")
+    }
+
+    /// Whether the section has anything under its heading.
+    pub fn has_entries(&self) -> bool {
+        self.text
+            .lines()
+            .skip(1)
+            .any(|line| !line.trim().is_empty())
+    }
+
+    /// `1.2.0 (2024-05-01)`, or just the version.
+    pub fn label(&self) -> String {
+        match &self.date {
+            Some(date) => format!("{} ({})", self.version, date),
+            None => self.version.clone(),
+        }
+    }
+}
+
+/// The changelog for the project at `project_dir`: `configured` if set,
+/// otherwise the first of [`CHANGELOG_FILES`] that exists.
+pub fn find(project_dir: &Path, configured: Option<&str>) -> Option<String> {
+    match configured {
+        Some(path) => Some(path.to_string()),
+        None => CHANGELOG_FILES
+            .iter()
+            .find(|name| project_dir.join(name).is_file())
+            .map(|name| name.to_string()),
+    }
+}
+
+/// Release sections in file order, newest first in a conventional changelog.
+pub fn releases(content: &str) -> Vec<Release> {
+    let headings: Vec<(usize, usize, Option<String>, &str)> = markdown_headings(content)
+        .into_iter()
+        .map(|(line, level, title)| (line, level, release_version(title), title))
+        .collect();
+    let Some(level) = headings
+        .iter()
+        .find(|(_, _, version, _)| version.is_some())
+        .map(|(_, level, _, _)| *level)
+    else {
+        return Vec::new();
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut releases = Vec::new();
+    for (index, (start, heading_level, version, title)) in headings.iter().enumerate() {
+        let Some(version) = version.clone().filter(|_| *heading_level == level) else {
+            continue;
+        };
+        let end = headings[index + 1..]
+            .iter()
+            .find(|(_, other, _, _)| *other <= level)
+            .map_or(lines.len(), |(line, ..)| *line);
+        releases.push(Release {
+            version,
+            date: DATE.find(title).map(|m| m.as_str().to_string()),
+            text: lines[*start..end].join("\n").trim_end().to_string(),
+        });
+    }
+    releases
+}
+
+/// The version a release heading names, if it is one.
+fn release_version(title: &str) -> Option<String> {
+    let title = title.trim();
+    let bare = title.trim_start_matches('[');
+    if bare
+        .get(..10)
+        .is_some_and(|word| word.eq_ignore_ascii_case("unreleased"))
+    {
+        return Some("Unreleased".to_string());
+    }
+    VERSION.captures(title).map(|c| c[1].to_string())
+}
+
+/// Whether `wanted` names `release`, ignoring a leading `v` and case.
+pub fn matches(release: &Release, wanted: &str) -> bool {
+    let wanted = wanted.trim().trim_start_matches(['v', 'V']);
+    release.version.eq_ignore_ascii_case(wanted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "\
+# Changelog
+
+All notable changes to this project are documented here.
+
+## [Unreleased]
+
+### Added
+- Dark mode
+
+## [1.2.0] - 2024-05-01
+
+### Fixed
+- Login redirect
+
+## v1.1.0 (2024-03-10)
+
+- First public release
+
+```
+## not a heading
+```
+
+## 1.0.0-beta.1
+";
+
+    #[test]
+    fn test_releases() {
+        let releases = releases(CHANGELOG);
+        let labels: Vec<String> = releases.iter().map(Release::label).collect();
+        assert_eq!(
+            labels,
+            [
+                "Unreleased",
+                "1.2.0 (2024-05-01)",
+                "1.1.0 (2024-03-10)",
+                "1.0.0-beta.1"
+            ]
+        );
+        assert!(releases[0].is_unreleased());
+        assert_eq!(
+            releases[1].text,
+            "## [1.2.0] - 2024-05-01\n\n### Fixed\n- Login redirect"
+        );
+        assert!(releases[2].text.ends_with("```\n## not a heading\n```"));
+        assert!(!releases[3].has_entries());
+        assert!(matches(&releases[2], "v1.1.0"));
+        assert!(matches(&releases[0], "unreleased"));
+        assert!(!matches(&releases[1], "1.2"));
+    }
+
+    #[test]
+    fn test_releases_without_versions() {
+        assert!(releases("# Notes\n\n## Ideas\n").is_empty());
+    }
+}
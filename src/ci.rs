@@ -0,0 +1,272 @@
+//! CI workflows for `get_ci_info`: the files a project's `[ci]` section
+//! lists, or the GitHub Actions workflows found for it, with triggers and
+//! jobs read from the GitHub Actions YAML.
+
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+/// Where GitHub Actions keeps workflows, relative to the repository root.
+pub const WORKFLOW_DIR: &str = ".github/workflows";
+
+/// A parsed GitHub Actions workflow.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Workflow {
+    pub name: Option<String>,
+    /// Events such as `push (branches: main)` or `schedule (0 3 * * *)`.
+    pub triggers: Vec<String>,
+    pub jobs: Vec<Job>,
+}
+
+impl Workflow {
+    /// Whether the workflow runs on pull requests, so its jobs can gate a
+    /// merge.
+    pub fn runs_on_pull_requests(&self) -> bool {
+        self.triggers
+            .iter()
+            .any(|t| t.starts_with("pull_request") || t.starts_with("merge_group"))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Job {
+    pub id: String,
+    pub name: Option<String>,
+    pub runs_on: Option<String>,
+    pub needs: Vec<String>,
+    /// Matrix dimensions, e.g. `os` and `rust`.
+    pub matrix: Vec<String>,
+    /// `run` steps as `name: command` (first line of the command only).
+    pub steps: Vec<String>,
+}
+
+/// The GitHub Actions workflows for the project at `project_dir`: the
+/// `.github/workflows` directory of the project or its nearest ancestor
+/// that has one, stopping at `root` or the repository root. Paths are
+/// returned with the directory the search found them in.
+pub fn find_workflows(project_dir: &Path, root: &Path) -> Option<(PathBuf, Vec<PathBuf>)> {
+    for dir in project_dir.ancestors() {
+        let workflows = dir.join(WORKFLOW_DIR);
+        if let Ok(entries) = std::fs::read_dir(&workflows) {
+            let mut files: Vec<PathBuf> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "yml" || e == "yaml"))
+                .collect();
+            files.sort();
+            return Some((dir.to_path_buf(), files));
+        }
+        if dir == root || dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Reads and parses a GitHub Actions workflow file.
+pub fn load_workflow(path: &Path) -> Result<Workflow, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_workflow(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn parse_workflow(content: &str) -> Result<Workflow, String> {
+    let value: Value = serde_yaml::from_str(content).map_err(|e| e.to_string())?;
+    let Some(jobs) = value.get("jobs").and_then(|j| j.as_mapping()) else {
+        return Err("not a GitHub Actions workflow (no jobs)".to_string());
+    };
+    Ok(Workflow {
+        name: string(value.get("name")),
+        triggers: value.get("on").map(triggers).unwrap_or_default(),
+        jobs: jobs
+            .iter()
+            .filter_map(|(id, job)| Some(parse_job(id.as_str()?, job)))
+            .collect(),
+    })
+}
+
+/// `on:` as a single event, a list of events, or a map of events to filters.
+fn triggers(on: &Value) -> Vec<String> {
+    match on {
+        Value::String(event) => vec![event.clone()],
+        Value::Sequence(events) => events.iter().filter_map(|e| string(Some(e))).collect(),
+        Value::Mapping(events) => events
+            .iter()
+            .filter_map(|(event, filters)| {
+                let event = event.as_str()?;
+                let details: Vec<String> = if event == "schedule" {
+                    filters
+                        .as_sequence()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|entry| string(entry.get("cron")))
+                        .collect()
+                } else {
+                    ["branches", "tags", "paths", "types"]
+                        .iter()
+                        .filter_map(|key| {
+                            let values = strings(filters.get(*key));
+                            (!values.is_empty()).then(|| format!("{}: {}", key, values.join(", ")))
+                        })
+                        .collect()
+                };
+                Some(if details.is_empty() {
+                    event.to_string()
+                } else {
+                    format!("{} ({})", event, details.join("; "))
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_job(id: &str, job: &Value) -> Job {
+    let matrix = job
+        .get("strategy")
+        .and_then(|s| s.get("matrix"))
+        .and_then(|m| m.as_mapping())
+        .map(|m| {
+            m.keys()
+                .filter_map(|k| k.as_str())
+                .filter(|k| !matches!(*k, "include" | "exclude"))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let steps = job
+        .get("steps")
+        .and_then(|s| s.as_sequence())
+        .into_iter()
+        .flatten()
+        .filter_map(|step| {
+            let run = step
+                .get("run")?
+                .as_str()?
+                .lines()
+                .find(|l| !l.trim().is_empty())?;
+            Some(match string(step.get("name")) {
+                Some(name) => format!("{}: `{}`", name, run.trim()),
+                None => format!("`{}`", run.trim()),
+            })
+        })
+        .collect();
+    Job {
+        id: id.to_string(),
+        name: string(job.get("name")),
+        runs_on: job.get("runs-on").and_then(|r| match r {
+            Value::Sequence(_) => Some(strings(Some(r)).join(", ")),
+            _ => string(Some(r)),
+        }),
+        needs: strings(job.get("needs")),
+        matrix,
+        steps,
+    }
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// A string or a list of strings.
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(|i| string(Some(i))).collect(),
+        other => string(other).into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"
+name: CI
+on:
+  push:
+    branches: [main]
+  pull_request:
+  schedule:
+    - cron: "0 3 * * *"
+jobs:
+  lint:
+    name: Lint
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - run: cargo fmt --check
+  test:
+    needs: lint
+    runs-on: ${{ matrix.os }}
+    strategy:
+      matrix:
+        os: [ubuntu-latest, macos-latest]
+        include:
+          - os: windows-latest
+    steps:
+      - name: Test
+        run: |
+          cargo test --all-features
+          cargo test --doc
+"#;
+
+    #[test]
+    fn test_parse_workflow() {
+        let workflow = parse_workflow(WORKFLOW).unwrap();
+        assert_eq!(workflow.name.as_deref(), Some("CI"));
+        assert_eq!(
+            workflow.triggers,
+            [
+                "push (branches: main)",
+                "pull_request",
+                "schedule (0 3 * * *)"
+            ]
+        );
+        assert!(workflow.runs_on_pull_requests());
+        assert_eq!(
+            workflow.jobs,
+            [
+                Job {
+                    id: "lint".to_string(),
+                    name: Some("Lint".to_string()),
+                    runs_on: Some("ubuntu-latest".to_string()),
+                    needs: vec![],
+                    matrix: vec![],
+                    steps: vec!["`cargo fmt --check`".to_string()],
+                },
+                Job {
+                    id: "test".to_string(),
+                    name: None,
+                    runs_on: Some("${{ matrix.os }}".to_string()),
+                    needs: vec!["lint".to_string()],
+                    matrix: vec!["os".to_string()],
+                    steps: vec!["Test: `cargo test --all-features`".to_string()],
+                },
+            ]
+        );
+
+        let simple = parse_workflow("on: [push, pull_request]\njobs: {}\n").unwrap();
+        assert_eq!(simple.triggers, ["push", "pull_request"]);
+        assert!(parse_workflow("stages: [build]\n").is_err());
+    }
+
+    #[test]
+    fn test_find_workflows() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let repo = temp.path();
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join(".github/workflows")).unwrap();
+        std::fs::create_dir_all(repo.join("services/api")).unwrap();
+        std::fs::write(repo.join(".github/workflows/ci.yml"), WORKFLOW).unwrap();
+        std::fs::write(repo.join(".github/workflows/README.md"), "").unwrap();
+
+        let (base, files) = find_workflows(&repo.join("services/api"), repo).unwrap();
+        assert_eq!(base, repo);
+        assert_eq!(files, [repo.join(".github/workflows/ci.yml")]);
+        assert!(find_workflows(&temp.path().join("services"), &repo.join("services")).is_none());
+    }
+}
@@ -0,0 +1,191 @@
+//! Ownership from `CODEOWNERS` files, as GitHub and GitLab read them.
+//!
+//! The file is looked up at `CODEOWNERS`, `.github/CODEOWNERS`, and
+//! `docs/CODEOWNERS`, starting in the project directory and moving up
+//! towards the repository root. Patterns are relative to the directory the
+//! lookup found them for, and the last matching rule wins.
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::{Path, PathBuf};
+
+/// Where a `CODEOWNERS` file may live, relative to the directory it covers.
+pub const LOCATIONS: &[&str] = &["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS"];
+
+/// One `pattern owner...` line.
+#[derive(Debug)]
+pub struct Rule {
+    pub pattern: String,
+    /// Users, teams, or emails; empty when the rule removes ownership.
+    pub owners: Vec<String>,
+    /// 1-based line number in the file.
+    pub line: usize,
+    matcher: GlobSet,
+}
+
+#[derive(Debug)]
+pub struct CodeOwners {
+    /// The `CODEOWNERS` file.
+    pub file: PathBuf,
+    /// The directory its patterns are relative to.
+    pub base: PathBuf,
+    pub rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Finds and parses the `CODEOWNERS` file covering `project_dir`,
+    /// searching up to `root` or the repository root, whichever comes first.
+    pub fn find(project_dir: &Path, root: &Path) -> Option<CodeOwners> {
+        for dir in project_dir.ancestors() {
+            for location in LOCATIONS {
+                let file = dir.join(location);
+                if let Ok(content) = std::fs::read_to_string(&file) {
+                    return Some(CodeOwners {
+                        file,
+                        base: dir.to_path_buf(),
+                        rules: parse(&content),
+                    });
+                }
+            }
+            if dir == root || dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+
+    /// The rule deciding who owns `path`, an absolute path or one relative to
+    /// [`CodeOwners::base`]. `None` when no rule matches.
+    pub fn rule_for(&self, path: &Path) -> Option<&Rule> {
+        let relative = path.strip_prefix(&self.base).unwrap_or(path);
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matcher.is_match(relative))
+    }
+}
+
+/// Parses `CODEOWNERS` content, skipping comments, section headers, and
+/// patterns that do not compile.
+fn parse(content: &str) -> Vec<Rule> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.split(" #").next().unwrap_or_default().trim();
+            // GitLab section headers look like `[Section]` or `^[Section]`.
+            if line.is_empty() || line.starts_with('#') || line.starts_with(['[', '^']) {
+                return None;
+            }
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?.to_string();
+            Some(Rule {
+                matcher: matcher(&pattern)?,
+                owners: fields.map(str::to_string).collect(),
+                line: index + 1,
+                pattern,
+            })
+        })
+        .collect()
+}
+
+/// Compiles a gitignore-style pattern: a leading or inner `/` anchors it to
+/// the base directory, otherwise it matches at any depth, and a match on a
+/// directory covers everything beneath it.
+fn matcher(pattern: &str) -> Option<GlobSet> {
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    let glob = match (trimmed.is_empty(), anchored) {
+        (true, _) => "**".to_string(),
+        (false, true) => trimmed.to_string(),
+        (false, false) => format!("**/{}", trimmed),
+    };
+    let mut builder = GlobSetBuilder::new();
+    if !directory_only {
+        builder.add(compile(&glob)?);
+    }
+    builder.add(compile(&format!("{}/**", glob))?);
+    builder.build().ok()
+}
+
+fn compile(glob: &str) -> Option<globset::Glob> {
+    GlobBuilder::new(glob).literal_separator(true).build().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const OWNERS: &str = "\
+# Default owners
+*       @org/everyone
+
+*.rs    @org/rust-team # Rust code
+/docs/  @org/docs
+build/  @org/release
+src/billing/ @alice bob@example.com
+src/billing/generated/
+
+[Frontend]
+web/**/*.tsx @org/frontend
+";
+
+    fn owners_of(owners: &CodeOwners, path: &str) -> Vec<String> {
+        owners
+            .rule_for(Path::new(path))
+            .map(|rule| rule.owners.clone())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let owners = CodeOwners {
+            file: PathBuf::from("CODEOWNERS"),
+            base: PathBuf::new(),
+            rules: parse(OWNERS),
+        };
+        assert_eq!(owners_of(&owners, "README.md"), ["@org/everyone"]);
+        assert_eq!(owners_of(&owners, "src/main.rs"), ["@org/rust-team"]);
+        assert_eq!(owners_of(&owners, "docs/guide/intro.md"), ["@org/docs"]);
+        assert_eq!(owners_of(&owners, "api/docs/x.md"), ["@org/everyone"]);
+        assert_eq!(owners_of(&owners, "tools/build/run.sh"), ["@org/release"]);
+        assert_eq!(
+            owners_of(&owners, "src/billing/invoice.rs"),
+            ["@alice", "bob@example.com"]
+        );
+        assert!(owners_of(&owners, "src/billing/generated/types.rs").is_empty());
+        assert_eq!(
+            owners_of(&owners, "web/app/pages/Home.tsx"),
+            ["@org/frontend"]
+        );
+
+        let rule = owners.rule_for(Path::new("src/billing/x.rs")).unwrap();
+        assert_eq!(rule.pattern, "src/billing/");
+        assert_eq!(rule.line, 7);
+    }
+
+    #[test]
+    fn test_find_searches_up_to_repository_root() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join(".github")).unwrap();
+        std::fs::create_dir_all(repo.join("services/api")).unwrap();
+        std::fs::write(repo.join(".github/CODEOWNERS"), "/services/api/ @org/api\n").unwrap();
+        std::fs::write(temp.path().join("CODEOWNERS"), "* @outside\n").unwrap();
+
+        let api = repo.join("services/api");
+        let owners = CodeOwners::find(&api, temp.path()).unwrap();
+        assert_eq!(owners.base, repo);
+        assert_eq!(owners.file, repo.join(".github/CODEOWNERS"));
+        assert_eq!(
+            owners_of(&owners, api.join("src/lib.rs").to_str().unwrap()),
+            ["@org/api"]
+        );
+
+        std::fs::remove_file(repo.join(".github/CODEOWNERS")).unwrap();
+        assert!(CodeOwners::find(&api, temp.path()).is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Line coverage from the report a project's `[coverage]` section points at,
+//! in lcov (`lcov.info`) or Cobertura XML format.
+//!
+//! Report paths may be absolute paths from the machine that produced the
+//! report, so files are matched by their path relative to the project when
+//! possible and by path suffix otherwise.
+
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::config::{CoverageConfig, CoverageFormat, FileRef};
+
+/// Hit counts per line of one source file.
+pub type LineHits = BTreeMap<usize, u64>;
+
+/// A parsed coverage report.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Source paths as written in the report, with their line hits.
+    pub files: Vec<(String, LineHits)>,
+}
+
+/// Covered and total instrumented lines.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl Summary {
+    pub fn percent(self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.covered as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    pub fn add(&mut self, other: Summary) {
+        self.covered += other.covered;
+        self.total += other.total;
+    }
+}
+
+/// Reads and parses the report configured for the project at `project_dir`.
+pub fn load(project_dir: &Path, config: &CoverageConfig) -> Result<Report, String> {
+    let path = project_dir.join(&config.report);
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "Failed to read coverage report {}: {}. Run the project's coverage command first.",
+            path.display(),
+            e
+        )
+    })?;
+    let format = config.format.unwrap_or_else(|| detect(&content));
+    let report = match format {
+        CoverageFormat::Lcov => parse_lcov(&content),
+        CoverageFormat::Cobertura => parse_cobertura(&content),
+    };
+    Ok(report)
+}
+
+/// Cobertura reports are XML; anything else is read as lcov.
+fn detect(content: &str) -> CoverageFormat {
+    if content.trim_start().starts_with('<') {
+        CoverageFormat::Cobertura
+    } else {
+        CoverageFormat::Lcov
+    }
+}
+
+/// `SF:` starts a file, `DA:<line>,<hits>` records a line, and
+/// `end_of_record` closes the file.
+fn parse_lcov(content: &str) -> Report {
+    let mut report = Report::default();
+    let mut current: Option<(String, LineHits)> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            current = Some((path.to_string(), LineHits::new()));
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            let mut fields = data.split(',');
+            let number = fields.next().and_then(|n| n.parse().ok());
+            let hits = fields.next().and_then(|h| h.parse::<u64>().ok());
+            if let (Some((_, lines)), Some(number), Some(hits)) = (&mut current, number, hits) {
+                *lines.entry(number).or_default() += hits;
+            }
+        } else if line == "end_of_record" {
+            report.files.extend(current.take());
+        }
+    }
+    report.files.extend(current);
+    report
+}
+
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(/?)([A-Za-z]+)([^>]*)>").expect("valid regex"));
+static ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([A-Za-z-]+)="([^"]*)""#).expect("valid regex"));
+
+/// Reads `<class filename=...>` elements and their `<line number hits>`
+/// children, skipping the per-method copies of the same lines. Filenames
+/// are joined to the first `<source>` directory.
+fn parse_cobertura(content: &str) -> Report {
+    let mut report = Report::default();
+    let mut source: Option<String> = None;
+    let mut current: Option<(String, LineHits)> = None;
+    let mut in_methods = false;
+    for tag in TAG.captures_iter(content) {
+        let closing = &tag[1] == "/";
+        let attributes = |name: &str| {
+            ATTRIBUTE
+                .captures_iter(&tag[3])
+                .find(|a| &a[1] == name)
+                .map(|a| a[2].to_string())
+        };
+        match (&tag[2], closing) {
+            ("source", false) if source.is_none() => {
+                let start = tag.get(0).map_or(0, |m| m.end());
+                let text = content[start..].split('<').next().unwrap_or_default();
+                source = Some(text.trim().to_string()).filter(|s| !s.is_empty());
+            }
+            ("class", false) => {
+                if let Some(filename) = attributes("filename") {
+                    let path = match &source {
+                        Some(source) if !Path::new(&filename).is_absolute() => {
+                            format!("{}/{}", source.trim_end_matches('/'), filename)
+                        }
+                        _ => filename,
+                    };
+                    report.files.extend(current.take());
+                    current = Some((path, LineHits::new()));
+                }
+            }
+            ("class", true) => report.files.extend(current.take()),
+            ("methods", closing) => in_methods = !closing,
+            ("line", false) if !in_methods => {
+                let number = attributes("number").and_then(|n| n.parse().ok());
+                let hits = attributes("hits").and_then(|h| h.parse::<u64>().ok());
+                if let (Some((_, lines)), Some(number), Some(hits)) = (&mut current, number, hits) {
+                    *lines.entry(number).or_default() += hits;
+                }
+            }
+            _ => {}
+        }
+    }
+    report.files.extend(current);
+    report
+}
+
+impl Report {
+    /// Coverage across every file in the report.
+    pub fn total(&self) -> Summary {
+        let mut total = Summary::default();
+        for (_, lines) in &self.files {
+            total.add(Summary {
+                covered: lines.values().filter(|hits| **hits > 0).count(),
+                total: lines.len(),
+            });
+        }
+        total
+    }
+
+    /// Coverage of a concept `files` entry: a file, a directory, or a file
+    /// narrowed to a line range. `None` when the report has no matching file.
+    pub fn summarize(&self, project_dir: &Path, entry: &str) -> Option<Summary> {
+        let file = FileRef::parse(entry);
+        let wanted = file.path.trim_start_matches("./").trim_end_matches('/');
+        let mut summary = None;
+        for (path, lines) in &self.files {
+            if !covers(project_dir, path, wanted) {
+                continue;
+            }
+            let mut file_summary = Summary::default();
+            for (number, hits) in lines {
+                if file
+                    .lines
+                    .is_none_or(|(start, end)| (start..=end).contains(number))
+                {
+                    file_summary.total += 1;
+                    if *hits > 0 {
+                        file_summary.covered += 1;
+                    }
+                }
+            }
+            summary
+                .get_or_insert_with(Summary::default)
+                .add(file_summary);
+        }
+        summary
+    }
+}
+
+/// Whether report `path` is the project file or directory `wanted`.
+fn covers(project_dir: &Path, path: &str, wanted: &str) -> bool {
+    let relative = match Path::new(path).strip_prefix(project_dir) {
+        Ok(rest) => rest.to_string_lossy().replace('\\', "/"),
+        Err(_) => path.replace('\\', "/").trim_start_matches("./").to_string(),
+    };
+    if relative == wanted || relative.starts_with(&format!("{}/", wanted)) {
+        return true;
+    }
+    // Paths from another machine: compare by suffix.
+    relative.starts_with('/')
+        && (relative.ends_with(&format!("/{}", wanted))
+            || relative.contains(&format!("/{}/", wanted)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LCOV: &str = "\
+TN:
+SF:/ci/build/app/src/auth.rs
+DA:1,3
+DA:2,0
+DA:3,1
+DA:10,0
+LF:4
+LH:2
+end_of_record
+SF:src/db/pool.rs
+DA:1,1
+DA:2,1
+end_of_record
+";
+
+    const COBERTURA: &str = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.5">
+  <sources>
+    <source>/ci/build/app</source>
+  </sources>
+  <packages><package name="app"><classes>
+    <class name="auth" filename="src/auth.py" line-rate="0.5">
+      <methods><method name="login"><lines><line number="1" hits="1"/></lines></method></methods>
+      <lines>
+        <line number="1" hits="1"/>
+        <line number="2" hits="0" branch="true" condition-coverage="50% (1/2)"/>
+      </lines>
+    </class>
+  </classes></package></packages>
+</coverage>
+"#;
+
+    #[test]
+    fn test_parse_lcov() {
+        let report = parse_lcov(LCOV);
+        let project = Path::new("/home/dev/app");
+        assert_eq!(
+            report.summarize(project, "src/auth.rs"),
+            Some(Summary {
+                covered: 2,
+                total: 4
+            })
+        );
+        assert_eq!(
+            report.summarize(project, "src/auth.rs:1-3"),
+            Some(Summary {
+                covered: 2,
+                total: 3
+            })
+        );
+        assert_eq!(
+            report.summarize(project, "src/db/"),
+            Some(Summary {
+                covered: 2,
+                total: 2
+            })
+        );
+        assert_eq!(report.summarize(project, "src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        assert_eq!(detect(COBERTURA), CoverageFormat::Cobertura);
+        let report = parse_cobertura(COBERTURA);
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].0, "/ci/build/app/src/auth.py");
+        assert_eq!(
+            report.summarize(Path::new("/ci/build/app"), "src/auth.py"),
+            Some(Summary {
+                covered: 1,
+                total: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Architecture decision records.
+//!
+//! Decisions live in `<project_root>/.jumble/decisions/NNNN-slug.md`, one file
+//! per decision, with YAML frontmatter for the title, date, and status and
+//! markdown sections for the context, the decision itself, and its
+//! consequences. Unlike memory entries they are plain files meant to be
+//! reviewed and committed alongside the code.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// Statuses a decision may have.
+pub const STATUSES: [&str; 4] = ["proposed", "accepted", "deprecated", "superseded"];
+
+/// Frontmatter of a decision file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionMeta {
+    pub title: String,
+    /// Date the decision was recorded (`YYYY-MM-DD`).
+    pub date: String,
+    pub status: String,
+}
+
+/// A decision read from disk.
+#[derive(Debug, Clone)]
+pub struct Decision {
+    /// Sequence number taken from the file name (`0003-...` is 3).
+    pub number: u32,
+    pub meta: DecisionMeta,
+    /// Markdown body following the frontmatter.
+    pub body: String,
+    pub path: PathBuf,
+}
+
+/// A decision to be written.
+pub struct NewDecision<'a> {
+    pub title: &'a str,
+    pub status: &'a str,
+    pub date: &'a str,
+    pub context: &'a str,
+    pub decision: &'a str,
+    pub consequences: Option<&'a str>,
+}
+
+/// Directory holding a project's decision records.
+pub fn decisions_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".jumble").join("decisions")
+}
+
+/// Loads every decision record for a project, ordered by number.
+///
+/// Files that are not named `NNNN-*.md` or lack valid frontmatter are skipped.
+pub fn load_decisions(project_root: &Path) -> Vec<Decision> {
+    let Ok(entries) = std::fs::read_dir(decisions_dir(project_root)) else {
+        return Vec::new();
+    };
+
+    let mut decisions: Vec<Decision> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| read_decision(&entry.path()))
+        .collect();
+    decisions.sort_by_key(|d| d.number);
+    decisions
+}
+
+fn read_decision(path: &Path) -> Option<Decision> {
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return None;
+    }
+    let number = file_number(path)?;
+    let content = std::fs::read_to_string(path).ok()?;
+    let (yaml, body) = config::split_frontmatter(&content)?;
+    let meta: DecisionMeta = serde_yaml::from_str(yaml).ok()?;
+    Some(Decision {
+        number,
+        meta,
+        body: body.trim().to_string(),
+        path: path.to_path_buf(),
+    })
+}
+
+/// Parses the leading digits of a file name, e.g. `12` from `0012-use-sqlite.md`.
+fn file_number(path: &Path) -> Option<u32> {
+    let name = path.file_name()?.to_str()?;
+    let (digits, _) = name.split_once('-')?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Turns a title into a file-name slug: `Use SQLite for cache` -> `use-sqlite-for-cache`.
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "decision".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Renders a decision file: YAML frontmatter followed by markdown sections.
+/// Fails for a status outside [`STATUSES`].
+pub fn render_decision(decision: &NewDecision) -> Result<String, String> {
+    if !STATUSES.contains(&decision.status) {
+        return Err(format!(
+            "Invalid status '{}'. Use one of: {}",
+            decision.status,
+            STATUSES.join(", ")
+        ));
+    }
+    let meta = DecisionMeta {
+        title: decision.title.to_string(),
+        date: decision.date.to_string(),
+        status: decision.status.to_string(),
+    };
+    let yaml =
+        serde_yaml::to_string(&meta).map_err(|e| format!("Failed to serialize decision: {}", e))?;
+
+    let mut output = format!(
+        "---\n{}---\n\n## Context\n\n{}\n\n## Decision\n\n{}\n",
+        yaml,
+        decision.context.trim(),
+        decision.decision.trim()
+    );
+    if let Some(consequences) = decision.consequences {
+        output.push_str(&format!("\n## Consequences\n\n{}\n", consequences.trim()));
+    }
+    Ok(output)
+}
+
+/// Path the next decision with `title` would be written to.
+pub fn next_decision_path(project_root: &Path, title: &str) -> PathBuf {
+    let next = load_decisions(project_root)
+        .last()
+        .map(|d| d.number + 1)
+        .unwrap_or(1);
+    decisions_dir(project_root).join(format!("{:04}-{}.md", next, slugify(title)))
+}
+
+/// Writes a new decision record with the next free number, returning its path.
+pub fn write_decision(project_root: &Path, decision: &NewDecision) -> Result<PathBuf, String> {
+    let content = render_decision(decision)?;
+    let path = next_decision_path(project_root, decision.title);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Today's date in the format used for decision records.
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn decision<'a>(title: &'a str, status: &'a str) -> NewDecision<'a> {
+        NewDecision {
+            title,
+            status,
+            date: "2026-10-17",
+            context: "Reads dominate.",
+            decision: "Cache in SQLite.",
+            consequences: Some("One more file to back up."),
+        }
+    }
+
+    #[test]
+    fn test_write_and_load_decisions() {
+        let temp = TempDir::new().unwrap();
+        let first =
+            write_decision(temp.path(), &decision("Use SQLite: for cache!", "accepted")).unwrap();
+        let second = write_decision(temp.path(), &decision("Drop Redis", "proposed")).unwrap();
+        assert!(first.ends_with(".jumble/decisions/0001-use-sqlite-for-cache.md"));
+        assert!(second.ends_with(".jumble/decisions/0002-drop-redis.md"));
+        std::fs::write(decisions_dir(temp.path()).join("README.md"), "# ADRs").unwrap();
+
+        let decisions = load_decisions(temp.path());
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].number, 1);
+        assert_eq!(decisions[0].meta.title, "Use SQLite: for cache!");
+        assert_eq!(decisions[0].meta.date, "2026-10-17");
+        assert_eq!(decisions[1].meta.status, "proposed");
+        assert!(decisions[0]
+            .body
+            .starts_with("## Context\n\nReads dominate."));
+        assert!(decisions[0].body.ends_with("One more file to back up."));
+    }
+
+    #[test]
+    fn test_write_decision_rejects_unknown_status() {
+        let temp = TempDir::new().unwrap();
+        let err = write_decision(temp.path(), &decision("X", "maybe")).unwrap_err();
+        assert!(err.contains("Invalid status 'maybe'"));
+        assert!(render_decision(&decision("X", "maybe")).is_err());
+    }
+
+    #[test]
+    fn test_load_decisions_skips_bad_frontmatter() {
+        let temp = TempDir::new().unwrap();
+        let dir = decisions_dir(temp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        let meta = "title: Keep\ndate: 2026-10-17\nstatus: accepted";
+        std::fs::write(
+            dir.join("0001-keep.md"),
+            format!("---\n{}\n---\n\nBody ---\n", meta),
+        )
+        .unwrap();
+        std::fs::write(dir.join("0002-open.md"), format!("---\n{}\nBody\n", meta)).unwrap();
+        std::fs::write(
+            dir.join("0003-dashes.md"),
+            format!("---\n{}\n-----\nBody\n", meta),
+        )
+        .unwrap();
+
+        let decisions = load_decisions(temp.path());
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].body, "Body ---");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("  Use gRPC -- internally "), "use-grpc-internally");
+        assert_eq!(slugify("!!!"), "decision");
+    }
+}
@@ -0,0 +1,202 @@
+//! External dependencies declared in a project's package manifests, for
+//! `jumble sync-deps` and `[dependencies] sync = true`.
+//!
+//! Only runtime dependencies are read: Cargo's `[dependencies]` (including
+//! target-specific ones), npm's `dependencies`, PEP 621 and Poetry
+//! dependencies in `pyproject.toml`, and `requirements.txt`.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::config::{self, ProjectConfig};
+
+/// Manifests read, in the order they are listed as sources.
+pub const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "requirements.txt",
+];
+
+/// Dependency names found in a project's manifests.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct External {
+    /// Sorted, deduplicated names.
+    pub names: Vec<String>,
+    /// The manifests they came from.
+    pub sources: Vec<&'static str>,
+}
+
+impl External {
+    /// The sources as one label, e.g. `Cargo.toml, package.json`.
+    pub fn source_label(&self) -> String {
+        self.sources.join(", ")
+    }
+}
+
+/// Reads the external dependencies of the project at `project_dir`. `None`
+/// when it has none of the [`MANIFESTS`].
+pub fn read(project_dir: &Path) -> Option<External> {
+    let mut names = BTreeSet::new();
+    let mut sources = Vec::new();
+    for manifest in MANIFESTS {
+        let path = project_dir.join(manifest);
+        if !path.is_file() {
+            continue;
+        }
+        let found = if *manifest == "requirements.txt" {
+            std::fs::read_to_string(&path)
+                .map(|content| parse_requirements(&content))
+                .ok()
+        } else {
+            config::load_config_value(&path)
+                .ok()
+                .map(|value| manifest_dependencies(manifest, &value))
+        };
+        if let Some(found) = found {
+            names.extend(found);
+            sources.push(*manifest);
+        }
+    }
+    (!sources.is_empty()).then(|| External {
+        names: names.into_iter().collect(),
+        sources,
+    })
+}
+
+/// Replaces `dependencies.external` with the manifest dependencies when the
+/// project opts in with `sync = true`, recording the source in
+/// `project.inferred`.
+pub fn sync_on_load(project_dir: &Path, config: &mut ProjectConfig) {
+    if !config.dependencies.sync {
+        return;
+    }
+    if let Some(external) = read(project_dir) {
+        config
+            .project
+            .inferred
+            .insert("dependencies.external".to_string(), external.source_label());
+        config.dependencies.external = external.names;
+    }
+}
+
+fn manifest_dependencies(manifest: &str, value: &serde_json::Value) -> Vec<String> {
+    let keys = |table: Option<&serde_json::Value>| -> Vec<String> {
+        table
+            .and_then(|t| t.as_object())
+            .map(|t| t.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+    match manifest {
+        "Cargo.toml" => {
+            let mut names = keys(value.get("dependencies"));
+            if let Some(targets) = value.get("target").and_then(|t| t.as_object()) {
+                for target in targets.values() {
+                    names.extend(keys(target.get("dependencies")));
+                }
+            }
+            names
+        }
+        "package.json" => keys(value.get("dependencies")),
+        "pyproject.toml" => {
+            let pep621 = value
+                .pointer("/project/dependencies")
+                .and_then(|d| d.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|d| d.as_str().and_then(requirement_name));
+            let poetry = keys(value.pointer("/tool/poetry/dependencies"))
+                .into_iter()
+                .filter(|name| name != "python");
+            pep621.chain(poetry).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Package names from `requirements.txt`, skipping comments, options such as
+/// `-r other.txt`, and bare URLs.
+fn parse_requirements(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split(" #").next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+        .filter(|line| !line.contains("://") || line.contains(" @ "))
+        .filter_map(requirement_name)
+        .collect()
+}
+
+/// The name at the start of a PEP 508 requirement such as
+/// `requests[socks]>=2.31; python_version > "3.8"`.
+fn requirement_name(requirement: &str) -> Option<String> {
+    let name: String = requirement
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_manifests() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        assert_eq!(read(dir), None);
+
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            r#"[package]
+name = "app"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+anyhow = "1"
+
+[dev-dependencies]
+tempfile = "3"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("requirements.txt"),
+            "# pinned\n-r base.txt\nrequests[socks]>=2.31 ; python_version > \"3.8\"\nDjango==5.0 # web\nhttps://example.com/pkg.whl\nlocalpkg @ file:///src/localpkg\n",
+        )
+        .unwrap();
+
+        let external = read(dir).unwrap();
+        assert_eq!(
+            external.names,
+            ["Django", "anyhow", "libc", "localpkg", "requests", "serde"]
+        );
+        assert_eq!(external.source_label(), "Cargo.toml, requirements.txt");
+    }
+
+    #[test]
+    fn test_manifest_dependencies_npm_and_pyproject() {
+        let npm = serde_json::json!({
+            "dependencies": { "react": "^18", "zod": "^3" },
+            "devDependencies": { "vitest": "^1" }
+        });
+        assert_eq!(
+            manifest_dependencies("package.json", &npm),
+            ["react", "zod"]
+        );
+
+        let pyproject = serde_json::json!({
+            "project": { "dependencies": ["httpx>=0.27", "pydantic"] },
+            "tool": { "poetry": { "dependencies": { "python": "^3.11", "rich": "*" } } }
+        });
+        assert_eq!(
+            manifest_dependencies("pyproject.toml", &pyproject),
+            ["httpx", "pydantic", "rich"]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Diagnostics collected while loading `.jumble` context.
+//!
+//! Discovery is deliberately forgiving: broken files are skipped rather than
+//! aborting startup. Diagnostics record what was skipped or looks suspicious so
+//! authors can find out via `jumble validate` or the `get_diagnostics` tool.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// A single problem found in a `.jumble` file or skill.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The file the diagnostic refers to.
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(path: &Path, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, path, message)
+    }
+
+    pub fn warning(path: &Path, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, path, message)
+    }
+
+    pub fn info(path: &Path, message: impl Into<String>) -> Self {
+        Self::new(Severity::Info, path, message)
+    }
+
+    fn new(severity: Severity, path: &Path, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.severity,
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+/// Counts diagnostics with the given severity.
+pub fn count(diagnostics: &[Diagnostic], severity: Severity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|d| d.severity == severity)
+        .count()
+}
+
+/// Formats diagnostics as a markdown list grouped by severity order.
+pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    if diagnostics.is_empty() {
+        return "No problems found.".to_string();
+    }
+
+    let mut output = format!(
+        "# Diagnostics\n\n{} error(s), {} warning(s)\n\n",
+        count(diagnostics, Severity::Error),
+        count(diagnostics, Severity::Warning)
+    );
+    for diagnostic in diagnostics {
+        output.push_str(&format!(
+            "- **{}** {}: {}\n",
+            diagnostic.severity,
+            diagnostic.path.display(),
+            diagnostic.message
+        ));
+    }
+    output
+}
+
+/// A short report printed to stderr at startup: the counts, then the first
+/// line of each error. `None` when there is nothing to report.
+pub fn startup_summary(diagnostics: &[Diagnostic]) -> Option<String> {
+    let errors = count(diagnostics, Severity::Error);
+    let warnings = count(diagnostics, Severity::Warning);
+    if errors + warnings == 0 {
+        return None;
+    }
+
+    let mut output = format!(
+        "jumble: {} error(s), {} warning(s) while loading .jumble files \
+         (run `jumble validate` or call get_diagnostics for details)",
+        errors, warnings
+    );
+    for diagnostic in diagnostics.iter().filter(|d| d.severity == Severity::Error) {
+        output.push_str(&format!(
+            "\n  {}: {}",
+            diagnostic.path.display(),
+            diagnostic.message.lines().next().unwrap_or_default()
+        ));
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_sort_errors_first() {
+        let mut diagnostics = [
+            Diagnostic::warning(Path::new("b.md"), "empty description"),
+            Diagnostic::error(Path::new("a.md"), "invalid frontmatter"),
+        ];
+        diagnostics.sort();
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "error: a.md: invalid frontmatter"
+        );
+    }
+
+    #[test]
+    fn test_format_diagnostics() {
+        assert_eq!(format_diagnostics(&[]), "No problems found.");
+
+        let diagnostics = vec![Diagnostic::warning(Path::new("x.md"), "unknown key 'foo'")];
+        let output = format_diagnostics(&diagnostics);
+        assert!(output.contains("0 error(s), 1 warning(s)"));
+        assert!(output.contains("- **warning** x.md: unknown key 'foo'"));
+    }
+
+    #[test]
+    fn test_startup_summary() {
+        assert_eq!(startup_summary(&[]), None);
+        assert_eq!(
+            startup_summary(&[Diagnostic::info(Path::new("x.md"), "note")]),
+            None
+        );
+
+        let diagnostics = vec![
+            Diagnostic::error(
+                Path::new("docs.toml"),
+                "file ignored: TOML parse error at line 2\n  |\n2 | path=1",
+            ),
+            Diagnostic::warning(Path::new("x.md"), "empty description"),
+        ];
+        let summary = startup_summary(&diagnostics).unwrap();
+        assert!(summary.starts_with("jumble: 1 error(s), 1 warning(s)"));
+        assert!(summary.ends_with("\n  docs.toml: file ignored: TOML parse error at line 2"));
+        assert!(!summary.contains("x.md"));
+    }
+}
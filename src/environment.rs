@@ -0,0 +1,234 @@
+//! Checks a project's `[environment]` requirements against the machine
+//! Jumble runs on: installed tool versions, and whether each environment
+//! variable is set in the process or in the project's `.env` file.
+
+use regex::Regex;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::process;
+
+/// Tools whose binary or version flag differs from `<name> --version`.
+const VERSION_COMMANDS: &[(&str, &str, &str)] = &[
+    ("rust", "rustc", "--version"),
+    ("python", "python3", "--version"),
+    ("go", "go", "version"),
+    ("java", "java", "-version"),
+];
+
+/// Other tools Jumble will ask for `<name> --version`. Names outside this
+/// list and [`VERSION_COMMANDS`] are never run, since the config may come
+/// from a remote source.
+const KNOWN_TOOLS: &[&str] = &[
+    "bun",
+    "bundler",
+    "cargo",
+    "cmake",
+    "composer",
+    "deno",
+    "docker",
+    "dotnet",
+    "gem",
+    "git",
+    "gradle",
+    "helm",
+    "just",
+    "kubectl",
+    "make",
+    "mvn",
+    "node",
+    "npm",
+    "php",
+    "pip",
+    "pip3",
+    "pnpm",
+    "poetry",
+    "protoc",
+    "python3",
+    "ruby",
+    "rustc",
+    "swift",
+    "terraform",
+    "uv",
+    "yarn",
+    "zig",
+];
+
+/// How long a version command may run before it is killed.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+static VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d+(?:\.\d+)+|\d+").expect("valid regex"));
+
+/// What was found for one required tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolCheck {
+    /// Installed at a version that meets the requirement.
+    Ok(String),
+    /// Installed at a version that does not.
+    Mismatch(String),
+    /// Installed, but the version could not be read.
+    Unknown,
+    NotFound,
+    /// Not a tool Jumble knows how to check, so nothing was run.
+    Unsupported,
+}
+
+/// Looks up `tool` on the `PATH` and compares its version to `requirement`.
+/// Only [`VERSION_COMMANDS`] and [`KNOWN_TOOLS`] are run, each for at most
+/// [`VERSION_TIMEOUT`].
+pub fn check_tool(tool: &str, requirement: &str) -> ToolCheck {
+    if tool.contains(['/', '\\']) {
+        return ToolCheck::Unsupported;
+    }
+    let (binary, flag) = match VERSION_COMMANDS.iter().find(|(name, ..)| *name == tool) {
+        Some((_, binary, flag)) => (*binary, *flag),
+        None if KNOWN_TOOLS.contains(&tool) => (tool, "--version"),
+        None => return ToolCheck::Unsupported,
+    };
+    let Ok(path) = which::which(binary) else {
+        return ToolCheck::NotFound;
+    };
+    let Some(text) = version_output(&path, flag) else {
+        return ToolCheck::Unknown;
+    };
+    match VERSION.find(&text) {
+        Some(version) if satisfies(version.as_str(), requirement) => {
+            ToolCheck::Ok(version.as_str().to_string())
+        }
+        Some(version) => ToolCheck::Mismatch(version.as_str().to_string()),
+        None => ToolCheck::Unknown,
+    }
+}
+
+/// Runs `binary flag` and returns stdout followed by stderr (some tools, such
+/// as `java -version`, print to stderr), or `None` if it fails to start or
+/// outlives [`VERSION_TIMEOUT`].
+fn version_output(binary: &Path, flag: &str) -> Option<String> {
+    let mut child = Command::new(binary)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    let stdout = process::read_pipe(child.stdout.take());
+    let stderr = process::read_pipe(child.stderr.take());
+    process::wait_with_timeout(&mut child, VERSION_TIMEOUT).ok()??;
+    Some(format!(
+        "{}{}",
+        stdout.join().unwrap_or_default(),
+        stderr.join().unwrap_or_default()
+    ))
+}
+
+/// Whether `version` meets `requirement`: `>=1.78`, `>`, `<=`, `<`, or a
+/// version prefix such as `20` or `3.11` (optionally written `=20`, `v20`,
+/// `^20`, or `~3.11`).
+pub fn satisfies(version: &str, requirement: &str) -> bool {
+    let requirement = requirement.trim();
+    let (op, wanted) = [">=", "<=", ">", "<"]
+        .iter()
+        .find_map(|op| requirement.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", requirement.trim_start_matches(['=', 'v', '^', '~'])));
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map_while(|part| part.parse().ok())
+            .collect()
+    };
+    let (have, want) = (parse(version), parse(wanted));
+    if want.is_empty() {
+        return true;
+    }
+    let ordering = (0..have.len().max(want.len()))
+        .map(|i| {
+            let a = have.get(i).copied().unwrap_or(0);
+            let b = want.get(i).copied().unwrap_or(0);
+            a.cmp(&b)
+        })
+        .find(|o| o.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal);
+    match op {
+        ">=" => ordering.is_ge(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        "<" => ordering.is_lt(),
+        _ => have.len() >= want.len() && have[..want.len()] == want[..],
+    }
+}
+
+/// Where an environment variable is defined, if anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableSource {
+    Process,
+    DotEnv,
+}
+
+/// Checks the process environment, then `.env` in `project_dir`. Values
+/// are never read back, only whether they are present.
+pub fn find_variable(project_dir: &Path, name: &str) -> Option<VariableSource> {
+    if std::env::var_os(name).is_some() {
+        return Some(VariableSource::Process);
+    }
+    let dotenv = std::fs::read_to_string(project_dir.join(".env")).ok()?;
+    dotenv
+        .lines()
+        .map(|line| line.trim().trim_start_matches("export "))
+        .filter_map(|line| line.split_once('='))
+        .any(|(key, _)| key.trim() == name)
+        .then_some(VariableSource::DotEnv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_satisfies() {
+        assert!(satisfies("20.11.1", "20"));
+        assert!(satisfies("v20.11.1", "v20"));
+        assert!(!satisfies("18.19.0", "20"));
+        assert!(satisfies("3.11.4", "~3.11"));
+        assert!(!satisfies("3.1.4", "3.11"));
+        assert!(satisfies("1.80.0", ">=1.78"));
+        assert!(!satisfies("1.70.0", ">=1.78"));
+        assert!(satisfies("1.78", ">= 1.78.0"));
+        assert!(satisfies("2.0", "<3"));
+        assert!(!satisfies("3.0.1", "<=3.0"));
+        assert!(satisfies("5.2", "latest"));
+    }
+
+    #[test]
+    fn test_check_tool_unsupported() {
+        for tool in ["jumble-missing-tool", "./node", "bin/node", "..\\node.exe"] {
+            assert_eq!(check_tool(tool, "1.0"), ToolCheck::Unsupported, "{}", tool);
+        }
+    }
+
+    #[test]
+    fn test_find_variable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join(".env"),
+            "# local\nexport API_TOKEN=abc\nDATABASE_URL = postgres://localhost/dev\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_variable(temp.path(), "PATH"),
+            Some(VariableSource::Process)
+        );
+        assert_eq!(
+            find_variable(temp.path(), "DATABASE_URL"),
+            Some(VariableSource::DotEnv)
+        );
+        assert_eq!(
+            find_variable(temp.path(), "API_TOKEN"),
+            Some(VariableSource::DotEnv)
+        );
+        assert_eq!(find_variable(temp.path(), "JUMBLE_TEST_UNSET_VAR"), None);
+    }
+}
@@ -0,0 +1,273 @@
+//! `jumble export`: renders all workspace and project context into one
+//! bundle, for agents that can't speak MCP and for context snapshots in PRs.
+//!
+//! Output is sorted by name throughout so that exports of an unchanged
+//! workspace are identical.
+
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::{Conventions, ProjectSkills};
+use crate::format::{format_commands, format_concept, format_entry_points};
+use crate::fuzzy;
+use crate::server::Server;
+use crate::tools::{capitalize, resolve_commands, ProjectData};
+
+/// Output formats for `jumble export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+/// Renders the bundle in `format`, limited to `project` if given.
+pub fn export(
+    server: &Server,
+    format: ExportFormat,
+    project: Option<&str>,
+) -> Result<String, String> {
+    let projects = selected_projects(server, project)?;
+    match format {
+        ExportFormat::Markdown => Ok(export_markdown(server, &projects)),
+        ExportFormat::Json => serde_json::to_string_pretty(&export_json(server, &projects))
+            .map_err(|e| format!("Failed to serialize export: {}", e)),
+    }
+}
+
+fn selected_projects<'a>(
+    server: &'a Server,
+    project: Option<&str>,
+) -> Result<BTreeMap<&'a str, &'a ProjectData>, String> {
+    match project {
+        Some(name) => {
+            let (name, data) = server.projects.get_key_value(name).ok_or_else(|| {
+                fuzzy::not_found("Project", name, server.projects.keys().map(|k| k.as_str()))
+            })?;
+            Ok(BTreeMap::from([(name.as_str(), data)]))
+        }
+        None => Ok(server
+            .projects
+            .iter()
+            .map(|(name, data)| (name.as_str(), data))
+            .collect()),
+    }
+}
+
+fn export_markdown(server: &Server, projects: &BTreeMap<&str, &ProjectData>) -> String {
+    let ws = server.workspace.as_ref();
+    let title = ws
+        .and_then(|ws| ws.workspace.name.as_deref())
+        .unwrap_or("Workspace");
+    let mut output = format!("# {} context\n\n", title);
+    if let Some(desc) = ws.and_then(|ws| ws.workspace.description.as_deref()) {
+        output.push_str(&format!("{}\n\n", desc));
+    }
+    output.push_str(&format!("**Root:** {}\n\n", server.root.display()));
+
+    output.push_str("## Projects\n\n");
+    for (name, (_, config, _, _, _, _)) in projects {
+        output.push_str(&format!("- **{}**: {}\n", name, config.project.description));
+    }
+
+    if let Some(ws) = ws {
+        push_entries(&mut output, "## Workspace Conventions", &ws.conventions);
+        push_entries(&mut output, "## Workspace Gotchas", &ws.gotchas);
+    }
+
+    for (name, data) in projects {
+        output.push_str("\n---\n\n");
+        output.push_str(&project_markdown(name, data));
+    }
+    output
+}
+
+fn project_markdown(name: &str, data: &ProjectData) -> String {
+    let (path, config, skills, conventions, docs, _) = data;
+    let mut output = format!("# {}\n\n{}\n\n", name, config.project.description);
+    if let Some(lang) = &config.project.language {
+        output.push_str(&format!("**Language:** {}\n", lang));
+    }
+    output.push_str(&format!("**Path:** {}\n", path.display()));
+
+    let commands = resolve_commands(config);
+    if !commands.is_empty() {
+        output.push_str("\n## Commands\n\n");
+        output.push_str(&format_commands(&commands));
+    }
+    if !config.entry_points.is_empty() {
+        output.push_str("\n## Entry Points\n\n");
+        output.push_str(&format_entry_points(&config.entry_points));
+    }
+
+    if !config.concepts.is_empty() {
+        output.push_str("\n## Concepts\n");
+        for (concept_name, concept) in sorted(&config.concepts) {
+            // Nest each concept's `##` heading under "Concepts".
+            output.push_str(&format!(
+                "\n#{}",
+                format_concept(path, concept_name, concept, &config.concepts)
+            ));
+        }
+    }
+
+    for (category, entries) in conventions.categories() {
+        push_entries(
+            &mut output,
+            &format!("## {}", capitalize(category)),
+            entries,
+        );
+    }
+
+    if !docs.docs.is_empty() {
+        output.push_str("\n## Docs\n\n");
+        for (key, doc) in sorted(&docs.docs) {
+            output.push_str(&format!("- **{}**: {} ({})\n", key, doc.summary, doc.path));
+        }
+    }
+
+    if !skills.skills.is_empty() {
+        output.push_str("\n## Skills\n\n");
+        for (skill, description) in skill_summaries(skills) {
+            match description {
+                Some(desc) => output.push_str(&format!("- **{}**: {}\n", skill, desc)),
+                None => output.push_str(&format!("- **{}**\n", skill)),
+            }
+        }
+    }
+    output
+}
+
+fn push_entries(output: &mut String, heading: &str, entries: &Conventions) {
+    if entries.is_empty() {
+        return;
+    }
+    output.push_str(&format!("\n{}\n\n", heading));
+    for (key, convention) in sorted(entries) {
+        output.push_str(&format!("- **{}**: {}\n", key, convention.with_badge()));
+    }
+}
+
+fn export_json(server: &Server, projects: &BTreeMap<&str, &ProjectData>) -> Value {
+    let projects: serde_json::Map<String, Value> = projects
+        .iter()
+        .map(|(name, (path, config, skills, conventions, docs, _))| {
+            let skills: Vec<Value> = skill_summaries(skills)
+                .into_iter()
+                .map(|(name, description)| json!({ "name": name, "description": description }))
+                .collect();
+            let project = json!({
+                "path": path,
+                "config": config,
+                "conventions": conventions,
+                "docs": docs.docs,
+                "skills": skills,
+            });
+            (name.to_string(), project)
+        })
+        .collect();
+
+    json!({
+        "root": server.root,
+        "workspace": server.workspace,
+        "projects": projects,
+    })
+}
+
+/// Skill names with their frontmatter description (or first preview line).
+fn skill_summaries(skills: &ProjectSkills) -> Vec<(&str, Option<&str>)> {
+    sorted(&skills.skills)
+        .into_iter()
+        .map(|(name, info)| {
+            let description = info
+                .frontmatter
+                .as_ref()
+                .and_then(|fm| fm.description.as_deref())
+                .filter(|d| !d.is_empty())
+                .or_else(|| info.preview.lines().next().map(str::trim))
+                .filter(|d| !d.is_empty());
+            (name.as_str(), description)
+        })
+        .collect()
+}
+
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<(&String, &V)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_server() -> (TempDir, Server) {
+        let temp = TempDir::new().unwrap();
+        let jumble_dir = temp.path().join("api/.jumble");
+        std::fs::create_dir_all(jumble_dir.join("skills")).unwrap();
+        std::fs::write(
+            jumble_dir.join("project.toml"),
+            r#"
+[project]
+name = "api"
+description = "HTTP API"
+
+[commands]
+test = "cargo test"
+build = "cargo build"
+
+[concepts.auth]
+summary = "Token checks"
+files = ["src/auth.rs"]
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            jumble_dir.join("conventions.toml"),
+            "[gotchas]\nasync = \"Never block the runtime\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            jumble_dir.join("skills/release.md"),
+            "---\ndescription: Cut a release\n---\nSteps",
+        )
+        .unwrap();
+        let server = Server::new(temp.path().to_path_buf()).unwrap();
+        (temp, server)
+    }
+
+    #[test]
+    fn test_export_markdown() {
+        let (_temp, server) = test_server();
+        let output = export(&server, ExportFormat::Markdown, None).unwrap();
+        assert!(output.starts_with("# Workspace context\n"));
+        assert!(output.contains("- **api**: HTTP API"));
+        let build = output.find("- **build**: `cargo build`").unwrap();
+        assert!(build < output.find("- **test**: `cargo test`").unwrap());
+        assert!(output.contains("### auth\n\nToken checks"));
+        assert!(output.contains("## Gotchas\n\n- **async**: Never block the runtime"));
+        assert!(output.contains("- **release**: Cut a release"));
+        assert_eq!(
+            output,
+            export(&server, ExportFormat::Markdown, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_export_json() {
+        let (_temp, server) = test_server();
+        let output = export(&server, ExportFormat::Json, Some("api")).unwrap();
+        let value: Value = serde_json::from_str(&output).unwrap();
+        let api = &value["projects"]["api"];
+        assert_eq!(api["config"]["commands"]["test"], "cargo test");
+        assert_eq!(
+            api["conventions"]["gotchas"]["async"],
+            "Never block the runtime"
+        );
+        assert_eq!(api["skills"][0]["description"], "Cut a release");
+
+        let err = export(&server, ExportFormat::Json, Some("apii")).unwrap_err();
+        assert!(err.contains("did you mean 'api'?"));
+    }
+}
@@ -1,27 +1,113 @@
 //! Formatting helpers for output strings.
 
-use crate::config::{ApiInfo, Concept, Dependencies, RelatedProjects};
-use std::collections::HashMap;
+use crate::ci::Workflow;
+use crate::config::{
+    ApiInfo, CommandDetails, Concept, Dependencies, EntryPoint, FileRef, RelatedProjects, Service,
+};
+use crate::services::ComposeService;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 
-pub fn format_commands(commands: &HashMap<String, String>) -> String {
+pub fn format_commands(commands: &HashMap<String, CommandDetails>) -> String {
     if commands.is_empty() {
         return "No commands defined.".to_string();
     }
+    let mut names: Vec<&String> = commands.keys().collect();
+    names.sort();
     let mut output = String::new();
-    for (name, cmd) in commands {
-        output.push_str(&format!("- **{}**: `{}`\n", name, cmd));
+    for name in names {
+        let command = &commands[name];
+        let line = match sequence_steps(command) {
+            Some(steps) => steps,
+            None => format!("`{}`", command.cmd),
+        };
+        output.push_str(&format!("- **{}**: {}", name, line));
+        if let Some(description) = &command.description {
+            output.push_str(&format!(" - {}", description));
+        }
+        output.push('\n');
+        for note in dependency_note(command)
+            .into_iter()
+            .chain(command_notes(command))
+        {
+            output.push_str(&format!("  - {}\n", note));
+        }
     }
     output
 }
 
-pub fn format_entry_points(entry_points: &HashMap<String, String>) -> String {
+/// A single command as `name: cmd`, followed by its description and notes.
+pub fn format_command(name: &str, command: &CommandDetails) -> String {
+    let line = sequence_steps(command).unwrap_or_else(|| command.cmd.clone());
+    let mut output = format!("{}: {}", name, line);
+    if let Some(description) = &command.description {
+        output.push_str(&format!("\n{}", description));
+    }
+    for note in dependency_note(command)
+        .into_iter()
+        .chain(command_notes(command))
+    {
+        output.push_str(&format!("\n{}", note));
+    }
+    output
+}
+
+/// "runs `a`, then `b`" for a sequence, which has no command line of its own.
+fn sequence_steps(command: &CommandDetails) -> Option<String> {
+    if !command.cmd.is_empty() {
+        return None;
+    }
+    let steps: Vec<String> = command
+        .depends_on
+        .iter()
+        .map(|step| format!("`{}`", step))
+        .collect();
+    Some(format!("runs {}", steps.join(", then ")))
+}
+
+fn dependency_note(command: &CommandDetails) -> Option<String> {
+    if command.cmd.is_empty() || command.depends_on.is_empty() {
+        return None;
+    }
+    let names: Vec<String> = command
+        .depends_on
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect();
+    Some(format!("depends on: {}", names.join(", ")))
+}
+
+/// One line each for a command's working directory and environment, when set.
+pub fn command_notes(command: &CommandDetails) -> Vec<String> {
+    let mut notes = Vec::new();
+    if let Some(cwd) = &command.cwd {
+        notes.push(format!("cwd: `{}`", cwd));
+    }
+    if !command.env.is_empty() {
+        let vars: Vec<String> = command
+            .env
+            .iter()
+            .map(|(name, value)| format!("`{}={}`", name, value))
+            .collect();
+        notes.push(format!("env: {}", vars.join(", ")));
+    }
+    notes
+}
+
+pub fn format_entry_points(entry_points: &HashMap<String, EntryPoint>) -> String {
     if entry_points.is_empty() {
         return "No entry points defined.".to_string();
     }
+    let mut names: Vec<&String> = entry_points.keys().collect();
+    names.sort();
     let mut output = String::new();
-    for (name, path) in entry_points {
-        output.push_str(&format!("- **{}**: {}\n", name, path));
+    for name in names {
+        let entry = &entry_points[name];
+        output.push_str(&format!("- **{}**: {}", name, entry.path()));
+        if let Some(description) = entry.description() {
+            output.push_str(&format!(" - {}", description));
+        }
+        output.push('\n');
     }
     output
 }
@@ -47,6 +133,94 @@ pub fn format_dependencies(deps: &Dependencies) -> String {
     }
 }
 
+/// A service as a `## name (kind)` section. Image and ports fall back to
+/// `compose`, the matching docker-compose service and the file defining it.
+pub fn format_service(
+    name: &str,
+    service: &Service,
+    compose: Option<(&str, &ComposeService)>,
+) -> String {
+    let mut output = format!("## {}", name);
+    if let Some(kind) = &service.kind {
+        output.push_str(&format!(" ({})", kind));
+    }
+    output.push_str("\n\n");
+    if let Some(description) = &service.description {
+        output.push_str(&format!("{}\n\n", description));
+    }
+    let image = service
+        .image
+        .as_ref()
+        .or(compose.and_then(|(_, c)| c.image.as_ref()));
+    if let Some(image) = image {
+        output.push_str(&format!("- **Image:** {}\n", image));
+    }
+    let ports = match (&service.ports, compose) {
+        (ports, Some((_, c))) if ports.is_empty() => &c.ports,
+        (ports, _) => ports,
+    };
+    if !ports.is_empty() {
+        let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+        output.push_str(&format!("- **Ports:** {}\n", ports.join(", ")));
+    }
+    if let Some(url) = &service.url {
+        output.push_str(&format!("- **URL:** {}\n", url));
+    }
+    if let Some(env) = &service.env {
+        output.push_str(&format!("- **Env:** `{}`\n", env));
+    }
+    if let Some((file, compose)) = compose {
+        if !compose.depends_on.is_empty() {
+            output.push_str(&format!(
+                "- **Depends on:** {}\n",
+                compose.depends_on.join(", ")
+            ));
+        }
+        output.push_str(&format!("- **Defined in:** {}\n", file));
+    }
+    output.push('\n');
+    output
+}
+
+/// A parsed workflow's name, triggers, and jobs with their `run` steps.
+pub fn format_workflow(workflow: &Workflow) -> String {
+    let mut output = String::new();
+    if let Some(name) = &workflow.name {
+        output.push_str(&format!("**Name:** {}\n", name));
+    }
+    if !workflow.triggers.is_empty() {
+        output.push_str(&format!("**Triggers:** {}\n", workflow.triggers.join(", ")));
+    }
+    if workflow.jobs.is_empty() {
+        return output;
+    }
+    output.push_str("**Jobs:**\n");
+    for job in &workflow.jobs {
+        output.push_str(&format!("- **{}**", job.id));
+        if let Some(name) = &job.name {
+            output.push_str(&format!(" ({})", name));
+        }
+        let mut details = Vec::new();
+        if let Some(runs_on) = &job.runs_on {
+            details.push(format!("on {}", runs_on));
+        }
+        if !job.needs.is_empty() {
+            details.push(format!("needs: {}", job.needs.join(", ")));
+        }
+        if !job.matrix.is_empty() {
+            details.push(format!("matrix: {}", job.matrix.join(", ")));
+        }
+        if !details.is_empty() {
+            output.push_str(&format!(" - {}", details.join("; ")));
+        }
+        output.push('\n');
+        for step in &job.steps {
+            output.push_str(&format!("  - {}\n", step));
+        }
+    }
+    output
+}
+
 pub fn format_related_projects(related: &RelatedProjects) -> String {
     let mut output = String::new();
     if !related.upstream.is_empty() {
@@ -94,14 +268,244 @@ pub fn format_api(api: &Option<ApiInfo>) -> String {
     }
 }
 
-pub fn format_concept(project_path: &Path, name: &str, concept: &Concept) -> String {
-    let mut output = format!("## {}\n\n{}\n\n**Files:**\n", name, concept.summary);
+/// Renders a concept; `concepts` (all of the project's concepts) supplies the
+/// summaries of related concepts.
+pub fn format_concept(
+    project_path: &Path,
+    name: &str,
+    concept: &Concept,
+    concepts: &HashMap<String, Concept>,
+) -> String {
+    let mut output = format!("## {}\n\n{}\n\n", name, concept.summary);
+    if let Some(owner) = &concept.owner {
+        output.push_str(&format!("**Owner:** {}\n", owner));
+    }
+    if let Some(contact) = &concept.contact {
+        output.push_str(&format!("**Contact:** {}\n", contact));
+    }
+    if concept.owner.is_some() || concept.contact.is_some() {
+        output.push('\n');
+    }
+    output.push_str("**Files:**\n");
     for file in &concept.files {
-        output.push_str(&format!("- {}/{}\n", project_path.display(), file));
+        output.push_str(&format_concept_file(project_path, file));
+    }
+    if !concept.related.is_empty() {
+        output.push_str("\n**Related concepts:**\n");
+        for related in &concept.related {
+            match concepts.get(related) {
+                Some(other) => output.push_str(&format!("- {}: {}\n", related, other.summary)),
+                None => output.push_str(&format!("- {} (not defined)\n", related)),
+            }
+        }
+    }
+    output
+}
+
+/// Renders one concept `files` entry as a list item, spelling out any line range.
+pub fn format_concept_file(project_path: &Path, entry: &str) -> String {
+    let file = FileRef::parse(entry);
+    let full_path = display_path(project_path, file.path);
+    match file.lines {
+        Some((start, end)) if start == end => format!("- {} (line {})\n", full_path, start),
+        Some((start, end)) => format!("- {} (lines {}-{})\n", full_path, start, end),
+        None => format!("- {}\n", full_path),
+    }
+}
+
+/// `path` under `base`, or `path` alone when `base` is empty (project-relative
+/// output).
+pub fn display_path(base: &Path, path: &str) -> String {
+    if base.as_os_str().is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", base.display(), path)
+    }
+}
+
+/// Renders a directed graph as Mermaid flowchart source.
+///
+/// Nodes get positional ids (`n0`, `n1`, ...) with the name as label, since
+/// project names may contain characters Mermaid does not accept in ids.
+pub fn format_mermaid_graph(nodes: &[&str], edges: &BTreeSet<(String, String)>) -> String {
+    let ids: HashMap<&str, String> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (*name, format!("n{}", i)))
+        .collect();
+
+    let mut output = String::from("graph LR\n");
+    for name in nodes {
+        output.push_str(&format!(
+            "    {}[\"{}\"]\n",
+            ids[name],
+            name.replace('"', "#quot;")
+        ));
+    }
+    for (from, to) in edges {
+        output.push_str(&format!(
+            "    {} --> {}\n",
+            ids[from.as_str()],
+            ids[to.as_str()]
+        ));
+    }
+    output
+}
+
+/// Renders a directed graph as Graphviz DOT source.
+pub fn format_dot_graph(nodes: &[&str], edges: &BTreeSet<(String, String)>) -> String {
+    let quote = |name: &str| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut output = String::from("digraph workspace {\n    rankdir=LR;\n");
+    for name in nodes {
+        output.push_str(&format!("    {};\n", quote(name)));
+    }
+    for (from, to) in edges {
+        output.push_str(&format!("    {} -> {};\n", quote(from), quote(to)));
+    }
+    output.push_str("}\n");
+    output
+}
+
+/// Substitutes `{{name}}` placeholders in `template` with values from `vars`.
+///
+/// Whitespace inside the braces is ignored (`{{ name }}` works too).
+/// Placeholders without a matching variable are left untouched so authors can
+/// see which values were not supplied.
+pub fn render_template(template: &str, vars: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        match after_open.find("}}") {
+            Some(end) => {
+                let name = after_open[..end].trim();
+                match vars.get(name) {
+                    Some(value) => output.push_str(value),
+                    None => output.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after_open[end + 2..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
     }
+    output.push_str(rest);
     output
 }
 
+/// Replaces `${VAR}` placeholders in `value` using `lookup`.
+///
+/// Placeholders that `lookup` cannot resolve are left untouched, matching
+/// [`render_template`], so a missing variable is visible in the output.
+pub fn interpolate_env<F>(value: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        match after_open.find('}') {
+            Some(end) => {
+                let name = &after_open[..end];
+                match lookup(name) {
+                    Some(resolved) => output.push_str(&resolved),
+                    None => output.push_str(&rest[start..start + 2 + end + 1]),
+                }
+                rest = &after_open[end + 1..];
+            }
+            None => {
+                output.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Returns the text of an ATX markdown heading (`# Title`, `## Title`, ...).
+///
+/// `#tag` is not a heading; the hashes must be followed by whitespace.
+pub fn markdown_heading(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix('#')?.trim_start_matches('#');
+    let title = rest.trim();
+    (rest.starts_with(char::is_whitespace) && !title.is_empty()).then_some(title)
+}
+
+/// Lists the headings in a markdown document as `(line index, level, title)`,
+/// skipping anything inside fenced code blocks.
+pub fn markdown_headings(content: &str) -> Vec<(usize, usize, &str)> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some(title) = markdown_heading(line) {
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            headings.push((i, level, title));
+        }
+    }
+    headings
+}
+
+/// Returns the section under the heading titled `title` (case-insensitive),
+/// from the heading line up to the next heading of the same or a higher level.
+pub fn markdown_section(content: &str, title: &str) -> Option<String> {
+    let headings = markdown_headings(content);
+    let position = headings
+        .iter()
+        .position(|(_, _, heading)| heading.eq_ignore_ascii_case(title))?;
+    let (start, level, _) = headings[position];
+    let end = headings[position + 1..]
+        .iter()
+        .find(|(_, other, _)| *other <= level)
+        .map(|(line, _, _)| *line);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let section = &lines[start..end.unwrap_or(lines.len())];
+    Some(section.join("\n").trim_end().to_string())
+}
+
+/// Truncates `text` to at most `max_chars` characters, cutting at the last
+/// line break that fits and appending a marker so the caller knows to narrow
+/// the request.
+pub fn truncate_output(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+
+    let cut = text
+        .char_indices()
+        .nth(max_chars)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let kept = match text[..cut].rfind('\n') {
+        Some(i) if i > 0 => &text[..i],
+        _ => &text[..cut],
+    };
+    format!(
+        "{}\n\n[truncated: showing {} of {} characters, refine your query]",
+        kept.trim_end(),
+        kept.chars().count(),
+        total
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,13 +519,49 @@ mod tests {
     #[test]
     fn test_format_commands() {
         let mut commands = HashMap::new();
-        commands.insert("build".to_string(), "cargo build".to_string());
+        commands.insert(
+            "build".to_string(),
+            CommandDetails {
+                cmd: "cargo build".to_string(),
+                ..Default::default()
+            },
+        );
 
         let result = format_commands(&commands);
         assert!(result.contains("**build**"));
         assert!(result.contains("`cargo build`"));
     }
 
+    #[test]
+    fn test_format_commands_with_details() {
+        let mut commands = HashMap::new();
+        commands.insert(
+            "test".to_string(),
+            CommandDetails {
+                cmd: "cargo test".to_string(),
+                description: Some("Unit and integration tests".to_string()),
+                cwd: Some("crates/api".to_string()),
+                env: [("RUST_LOG".to_string(), "debug".to_string())].into(),
+                depends_on: Vec::new(),
+            },
+        );
+        commands.insert(
+            "build".to_string(),
+            CommandDetails {
+                cmd: "cargo build".to_string(),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            format_commands(&commands),
+            "- **build**: `cargo build`\n\
+             - **test**: `cargo test` - Unit and integration tests\n  \
+             - cwd: `crates/api`\n  \
+             - env: `RUST_LOG=debug`\n"
+        );
+    }
+
     #[test]
     fn test_format_entry_points_empty() {
         let entry_points = HashMap::new();
@@ -134,11 +574,19 @@ mod tests {
     #[test]
     fn test_format_entry_points() {
         let mut entry_points = HashMap::new();
-        entry_points.insert("main".to_string(), "src/main.rs".to_string());
+        entry_points.insert("main".to_string(), "src/main.rs".into());
+        entry_points.insert(
+            "api".to_string(),
+            EntryPoint::Detailed {
+                path: "src/api/mod.rs".to_string(),
+                description: Some("HTTP routes and handlers".to_string()),
+            },
+        );
 
-        let result = format_entry_points(&entry_points);
-        assert!(result.contains("**main**"));
-        assert!(result.contains("src/main.rs"));
+        assert_eq!(
+            format_entry_points(&entry_points),
+            "- **api**: src/api/mod.rs - HTTP routes and handlers\n- **main**: src/main.rs\n"
+        );
     }
 
     #[test]
@@ -152,6 +600,7 @@ mod tests {
         let deps = Dependencies {
             internal: vec!["shared-lib".to_string()],
             external: vec![],
+            sync: false,
         };
 
         let result = format_dependencies(&deps);
@@ -165,6 +614,7 @@ mod tests {
         let deps = Dependencies {
             internal: vec!["core".to_string()],
             external: vec!["serde".to_string(), "tokio".to_string()],
+            sync: false,
         };
 
         let result = format_dependencies(&deps);
@@ -233,13 +683,162 @@ mod tests {
         let concept = Concept {
             files: vec!["src/auth.rs".to_string(), "src/jwt.rs".to_string()],
             summary: "Authentication module".to_string(),
+            related: Vec::new(),
+            owner: None,
+            contact: None,
         };
         let path = Path::new("/project");
 
-        let result = format_concept(path, "authentication", &concept);
+        let result = format_concept(path, "authentication", &concept, &HashMap::new());
         assert!(result.contains("## authentication"));
         assert!(result.contains("Authentication module"));
         assert!(result.contains("/project/src/auth.rs"));
         assert!(result.contains("/project/src/jwt.rs"));
+        assert!(!result.contains("Related concepts"));
+    }
+
+    #[test]
+    fn test_format_concept_owner() {
+        let concept = Concept {
+            files: vec!["src/billing.rs".to_string()],
+            summary: "Invoices and payments".to_string(),
+            related: Vec::new(),
+            owner: Some("payments-team".to_string()),
+            contact: Some("#payments-help".to_string()),
+        };
+        let result = format_concept(Path::new(""), "billing", &concept, &HashMap::new());
+        assert!(result.starts_with(
+            "## billing\n\nInvoices and payments\n\n\
+             **Owner:** payments-team\n**Contact:** #payments-help\n\n**Files:**\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_concept_file() {
+        let path = Path::new("/project");
+        assert_eq!(
+            format_concept_file(path, "src/server.rs:120-210"),
+            "- /project/src/server.rs (lines 120-210)\n"
+        );
+        assert_eq!(
+            format_concept_file(path, "src/main.rs:7"),
+            "- /project/src/main.rs (line 7)\n"
+        );
+        assert_eq!(format_concept_file(path, "src/db/"), "- /project/src/db/\n");
+        assert_eq!(
+            format_concept_file(Path::new(""), "src/main.rs:7"),
+            "- src/main.rs (line 7)\n"
+        );
+    }
+
+    #[test]
+    fn test_format_concept_related() {
+        let mut concepts = HashMap::new();
+        concepts.insert(
+            "sessions".to_string(),
+            Concept {
+                files: vec!["src/session.rs".to_string()],
+                summary: "Session storage".to_string(),
+                related: Vec::new(),
+                owner: None,
+                contact: None,
+            },
+        );
+        let concept = Concept {
+            files: vec!["src/auth.rs".to_string()],
+            summary: "Authentication module".to_string(),
+            related: vec!["sessions".to_string(), "routing".to_string()],
+            owner: None,
+            contact: None,
+        };
+
+        let result = format_concept(Path::new("/project"), "auth", &concept, &concepts);
+        assert!(result.contains("**Related concepts:**\n- sessions: Session storage\n"));
+        assert!(result.contains("- routing (not defined)\n"));
+    }
+
+    #[test]
+    fn test_markdown_heading() {
+        assert_eq!(markdown_heading("# Title"), Some("Title"));
+        assert_eq!(markdown_heading("  ### Deep dive  "), Some("Deep dive"));
+        assert_eq!(markdown_heading("#tag"), None);
+        assert_eq!(markdown_heading("#"), None);
+        assert_eq!(markdown_heading("plain text"), None);
+    }
+
+    #[test]
+    fn test_markdown_section() {
+        let doc = "# Design\n\nIntro\n\n## Storage\n\nUses SQLite.\n\n```sh\n# not a heading\n```\n\n### Migrations\n\nRun on start.\n\n## Caching\n\nNone yet.\n";
+
+        let storage = markdown_section(doc, "storage").unwrap();
+        assert!(storage.starts_with("## Storage"));
+        assert!(storage.contains("# not a heading"));
+        assert!(storage.ends_with("Run on start."));
+        assert!(!storage.contains("Caching"));
+
+        assert_eq!(
+            markdown_section(doc, "Caching").unwrap(),
+            "## Caching\n\nNone yet."
+        );
+        assert!(markdown_section(doc, "not a heading").is_none());
+
+        let titles: Vec<&str> = markdown_headings(doc).iter().map(|h| h.2).collect();
+        assert_eq!(titles, ["Design", "Storage", "Migrations", "Caching"]);
+    }
+
+    #[test]
+    fn test_format_graphs() {
+        let nodes = ["api", "core \"lib\""];
+        let mut edges = BTreeSet::new();
+        edges.insert(("api".to_string(), "core \"lib\"".to_string()));
+
+        let mermaid = format_mermaid_graph(&nodes, &edges);
+        assert_eq!(
+            mermaid,
+            "graph LR\n    n0[\"api\"]\n    n1[\"core #quot;lib#quot;\"]\n    n0 --> n1\n"
+        );
+
+        let dot = format_dot_graph(&nodes, &edges);
+        assert!(dot.contains("    \"api\" -> \"core \\\"lib\\\"\";\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_render_template() {
+        let mut vars = HashMap::new();
+        vars.insert("project".to_string(), "my-app".to_string());
+        vars.insert("service".to_string(), "billing".to_string());
+
+        let result = render_template("Deploy {{project}} ({{ service }}) via {{unknown}}", &vars);
+        assert_eq!(result, "Deploy my-app (billing) via {{unknown}}");
+    }
+
+    #[test]
+    fn test_render_template_unclosed_placeholder() {
+        let vars = HashMap::new();
+        assert_eq!(render_template("Keep {{open", &vars), "Keep {{open");
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        let lookup = |name: &str| match name {
+            "HOME_DIR" => Some("/home/dev".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            interpolate_env("ls ${HOME_DIR}/src ${MISSING} ${open", lookup),
+            "ls /home/dev/src ${MISSING} ${open"
+        );
+    }
+
+    #[test]
+    fn test_truncate_output() {
+        let text = "first line\nsecond line\nthird line";
+        assert_eq!(truncate_output(text, 100), text);
+        assert_eq!(
+            truncate_output(text, 15),
+            "first line\n\n[truncated: showing 10 of 33 characters, refine your query]"
+        );
+        assert!(truncate_output("ééééé", 3).starts_with("ééé\n\n[truncated"));
     }
 }
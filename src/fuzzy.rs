@@ -0,0 +1,205 @@
+//! Fuzzy name matching: "did you mean" suggestions, resolving loosely typed
+//! names for concept, skill, doc, and project lookups, and completing
+//! partially typed ones.
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::HashMap;
+
+/// Minimum similarity for a candidate to be suggested.
+const SIMILARITY_THRESHOLD: f64 = 0.7;
+
+/// Shortest normalized query that [`resolve`] will match as a subsequence;
+/// anything shorter matches too much to be meaningful.
+const MIN_SUBSEQUENCE_LEN: usize = 3;
+
+/// Minimum skim score per query character. Letters scattered across a long
+/// name ("data" in `deployment_automation_tests`) score below this.
+const MIN_SCORE_PER_CHAR: i64 = 17;
+
+/// Returns the candidate most similar to `query`, if any is close enough.
+///
+/// Matching ignores case. Ties go to the alphabetically first candidate so the
+/// suggestion does not depend on `HashMap` iteration order.
+pub fn closest_match<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let query = query.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let score = strsim::normalized_damerau_levenshtein(&query, &candidate.to_lowercase());
+            (candidate, score)
+        })
+        .filter(|(_, score)| *score >= SIMILARITY_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(candidate, _)| candidate)
+}
+
+/// Resolves a loosely typed name to a single candidate.
+///
+/// Tries, in order: a match ignoring case and `-`, `_`, `.`, and spaces
+/// ("Auth Flow" finds `auth-flow`); a skim-style subsequence match
+/// ("auth-flow" finds `authentication-flow`); then the closest typo
+/// ("authentcation" finds `authentication`). Ambiguous results resolve to
+/// nothing rather than to an arbitrary candidate.
+pub fn resolve<'a>(query: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let query_key = normalize(query);
+    let candidates: Vec<(&str, String)> = candidates
+        .into_iter()
+        .map(|candidate| (candidate, normalize(candidate)))
+        .collect();
+
+    let mut equal = candidates.iter().filter(|(_, key)| *key == query_key);
+    if let Some((candidate, _)) = equal.next() {
+        return equal.next().is_none().then_some(*candidate);
+    }
+
+    let query_len = query_key.chars().count() as i64;
+    if query_len >= MIN_SUBSEQUENCE_LEN as i64 {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        let mut scored: Vec<(i64, &str)> = candidates
+            .iter()
+            .filter_map(|(candidate, key)| {
+                matcher
+                    .fuzzy_match(key, &query_key)
+                    .filter(|score| *score >= MIN_SCORE_PER_CHAR * query_len)
+                    .map(|score| (score, *candidate))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        match scored.as_slice() {
+            [(_, only)] => return Some(only),
+            [(best, candidate), (second, _), ..] if best > second => return Some(candidate),
+            [] => {}
+            _ => return None,
+        }
+    }
+
+    closest_match(query, candidates.iter().map(|(candidate, _)| *candidate))
+}
+
+/// Looks `query` up in `map`, exactly first and then through [`resolve`].
+/// Returns the matched key along with its value.
+pub fn lookup<'a, V>(map: &'a HashMap<String, V>, query: &str) -> Option<(&'a str, &'a V)> {
+    if let Some((key, value)) = map.get_key_value(query) {
+        return Some((key.as_str(), value));
+    }
+    let key = resolve(query, map.keys().map(|k| k.as_str()))?;
+    Some((key, &map[key]))
+}
+
+/// The candidates `partial` could be the start of, followed by those that
+/// merely contain it, each group sorted and without duplicates. Matching
+/// ignores case and separators, like [`resolve`].
+pub fn complete<'a>(partial: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let partial = normalize(partial);
+    let mut matches: Vec<(bool, &str)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let normalized = normalize(candidate);
+            if normalized.starts_with(&partial) {
+                Some((false, candidate))
+            } else if normalized.contains(&partial) {
+                Some((true, candidate))
+            } else {
+                None
+            }
+        })
+        .collect();
+    matches.sort();
+    matches.dedup();
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Lowercases `name` and drops the separators people use interchangeably.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | '.' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Builds "Kind 'name' not found", followed by a suggestion when one is close.
+pub fn not_found<'a>(
+    kind: &str,
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut message = format!("{} '{}' not found", kind, name);
+    if let Some(suggestion) = closest_match(name, candidates) {
+        message.push_str(&format!(", did you mean '{}'?", suggestion));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closest_match() {
+        let candidates = ["jumble", "jungle-api", "docs"];
+        assert_eq!(closest_match("jmble", candidates), Some("jumble"));
+        assert_eq!(closest_match("JUMBLE", candidates), Some("jumble"));
+        assert_eq!(closest_match("billing", candidates), None);
+        assert_eq!(closest_match("anything", []), None);
+    }
+
+    #[test]
+    fn test_closest_match_breaks_ties_alphabetically() {
+        assert_eq!(closest_match("cats", ["cabs", "cars"]), Some("cabs"));
+        assert_eq!(closest_match("cats", ["cars", "cabs"]), Some("cabs"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let candidates = ["authentication", "authorization", "auth-flow-v2", "billing"];
+        assert_eq!(resolve("authntication", candidates), Some("authentication"));
+        assert_eq!(resolve("Auth Flow V2", candidates), Some("auth-flow-v2"));
+        assert_eq!(resolve("authflow", candidates), Some("auth-flow-v2"));
+        assert_eq!(resolve("bllng", candidates), Some("billing"));
+        assert_eq!(resolve("zzz", candidates), None);
+        assert_eq!(resolve("data", ["deployment_automation_tests"]), None);
+        // Both "authentication" and "authorization" contain "auth".
+        assert_eq!(resolve("ath", ["authentication", "authorization"]), None);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut map = HashMap::new();
+        map.insert("auth_flow".to_string(), 1);
+        map.insert("auth-flow".to_string(), 2);
+        assert_eq!(lookup(&map, "auth_flow"), Some(("auth_flow", &1)));
+        // Two keys normalize to the same name, so the loose form is ambiguous.
+        assert_eq!(lookup(&map, "auth flow"), None);
+    }
+
+    #[test]
+    fn test_complete() {
+        let candidates = ["web-app", "api", "webhooks", "admin-web", "api"];
+        assert_eq!(
+            complete("web", candidates),
+            ["web-app", "webhooks", "admin-web"]
+        );
+        assert_eq!(complete("WebA", candidates), ["web-app"]);
+        assert_eq!(complete("", candidates).len(), 4);
+        assert!(complete("billing", candidates).is_empty());
+    }
+
+    #[test]
+    fn test_not_found() {
+        assert_eq!(
+            not_found("Project", "jmble", ["jumble"]),
+            "Project 'jmble' not found, did you mean 'jumble'?"
+        );
+        assert_eq!(
+            not_found("Project", "zzz", ["jumble"]),
+            "Project 'zzz' not found"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Helpers for querying git repositories.
+//!
+//! Jumble shells out to the `git` binary rather than linking a git library;
+//! all helpers run inside a given directory and return stdout on success.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Runs `git` with the given arguments inside `dir` and returns its stdout.
+pub fn run_git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.join(" "), stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Which commits to inspect when collecting recent changes.
+#[derive(Debug, Clone)]
+pub enum ChangeRange {
+    /// The last N commits reachable from HEAD.
+    LastCommits(usize),
+    /// All commits after the given ref (exclusive) up to HEAD.
+    SinceRef(String),
+}
+
+/// Returns the files touched by each commit in `range`, limited to `dir`.
+///
+/// Paths are relative to `dir`. Commits that only touch files outside `dir`
+/// are omitted.
+pub fn changed_files_per_commit(
+    dir: &Path,
+    range: &ChangeRange,
+) -> Result<Vec<Vec<String>>, String> {
+    let revision;
+    let count;
+    let mut args = vec!["log", "--name-only", "--relative", "--format=commit %H"];
+    match range {
+        ChangeRange::LastCommits(n) => {
+            count = format!("-n{}", n);
+            args.push(&count);
+        }
+        ChangeRange::SinceRef(since) => {
+            revision = range_to_head(since)?;
+            args.extend(["--end-of-options", &revision]);
+        }
+    }
+    args.extend(["--", "."]);
+
+    let stdout = run_git(dir, &args)?;
+    Ok(parse_name_only_log(&stdout))
+}
+
+/// A tag with its date, the commit it points at, and its subject line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// `YYYY-MM-DD`: when an annotated tag was made, or the commit date for
+    /// a lightweight one.
+    pub date: String,
+    pub commit: String,
+    /// The annotation's first line, or the commit's for a lightweight tag.
+    pub subject: String,
+}
+
+/// Tags in the repository containing `dir`, newest first.
+pub fn tags(dir: &Path) -> Result<Vec<Tag>, String> {
+    let stdout = run_git(
+        dir,
+        &[
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--format=%(refname:short)%09%(creatordate:short)%09%(objectname:short)%09%(*objectname:short)%09%(contents:subject)",
+            "refs/tags",
+        ],
+    )?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            let name = fields.next()?.to_string();
+            let date = fields.next()?.to_string();
+            let object = fields.next()?;
+            // Annotated tags point at a tag object; `*objectname` is its commit.
+            let commit = fields.next().filter(|c| !c.is_empty()).unwrap_or(object);
+            Some(Tag {
+                name,
+                date,
+                commit: commit.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// How many commits after `since` up to HEAD touch files under `dir`.
+pub fn commits_since(dir: &Path, since: &str) -> Result<usize, String> {
+    let range = range_to_head(since)?;
+    let stdout = run_git(
+        dir,
+        &["rev-list", "--count", "--end-of-options", &range, "--", "."],
+    )?;
+    stdout
+        .trim()
+        .parse()
+        .map_err(|e| format!("Unexpected git rev-list output '{}': {}", stdout.trim(), e))
+}
+
+/// `<since>..HEAD`, refusing a revision git would read as an option.
+fn range_to_head(since: &str) -> Result<String, String> {
+    if since.starts_with('-') {
+        return Err(format!("Invalid revision '{}'", since));
+    }
+    Ok(format!("{}..HEAD", since))
+}
+
+/// Parses `git log --name-only --format="commit %H"` output into per-commit file lists.
+fn parse_name_only_log(output: &str) -> Vec<Vec<String>> {
+    let mut commits: Vec<Vec<String>> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with("commit ") {
+            commits.push(Vec::new());
+        } else if let Some(files) = commits.last_mut() {
+            files.push(line.to_string());
+        }
+    }
+    commits.retain(|files| !files.is_empty());
+    commits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let mut full = vec!["-c", "user.name=test", "-c", "user.email=test@example.com"];
+        full.extend_from_slice(args);
+        run_git(dir, &full).unwrap();
+    }
+
+    #[test]
+    fn test_tags() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q"]);
+        std::fs::create_dir_all(dir.join("api")).unwrap();
+
+        std::fs::write(dir.join("api/a.txt"), "one").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "first"]);
+        git(dir, &["tag", "v0.1.0"]);
+        std::fs::write(dir.join("api/a.txt"), "two").unwrap();
+        git(dir, &["commit", "-q", "-am", "second"]);
+        git(dir, &["tag", "-a", "v0.2.0", "-m", "Release 0.2.0"]);
+        std::fs::write(dir.join("b.txt"), "outside").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "third"]);
+
+        let tags = tags(dir).unwrap();
+        let mut summary: Vec<(&str, &str)> = tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.subject.as_str()))
+            .collect();
+        summary.sort();
+        assert_eq!(summary, [("v0.1.0", "first"), ("v0.2.0", "Release 0.2.0")]);
+        let head_1 = run_git(dir, &["rev-parse", "--short", "HEAD~1"]).unwrap();
+        let annotated = tags.iter().find(|t| t.name == "v0.2.0").unwrap();
+        assert_eq!(annotated.commit, head_1.trim());
+
+        assert_eq!(commits_since(dir, "v0.1.0").unwrap(), 2);
+        assert_eq!(commits_since(&dir.join("api"), "v0.1.0").unwrap(), 1);
+        assert_eq!(commits_since(&dir.join("api"), "v0.2.0").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_name_only_log() {
+        let output = "commit abc\n\nsrc/a.rs\nsrc/b.rs\ncommit def\n\nsrc/a.rs\n";
+        let commits = parse_name_only_log(output);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0], vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(commits[1], vec!["src/a.rs"]);
+    }
+
+    #[test]
+    fn test_changed_files_per_commit() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q"]);
+
+        std::fs::write(dir.join("a.txt"), "one").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "first"]);
+
+        std::fs::write(dir.join("a.txt"), "two").unwrap();
+        std::fs::write(dir.join("b.txt"), "two").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "second"]);
+
+        let commits = changed_files_per_commit(dir, &ChangeRange::LastCommits(1)).unwrap();
+        assert_eq!(
+            commits,
+            vec![vec!["a.txt".to_string(), "b.txt".to_string()]]
+        );
+
+        let commits =
+            changed_files_per_commit(dir, &ChangeRange::SinceRef("HEAD~1".to_string())).unwrap();
+        assert_eq!(commits.len(), 1);
+
+        let commits = changed_files_per_commit(dir, &ChangeRange::LastCommits(10)).unwrap();
+        assert_eq!(commits.len(), 2);
+
+        let since = ChangeRange::SinceRef("--output=/tmp/jumble-injected".to_string());
+        assert_eq!(
+            changed_files_per_commit(dir, &since).unwrap_err(),
+            "Invalid revision '--output=/tmp/jumble-injected'"
+        );
+        assert!(commits_since(dir, "-n1").is_err());
+    }
+}
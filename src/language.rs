@@ -0,0 +1,165 @@
+//! Infers a project's language when `project.language` is unset: first from
+//! the manifests in the project root, then from the most common source file
+//! extension.
+
+use std::collections::HashMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Manifests that settle the language on their own, in order of preference.
+/// `package.json` is handled separately to tell TypeScript from JavaScript.
+const MANIFEST_LANGUAGES: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("go.mod", "go"),
+    ("pyproject.toml", "python"),
+    ("setup.py", "python"),
+    ("requirements.txt", "python"),
+    ("Gemfile", "ruby"),
+    ("build.gradle.kts", "kotlin"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("composer.json", "php"),
+    ("mix.exs", "elixir"),
+    ("Package.swift", "swift"),
+];
+
+/// Source extensions and the language they count towards.
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("go", "go"),
+    ("py", "python"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("mjs", "javascript"),
+    ("rb", "ruby"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("swift", "swift"),
+    ("php", "php"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("hpp", "cpp"),
+    ("cs", "csharp"),
+];
+
+/// Directories that hold dependencies or build output rather than the
+/// project's own sources.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// How deep the extension count looks below the project root.
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Most files examined by the extension count.
+const MAX_SCANNED_FILES: usize = 2000;
+
+/// The language of the project at `dir`, or `None` when nothing points to one.
+pub fn detect(dir: &Path) -> Option<String> {
+    from_manifests(dir)
+        .or_else(|| from_extensions(dir))
+        .map(str::to_string)
+}
+
+fn from_manifests(dir: &Path) -> Option<&'static str> {
+    if let Some((_, language)) = MANIFEST_LANGUAGES
+        .iter()
+        .find(|(manifest, _)| dir.join(manifest).is_file())
+    {
+        return Some(language);
+    }
+    if dir.join("package.json").is_file() {
+        let typescript = dir.join("tsconfig.json").is_file();
+        return Some(if typescript {
+            "typescript"
+        } else {
+            "javascript"
+        });
+    }
+    None
+}
+
+fn from_extensions(dir: &Path) -> Option<&'static str> {
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    let files = WalkDir::new(dir)
+        .max_depth(MAX_SCAN_DEPTH)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0 || !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .take(MAX_SCANNED_FILES);
+    for file in files {
+        let extension = file.path().extension().and_then(|e| e.to_str());
+        if let Some((_, language)) = EXTENSION_LANGUAGES
+            .iter()
+            .find(|(ext, _)| Some(*ext) == extension)
+        {
+            *counts.entry(language).or_default() += 1;
+        }
+    }
+    // Ties go to the alphabetically first language so the result is stable.
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(language, _)| language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project(files: &[&str]) -> TempDir {
+        let temp = TempDir::new().unwrap();
+        for file in files {
+            let path = temp.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        temp
+    }
+
+    #[test]
+    fn test_detect_from_manifests() {
+        let cases: &[(&[&str], &str)] = &[
+            (&["Cargo.toml", "scripts/build.py"], "rust"),
+            (&["package.json", "tsconfig.json"], "typescript"),
+            (&["package.json"], "javascript"),
+            (&["pyproject.toml", "package.json"], "python"),
+            (&["build.gradle.kts"], "kotlin"),
+        ];
+        for (files, language) in cases {
+            let temp = project(files);
+            assert_eq!(
+                detect(temp.path()).as_deref(),
+                Some(*language),
+                "{:?}",
+                files
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_from_extensions() {
+        let temp = project(&[
+            "src/main.py",
+            "src/util.py",
+            "scripts/deploy.sh",
+            "web/app.js",
+            "node_modules/dep/a.js",
+            "node_modules/dep/b.js",
+            "node_modules/dep/c.js",
+        ]);
+        assert_eq!(detect(temp.path()).as_deref(), Some("python"));
+
+        let temp = project(&["README.md", "docs/guide.md"]);
+        assert_eq!(detect(temp.path()), None);
+    }
+}
@@ -0,0 +1,226 @@
+//! `jumble lint`: opinionated checks on the content of loaded context, as
+//! opposed to `jumble validate`, which reports files that fail to load.
+//!
+//! Lints catch context that parses fine but serves agents poorly: a project
+//! with no concepts or so many that none stands out, empty summaries,
+//! conventions long enough to be documents, docs entries whose file is gone,
+//! and skills that never say when to use them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::diagnostics::Diagnostic;
+use crate::tools::ProjectData;
+
+/// More concepts than this and no single one stands out.
+pub const MAX_CONCEPTS: usize = 30;
+
+/// Longest convention or gotcha, in characters, before it reads like a doc.
+pub const MAX_CONVENTION_CHARS: usize = 400;
+
+/// Lints the given projects, sorted errors first and then by file.
+pub fn lint_projects<'a>(projects: impl IntoIterator<Item = &'a ProjectData>) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = projects.into_iter().flat_map(lint_project).collect();
+    diagnostics.sort();
+    diagnostics
+}
+
+/// Lints one project's config, conventions, docs index, and skills.
+pub fn lint_project(data: &ProjectData) -> Vec<Diagnostic> {
+    let (dir, config, skills, conventions, docs, _) = data;
+    let mut diagnostics = Vec::new();
+
+    let project_file =
+        config::find_project_config(dir).unwrap_or_else(|| dir.join(".jumble/project.toml"));
+    if config.project.description.trim().is_empty() {
+        diagnostics.push(Diagnostic::warning(
+            &project_file,
+            "project has an empty description",
+        ));
+    }
+    if config.concepts.is_empty() {
+        diagnostics.push(Diagnostic::warning(
+            &project_file,
+            "project has no concepts; add [concepts] so agents can find the code for an area",
+        ));
+    } else if config.concepts.len() > MAX_CONCEPTS {
+        diagnostics.push(Diagnostic::warning(
+            &project_file,
+            format!(
+                "project has {} concepts (more than {}); merge related ones so each stands out",
+                config.concepts.len(),
+                MAX_CONCEPTS
+            ),
+        ));
+    }
+    for (name, concept) in sorted(&config.concepts) {
+        if concept.summary.trim().is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                &project_file,
+                format!("concept '{}' has an empty summary", name),
+            ));
+        }
+        if concept.files.is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                &project_file,
+                format!("concept '{}' lists no files", name),
+            ));
+        }
+    }
+
+    let conventions_file = context_file(dir, "conventions");
+    for (category, entries) in conventions.categories() {
+        for (name, text) in sorted(entries) {
+            let length = text.description().trim().chars().count();
+            if length == 0 {
+                diagnostics.push(Diagnostic::warning(
+                    &conventions_file,
+                    format!("{}.{} is empty", category, name),
+                ));
+            } else if length > MAX_CONVENTION_CHARS {
+                diagnostics.push(Diagnostic::warning(
+                    &conventions_file,
+                    format!(
+                        "{}.{} is {} characters (more than {}); keep the rule short and move the detail into a doc or skill",
+                        category, name, length, MAX_CONVENTION_CHARS
+                    ),
+                ));
+            }
+        }
+    }
+
+    let docs_file = context_file(dir, "docs");
+    for (name, doc) in sorted(&docs.docs) {
+        if !dir.join(&doc.path).exists() {
+            diagnostics.push(Diagnostic::error(
+                &docs_file,
+                format!("doc '{}' points at missing file {}", name, doc.path),
+            ));
+        }
+        if doc.summary.trim().is_empty() {
+            diagnostics.push(Diagnostic::warning(
+                &docs_file,
+                format!("doc '{}' has an empty summary", name),
+            ));
+        }
+    }
+
+    for (name, skill) in sorted(&skills.skills) {
+        let described = skill
+            .frontmatter
+            .as_ref()
+            .and_then(|f| f.description.as_deref())
+            .is_some_and(|d| !d.trim().is_empty());
+        if !described {
+            diagnostics.push(Diagnostic::warning(
+                &skill.path,
+                format!(
+                    "skill '{}' has no description; agents won't know when to use it",
+                    name
+                ),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// The file a `.jumble/<stem>` section lives in: its own file, or the
+/// project's single-file `jumble.toml`.
+fn context_file(dir: &Path, stem: &str) -> PathBuf {
+    config::find_config_file(&dir.join(".jumble"), stem)
+        .or_else(|| config::find_single_file_config(dir))
+        .unwrap_or_else(|| dir.join(format!(".jumble/{}.toml", stem)))
+}
+
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<(&String, &V)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Severity;
+    use crate::server::Server;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lint_project() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join(".jumble/skills")).unwrap();
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        std::fs::write(dir.join("docs/guide.md"), "# Guide").unwrap();
+        std::fs::write(
+            dir.join(".jumble/project.toml"),
+            r#"
+[project]
+name = "app"
+description = ""
+
+[concepts.auth]
+files = ["src/auth.rs"]
+summary = "Login and sessions"
+
+[concepts.billing]
+files = []
+summary = " "
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".jumble/conventions.toml"),
+            format!(
+                "[conventions]\nerrors = \"Use anyhow\"\nstyle = \"{}\"\n\n[gotchas]\ncache = \"\"\n",
+                "x".repeat(MAX_CONVENTION_CHARS + 1)
+            ),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".jumble/docs.toml"),
+            r#"
+[docs.guide]
+path = "docs/guide.md"
+summary = "How to use it"
+
+[docs.old]
+path = "docs/removed.md"
+summary = ""
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(".jumble/skills/deploy.md"),
+            "---\ndescription: Deploy to staging\n---\n# Deploy\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join(".jumble/skills/notes.md"), "# Notes\n").unwrap();
+
+        let server = Server::with_global_skills(dir.to_path_buf(), false).unwrap();
+        let diagnostics = lint_projects(server.projects.values());
+        let messages: Vec<(Severity, String)> = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.message.clone()))
+            .collect();
+        let expected = [
+            "doc 'old' points at missing file docs/removed.md",
+            "conventions.style is 401 characters (more than 400); keep the rule short and move the detail into a doc or skill",
+            "gotchas.cache is empty",
+            "doc 'old' has an empty summary",
+            "concept 'billing' has an empty summary",
+            "concept 'billing' lists no files",
+            "project has an empty description",
+            "skill 'notes' has no description; agents won't know when to use it",
+        ];
+        assert_eq!(
+            messages.iter().map(|(_, m)| m).collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(messages[0].0, Severity::Error);
+        assert!(messages[1..].iter().all(|(s, _)| *s == Severity::Warning));
+        assert!(diagnostics[0].path.ends_with(".jumble/docs.toml"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Logging setup built on `tracing`.
+//!
+//! Stdout carries the MCP protocol, so logs never go there. Everything at the
+//! configured level is appended to a log file, and warnings and errors are
+//! mirrored to stderr, where MCP clients usually surface server output.
+
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, Layer};
+
+/// Default log location: `~/.jumble/logs/jumble.log`.
+pub fn default_log_file() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".jumble").join("logs").join("jumble.log"))
+}
+
+/// Installs the global subscriber.
+///
+/// If the log file cannot be opened, logging continues on stderr alone at the
+/// requested level.
+pub fn init(level: LevelFilter, log_file: Option<&Path>) {
+    let file = log_file.and_then(|path| match open_log_file(path) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            eprintln!("jumble: failed to open log file {}: {}", path.display(), e);
+            None
+        }
+    });
+
+    let stderr_level = if file.is_some() {
+        level.min(LevelFilter::WARN)
+    } else {
+        level
+    };
+
+    let file_layer = file.map(|file| {
+        fmt::layer()
+            .with_writer(Mutex::new(file))
+            .with_ansi(false)
+            .with_filter(level)
+    });
+    let stderr_layer = fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .without_time()
+        .with_target(false)
+        .with_filter(stderr_level);
+
+    // Ignore the error if a subscriber is already installed.
+    let _ = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(stderr_layer)
+        .try_init();
+}
+
+fn open_log_file(path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_open_log_file_creates_parent_dirs() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("logs").join("jumble.log");
+        open_log_file(&path).unwrap();
+        assert!(path.is_file());
+    }
+}
@@ -1,18 +1,61 @@
+// The tool schema in `tools::tools_list` is one large `json!` literal.
+#![recursion_limit = "256"]
+
+mod audit;
+mod authoring;
+mod changelog;
+mod ci;
+mod codeowners;
 mod config;
+mod coverage;
+mod decisions;
+mod deps;
+mod diagnostics;
+mod environment;
+mod export;
 mod format;
+mod fuzzy;
+mod git;
+mod language;
+mod lint;
+mod logging;
+mod members;
 mod memory;
+mod memory_audit;
+mod migrate;
+mod plugins;
+mod process;
 mod protocol;
+mod remote;
+mod schema;
+mod scripts;
 mod server;
+mod services;
 mod setup;
+mod skill_packs;
+mod stats;
+mod suggest;
+mod tasks;
+mod templates;
+mod test_map;
 mod tools;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use std::env;
-use std::io::{self, BufRead, Write};
+use std::io::{self, IsTerminal};
 use std::path::PathBuf;
+use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, PoisonError, RwLock};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tracing::level_filters::LevelFilter;
 
-use protocol::{JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+use diagnostics::Severity;
+use protocol::{
+    Framing, JsonRpcClientResponse, JsonRpcError, JsonRpcRequest, JsonRpcResponse, PendingRequests,
+    PARSE_ERROR,
+};
 use server::Server;
 
 /// An MCP server that provides queryable, on-demand project context to LLMs
@@ -25,6 +68,23 @@ struct Args {
     /// Root directory to scan for .jumble/project.toml files (server mode only)
     #[arg(long, env = "JUMBLE_ROOT", global = true)]
     root: Option<PathBuf>,
+
+    /// Log verbosity: off, error, warn, info, debug, or trace
+    #[arg(long, env = "JUMBLE_LOG_LEVEL", global = true, default_value = "info")]
+    log_level: LevelFilter,
+
+    /// Log file path (defaults to ~/.jumble/logs/jumble.log)
+    #[arg(long, env = "JUMBLE_LOG_FILE", global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Message framing on stdin/stdout: newline-delimited JSON or LSP-style
+    /// Content-Length headers (server mode only)
+    #[arg(long, value_enum, global = true, default_value = "lines")]
+    framing: Framing,
+
+    /// Skip skills in ~/.jumble/skills, ~/.claude/skills and ~/.codex/skills
+    #[arg(long, env = "JUMBLE_NO_GLOBAL_SKILLS", global = true)]
+    no_global_skills: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -35,6 +95,137 @@ enum Commands {
     /// Initialize a new jumble project
     Init,
 
+    /// Check .jumble files and skills for problems and exit non-zero on errors
+    Validate,
+
+    /// Check context content for quality problems such as empty summaries,
+    /// overlong conventions, and docs pointing at missing files
+    Lint {
+        /// Only lint this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Exit non-zero on warnings as well as errors
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Print the discovered projects with their language, path, and context counts
+    List,
+
+    /// Upgrade .jumble files written for an older config format and stamp
+    /// them with the current schema_version
+    Migrate {
+        /// Only migrate this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Report what would change and exit non-zero instead of writing
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Print a commented starter template for a context file or skill
+    Template {
+        /// Which file to generate
+        #[arg(value_enum)]
+        kind: templates::TemplateKind,
+
+        /// Write the template to its default path under .jumble/ instead of
+        /// printing it
+        #[arg(long)]
+        write: bool,
+
+        /// Overwrite the file if it already exists
+        #[arg(long, requires = "write")]
+        force: bool,
+    },
+
+    /// Print JSON Schemas for .jumble config files (all kinds if none given)
+    Schema {
+        /// Which config file to print the schema for
+        #[arg(value_enum)]
+        kind: Option<schema::ConfigKind>,
+    },
+
+    /// Fetch or refresh remote skill packs listed in [skill_sources]
+    SyncSkills,
+
+    /// Show tool usage statistics recorded by the server
+    Stats,
+
+    /// Call a tool directly and print its result
+    Query {
+        /// Tool name, e.g. get_commands
+        tool: String,
+
+        /// Tool argument as key=value (repeatable). The value is a string
+        /// unless the tool's schema expects another type; use key:=value to
+        /// pass raw JSON
+        #[arg(long = "arg", value_name = "KEY=VALUE", value_parser = parse_tool_arg)]
+        args: Vec<(String, ToolArg)>,
+    },
+
+    /// Render all workspace and project context into a single bundle
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "markdown")]
+        format: export::ExportFormat,
+
+        /// Only export this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Propose concepts from each project's source layout, as TOML to paste
+    /// into project.toml
+    SuggestConcepts {
+        /// Only analyze this project
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Propose `[commands]` entries from each project's justfile, Makefile,
+    /// and package.json scripts
+    #[command(name = "import-commands")]
+    ImportScripts {
+        /// Only import for this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Add the proposed commands to the project file without asking
+        #[arg(long)]
+        write: bool,
+    },
+
+    /// Rewrite each project's `[dependencies] external` list from its
+    /// Cargo.toml, package.json, pyproject.toml, or requirements.txt
+    SyncDeps {
+        /// Only sync this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Report out-of-date lists and exit non-zero instead of writing
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Manage project memory stores
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommands,
+    },
+
+    /// List, print, and scaffold skills
+    Skills {
+        #[command(subcommand)]
+        command: SkillsCommands,
+    },
+
     /// Setup AI agent integrations
     Setup {
         #[command(subcommand)]
@@ -42,6 +233,86 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum MemoryCommands {
+    /// Convert memory stores to another storage format, keeping every entry
+    Migrate {
+        /// Storage format to convert to
+        #[arg(long, value_enum)]
+        to: memory::MemoryFormat,
+
+        /// Only migrate this project
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// List a project's memories with when and by whom they were stored
+    List {
+        #[arg(long)]
+        project: String,
+    },
+
+    /// Show one memory
+    Get {
+        #[arg(long)]
+        project: String,
+        key: String,
+    },
+
+    /// Store a memory, replacing any existing value for the key
+    Set {
+        #[arg(long)]
+        project: String,
+        key: String,
+        value: String,
+        /// Recorded as the memory's source
+        #[arg(long, default_value = "cli")]
+        source: String,
+    },
+
+    /// Delete one memory
+    Delete {
+        #[arg(long)]
+        project: String,
+        key: String,
+    },
+
+    /// Delete all of a project's memories
+    Clear {
+        #[arg(long)]
+        project: String,
+        /// Confirm the deletion
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SkillsCommands {
+    /// List a project's skills with where each was found
+    List {
+        #[arg(long)]
+        project: String,
+    },
+
+    /// Print a skill file
+    Show {
+        #[arg(long)]
+        project: String,
+        name: String,
+    },
+
+    /// Create .jumble/skills/<name>.md with frontmatter to fill in
+    New {
+        #[arg(long)]
+        project: String,
+        name: String,
+        /// When the skill applies, for the frontmatter
+        #[arg(long, default_value = "TODO: describe when to use this skill")]
+        description: String,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum SetupCommands {
     /// Setup Warp integration by creating/updating WARP.md
@@ -83,17 +354,65 @@ enum SetupCommands {
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    let log_file = args.log_file.clone().or_else(logging::default_log_file);
+    logging::init(args.log_level, log_file.as_deref());
+
     let root = args
         .root
         .or_else(|| env::var("JUMBLE_ROOT").ok().map(PathBuf::from))
         .unwrap_or_else(|| env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
+    let global_skills = !args.no_global_skills;
+
     match args.command {
         Some(Commands::Server) | None => {
             // Run MCP server (default mode)
-            run_server(root)
+            run_server(root, args.framing, global_skills)
         }
         Some(Commands::Init) => setup::setup_init(&root),
+        Some(Commands::Validate) => run_validate(root, global_skills),
+        Some(Commands::Lint { project, strict }) => {
+            run_lint(root, project.as_deref(), strict, global_skills)
+        }
+        Some(Commands::List) => {
+            let server = Server::with_global_skills(root, global_skills)?;
+            println!("{}", tools::format_project_table(&server.projects));
+            Ok(())
+        }
+        Some(Commands::Schema { kind }) => {
+            let schema = match kind {
+                Some(kind) => kind.schema(),
+                None => schema::all_schemas(),
+            };
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            Ok(())
+        }
+        Some(Commands::SyncSkills) => run_sync_skills(root, global_skills),
+        Some(Commands::Stats) => run_stats(),
+        Some(Commands::Query { tool, args }) => run_query(root, tool, args, global_skills),
+        Some(Commands::Migrate { project, check }) => run_migrate(root, project.as_deref(), check),
+        Some(Commands::Template { kind, write, force }) => run_template(&root, kind, write, force),
+        Some(Commands::Export {
+            format,
+            project,
+            output,
+        }) => run_export(root, format, project.as_deref(), output, global_skills),
+        Some(Commands::SuggestConcepts { project }) => {
+            run_suggest_concepts(root, project.as_deref())
+        }
+        Some(Commands::ImportScripts { project, write }) => {
+            run_import_commands(root, project.as_deref(), write)
+        }
+        Some(Commands::SyncDeps { project, check }) => {
+            run_sync_deps(root, project.as_deref(), check)
+        }
+        Some(Commands::Memory { command }) => match command {
+            MemoryCommands::Migrate { to, project } => {
+                run_memory_migrate(root, to, project.as_deref())
+            }
+            command => run_memory(root, command),
+        },
+        Some(Commands::Skills { command }) => run_skills(root, command, global_skills),
         Some(Commands::Setup { agent }) => match agent {
             SetupCommands::Warp { force } => setup::setup_warp(&root, force),
             SetupCommands::Claude { global } => setup::setup_claude(&root, global),
@@ -104,43 +423,873 @@ fn main() -> Result<()> {
     }
 }
 
-fn run_server(root: PathBuf) -> Result<()> {
-    let mut server = Server::new(root)?;
+fn run_validate(root: PathBuf, global_skills: bool) -> Result<()> {
+    let server = Server::with_global_skills(root, global_skills)?;
+
+    for diagnostic in &server.diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    let errors = diagnostics::count(&server.diagnostics, Severity::Error);
+    let warnings = diagnostics::count(&server.diagnostics, Severity::Warning);
+    println!(
+        "Checked {} project(s): {} error(s), {} warning(s)",
+        server.projects.len(),
+        errors,
+        warnings
+    );
+
+    if errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_lint(root: PathBuf, project: Option<&str>, strict: bool, global_skills: bool) -> Result<()> {
+    let server = Server::with_global_skills(root, global_skills)?;
+    if let Some(name) = project.filter(|name| !server.projects.contains_key(*name)) {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            name,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    }
+    let selected: Vec<_> = server
+        .projects
+        .iter()
+        .filter(|(name, _)| project.is_none_or(|p| p == name.as_str()))
+        .map(|(_, data)| data)
+        .collect();
+    let diagnostics = lint::lint_projects(selected.iter().copied());
+
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+    let errors = diagnostics::count(&diagnostics, Severity::Error);
+    let warnings = diagnostics::count(&diagnostics, Severity::Warning);
+    println!(
+        "Linted {} project(s): {} error(s), {} warning(s)",
+        selected.len(),
+        errors,
+        warnings
+    );
+
+    if errors > 0 || (strict && warnings > 0) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_sync_skills(root: PathBuf, global_skills: bool) -> Result<()> {
+    let mut server = Server::with_global_skills(root, global_skills)?;
+    let results = server.sync_skill_packs()?;
+
+    if results.is_empty() {
+        println!("No skill sources configured.");
+        return Ok(());
+    }
+
+    let mut failed = false;
+    for (source, result) in &results {
+        match result {
+            Ok(dir) => println!("{}: synced to {}", source.name, dir.display()),
+            Err(e) => {
+                failed = true;
+                println!("{}: {}", source.name, e);
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// A `--arg` value for `jumble query`.
+#[derive(Debug, Clone, PartialEq)]
+enum ToolArg {
+    /// `key:=value`: raw JSON, passed as parsed.
+    Json(serde_json::Value),
+    /// `key=value`: a string, unless the tool's schema expects another type.
+    Text(String),
+}
+
+/// Parses a `--arg key=value` or `--arg key:=json` pair for `jumble query`.
+fn parse_tool_arg(arg: &str) -> Result<(String, ToolArg), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", arg))?;
+    match key.strip_suffix(':') {
+        Some(key) => {
+            let value = serde_json::from_str(value)
+                .map_err(|e| format!("invalid JSON for '{}': {}", key, e))?;
+            Ok((key.to_string(), ToolArg::Json(value)))
+        }
+        None => Ok((key.to_string(), ToolArg::Text(value.to_string()))),
+    }
+}
+
+/// The arguments for a call to the tool with `schema`. Plain values become
+/// strings, except for properties the schema types as something else
+/// (numbers, booleans, arrays), which are parsed as JSON.
+fn tool_arguments(
+    schema: Option<&serde_json::Value>,
+    args: Vec<(String, ToolArg)>,
+) -> serde_json::Map<String, serde_json::Value> {
+    args.into_iter()
+        .map(|(key, arg)| {
+            let value = match arg {
+                ToolArg::Json(value) => value,
+                ToolArg::Text(text) => {
+                    let types =
+                        schema.and_then(|s| s["inputSchema"]["properties"][&key].get("type"));
+                    let is_string = match types {
+                        Some(serde_json::Value::Array(types)) => {
+                            types.iter().any(|t| t == "string")
+                        }
+                        Some(ty) => ty == "string",
+                        None => true,
+                    };
+                    if is_string {
+                        serde_json::Value::String(text)
+                    } else {
+                        serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text))
+                    }
+                }
+            };
+            (key, value)
+        })
+        .collect()
+}
+
+fn run_query(
+    root: PathBuf,
+    tool: String,
+    args: Vec<(String, ToolArg)>,
+    global_skills: bool,
+) -> Result<()> {
+    let mut server = Server::with_global_skills(root, global_skills)?;
+    let tools = server
+        .handle_request(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(serde_json::json!(0)),
+            method: "tools/list".to_string(),
+            params: serde_json::json!({}),
+        })
+        .and_then(|response| response.result)
+        .unwrap_or_default();
+    let schema = tools["tools"]
+        .as_array()
+        .and_then(|tools| tools.iter().find(|t| t["name"] == tool.as_str()));
+    let arguments = tool_arguments(schema, args);
+    let request = JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        id: Some(serde_json::json!(1)),
+        method: "tools/call".to_string(),
+        params: serde_json::json!({ "name": tool, "arguments": arguments }),
+    };
+    let response = server
+        .handle_request(request)
+        .context("No response from tool call")?;
+
+    if let Some(error) = response.error {
+        eprintln!("Error: {}", error.message);
+        if let Some(data) = error.data {
+            eprintln!("{}", serde_json::to_string_pretty(&data)?);
+        }
+        std::process::exit(1);
+    }
+    let result = response.result.unwrap_or_default();
+    let text = result["content"][0]["text"].as_str().unwrap_or_default();
+    if result["isError"] == true {
+        eprintln!("{}", text);
+        std::process::exit(1);
+    }
+    println!("{}", text);
+    Ok(())
+}
+
+fn run_migrate(root: PathBuf, project: Option<&str>, check: bool) -> Result<()> {
+    let server = Server::new(root.clone())?;
+    if let Some(name) = project.filter(|name| !server.projects.contains_key(*name)) {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            name,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    }
+    let mut targets: Vec<(String, PathBuf)> = server
+        .projects
+        .iter()
+        .filter(|(name, _)| project.is_none_or(|p| p == name.as_str()))
+        // Projects from remote sources live in a cache outside the workspace.
+        .filter(|(_, (path, ..))| path.starts_with(&root))
+        .map(|(name, (path, ..))| (name.clone(), path.clone()))
+        .collect();
+    targets.sort();
+    let has_workspace = config::find_config_file(&root.join(".jumble"), "workspace").is_some();
+    if project.is_none() && has_workspace && !targets.iter().any(|(_, path)| *path == root) {
+        targets.insert(0, ("workspace".to_string(), root));
+    }
+
+    let mut pending = false;
+    for (name, dir) in targets {
+        let changes = migrate::migrate(&dir, check)?;
+        if changes.is_empty() {
+            println!("{}: up to date", name);
+            continue;
+        }
+        pending = true;
+        let verb = if check { "would migrate" } else { "migrated" };
+        println!("{}: {}", name, verb);
+        for change in changes {
+            println!("  {}", change);
+        }
+    }
+
+    if check && pending {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_template(
+    root: &std::path::Path,
+    kind: templates::TemplateKind,
+    write: bool,
+    force: bool,
+) -> Result<()> {
+    if !write {
+        print!("{}", kind.content());
+        return Ok(());
+    }
+    let path = root.join(kind.default_path());
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, kind.content())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn run_export(
+    root: PathBuf,
+    format: export::ExportFormat,
+    project: Option<&str>,
+    output: Option<PathBuf>,
+    global_skills: bool,
+) -> Result<()> {
+    let server = Server::with_global_skills(root, global_skills)?;
+    let bundle = export::export(&server, format, project).map_err(anyhow::Error::msg)?;
+    match output {
+        Some(path) => std::fs::write(&path, bundle)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => println!("{}", bundle),
+    }
+    Ok(())
+}
+
+fn run_suggest_concepts(root: PathBuf, project: Option<&str>) -> Result<()> {
+    let server = Server::new(root)?;
+    if let Some(name) = project.filter(|name| !server.projects.contains_key(*name)) {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            name,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    }
+    let mut names: Vec<&String> = server
+        .projects
+        .keys()
+        .filter(|name| project.is_none_or(|p| p == name.as_str()))
+        .collect();
+    names.sort();
+
+    for name in names {
+        let (path, config, ..) = &server.projects[name];
+        let suggestions = suggest::suggest_concepts(path, &config.concepts);
+        println!(
+            "# {} ({})",
+            name,
+            path.join(".jumble/project.toml").display()
+        );
+        if suggestions.is_empty() {
+            println!("# No new concepts found.\n");
+        } else {
+            println!("{}", suggest::render(&suggestions));
+        }
+    }
+    Ok(())
+}
+
+fn run_import_commands(root: PathBuf, project: Option<&str>, write: bool) -> Result<()> {
+    let server = Server::new(root)?;
+    if let Some(name) = project.filter(|name| !server.projects.contains_key(*name)) {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            name,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    }
+    let mut names: Vec<&String> = server
+        .projects
+        .keys()
+        .filter(|name| project.is_none_or(|p| p == name.as_str()))
+        .collect();
+    names.sort();
+
+    let interactive = io::stdin().is_terminal();
+    for name in names {
+        let (path, config, ..) = &server.projects[name];
+        let imported = scripts::import_commands(path, &config.commands);
+        if imported.is_empty() {
+            println!("# {}: no new commands found.\n", name);
+            continue;
+        }
+        let mut sources: Vec<&str> = imported.iter().map(|i| i.source).collect();
+        sources.dedup();
+        let commands: Vec<(String, config::CommandSpec)> =
+            imported.into_iter().map(|i| (i.name, i.spec)).collect();
+        println!("# {} (from {})", name, sources.join(", "));
+        println!("{}", config::commands_snippet(&commands));
+
+        let Some(file) = config::find_editable_project_config(path) else {
+            println!("# Only TOML project files can be updated; paste these in by hand.\n");
+            continue;
+        };
+        let confirmed = write
+            || (interactive
+                && confirm(&format!(
+                    "Add {} commands to {}? [y/N] ",
+                    commands.len(),
+                    file.display()
+                ))?);
+        if confirmed {
+            let added = config::insert_commands(&file, &commands)?;
+            println!("Added {} commands to {}\n", added, file.display());
+        } else if !interactive {
+            println!("# Run with --write to add them to {}\n", file.display());
+        }
+    }
+    Ok(())
+}
+
+/// Asks a yes/no question on the terminal; anything but `y` or `yes` is no.
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{}", prompt);
+    io::Write::flush(&mut io::stdout())?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn run_sync_deps(root: PathBuf, project: Option<&str>, check: bool) -> Result<()> {
+    let server = Server::new(root)?;
+    if let Some(name) = project.filter(|name| !server.projects.contains_key(*name)) {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            name,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    }
+    let mut names: Vec<&String> = server
+        .projects
+        .keys()
+        .filter(|name| project.is_none_or(|p| p == name.as_str()))
+        .collect();
+    names.sort();
+
+    let mut stale = false;
+    for name in names {
+        let (path, ..) = &server.projects[name];
+        let Some(external) = deps::read(path) else {
+            println!("{}: no package manifest found", name);
+            continue;
+        };
+        let Some(file) = config::find_editable_project_config(path) else {
+            println!(
+                "{}: only TOML project files can be updated; set `sync = true` under [dependencies] instead",
+                name
+            );
+            continue;
+        };
+        let changed = config::sync_external_dependencies(&file, &external.names, check)?;
+        let summary = format!(
+            "{} external dependencies from {}",
+            external.names.len(),
+            external.source_label()
+        );
+        match (changed, check) {
+            (false, _) => println!("{}: up to date ({})", name, summary),
+            (true, true) => {
+                stale = true;
+                println!("{}: {} is out of date ({})", name, file.display(), summary);
+            }
+            (true, false) => println!("{}: wrote {} to {}", name, summary, file.display()),
+        }
+    }
+
+    if stale {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_memory_migrate(
+    root: PathBuf,
+    to: memory::MemoryFormat,
+    project: Option<&str>,
+) -> Result<()> {
+    let server = Server::new(root.clone())?;
+    if let Some(name) = project.filter(|name| !server.projects.contains_key(*name)) {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            name,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    }
+    let mut targets: Vec<(String, PathBuf)> = server
+        .projects
+        .iter()
+        .filter(|(name, _)| project.is_none_or(|p| p == name.as_str()))
+        // Projects from remote sources live in a cache outside the workspace.
+        .filter(|(_, (path, ..))| path.starts_with(&root))
+        .map(|(name, (path, ..))| (name.clone(), path.clone()))
+        .collect();
+    targets.sort();
+    // Close the stores the server opened before moving their files.
+    drop(server);
+
+    let mut failed = false;
+    for (name, path) in targets {
+        match memory::migrate(&path, to) {
+            Ok(message) => println!("{}: {}", name, message),
+            Err(e) => {
+                failed = true;
+                println!("{}: {}", name, e);
+            }
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `jumble memory list|get|set|delete|clear`. Changes go through the same
+/// functions as the memory tools.
+fn run_memory(root: PathBuf, command: MemoryCommands) -> Result<()> {
+    let server = Server::new(root)?;
+    let project = match &command {
+        MemoryCommands::List { project }
+        | MemoryCommands::Get { project, .. }
+        | MemoryCommands::Set { project, .. }
+        | MemoryCommands::Delete { project, .. }
+        | MemoryCommands::Clear { project, .. } => project.as_str(),
+        MemoryCommands::Migrate { .. } => unreachable!("handled by run_memory_migrate"),
+    };
+    let Some((.., memory_db)) = server.projects.get(project) else {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            project,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    };
+
+    let read = |f: &dyn Fn(&memory::MemoryDb) -> Result<String, String>| {
+        memory_db.read(f).and_then(|result| result)
+    };
+    let result = match &command {
+        MemoryCommands::List { .. } => read(&|db| {
+            if db.is_empty() {
+                return Ok(format!("No memories stored for project '{}'.", project));
+            }
+            let mut keys: Vec<&String> = db.keys().collect();
+            keys.sort();
+            let entries: Vec<String> = keys
+                .into_iter()
+                .map(|key| memory::format_entry(key, &db[key]))
+                .collect();
+            Ok(entries.join("\n"))
+        }),
+        MemoryCommands::Get { key, .. } => read(&|db| {
+            db.get(key)
+                .map(|entry| memory::format_entry(key, entry))
+                .ok_or_else(|| fuzzy::not_found("Memory key", key, db.keys().map(|k| k.as_str())))
+        }),
+        MemoryCommands::Set {
+            key, value, source, ..
+        } => tools::store_memory(
+            &server.projects,
+            // Curating by hand is exempt from the `[memory]` quotas.
+            None,
+            &serde_json::json!({"project": project, "key": key, "value": value, "source": source}),
+        ),
+        MemoryCommands::Delete { key, .. } => tools::delete_memory(
+            &server.projects,
+            &serde_json::json!({"project": project, "key": key}),
+        ),
+        MemoryCommands::Clear { yes, .. } => {
+            if !yes {
+                anyhow::bail!(
+                    "This deletes every memory of '{}'. Pass --yes to confirm.",
+                    project
+                );
+            }
+            tools::clear_memories(
+                &server.projects,
+                &serde_json::json!({"project": project, "confirm": true}),
+            )
+        }
+        MemoryCommands::Migrate { .. } => unreachable!("handled by run_memory_migrate"),
+    };
+    println!("{}", result.map_err(anyhow::Error::msg)?.trim_end());
+    Ok(())
+}
+
+fn run_skills(root: PathBuf, command: SkillsCommands, global_skills: bool) -> Result<()> {
+    let mut server = Server::with_global_skills(root, global_skills)?;
+    let project = match &command {
+        SkillsCommands::List { project }
+        | SkillsCommands::Show { project, .. }
+        | SkillsCommands::New { project, .. } => project.clone(),
+    };
+    let Some((project_dir, _, skills, ..)) = server.projects.get(&project) else {
+        anyhow::bail!(fuzzy::not_found(
+            "Project",
+            &project,
+            server.projects.keys().map(|k| k.as_str())
+        ));
+    };
+
+    match command {
+        SkillsCommands::List { .. } => {
+            if skills.skills.is_empty() {
+                println!("No skills found for '{}'.", project);
+                return Ok(());
+            }
+            let home = server::resolve_home_dir();
+            let mut names: Vec<&String> = skills.skills.keys().collect();
+            names.sort();
+            for name in names {
+                let info = &skills.skills[name];
+                let description = info
+                    .frontmatter
+                    .as_ref()
+                    .and_then(|fm| fm.description.as_deref())
+                    .unwrap_or_else(|| info.preview.lines().next().unwrap_or_default().trim());
+                let source = tools::skill_source(project_dir, home.as_deref(), &info.path);
+                println!("{} [{}] {}", name, source, description);
+            }
+        }
+        SkillsCommands::Show { name, .. } => {
+            let Some((_, info)) = fuzzy::lookup(&skills.skills, &name) else {
+                anyhow::bail!(fuzzy::not_found(
+                    "Skill",
+                    &name,
+                    skills.skills.keys().map(|k| k.as_str())
+                ));
+            };
+            let content = std::fs::read_to_string(&info.path)
+                .with_context(|| format!("Failed to read {}", info.path.display()))?;
+            println!("{}", content.trim_end());
+        }
+        SkillsCommands::New {
+            name, description, ..
+        } => {
+            let args = serde_json::json!({
+                "project": project,
+                "name": name,
+                "description": description,
+                "content": format!("# {}\n\nTODO: the steps to follow.", name),
+                "confirm": true
+            });
+            let message =
+                tools::create_skill(&mut server.projects, &args).map_err(anyhow::Error::msg)?;
+            println!("{}", message);
+        }
+    }
+    Ok(())
+}
+
+fn run_stats() -> Result<()> {
+    let path = stats::default_stats_file().context("Could not determine home directory")?;
+    if !path.exists() {
+        println!("No tool calls recorded yet.");
+        return Ok(());
+    }
+    let db = stats::open_or_create_stats_db(&path).map_err(anyhow::Error::msg)?;
+    let usage = db
+        .read(|usage| usage.clone())
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    println!("{}", stats::format_stats(&usage));
+    Ok(())
+}
+
+fn run_server(root: PathBuf, framing: Framing, global_skills: bool) -> Result<()> {
+    tracing::info!(root = %root.display(), version = env!("CARGO_PKG_VERSION"), ?framing, "starting jumble server");
+    let mut server = Server::with_global_skills(root, global_skills)?;
+    if let Some(summary) = diagnostics::startup_summary(&server.diagnostics) {
+        eprintln!("{}", summary);
+    }
+
+    // Responses and notifications are written by a single thread so that
+    // concurrently handled requests never interleave their output.
+    let (output, outgoing) = std_mpsc::channel::<String>();
+    let writer = std::thread::spawn(move || {
+        let mut stdout = io::stdout();
+        for message in outgoing {
+            if let Err(e) = framing.write_message(&mut stdout, &message) {
+                tracing::warn!(error = %e, "failed to write to stdout");
+                break;
+            }
+        }
+    });
+
+    let notifications = output.clone();
+    server.set_notifier(Box::new(move |notification| {
+        match serde_json::to_string(&notification) {
+            Ok(json) => {
+                let _ = notifications.send(json);
+            }
+            Err(e) => tracing::warn!(error = %e, "failed to serialize notification"),
+        }
+    }));
+    match stats::default_stats_file().map(|path| stats::open_or_create_stats_db(&path)) {
+        Some(Ok(db)) => server.set_stats(db),
+        Some(Err(e)) => tracing::warn!(error = %e, "tool usage will not be recorded"),
+        None => tracing::warn!("no home directory; tool usage will not be recorded"),
+    }
+
+    match memory::open_global_memory_db() {
+        Ok(db) => server.set_global_memory(db),
+        Err(e) => tracing::warn!(error = %e, "global memory will not be available"),
+    }
 
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("Failed to start async runtime")?;
+    let pending = server.pending_requests();
+    let result = runtime.block_on(serve(
+        Arc::new(RwLock::new(server)),
+        pending,
+        framing,
+        output,
+    ));
+    writer.join().ok();
+    result
+}
 
-    for line in stdin.lock().lines() {
-        let line = line.context("Failed to read from stdin")?;
-        if line.is_empty() {
+/// Reads messages until stdin closes, an `exit` notification arrives, or the
+/// process is asked to terminate, handling each request on the blocking pool
+/// so slow tools don't hold up the others. Requests already in flight are
+/// finished before returning.
+async fn serve(
+    server: Arc<RwLock<Server>>,
+    pending: Arc<PendingRequests>,
+    framing: Framing,
+    output: std_mpsc::Sender<String>,
+) -> Result<()> {
+    let (incoming, mut messages) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            let message = framing.read_message(&mut stdin).transpose();
+            let done = !matches!(message, Some(Ok(_)));
+            if let Some(message) = message {
+                if incoming.send(message).is_err() {
+                    break;
+                }
+            }
+            if done {
+                break;
+            }
+        }
+    });
+
+    let mut in_flight = JoinSet::new();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    let result = loop {
+        let message = tokio::select! {
+            message = messages.recv() => message,
+            () = &mut shutdown => {
+                tracing::info!("received termination signal, shutting down");
+                break Ok(());
+            }
+        };
+        let message = match message {
+            Some(Ok(message)) => message,
+            Some(Err(e)) => break Err(anyhow::Error::new(e).context("Failed to read from stdin")),
+            None => {
+                tracing::info!("stdin closed, shutting down");
+                break Ok(());
+            }
+        };
+        if message.trim().is_empty() {
             continue;
         }
 
-        let request: JsonRpcRequest = match serde_json::from_str(&line) {
+        // Answers to requests we sent the client are handed straight to the
+        // tool call waiting on them rather than queued behind the server lock.
+        if let Some(response) = JsonRpcClientResponse::parse(&message) {
+            let Some(response) = pending.deliver(response) else {
+                continue;
+            };
+            let server = Arc::clone(&server);
+            in_flight.spawn_blocking(move || {
+                let mut server = server.write().unwrap_or_else(PoisonError::into_inner);
+                server.handle_response(response);
+            });
+            continue;
+        }
+
+        let request: JsonRpcRequest = match serde_json::from_str(&message) {
             Ok(req) => req,
             Err(e) => {
+                tracing::warn!(error = %e, "failed to parse JSON-RPC message");
                 let error_response = JsonRpcResponse {
                     jsonrpc: "2.0".to_string(),
                     id: None,
                     result: None,
-                    error: Some(JsonRpcError {
-                        code: -32700,
-                        message: format!("Parse error: {}", e),
-                        data: None,
-                    }),
+                    error: Some(JsonRpcError::new(
+                        PARSE_ERROR,
+                        format!("Parse error: {}", e),
+                    )),
                 };
-                let response_json = serde_json::to_string(&error_response)?;
-                writeln!(stdout, "{}", response_json)?;
-                stdout.flush()?;
+                let _ = output.send(serde_json::to_string(&error_response)?);
                 continue;
             }
         };
 
-        let response = server.handle_request(request);
-        let response_json = serde_json::to_string(&response)?;
-        writeln!(stdout, "{}", response_json)?;
-        stdout.flush()?;
+        if request.method == "exit" {
+            let server = server.read().unwrap_or_else(PoisonError::into_inner);
+            server.handle_shared_request(request);
+            if server.exit_requested() {
+                tracing::info!("exit requested, shutting down");
+                break Ok(());
+            }
+            continue;
+        }
+
+        let server = Arc::clone(&server);
+        let output = output.clone();
+        in_flight.spawn_blocking(move || {
+            let response = if Server::needs_exclusive(&request) {
+                let mut server = server.write().unwrap_or_else(PoisonError::into_inner);
+                server.handle_request(request)
+            } else if Server::needs_sampling(&request) {
+                Server::handle_sampling_request(&server, request)
+            } else {
+                let server = server.read().unwrap_or_else(PoisonError::into_inner);
+                server.handle_shared_request(request)
+            };
+            if let Some(response) = response {
+                match serde_json::to_string(&response) {
+                    Ok(json) => {
+                        let _ = output.send(json);
+                    }
+                    Err(e) => tracing::warn!(error = %e, "failed to serialize response"),
+                }
+            }
+        });
+    };
+
+    // Let requests that are already running finish their writes.
+    while in_flight.join_next().await.is_some() {}
+    result
+}
+
+/// Resolves on Ctrl-C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to listen for SIGTERM");
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
     }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_tool_arg() {
+        let text = |key: &str, value: &str| (key.to_string(), ToolArg::Text(value.to_string()));
+        assert_eq!(
+            parse_tool_arg("project=foo").unwrap(),
+            text("project", "foo")
+        );
+        assert_eq!(
+            parse_tool_arg("confirm=true").unwrap(),
+            text("confirm", "true")
+        );
+        assert_eq!(parse_tool_arg("query=a=b").unwrap(), text("query", "a=b"));
+        assert_eq!(
+            parse_tool_arg("files:=[\"a.rs\",\"b.rs\"]").unwrap(),
+            ("files".to_string(), ToolArg::Json(json!(["a.rs", "b.rs"])))
+        );
+        assert!(parse_tool_arg("files:=[oops").is_err());
+        assert!(parse_tool_arg("project").is_err());
+    }
+
+    #[test]
+    fn test_tool_arguments_follow_schema() {
+        let schema = json!({"inputSchema": {"properties": {
+            "key": {"type": "string"},
+            "max_chars": {"type": "integer"},
+            "confirm": {"type": "boolean"},
+            "value": {"type": ["string", "number"]}
+        }}});
+        let args = [
+            "key=123",
+            "max_chars=2000",
+            "confirm=true",
+            "value=42",
+            "other=7",
+            "raw:=7",
+        ]
+        .into_iter()
+        .map(|arg| parse_tool_arg(arg).unwrap())
+        .collect();
+        assert_eq!(
+            serde_json::Value::Object(tool_arguments(Some(&schema), args)),
+            json!({
+                "key": "123",
+                "max_chars": 2000,
+                "confirm": true,
+                "value": "42",
+                "other": "7",
+                "raw": 7
+            })
+        );
+    }
 }
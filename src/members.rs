@@ -0,0 +1,268 @@
+//! Member projects listed by workspace manifests at the workspace root:
+//! `pnpm-workspace.yaml` `packages`, `Cargo.toml` `[workspace].members`, and
+//! `go.work` `use` directives.
+//!
+//! Discovery itself still walks for Jumble configs; the member lists are only
+//! used to report which members have no Jumble context yet.
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directories never searched for members.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target"];
+
+/// The workspace manifests read, each with the file that marks a member
+/// directory.
+const MANIFESTS: &[(&str, &str)] = &[
+    ("pnpm-workspace.yaml", "package.json"),
+    ("Cargo.toml", "Cargo.toml"),
+    ("go.work", "go.mod"),
+];
+
+/// The members one workspace manifest lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceMembers {
+    /// The manifest at the workspace root.
+    pub manifest: PathBuf,
+    /// Member directories, sorted.
+    pub members: Vec<PathBuf>,
+}
+
+/// Reads every workspace manifest at `root` that lists members. Manifests
+/// that are missing, unreadable, or list no members are skipped.
+pub fn workspace_members(root: &Path) -> Vec<WorkspaceMembers> {
+    MANIFESTS
+        .iter()
+        .filter_map(|(file, marker)| {
+            let manifest = root.join(file);
+            let content = std::fs::read_to_string(&manifest).ok()?;
+            let (include, exclude) = match *file {
+                "pnpm-workspace.yaml" => pnpm_patterns(&content)?,
+                "Cargo.toml" => cargo_patterns(&content)?,
+                _ => (go_work_patterns(&content), Vec::new()),
+            };
+            let members = expand(root, &include, &exclude, marker);
+            (!members.is_empty()).then_some(WorkspaceMembers { manifest, members })
+        })
+        .collect()
+}
+
+/// `packages` globs; entries starting with `!` exclude.
+fn pnpm_patterns(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+    let value: serde_yaml::Value = serde_yaml::from_str(content).ok()?;
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    for pattern in value.get("packages")?.as_sequence()? {
+        let Some(pattern) = pattern.as_str() else {
+            continue;
+        };
+        match pattern.strip_prefix('!') {
+            Some(excluded) => exclude.push(excluded.to_string()),
+            None => include.push(pattern.to_string()),
+        }
+    }
+    Some((include, exclude))
+}
+
+/// `[workspace]` `members` and `exclude`.
+fn cargo_patterns(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let workspace = value.get("workspace")?;
+    let list = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    };
+    Some((list("members"), list("exclude")))
+}
+
+/// Paths from `use ./dir` and `use ( ... )` blocks.
+fn go_work_patterns(content: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                paths.push(line.trim_matches('"').to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                paths.push(rest.trim_matches('"').to_string());
+            }
+        }
+    }
+    paths
+}
+
+/// Directories under `root` matching an `include` pattern and no `exclude`
+/// pattern that contain `marker`.
+fn expand(root: &Path, include: &[String], exclude: &[String], marker: &str) -> Vec<PathBuf> {
+    let (Some(include), Some(exclude)) = (glob_set(include), glob_set(exclude)) else {
+        return Vec::new();
+    };
+    // Without `**` no member can sit deeper than the longest pattern.
+    let max_depth = if include.patterns.iter().any(|p| p.contains("**")) {
+        usize::MAX
+    } else {
+        include
+            .patterns
+            .iter()
+            .map(|p| p.split('/').count())
+            .max()
+            .unwrap_or(0)
+    };
+    let mut members: Vec<PathBuf> = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.file_type().is_dir()
+                && !name.starts_with('.')
+                && !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .flatten()
+        .filter(|entry| {
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            include.set.is_match(relative)
+                && !exclude.set.is_match(relative)
+                && entry.path().join(marker).is_file()
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+    members.sort();
+    members
+}
+
+struct Patterns {
+    patterns: Vec<String>,
+    set: GlobSet,
+}
+
+/// Compiles manifest patterns, where `*` stays within one path component.
+/// `None` if any pattern is invalid.
+fn glob_set(patterns: &[String]) -> Option<Patterns> {
+    let patterns: Vec<String> = patterns
+        .iter()
+        .map(|p| {
+            let p = p.trim_start_matches("./").trim_end_matches('/');
+            p.to_string()
+        })
+        .filter(|p| !p.is_empty() && p != ".")
+        .collect();
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .ok()?;
+        builder.add(glob);
+    }
+    Some(Patterns {
+        set: builder.build().ok()?,
+        patterns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, file: &str, content: &str) {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_cargo_members_with_globs_and_excludes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/legacy\"]\n",
+        );
+        for dir in [
+            "crates/core",
+            "crates/legacy",
+            "tools/cli",
+            "crates/core/nested",
+        ] {
+            write(root, &format!("{}/Cargo.toml", dir), "[package]\n");
+        }
+        std::fs::create_dir_all(root.join("crates/empty")).unwrap();
+
+        let found = workspace_members(root);
+        assert_eq!(
+            found,
+            [WorkspaceMembers {
+                manifest: root.join("Cargo.toml"),
+                members: vec![root.join("crates/core"), root.join("tools/cli")],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_pnpm_and_go_work_members() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'packages/**'\n  - '!**/fixtures/**'\n",
+        );
+        for dir in [
+            "packages/ui",
+            "packages/apps/web",
+            "packages/ui/fixtures/app",
+        ] {
+            write(root, &format!("{}/package.json", dir), "{}");
+        }
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./svc/api // main API\n\t./svc/worker\n)\nuse ./tools\n",
+        );
+        for dir in ["svc/api", "svc/worker", "tools"] {
+            write(root, &format!("{}/go.mod", dir), "module x\n");
+        }
+
+        let found = workspace_members(root);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].manifest, root.join("pnpm-workspace.yaml"));
+        assert_eq!(
+            found[0].members,
+            [root.join("packages/apps/web"), root.join("packages/ui")]
+        );
+        assert_eq!(found[1].manifest, root.join("go.work"));
+        assert_eq!(
+            found[1].members,
+            [
+                root.join("svc/api"),
+                root.join("svc/worker"),
+                root.join("tools")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manifests_without_members_are_skipped() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "Cargo.toml", "[package]\nname = \"app\"\n");
+        write(temp.path(), "pnpm-workspace.yaml", "not: [valid");
+        assert!(workspace_members(temp.path()).is_empty());
+    }
+}
@@ -1,15 +1,31 @@
 //! Memory storage for AI agents.
 //!
-//! This module provides persistent JSON-backed storage for AI agents to store
-//! and retrieve learned information, preferences, and context over time.
+//! This module provides persistent storage for AI agents to store and
+//! retrieve learned information, preferences, and context over time. Each
+//! project keeps its memories in `.jumble/` in one of several formats (see
+//! [`MemoryFormat`]); `jumble memory migrate` converts between them.
 
-use rustbreak::{deser::Ron, FileDatabase};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::ValueEnum;
+use fd_lock::RwLock;
+use rusqlite::{params, Connection, TransactionBehavior};
+use rustbreak::deser::{DeSerializer, Ron};
+use rustbreak::error::{DeSerError, DeSerResult};
+use rustbreak::FileDatabase;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs::{File, OpenOptions};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::audit;
+use crate::memory_audit::{self, MemoryAction};
 
 /// A single memory entry with metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MemoryEntry {
     /// The stored value.
     pub value: String,
@@ -17,18 +33,265 @@ pub struct MemoryEntry {
     pub timestamp: String,
     /// Optional source identifier (e.g., which agent or tool stored this).
     pub source: Option<String>,
+    /// Where the entry was promoted to by `promote_memory`, e.g. `gotchas.async`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promoted: Option<String>,
 }
 
 /// Memory database type: a simple key-value store.
 pub type MemoryDb = HashMap<String, MemoryEntry>;
 
-/// Type alias for the FileDatabase used in memory storage.
-pub type MemoryDatabase = FileDatabase<MemoryDb, Ron>;
+/// On-disk formats for a project's memory store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MemoryFormat {
+    /// `.jumble/memory.ron` (the default for new projects)
+    Ron,
+    /// `.jumble/memory.json`
+    Json,
+    /// `.jumble/memory.db`, a SQLite database
+    Sqlite,
+}
+
+impl MemoryFormat {
+    /// Formats in the order [`MemoryFormat::detect`] looks for them.
+    const DETECTION_ORDER: [MemoryFormat; 3] =
+        [MemoryFormat::Sqlite, MemoryFormat::Json, MemoryFormat::Ron];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            MemoryFormat::Ron => "memory.ron",
+            MemoryFormat::Json => "memory.json",
+            MemoryFormat::Sqlite => "memory.db",
+        }
+    }
+
+    /// Location of a store in this format for a project.
+    pub fn path(self, project_root: &Path) -> PathBuf {
+        project_root.join(".jumble").join(self.file_name())
+    }
+
+    /// The format of the store a project already has, or RON if it has none.
+    pub fn detect(project_root: &Path) -> MemoryFormat {
+        Self::DETECTION_ORDER
+            .into_iter()
+            .find(|format| format.path(project_root).exists())
+            .unwrap_or(MemoryFormat::Ron)
+    }
+}
+
+/// Pretty-printed JSON for [`FileDatabase`].
+#[derive(Debug, Default, Clone)]
+pub struct Json;
+
+impl DeSerializer<MemoryDb> for Json {
+    fn serialize(&self, val: &MemoryDb) -> DeSerResult<Vec<u8>> {
+        serde_json::to_vec_pretty(val).map_err(|e| DeSerError::Other(e.into()))
+    }
+
+    fn deserialize<R: Read>(&self, s: R) -> DeSerResult<MemoryDb> {
+        serde_json::from_reader(s).map_err(|e| DeSerError::Other(e.into()))
+    }
+}
+
+/// A project's memory store, safe to share between jumble instances.
+///
+/// Several servers (one per agent) may point at the same project, so every
+/// access reloads the store under a lock: reads see a consistent snapshot,
+/// and [`MemoryDatabase::update`] holds an exclusive lock for the whole
+/// reload-modify-save cycle, so concurrent writes can't drop each other's
+/// entries. File stores take an advisory lock on `.jumble/memory.lock`;
+/// SQLite stores use transactions.
+pub struct MemoryDatabase {
+    /// The mutex serializes access between threads, which share one handle
+    /// and so can't exclude each other through the file lock alone.
+    store: Mutex<Store>,
+    /// Where [`MemoryDatabase::update_audited`] records changes.
+    audit_log: PathBuf,
+}
+
+enum Store {
+    Ron {
+        db: FileDatabase<MemoryDb, Ron>,
+        /// Handle on `.jumble/memory.lock`, used only for locking. The lock
+        /// file is never rewritten, so instances exclude each other however
+        /// the store file itself gets written or replaced.
+        lock_file: RwLock<File>,
+    },
+    Json {
+        db: FileDatabase<MemoryDb, Json>,
+        lock_file: RwLock<File>,
+    },
+    Sqlite(Connection),
+}
+
+impl MemoryDatabase {
+    /// Runs `f` on the current contents of the store.
+    pub fn read<T>(&self, f: impl FnOnce(&MemoryDb) -> T) -> Result<T, String> {
+        let mut store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        match &mut *store {
+            Store::Ron { db, lock_file } => read_file(db, lock_file, f),
+            Store::Json { db, lock_file } => read_file(db, lock_file, f),
+            Store::Sqlite(conn) => {
+                let tx = conn
+                    .transaction()
+                    .map_err(|e| format!("Failed to read from memory database: {}", e))?;
+                Ok(f(&load_rows(&tx)?))
+            }
+        }
+    }
+
+    /// Applies `f` to the latest contents of the store and saves the result.
+    pub fn update<T>(&self, f: impl FnOnce(&mut MemoryDb) -> T) -> Result<T, String> {
+        let mut store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        match &mut *store {
+            Store::Ron { db, lock_file } => update_file(db, lock_file, f),
+            Store::Json { db, lock_file } => update_file(db, lock_file, f),
+            Store::Sqlite(conn) => update_sqlite(conn, f),
+        }
+    }
+
+    /// Like [`MemoryDatabase::update`], and records every entry `f` adds,
+    /// changes, or removes in the memory audit log. A failure to write the
+    /// log is logged rather than undoing the change.
+    pub fn update_audited<T>(
+        &self,
+        action: MemoryAction,
+        source: Option<&str>,
+        f: impl FnOnce(&mut MemoryDb) -> T,
+    ) -> Result<T, String> {
+        let (value, changes) = self.update(|db| {
+            let before = db.clone();
+            let value = f(db);
+            (value, memory_audit::changes(action, source, &before, db))
+        })?;
+        for change in &changes {
+            if let Err(e) = audit::append(&self.audit_log, change) {
+                warn!(key = %change.key, error = %e, "failed to write memory audit log");
+                break;
+            }
+        }
+        Ok(value)
+    }
+
+    /// The memory audit log for this store.
+    pub fn audit_log(&self) -> &Path {
+        &self.audit_log
+    }
+}
+
+fn read_file<D: DeSerializer<MemoryDb>, T>(
+    db: &FileDatabase<MemoryDb, D>,
+    lock_file: &RwLock<File>,
+    f: impl FnOnce(&MemoryDb) -> T,
+) -> Result<T, String> {
+    let _guard = lock_file
+        .read()
+        .map_err(|e| format!("Failed to lock memory database: {}", e))?;
+    reload(db)?;
+    db.read(f)
+        .map_err(|e| format!("Failed to read from memory database: {}", e))
+}
+
+fn update_file<D: DeSerializer<MemoryDb>, T>(
+    db: &FileDatabase<MemoryDb, D>,
+    lock_file: &mut RwLock<File>,
+    f: impl FnOnce(&mut MemoryDb) -> T,
+) -> Result<T, String> {
+    let _guard = lock_file
+        .write()
+        .map_err(|e| format!("Failed to lock memory database: {}", e))?;
+    reload(db)?;
+    let value = db
+        .write(f)
+        .map_err(|e| format!("Failed to write to memory database: {}", e))?;
+    db.save()
+        .map_err(|e| format!("Failed to save memory database: {}", e))?;
+    Ok(value)
+}
+
+fn reload<D: DeSerializer<MemoryDb>>(db: &FileDatabase<MemoryDb, D>) -> Result<(), String> {
+    db.load()
+        .map_err(|e| format!("Failed to reload memory database: {}", e))
+}
+
+fn load_rows(conn: &Connection) -> Result<MemoryDb, String> {
+    let read_error = |e: rusqlite::Error| format!("Failed to read from memory database: {}", e);
+    let mut statement = conn
+        .prepare("SELECT key, value, timestamp, source, promoted FROM memories")
+        .map_err(read_error)?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                MemoryEntry {
+                    value: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    source: row.get(3)?,
+                    promoted: row.get(4)?,
+                },
+            ))
+        })
+        .map_err(read_error)?;
+    rows.collect::<Result<MemoryDb, _>>().map_err(read_error)
+}
+
+/// Runs `f` on a copy of the stored rows and writes back only what changed,
+/// inside one immediate (write-locked) transaction.
+fn update_sqlite<T>(
+    conn: &mut Connection,
+    f: impl FnOnce(&mut MemoryDb) -> T,
+) -> Result<T, String> {
+    let write_error = |e: rusqlite::Error| format!("Failed to write to memory database: {}", e);
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(write_error)?;
+    let before = load_rows(&tx)?;
+    let mut after = before.clone();
+    let value = f(&mut after);
+
+    for key in before.keys().filter(|key| !after.contains_key(*key)) {
+        tx.execute("DELETE FROM memories WHERE key = ?1", params![key])
+            .map_err(write_error)?;
+    }
+    for (key, entry) in &after {
+        if before.get(key) != Some(entry) {
+            tx.execute(
+                "INSERT OR REPLACE INTO memories (key, value, timestamp, source, promoted) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    key,
+                    entry.value,
+                    entry.timestamp,
+                    entry.source,
+                    entry.promoted
+                ],
+            )
+            .map_err(write_error)?;
+        }
+    }
+    tx.commit().map_err(write_error)?;
+    Ok(value)
+}
+
+/// Opens `memory.lock` next to a file store, creating it when missing. The
+/// lock file is never written or replaced, so every instance locks the same
+/// file.
+fn open_lock_file(memory_path: &Path) -> std::io::Result<RwLock<File>> {
+    let path = memory_path.with_file_name("memory.lock");
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    Ok(RwLock::new(file))
+}
 
 /// Opens or creates a memory database for a project.
 ///
-/// The database is stored at `<project_root>/.jumble/memory.json`.
-/// If the file doesn't exist, it will be created with an empty HashMap.
+/// The database is stored in `<project_root>/.jumble/`, in whichever format
+/// the project already uses (see [`MemoryFormat::detect`]). If there is no
+/// store yet, an empty `memory.ron` is created.
 ///
 /// # Arguments
 /// * `project_root` - The root directory of the project (where `.jumble/` is located).
@@ -37,7 +300,12 @@ pub type MemoryDatabase = FileDatabase<MemoryDb, Ron>;
 /// * `Ok(MemoryDatabase)` - Successfully opened or created the database.
 /// * `Err(String)` - Failed to open/create the database.
 pub fn open_or_create_memory_db(project_root: &Path) -> Result<MemoryDatabase, String> {
-    let memory_path = project_root.join(".jumble/memory.ron");
+    open_memory_db(project_root, MemoryFormat::detect(project_root))
+}
+
+/// Opens or creates the store in a specific format.
+pub fn open_memory_db(project_root: &Path, format: MemoryFormat) -> Result<MemoryDatabase, String> {
+    let memory_path = format.path(project_root);
 
     // Ensure .jumble directory exists
     if let Some(parent) = memory_path.parent() {
@@ -45,11 +313,108 @@ pub fn open_or_create_memory_db(project_root: &Path) -> Result<MemoryDatabase, S
             .map_err(|e| format!("Failed to create .jumble directory: {}", e))?;
     }
 
-    // Open or create the database
-    let db = FileDatabase::<MemoryDb, Ron>::load_from_path_or(memory_path, HashMap::new())
-        .map_err(|e| format!("Failed to open memory database: {}", e))?;
+    let open_error = |e: &dyn std::fmt::Display| format!("Failed to open memory database: {}", e);
+    let store = match format {
+        MemoryFormat::Ron => Store::Ron {
+            db: FileDatabase::load_from_path_or(&memory_path, HashMap::new())
+                .map_err(|e| open_error(&e))?,
+            lock_file: open_lock_file(&memory_path).map_err(|e| open_error(&e))?,
+        },
+        MemoryFormat::Json => Store::Json {
+            db: FileDatabase::load_from_path_or(&memory_path, HashMap::new())
+                .map_err(|e| open_error(&e))?,
+            lock_file: open_lock_file(&memory_path).map_err(|e| open_error(&e))?,
+        },
+        MemoryFormat::Sqlite => {
+            let conn = Connection::open(&memory_path).map_err(|e| open_error(&e))?;
+            conn.busy_timeout(Duration::from_secs(5))
+                .map_err(|e| open_error(&e))?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS memories (\
+                 key TEXT PRIMARY KEY, \
+                 value TEXT NOT NULL, \
+                 timestamp TEXT NOT NULL, \
+                 source TEXT, \
+                 promoted TEXT)",
+                [],
+            )
+            .map_err(|e| open_error(&e))?;
+            add_promoted_column(&conn).map_err(|e| open_error(&e))?;
+            Store::Sqlite(conn)
+        }
+    };
+
+    Ok(MemoryDatabase {
+        store: Mutex::new(store),
+        audit_log: memory_audit::memory_audit_log_path(project_root),
+    })
+}
+
+/// Adds the `promoted` column to tables created before `promote_memory`
+/// existed.
+fn add_promoted_column(conn: &Connection) -> rusqlite::Result<()> {
+    let mut columns = conn.prepare("PRAGMA table_info(memories)")?;
+    let has_promoted = columns
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .iter()
+        .any(|name| name == "promoted");
+    if !has_promoted {
+        conn.execute("ALTER TABLE memories ADD COLUMN promoted TEXT", [])?;
+    }
+    Ok(())
+}
+
+/// Converts a project's memory store to `to`, keeping every entry and its
+/// timestamp. The old file is kept alongside as `<name>.bak`.
+pub fn migrate(project_root: &Path, to: MemoryFormat) -> Result<String, String> {
+    let from = MemoryFormat::detect(project_root);
+    let from_path = from.path(project_root);
+    if !from_path.exists() {
+        return Ok("no memory store".to_string());
+    }
+    if from == to {
+        return Ok(format!("already uses {}", to.file_name()));
+    }
+    let to_path = to.path(project_root);
+    if to_path.exists() {
+        return Err(format!(
+            "{} already exists; move it aside before migrating",
+            to_path.display()
+        ));
+    }
+
+    let entries = open_memory_db(project_root, from)?.read(|db| db.clone())?;
+    let target = open_memory_db(project_root, to)?;
+    target.update(|db| *db = entries.clone())?;
+    let verified = target.read(|db| db == &entries)?;
+    drop(target);
+    if !verified {
+        let _ = std::fs::remove_file(&to_path);
+        return Err(format!(
+            "Entries read back from {} did not match; the original store was left in place",
+            to_path.display()
+        ));
+    }
+
+    let backup = from_path.with_file_name(format!("{}.bak", from.file_name()));
+    std::fs::rename(&from_path, &backup)
+        .map_err(|e| format!("Failed to move {} aside: {}", from_path.display(), e))?;
+    Ok(format!(
+        "migrated {} memor{} from {} to {} (previous store kept as {})",
+        entries.len(),
+        if entries.len() == 1 { "y" } else { "ies" },
+        from.file_name(),
+        to.file_name(),
+        backup.display()
+    ))
+}
 
-    Ok(db)
+/// Opens or creates the user-level store at `~/.jumble/memory.ron` (or
+/// `memory.json`/`memory.db`), shared by every project.
+pub fn open_global_memory_db() -> Result<MemoryDatabase, String> {
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    open_or_create_memory_db(&home)
 }
 
 /// Generates an ISO 8601 timestamp for the current time.
@@ -57,9 +422,60 @@ pub fn current_timestamp() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
+/// Parses an RFC 3339 timestamp, or a `YYYY-MM-DD` date taken as midnight UTC.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// A stored timestamp as `YYYY-MM-DD HH:MM UTC`, or as stored when it does
+/// not parse.
+pub fn display_timestamp(value: &str) -> String {
+    parse_timestamp(value)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// One entry as printed by `jumble memory list` and `get`: the key with when
+/// and by whom it was stored, then the value indented below.
+pub fn format_entry(key: &str, entry: &MemoryEntry) -> String {
+    let mut header = format!("{}  {}", key, display_timestamp(&entry.timestamp));
+    if let Some(source) = &entry.source {
+        header.push_str(&format!("  source: {}", source));
+    }
+    if let Some(target) = &entry.promoted {
+        header.push_str(&format!("  promoted to: {}", target));
+    }
+    let value: Vec<String> = entry
+        .value
+        .lines()
+        .map(|line| format!("    {}", line))
+        .collect();
+    format!("{}\n{}\n", header, value.join("\n"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_format_entry() {
+        let entry = MemoryEntry {
+            value: "Run migrations first\nthen deploy".to_string(),
+            timestamp: "2026-03-04T05:06:07+02:00".to_string(),
+            source: Some("cli".to_string()),
+            promoted: None,
+        };
+        assert_eq!(
+            format_entry("deploy", &entry),
+            "deploy  2026-03-04 03:06 UTC  source: cli\n    Run migrations first\n    then deploy\n"
+        );
+        assert_eq!(display_timestamp("yesterday"), "yesterday");
+    }
     use std::fs;
     use tempfile::TempDir;
 
@@ -78,20 +494,19 @@ mod tests {
         assert!(project_root.join(".jumble/memory.ron").exists());
 
         // Write some data
-        db.write(|db_data| {
+        db.update(|db_data| {
             db_data.insert(
                 "test_key".to_string(),
                 MemoryEntry {
                     value: "test_value".to_string(),
                     timestamp: current_timestamp(),
                     source: Some("test".to_string()),
+                    promoted: None,
                 },
             );
         })
         .unwrap();
 
-        db.save().unwrap();
-
         // Open again and verify data persisted
         let db2 = open_or_create_memory_db(&project_root).unwrap();
         db2.read(|db_data| {
@@ -108,4 +523,183 @@ mod tests {
         assert!(ts.contains('T'));
         assert!(ts.contains('Z') || ts.contains('+'));
     }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let time = parse_timestamp("2026-10-17T09:30:00+02:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-10-17T07:30:00+00:00");
+        let date = parse_timestamp("2026-10-17").unwrap();
+        assert_eq!(date.to_rfc3339(), "2026-10-17T00:00:00+00:00");
+        assert!(parse_timestamp("last week").is_none());
+    }
+
+    #[test]
+    fn test_concurrent_instances_keep_all_entries() {
+        for format in [MemoryFormat::Ron, MemoryFormat::Json, MemoryFormat::Sqlite] {
+            let temp_dir = TempDir::new().unwrap();
+            let root = temp_dir.path();
+            let first = open_memory_db(root, format).unwrap();
+            let second = open_memory_db(root, format).unwrap();
+
+            std::thread::scope(|scope| {
+                for (name, db) in [("first", &first), ("second", &second)] {
+                    scope.spawn(move || {
+                        for i in 0..20 {
+                            db.update(|data| {
+                                data.insert(
+                                    format!("{}-{}", name, i),
+                                    MemoryEntry {
+                                        value: i.to_string(),
+                                        timestamp: current_timestamp(),
+                                        source: None,
+                                        promoted: None,
+                                    },
+                                );
+                            })
+                            .unwrap();
+                        }
+                    });
+                }
+            });
+
+            assert_eq!(first.read(|data| data.len()).unwrap(), 40, "{:?}", format);
+            let reopened = open_or_create_memory_db(root).unwrap();
+            assert_eq!(
+                reopened.read(|data| data.len()).unwrap(),
+                40,
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_instance_opened_after_save_shares_lock() {
+        for format in [MemoryFormat::Ron, MemoryFormat::Json] {
+            let temp_dir = TempDir::new().unwrap();
+            let root = temp_dir.path();
+            let entry = |value: &str| MemoryEntry {
+                value: value.to_string(),
+                timestamp: current_timestamp(),
+                source: None,
+                promoted: None,
+            };
+            let first = open_memory_db(root, format).unwrap();
+            first
+                .update(|data| {
+                    data.insert("seed".to_string(), entry("0"));
+                })
+                .unwrap();
+            let second = open_memory_db(root, format).unwrap();
+
+            // The second instance writes while the first is mid-update; it
+            // must wait for the first to save rather than be overwritten.
+            std::thread::scope(|scope| {
+                first
+                    .update(|data| {
+                        scope.spawn(|| {
+                            second
+                                .update(|data| {
+                                    data.insert("second".to_string(), entry("2"));
+                                })
+                                .unwrap();
+                        });
+                        std::thread::sleep(Duration::from_millis(200));
+                        data.insert("first".to_string(), entry("1"));
+                    })
+                    .unwrap();
+            });
+
+            let reopened = open_memory_db(root, format).unwrap();
+            let mut keys = reopened
+                .read(|data| data.keys().cloned().collect::<Vec<_>>())
+                .unwrap();
+            keys.sort();
+            assert_eq!(keys, ["first", "second", "seed"], "{:?}", format);
+            assert!(root.join(".jumble/memory.lock").exists());
+        }
+    }
+
+    #[test]
+    fn test_migrate_preserves_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let entries: MemoryDb = [
+            ("api/auth", "2026-10-01T08:00:00+00:00", Some("agent")),
+            ("build", "2026-10-02T09:30:00+00:00", None),
+        ]
+        .into_iter()
+        .map(|(key, timestamp, source)| {
+            let entry = MemoryEntry {
+                value: format!("notes on {}", key),
+                timestamp: timestamp.to_string(),
+                source: source.map(str::to_string),
+                promoted: None,
+            };
+            (key.to_string(), entry)
+        })
+        .collect();
+        open_or_create_memory_db(root)
+            .unwrap()
+            .update(|db| *db = entries.clone())
+            .unwrap();
+
+        let message = migrate(root, MemoryFormat::Sqlite).unwrap();
+        assert!(message.starts_with("migrated 2 memories from memory.ron to memory.db"));
+        assert_eq!(MemoryFormat::detect(root), MemoryFormat::Sqlite);
+        assert!(root.join(".jumble/memory.ron.bak").exists());
+        assert!(!root.join(".jumble/memory.ron").exists());
+
+        let sqlite = open_or_create_memory_db(root).unwrap();
+        assert_eq!(sqlite.read(|db| db.clone()).unwrap(), entries);
+        sqlite.update(|db| db.remove("build")).unwrap();
+        assert_eq!(sqlite.read(|db| db.len()).unwrap(), 1);
+        drop(sqlite);
+
+        migrate(root, MemoryFormat::Json).unwrap();
+        assert_eq!(MemoryFormat::detect(root), MemoryFormat::Json);
+        let json = std::fs::read_to_string(root.join(".jumble/memory.json")).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["api/auth"]["timestamp"], "2026-10-01T08:00:00+00:00");
+        assert_eq!(value["api/auth"]["source"], "agent");
+
+        assert_eq!(
+            migrate(root, MemoryFormat::Json).unwrap(),
+            "already uses memory.json"
+        );
+        std::fs::write(root.join(".jumble/memory.ron"), "{}").unwrap();
+        assert!(migrate(root, MemoryFormat::Ron)
+            .unwrap_err()
+            .contains("already exists"));
+    }
+
+    #[test]
+    fn test_sqlite_store_without_promoted_column() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let path = MemoryFormat::Sqlite.path(root);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE memories (key TEXT PRIMARY KEY, value TEXT NOT NULL, \
+             timestamp TEXT NOT NULL, source TEXT); \
+             INSERT INTO memories VALUES ('build', 'use make', '2026-10-01T08:00:00+00:00', NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let db = open_memory_db(root, MemoryFormat::Sqlite).unwrap();
+        assert_eq!(db.read(|db| db["build"].value.clone()).unwrap(), "use make");
+        db.update(|db| db.get_mut("build").unwrap().promoted = Some("gotchas.build".to_string()))
+            .unwrap();
+        drop(db);
+
+        let db = open_memory_db(root, MemoryFormat::Sqlite).unwrap();
+        assert_eq!(
+            db.read(|db| db["build"].promoted.clone())
+                .unwrap()
+                .as_deref(),
+            Some("gotchas.build")
+        );
+    }
 }
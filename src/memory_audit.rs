@@ -0,0 +1,216 @@
+//! Audit trail of memory changes.
+//!
+//! Every store, delete, clear, and promote is appended to
+//! `.jumble/memory-audit.log` next to the memory store (or
+//! `~/.jumble/memory-audit.log` for global memories), one JSON object per
+//! changed key, with the entry before and after the change. The log is never
+//! rewritten, so a memory an agent overwrote or deleted can be found and
+//! stored again; `get_memory_audit` reads it back.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::memory::{self, MemoryDb, MemoryEntry};
+
+/// What kind of change was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryAction {
+    Store,
+    Delete,
+    Clear,
+    Promote,
+}
+
+impl MemoryAction {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "store" => Some(MemoryAction::Store),
+            "delete" => Some(MemoryAction::Delete),
+            "clear" => Some(MemoryAction::Clear),
+            "promote" => Some(MemoryAction::Promote),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            MemoryAction::Store => "store",
+            MemoryAction::Delete => "delete",
+            MemoryAction::Clear => "clear",
+            MemoryAction::Promote => "promote",
+        }
+    }
+}
+
+/// One line of the memory audit log: a single key's change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAuditEntry {
+    /// ISO 8601 timestamp of the change.
+    pub timestamp: String,
+    pub action: MemoryAction,
+    pub key: String,
+    /// Who made the change, e.g. an agent name, when the caller gave one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The entry before the change; `None` when the key was new.
+    #[serde(default)]
+    pub old: Option<MemoryEntry>,
+    /// The entry after the change; `None` when the key was removed.
+    #[serde(default)]
+    pub new: Option<MemoryEntry>,
+}
+
+/// Memory audit log location for a project root (or the home directory).
+pub fn memory_audit_log_path(project_root: &Path) -> PathBuf {
+    project_root.join(".jumble").join("memory-audit.log")
+}
+
+/// One entry per key that differs between `before` and `after`, by key.
+pub fn changes(
+    action: MemoryAction,
+    source: Option<&str>,
+    before: &MemoryDb,
+    after: &MemoryDb,
+) -> Vec<MemoryAuditEntry> {
+    let mut keys: Vec<&String> = before
+        .keys()
+        .filter(|key| !after.contains_key(*key))
+        .chain(
+            after
+                .iter()
+                .filter(|(key, entry)| before.get(*key) != Some(entry))
+                .map(|(key, _)| key),
+        )
+        .collect();
+    keys.sort();
+
+    let timestamp = memory::current_timestamp();
+    keys.into_iter()
+        .map(|key| MemoryAuditEntry {
+            timestamp: timestamp.clone(),
+            action,
+            key: key.clone(),
+            source: source.map(str::to_string),
+            old: before.get(key).cloned(),
+            new: after.get(key).cloned(),
+        })
+        .collect()
+}
+
+/// Reads the log, oldest first. A missing log is empty; lines that do not
+/// parse are skipped.
+pub fn read(path: &Path) -> Result<Vec<MemoryAuditEntry>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// One entry as a markdown list item with the values before and after.
+pub fn format_entry(entry: &MemoryAuditEntry) -> String {
+    let mut output = format!(
+        "- **{}** {} `{}`",
+        memory::display_timestamp(&entry.timestamp),
+        entry.action.as_str(),
+        entry.key
+    );
+    if let Some(source) = &entry.source {
+        output.push_str(&format!(" by {}", source));
+    }
+    output.push('\n');
+    match (&entry.old, &entry.new) {
+        // Promoting with `keep` marks the entry without changing its value.
+        (Some(old), Some(new)) if old.value == new.value => {
+            output.push_str(&format!("  - Value: {}\n", new.value));
+            if let Some(target) = new
+                .promoted
+                .as_ref()
+                .filter(|_| old.promoted != new.promoted)
+            {
+                output.push_str(&format!("  - Promoted to: {}\n", target));
+            }
+        }
+        (old, new) => {
+            for (label, value) in [("Old", old), ("New", new)] {
+                if let Some(value) = value {
+                    output.push_str(&format!("  - {}: {}\n", label, value.value));
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit;
+    use tempfile::TempDir;
+
+    fn entry(value: &str) -> MemoryEntry {
+        MemoryEntry {
+            value: value.to_string(),
+            timestamp: "2024-05-01T00:00:00Z".to_string(),
+            source: None,
+            promoted: None,
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let before = MemoryDb::from([
+            ("build".to_string(), entry("cargo build")),
+            ("keep".to_string(), entry("same")),
+            ("old".to_string(), entry("gone")),
+        ]);
+        let after = MemoryDb::from([
+            ("build".to_string(), entry("just build")),
+            ("keep".to_string(), entry("same")),
+            ("new".to_string(), entry("added")),
+        ]);
+
+        let changes = changes(MemoryAction::Store, Some("agent"), &before, &after);
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = changes
+            .iter()
+            .map(|c| {
+                (
+                    c.key.as_str(),
+                    c.old.as_ref().map(|e| e.value.as_str()),
+                    c.new.as_ref().map(|e| e.value.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("build", Some("cargo build"), Some("just build")),
+                ("new", None, Some("added")),
+                ("old", Some("gone"), None),
+            ]
+        );
+        assert!(changes.iter().all(|c| c.source.as_deref() == Some("agent")));
+    }
+
+    #[test]
+    fn test_read_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let path = memory_audit_log_path(temp.path());
+        assert!(read(&path).unwrap().is_empty());
+
+        let before = MemoryDb::from([("build".to_string(), entry("cargo build"))]);
+        for change in changes(MemoryAction::Delete, None, &before, &MemoryDb::new()) {
+            audit::append(&path, &change).unwrap();
+        }
+        let entries = read(&path).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, MemoryAction::Delete);
+        assert_eq!(entries[0].new, None);
+        assert!(format_entry(&entries[0]).contains("delete `build`\n  - Old: cargo build\n"));
+    }
+}
@@ -0,0 +1,206 @@
+//! `jumble migrate`: upgrades `.jumble` files written for an older config
+//! format to [`SCHEMA_VERSION`].
+//!
+//! Files record the format they were written for in `schema_version`; a file
+//! without one predates versioning and counts as version 1. Each format
+//! change that older files would break under is a [`Migration`] to the
+//! version that introduced it. Migrating a directory runs every step newer
+//! than its oldest file, then stamps each TOML file with the current version.
+//! Changes that stay backward compatible, such as table-form `[commands]`
+//! entries next to plain command lines, need no step.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, SCHEMA_VERSION};
+
+/// The version of a file without `schema_version`.
+const UNVERSIONED: u32 = 1;
+
+/// The `.jumble` files that carry a `schema_version`.
+const CONFIG_STEMS: &[&str] = &["project", "workspace", "conventions", "docs"];
+
+/// One format change and how to bring a `.jumble` directory past it.
+struct Migration {
+    /// The version that introduced the change.
+    version: u32,
+    /// Applies the change to a `.jumble` directory, or with `check` only
+    /// reports what it would do. Returns one line per change.
+    apply: fn(jumble_dir: &Path, check: bool) -> Result<Vec<String>>,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 2,
+    apply: move_prompts_to_skills,
+}];
+
+/// Brings the `.jumble` files under `dir` (a project or workspace root) to
+/// the current format. With `check`, nothing is written. Returns one line per
+/// change, so an empty list means the files are up to date.
+pub fn migrate(dir: &Path, check: bool) -> Result<Vec<String>> {
+    let jumble_dir = dir.join(".jumble");
+    let mut changes = Vec::new();
+    let mut files = Vec::new();
+    for path in config_files(dir) {
+        if path.extension().is_none_or(|e| e != "toml") {
+            changes.push(format!(
+                "{}: only TOML files can be rewritten; set schema_version = {} by hand",
+                path.display(),
+                SCHEMA_VERSION
+            ));
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let doc: toml_edit::DocumentMut = content
+            .parse()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        let version = doc
+            .get("schema_version")
+            .and_then(|v| v.as_integer())
+            .map(|v| u32::try_from(v).unwrap_or(u32::MAX));
+        if version.is_some_and(|v| v > SCHEMA_VERSION) {
+            bail!(
+                "{} is written for config format {}, newer than this Jumble supports ({}); upgrade Jumble",
+                path.display(),
+                version.unwrap_or_default(),
+                SCHEMA_VERSION
+            );
+        }
+        files.push((path, doc, version));
+    }
+
+    let oldest = files
+        .iter()
+        .map(|(_, _, version)| version.unwrap_or(UNVERSIONED))
+        .min()
+        .unwrap_or(UNVERSIONED);
+    for migration in MIGRATIONS.iter().filter(|m| m.version > oldest) {
+        changes.extend((migration.apply)(&jumble_dir, check)?);
+    }
+
+    for (path, mut doc, version) in files {
+        if version == Some(SCHEMA_VERSION) {
+            continue;
+        }
+        if !check {
+            doc.insert(
+                "schema_version",
+                toml_edit::value(i64::from(SCHEMA_VERSION)),
+            );
+            std::fs::write(&path, doc.to_string())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        changes.push(format!(
+            "{}: schema_version {} -> {}",
+            path.display(),
+            version.map_or("unset".to_string(), |v| v.to_string()),
+            SCHEMA_VERSION
+        ));
+    }
+    Ok(changes)
+}
+
+/// The config files under `dir`: `.jumble/<stem>.*` and a single-file
+/// `jumble.toml`.
+fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let jumble_dir = dir.join(".jumble");
+    CONFIG_STEMS
+        .iter()
+        .filter_map(|stem| config::find_config_file(&jumble_dir, stem))
+        .chain(config::find_single_file_config(dir))
+        .collect()
+}
+
+/// Version 2: skills moved from `.jumble/prompts/` to `.jumble/skills/`.
+fn move_prompts_to_skills(jumble_dir: &Path, check: bool) -> Result<Vec<String>> {
+    let prompts_dir = jumble_dir.join("prompts");
+    let Ok(entries) = std::fs::read_dir(&prompts_dir) else {
+        return Ok(Vec::new());
+    };
+    let skills_dir = jumble_dir.join("skills");
+    let mut sources: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    sources.sort();
+
+    let mut changes = Vec::new();
+    for source in sources {
+        let Some(name) = source.file_name() else {
+            continue;
+        };
+        let target = skills_dir.join(name);
+        if target.exists() {
+            bail!(
+                "{} and {} both exist; merge them by hand and run `jumble migrate` again",
+                source.display(),
+                target.display()
+            );
+        }
+        if !check {
+            std::fs::create_dir_all(&skills_dir)
+                .with_context(|| format!("Failed to create {}", skills_dir.display()))?;
+            std::fs::rename(&source, &target)
+                .with_context(|| format!("Failed to move {}", source.display()))?;
+        }
+        changes.push(format!("{} -> {}", source.display(), target.display()));
+    }
+    if !check {
+        std::fs::remove_dir(&prompts_dir)
+            .with_context(|| format!("Failed to remove {}", prompts_dir.display()))?;
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_migrate() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let jumble_dir = dir.join(".jumble");
+        std::fs::create_dir_all(jumble_dir.join("prompts")).unwrap();
+        std::fs::write(jumble_dir.join("prompts/deploy.md"), "# Deploy").unwrap();
+        std::fs::write(
+            jumble_dir.join("project.toml"),
+            "# Billing\n[project]\nname = \"billing\"\ndescription = \"Billing\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            jumble_dir.join("docs.toml"),
+            format!("schema_version = {}\n", SCHEMA_VERSION),
+        )
+        .unwrap();
+
+        let planned = migrate(dir, true).unwrap();
+        assert_eq!(planned.len(), 2);
+        assert!(planned[0].ends_with(&format!(
+            "{} -> {}",
+            jumble_dir.join("prompts/deploy.md").display(),
+            jumble_dir.join("skills/deploy.md").display()
+        )));
+        assert!(planned[1].ends_with("project.toml: schema_version unset -> 2"));
+        assert!(jumble_dir.join("prompts/deploy.md").exists());
+
+        assert_eq!(migrate(dir, false).unwrap(), planned);
+        assert!(!jumble_dir.join("prompts").exists());
+        assert!(jumble_dir.join("skills/deploy.md").exists());
+        let project = std::fs::read_to_string(jumble_dir.join("project.toml")).unwrap();
+        assert_eq!(
+            project,
+            "schema_version = 2\n# Billing\n[project]\nname = \"billing\"\ndescription = \"Billing\"\n"
+        );
+        assert!(migrate(dir, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_files() {
+        let temp = TempDir::new().unwrap();
+        let jumble_dir = temp.path().join(".jumble");
+        std::fs::create_dir_all(&jumble_dir).unwrap();
+        std::fs::write(jumble_dir.join("docs.toml"), "schema_version = 99\n").unwrap();
+        let error = migrate(temp.path(), false).unwrap_err().to_string();
+        assert!(error.contains("newer than this Jumble supports"));
+    }
+}
@@ -0,0 +1,160 @@
+//! External tool providers.
+//!
+//! A `[plugins]` table in `~/.jumble/jumble.toml` declares executables that
+//! handle tools of their own, so language-specific analyzers can plug into
+//! jumble without implementing MCP:
+//!
+//! ```toml
+//! [plugins.rust]
+//! command = "jumble-rust-analyzer"
+//! args = ["--stdio"]
+//! timeout_secs = 60
+//!
+//! [plugins.rust.tools.unsafe_report]
+//! description = "Lists unsafe blocks in a crate"
+//! arguments = { project = "The project name" }
+//! required = ["project"]
+//! ```
+//!
+//! Each call spawns the command and writes one JSON request to its stdin:
+//!
+//! ```json
+//! {"tool": "unsafe_report", "arguments": {"project": "api"}, "root": "/work",
+//!  "project": {"name": "api", "path": "/work/api"}}
+//! ```
+//!
+//! `project` is present when the `project` argument names a known project.
+//! The plugin answers on stdout with `{"content": "<markdown>"}` or
+//! `{"error": "<message>"}` and exits.
+
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::config::PluginConfig;
+use crate::process::{read_pipe, wait_with_timeout};
+
+/// How long a plugin may run before it is killed.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Sends `request` to the plugin and returns the markdown it answers with.
+pub fn call(name: &str, plugin: &PluginConfig, request: &Value) -> Result<String, String> {
+    let mut child = Command::new(&plugin.command)
+        .args(&plugin.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start plugin '{}': {}", name, e))?;
+
+    // Read both pipes on their own threads so a chatty plugin can't block on
+    // a full pipe while we wait for it to exit.
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin that exits without reading its input is not an error here;
+        // its exit status and output decide the result.
+        let _ = writeln!(stdin, "{}", request);
+    }
+
+    let timeout = Duration::from_secs(plugin.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    let status = wait_with_timeout(&mut child, timeout)
+        .map_err(|e| format!("Failed to wait for plugin '{}': {}", name, e))?
+        .ok_or_else(|| {
+            format!(
+                "Plugin '{}' timed out after {} seconds",
+                name,
+                timeout.as_secs()
+            )
+        })?;
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        return Err(format!(
+            "Plugin '{}' failed ({}): {}",
+            name,
+            status,
+            stderr.trim()
+        ));
+    }
+    parse_response(name, &stdout)
+}
+
+/// Interprets a plugin's stdout as `{"content": ...}` or `{"error": ...}`.
+fn parse_response(name: &str, stdout: &str) -> Result<String, String> {
+    let response: Value = serde_json::from_str(stdout.trim())
+        .map_err(|e| format!("Plugin '{}' returned invalid JSON: {}", name, e))?;
+    if let Some(error) = response.get("error").and_then(|v| v.as_str()) {
+        return Err(error.to_string());
+    }
+    response
+        .get("content")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("Plugin '{}' returned neither 'content' nor 'error'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Instant;
+
+    fn shell_plugin(script: &str) -> PluginConfig {
+        PluginConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_secs: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_call_round_trips_json() {
+        // Echo the tool name back from the request.
+        let plugin = shell_plugin(
+            r#"read line; tool=$(echo "$line" | sed 's/.*"tool":"\([^"]*\)".*/\1/'); printf '{"content": "ran %s"}' "$tool""#,
+        );
+        let request = json!({"tool": "unsafe_report", "arguments": {}});
+        assert_eq!(
+            call("rust", &plugin, &request).unwrap(),
+            "ran unsafe_report"
+        );
+    }
+
+    #[test]
+    fn test_call_reports_plugin_errors() {
+        let plugin = shell_plugin(r#"echo '{"error": "crate not found"}'"#);
+        assert_eq!(
+            call("rust", &plugin, &json!({})).unwrap_err(),
+            "crate not found"
+        );
+
+        let plugin = shell_plugin("echo broken >&2; exit 3");
+        let err = call("rust", &plugin, &json!({})).unwrap_err();
+        assert!(err.starts_with("Plugin 'rust' failed"));
+        assert!(err.ends_with("broken"));
+
+        let plugin = shell_plugin("echo not json");
+        let err = call("rust", &plugin, &json!({})).unwrap_err();
+        assert!(err.contains("invalid JSON"));
+
+        let plugin = PluginConfig {
+            command: "/nonexistent/jumble-plugin".to_string(),
+            ..Default::default()
+        };
+        let err = call("rust", &plugin, &json!({})).unwrap_err();
+        assert!(err.starts_with("Failed to start plugin 'rust'"));
+    }
+
+    #[test]
+    fn test_call_times_out() {
+        let plugin = shell_plugin("sleep 5");
+        let started = Instant::now();
+        let err = call("slow", &plugin, &json!({})).unwrap_err();
+        assert_eq!(err, "Plugin 'slow' timed out after 1 seconds");
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
@@ -0,0 +1,42 @@
+//! Helpers for running external commands with a time limit, shared by
+//! plugins and environment checks.
+
+use std::io::Read;
+use std::process::{Child, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a running child is checked for exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Reads `pipe` to the end on its own thread, so a chatty child can't block
+/// on a full pipe while we wait for it to exit.
+pub fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut output);
+        }
+        output
+    })
+}
+
+/// Waits for `child` to exit, killing it once `timeout` has passed.
+/// Returns `None` on timeout.
+pub fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> std::io::Result<Option<ExitStatus>> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -1,7 +1,53 @@
 //! MCP Protocol types for JSON-RPC communication.
 
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::sync::{mpsc, Mutex};
+
+/// MCP protocol versions this server speaks, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
+/// Picks the protocol version to answer `initialize` with.
+///
+/// A supported version is echoed back. A newer, unknown version falls back to
+/// our latest one (per MCP, the client then decides whether to continue). A
+/// malformed version or one older than anything we support is rejected.
+pub fn negotiate_protocol_version(requested: Option<&str>) -> Result<&'static str, JsonRpcError> {
+    let latest = SUPPORTED_PROTOCOL_VERSIONS[0];
+    let oldest = SUPPORTED_PROTOCOL_VERSIONS[SUPPORTED_PROTOCOL_VERSIONS.len() - 1];
+    let Some(requested) = requested else {
+        return Ok(latest);
+    };
+
+    if let Some(version) = SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| **v == requested)
+    {
+        return Ok(version);
+    }
+
+    // Versions are ISO dates, so string comparison orders them correctly.
+    let is_date = chrono::NaiveDate::parse_from_str(requested, "%Y-%m-%d").is_ok();
+    if is_date && requested > latest {
+        return Ok(latest);
+    }
+
+    let reason = if is_date {
+        format!("Unsupported protocol version: {}", requested)
+    } else {
+        format!("Invalid protocol version: {}", requested)
+    };
+    Err(
+        JsonRpcError::new(INVALID_PARAMS, reason).with_data(serde_json::json!({
+            "requested": requested,
+            "supported": SUPPORTED_PROTOCOL_VERSIONS,
+            "minimum": oldest,
+        })),
+    )
+}
 
 #[derive(Debug, Deserialize)]
 pub struct JsonRpcRequest {
@@ -32,6 +78,171 @@ pub struct JsonRpcError {
     pub data: Option<Value>,
 }
 
+/// A server-initiated message. Notifications expect no response; requests
+/// carry an `id` and are answered with a [`JsonRpcClientResponse`].
+#[derive(Debug, Serialize)]
+pub struct JsonRpcNotification {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub method: String,
+    pub params: Value,
+}
+
+impl JsonRpcNotification {
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id: None,
+            method: method.into(),
+            params,
+        }
+    }
+
+    /// A server-to-client request, such as MCP `roots/list`.
+    pub fn request(id: Value, method: impl Into<String>, params: Value) -> Self {
+        Self {
+            id: Some(id),
+            ..Self::new(method, params)
+        }
+    }
+
+    /// An MCP `notifications/progress` message for the request that sent `token`.
+    pub fn progress(token: Value, progress: u64, total: Option<u64>, message: String) -> Self {
+        let mut params = serde_json::json!({
+            "progressToken": token,
+            "progress": progress,
+            "message": message,
+        });
+        if let Some(total) = total {
+            params["total"] = total.into();
+        }
+        Self::new("notifications/progress", params)
+    }
+}
+
+/// The client's answer to a request the server sent.
+#[derive(Debug, Deserialize)]
+pub struct JsonRpcClientResponse {
+    pub id: Value,
+    #[serde(default)]
+    pub result: Option<Value>,
+    #[serde(default)]
+    pub error: Option<Value>,
+}
+
+impl JsonRpcClientResponse {
+    /// Parses `message` as a response, i.e. a message with an `id`, no
+    /// `method`, and a `result` or `error`.
+    pub fn parse(message: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(message).ok()?;
+        if value.get("method").is_some() {
+            return None;
+        }
+        let response: Self = serde_json::from_value(value).ok()?;
+        (response.result.is_some() || response.error.is_some()).then_some(response)
+    }
+}
+
+/// Server-to-client requests whose answer a caller is blocked on, such as
+/// `sampling/createMessage` sent during a tool call. The transport loop
+/// delivers answers here without taking the server lock the caller holds.
+#[derive(Debug, Default)]
+pub struct PendingRequests {
+    waiting: Mutex<HashMap<String, mpsc::Sender<JsonRpcClientResponse>>>,
+}
+
+impl PendingRequests {
+    /// Starts waiting for the answer to request `id`.
+    pub fn register(&self, id: &Value) -> mpsc::Receiver<JsonRpcClientResponse> {
+        let (sender, receiver) = mpsc::channel();
+        self.lock().insert(id.to_string(), sender);
+        receiver
+    }
+
+    /// Stops waiting for `id`, e.g. after a timeout.
+    pub fn forget(&self, id: &Value) {
+        self.lock().remove(&id.to_string());
+    }
+
+    /// Hands `response` to whoever is waiting for it, or gives it back when
+    /// nobody is.
+    pub fn deliver(&self, response: JsonRpcClientResponse) -> Option<JsonRpcClientResponse> {
+        match self.lock().remove(&response.id.to_string()) {
+            Some(sender) => sender.send(response).err().map(|e| e.0),
+            None => Some(response),
+        }
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, mpsc::Sender<JsonRpcClientResponse>>> {
+        self.waiting
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// The local path named by a `file://` URI, as sent in MCP roots.
+/// Percent-escapes are decoded; other schemes and remote hosts give `None`.
+pub fn file_uri_to_path(uri: &str) -> Option<std::path::PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !path.starts_with('/') {
+        return None;
+    }
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // `file:///C:/src` names a Windows drive path.
+    let path = match path.get(1..3) {
+        Some(drive) if cfg!(windows) && drive.ends_with(':') => &path[1..],
+        _ => path.as_str(),
+    };
+    Some(std::path::PathBuf::from(path))
+}
+
+// Standard JSON-RPC error codes.
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+/// Implementation-defined server error used for failed tool calls.
+pub const TOOL_ERROR: i32 = -32000;
+
+impl JsonRpcError {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Attaches a machine-readable `data` payload clients can act on.
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
 impl JsonRpcResponse {
     pub fn success(id: Option<Value>, result: Value) -> Self {
         Self {
@@ -52,6 +263,105 @@ impl JsonRpcResponse {
     }
 }
 
+/// How JSON-RPC messages are delimited on stdin/stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Framing {
+    /// One JSON message per line, as in the MCP stdio transport.
+    #[default]
+    Lines,
+    /// `Content-Length` headers followed by a blank line and the body, as in LSP.
+    Lsp,
+}
+
+impl Framing {
+    /// Reads the next message body, or `None` at end of input.
+    pub fn read_message(self, reader: &mut impl BufRead) -> io::Result<Option<String>> {
+        match self {
+            Framing::Lines => {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    return Ok(None);
+                }
+                Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+            }
+            Framing::Lsp => read_lsp_message(reader),
+        }
+    }
+
+    /// Writes one message body and flushes.
+    pub fn write_message(self, writer: &mut impl Write, message: &str) -> io::Result<()> {
+        match self {
+            Framing::Lines => writeln!(writer, "{}", message)?,
+            Framing::Lsp => write!(
+                writer,
+                "Content-Length: {}\r\n\r\n{}",
+                message.len(),
+                message
+            )?,
+        }
+        writer.flush()
+    }
+}
+
+/// Largest message body accepted with LSP framing, so a bad header can't
+/// make the server allocate without bound.
+const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+fn read_lsp_message(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut content_length = None;
+    let mut saw_header = false;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            if saw_header {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "input ended inside message headers",
+                ));
+            }
+            return Ok(None);
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if saw_header {
+                break;
+            }
+            continue;
+        }
+        saw_header = true;
+        // Other headers (e.g. Content-Type) are accepted and ignored.
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                let length = value.trim().parse::<usize>().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid Content-Length: {}", value.trim()),
+                    )
+                })?;
+                if length > MAX_CONTENT_LENGTH {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "Content-Length {} exceeds the {} byte limit",
+                            length, MAX_CONTENT_LENGTH
+                        ),
+                    ));
+                }
+                content_length = Some(length);
+            }
+        }
+    }
+
+    let length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +435,109 @@ mod tests {
         assert!(!serialized.contains("\"result\""));
     }
 
+    #[test]
+    fn test_negotiate_protocol_version() {
+        assert_eq!(
+            negotiate_protocol_version(Some("2024-11-05")).unwrap(),
+            "2024-11-05"
+        );
+        assert_eq!(
+            negotiate_protocol_version(Some("2025-03-26")).unwrap(),
+            "2025-03-26"
+        );
+        // Newer clients and clients that omit the version get our latest.
+        assert_eq!(
+            negotiate_protocol_version(Some("2099-01-01")).unwrap(),
+            "2025-03-26"
+        );
+        assert_eq!(negotiate_protocol_version(None).unwrap(), "2025-03-26");
+
+        let error = negotiate_protocol_version(Some("2023-01-01")).unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert_eq!(error.data.unwrap()["minimum"], "2024-11-05");
+        assert!(negotiate_protocol_version(Some("v1"))
+            .unwrap_err()
+            .message
+            .contains("Invalid"));
+    }
+
+    #[test]
+    fn test_progress_notification_serialization() {
+        let notification = JsonRpcNotification::progress(json!("tok"), 3, None, "x".to_string());
+        let value = serde_json::to_value(&notification).unwrap();
+
+        assert_eq!(value["method"], "notifications/progress");
+        assert_eq!(value["params"]["progressToken"], "tok");
+        assert_eq!(value["params"]["progress"], 3);
+        assert!(value.get("id").is_none());
+        assert!(value["params"].get("total").is_none());
+    }
+
+    #[test]
+    fn test_server_request_serialization() {
+        let request = JsonRpcNotification::request(json!("roots-1"), "roots/list", json!({}));
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["id"], "roots-1");
+        assert_eq!(value["method"], "roots/list");
+    }
+
+    #[test]
+    fn test_parse_client_response() {
+        let response = JsonRpcClientResponse::parse(
+            r#"{"jsonrpc":"2.0","id":"roots-1","result":{"roots":[]}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.id, "roots-1");
+        assert_eq!(response.result, Some(json!({"roots": []})));
+        assert!(
+            JsonRpcClientResponse::parse(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#).is_none()
+        );
+        assert!(JsonRpcClientResponse::parse(r#"{"jsonrpc":"2.0","id":1}"#).is_none());
+    }
+
+    #[test]
+    fn test_pending_requests_deliver_to_waiter() {
+        let pending = PendingRequests::default();
+        let receiver = pending.register(&json!("s-1"));
+        let answer = |id: &str| JsonRpcClientResponse {
+            id: json!(id),
+            result: Some(json!({"ok": true})),
+            error: None,
+        };
+        assert!(pending.deliver(answer("s-1")).is_none());
+        assert_eq!(receiver.recv().unwrap().result, Some(json!({"ok": true})));
+
+        // Unknown or forgotten ids are handed back.
+        assert!(pending.deliver(answer("s-2")).is_some());
+        let _receiver = pending.register(&json!("s-3"));
+        pending.forget(&json!("s-3"));
+        assert!(pending.deliver(answer("s-3")).is_some());
+    }
+
+    #[test]
+    fn test_file_uri_to_path() {
+        assert_eq!(
+            file_uri_to_path("file:///home/dev/my%20app"),
+            Some(std::path::PathBuf::from("/home/dev/my app"))
+        );
+        assert_eq!(
+            file_uri_to_path("file://localhost/srv/work"),
+            Some(std::path::PathBuf::from("/srv/work"))
+        );
+        assert_eq!(file_uri_to_path("file://server/share"), None);
+        assert_eq!(file_uri_to_path("https://example.com/repo"), None);
+    }
+
+    #[test]
+    fn test_error_builder() {
+        let error = JsonRpcError::new(INVALID_PARAMS, "Project 'x' not found")
+            .with_data(json!({"available_projects": ["a", "b"]}));
+        let serialized = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(serialized["code"], -32602);
+        assert_eq!(serialized["data"]["available_projects"][1], "b");
+    }
+
     #[test]
     fn test_error_with_data() {
         let error = JsonRpcError {
@@ -138,4 +551,62 @@ mod tests {
         assert!(serialized.contains("\"data\""));
         assert!(serialized.contains("\"field\":\"project\""));
     }
+
+    #[test]
+    fn test_line_framing() {
+        let mut input = io::Cursor::new("{\"a\":1}\r\n{\"b\":2}\n");
+        assert_eq!(
+            Framing::Lines.read_message(&mut input).unwrap().unwrap(),
+            "{\"a\":1}"
+        );
+        assert_eq!(
+            Framing::Lines.read_message(&mut input).unwrap().unwrap(),
+            "{\"b\":2}"
+        );
+        assert_eq!(Framing::Lines.read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_lsp_framing_round_trip() {
+        let mut output = Vec::new();
+        Framing::Lsp
+            .write_message(&mut output, "{\"id\":1}")
+            .unwrap();
+        Framing::Lsp
+            .write_message(&mut output, "{\"é\":2}")
+            .unwrap();
+        assert!(output.starts_with(b"Content-Length: 8\r\n\r\n{\"id\":1}"));
+
+        let mut input = io::Cursor::new(output);
+        assert_eq!(
+            Framing::Lsp.read_message(&mut input).unwrap().unwrap(),
+            "{\"id\":1}"
+        );
+        assert_eq!(
+            Framing::Lsp.read_message(&mut input).unwrap().unwrap(),
+            "{\"é\":2}"
+        );
+        assert_eq!(Framing::Lsp.read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_lsp_framing_headers() {
+        let raw = "content-type: application/json\r\nContent-Length: 2\r\n\r\n{}";
+        let mut input = io::Cursor::new(raw);
+        assert_eq!(
+            Framing::Lsp.read_message(&mut input).unwrap().unwrap(),
+            "{}"
+        );
+
+        let mut input = io::Cursor::new("Content-Type: x\r\n\r\n{}");
+        let err = Framing::Lsp.read_message(&mut input).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let raw = format!("Content-Length: {}\r\n\r\n{{}}", MAX_CONTENT_LENGTH + 1);
+        let err = Framing::Lsp
+            .read_message(&mut io::Cursor::new(raw))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("exceeds"));
+    }
 }
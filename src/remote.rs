@@ -0,0 +1,226 @@
+//! Remote workspace sources.
+//!
+//! A `[remote_sources]` table in `~/.jumble/jumble.toml` maps names to git
+//! repositories or `.tar.gz` archives holding `.jumble` context published by
+//! another team:
+//!
+//! ```toml
+//! [remote_sources]
+//! platform = "https://github.com/acme/platform-context.git"
+//! payments = "https://example.com/context/payments.tar.gz"
+//! ```
+//!
+//! Sources are cached under `~/.jumble/remote-cache/<name>` and refreshed
+//! during discovery once the cached copy is older than
+//! `remote_refresh_hours`. When a refresh fails the stale copy keeps being
+//! served. Projects found in a source are read-only.
+
+use crate::git;
+use crate::skill_packs;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+/// How long a cached source is used before it is fetched again.
+pub const DEFAULT_REFRESH_HOURS: u64 = 24;
+
+/// A remote context source declared in the global configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteSource {
+    pub name: String,
+    pub url: String,
+}
+
+/// The `[remote_sources]` table as sources, sorted by name.
+pub fn sources(table: &HashMap<String, String>) -> Vec<RemoteSource> {
+    let mut sources: Vec<RemoteSource> = table
+        .iter()
+        .map(|(name, url)| RemoteSource {
+            name: name.clone(),
+            url: url.clone(),
+        })
+        .collect();
+    sources.sort_by(|a, b| a.name.cmp(&b.name));
+    sources
+}
+
+/// Directory under which all sources are cached.
+pub fn cache_root(home_dir: &Path) -> PathBuf {
+    home_dir.join(".jumble").join("remote-cache")
+}
+
+/// Makes sure `source` is in the cache and no older than `max_age`, returning
+/// its directory.
+pub fn ensure_source(
+    cache_root: &Path,
+    source: &RemoteSource,
+    max_age: Duration,
+) -> Result<PathBuf, String> {
+    skill_packs::validate_name(&source.name)?;
+    let dir = cache_root.join(&source.name);
+    // The fetch time lives beside the checkout so it never shows up as context.
+    let stamp = cache_root.join(format!("{}.fetched", source.name));
+    if dir.is_dir() && is_fresh(&stamp, max_age) {
+        return Ok(dir);
+    }
+
+    match fetch(cache_root, &dir, &source.url) {
+        Ok(()) => {
+            if let Err(e) = std::fs::write(&stamp, "") {
+                warn!(source = %source.name, error = %e, "failed to record remote source fetch");
+            }
+            Ok(dir)
+        }
+        Err(e) if dir.is_dir() => {
+            warn!(source = %source.name, error = %e, "refresh failed; serving cached copy");
+            Ok(dir)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn is_fresh(stamp: &Path, max_age: Duration) -> bool {
+    std::fs::metadata(stamp)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < max_age)
+}
+
+fn fetch(cache_root: &Path, dir: &Path, url: &str) -> Result<(), String> {
+    std::fs::create_dir_all(cache_root)
+        .map_err(|e| format!("Failed to create {}: {}", cache_root.display(), e))?;
+
+    if is_archive(url) {
+        // Download first so a failed fetch leaves the cached copy intact.
+        let archive = PathBuf::from(format!("{}.tar.gz.part", dir.display()));
+        skill_packs::download(url, &archive)?;
+        let extracted = replace_dir(dir).and_then(|()| extract(&archive, dir));
+        let _ = std::fs::remove_file(&archive);
+        return extracted;
+    }
+
+    if dir.join(".git").exists() {
+        git::run_git(dir, &["pull", "--ff-only", "-q"])?;
+        return Ok(());
+    }
+    replace_dir(dir)?;
+    let target = dir.to_string_lossy().into_owned();
+    git::run_git(
+        cache_root,
+        &["clone", "--depth", "1", "-q", "--", url, &target],
+    )?;
+    Ok(())
+}
+
+/// Clears `dir` so a fresh copy can be written in its place.
+fn replace_dir(dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)
+            .map_err(|e| format!("Failed to clear {}: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Unpacks a gzipped tarball into `dir` using the `tar` binary.
+fn extract(archive: &Path, dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let output = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dir)
+        .output()
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "Failed to extract {}: {}",
+            archive.display(),
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
+/// URLs ending in `.tar.gz` or `.tgz` are downloaded and unpacked; everything
+/// else is treated as a git repository.
+fn is_archive(url: &str) -> bool {
+    url.split(['?', '#'])
+        .next()
+        .map(|path| path.to_ascii_lowercase())
+        .is_some_and(|path| path.ends_with(".tar.gz") || path.ends_with(".tgz"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_archive() {
+        assert!(is_archive("https://example.com/context.tar.gz"));
+        assert!(is_archive("https://example.com/context.TGZ?token=abc"));
+        assert!(!is_archive("https://github.com/acme/context.git"));
+    }
+
+    #[test]
+    fn test_ensure_source_unpacks_archive_and_caches_it() {
+        let upstream = TempDir::new().unwrap();
+        let content = upstream.path().join("content/api/.jumble");
+        std::fs::create_dir_all(&content).unwrap();
+        std::fs::write(content.join("project.toml"), "[project]\nname = \"api\"\n").unwrap();
+        let archive = upstream.path().join("context.tar.gz");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(upstream.path().join("content"))
+            .arg(".")
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let cache = TempDir::new().unwrap();
+        let source = RemoteSource {
+            name: "platform".to_string(),
+            url: format!("file://{}", archive.display()),
+        };
+        let hour = Duration::from_secs(3600);
+        let dir = ensure_source(cache.path(), &source, hour).unwrap();
+        assert!(dir.join("api/.jumble/project.toml").exists());
+
+        // A fresh copy is served as is, even once the upstream is gone.
+        std::fs::remove_file(&archive).unwrap();
+        assert_eq!(ensure_source(cache.path(), &source, hour).unwrap(), dir);
+
+        // A stale copy whose refresh fails is still served.
+        assert_eq!(
+            ensure_source(cache.path(), &source, Duration::ZERO).unwrap(),
+            dir
+        );
+        assert!(dir.join("api/.jumble/project.toml").exists());
+
+        let missing = RemoteSource {
+            name: "missing".to_string(),
+            url: format!("file://{}", archive.display()),
+        };
+        assert!(ensure_source(cache.path(), &missing, hour).is_err());
+    }
+
+    #[test]
+    fn test_ensure_source_treats_url_as_repository() {
+        let cache = TempDir::new().unwrap();
+        let hour = Duration::from_secs(3600);
+        let source = RemoteSource {
+            name: "platform".to_string(),
+            url: "--upload-pack=true".to_string(),
+        };
+        let err = ensure_source(cache.path(), &source, hour).unwrap_err();
+        assert!(err.contains("repository '--upload-pack=true'"), "{}", err);
+    }
+}
@@ -0,0 +1,367 @@
+//! JSON Schemas for `.jumble` config files and schema-based validation.
+//!
+//! Serde silently ignores unknown keys, so a typo such as `[comands]` would
+//! otherwise just make commands disappear. Validation walks the raw file
+//! against the generated schema and reports keys the schema does not know,
+//! suggesting the closest known key.
+
+use crate::config::{
+    self, ProjectConfig, ProjectConventions, ProjectDocs, SingleFileConfig, SingleFileSections,
+    WorkspaceConfig,
+};
+use crate::diagnostics::Diagnostic;
+use crate::fuzzy;
+use clap::ValueEnum;
+use schemars::schema_for;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// The kinds of `.jumble` config files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConfigKind {
+    Project,
+    Workspace,
+    Conventions,
+    Docs,
+    /// A single-file `jumble.toml` at a project root.
+    SingleFile,
+}
+
+impl ConfigKind {
+    pub const ALL: [ConfigKind; 5] = [
+        ConfigKind::Project,
+        ConfigKind::Workspace,
+        ConfigKind::Conventions,
+        ConfigKind::Docs,
+        ConfigKind::SingleFile,
+    ];
+
+    /// File name without extension, e.g. `project` for `project.toml`.
+    pub fn file_stem(self) -> &'static str {
+        match self {
+            ConfigKind::Project => "project",
+            ConfigKind::Workspace => "workspace",
+            ConfigKind::Conventions => "conventions",
+            ConfigKind::Docs => "docs",
+            ConfigKind::SingleFile => "jumble",
+        }
+    }
+
+    /// The JSON Schema describing this kind of file.
+    pub fn schema(self) -> Value {
+        let schema = match self {
+            ConfigKind::Project => schema_for!(ProjectConfig),
+            ConfigKind::Workspace => schema_for!(WorkspaceConfig),
+            ConfigKind::Conventions => schema_for!(ProjectConventions),
+            ConfigKind::Docs => schema_for!(ProjectDocs),
+            ConfigKind::SingleFile => schema_for!(SingleFileConfig),
+        };
+        schema.to_value()
+    }
+
+    /// Loads the file as its typed config, surfacing missing or mistyped fields.
+    fn check_typed(self, path: &Path) -> anyhow::Result<()> {
+        match self {
+            ConfigKind::Project => config::load_project_config(path).map(|_| ()),
+            ConfigKind::Workspace => config::load_config_file::<WorkspaceConfig>(path).map(|_| ()),
+            ConfigKind::Conventions => {
+                config::load_config_file::<ProjectConventions>(path).map(|_| ())
+            }
+            ConfigKind::Docs => config::load_config_file::<ProjectDocs>(path).map(|_| ()),
+            ConfigKind::SingleFile => {
+                config::load_project_config(path)?;
+                SingleFileSections::load(path).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Returns the schemas for every config kind, keyed by file stem.
+pub fn all_schemas() -> Value {
+    let schemas: Map<String, Value> = ConfigKind::ALL
+        .iter()
+        .map(|kind| (kind.file_stem().to_string(), kind.schema()))
+        .collect();
+    Value::Object(schemas)
+}
+
+/// Validates a config file, returning one diagnostic per problem found.
+pub fn validate_config_file(path: &Path, kind: ConfigKind) -> Vec<Diagnostic> {
+    let value = match config::load_config_value(path) {
+        Ok(value) => value,
+        Err(e) => return vec![Diagnostic::error(path, ignored_message(&e))],
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = unknown_fields(&kind.schema(), &value)
+        .into_iter()
+        .map(|message| Diagnostic::error(path, message))
+        .collect();
+
+    diagnostics.extend(schema_version_diagnostic(path, &value));
+    if let Err(e) = kind.check_typed(path) {
+        diagnostics.push(Diagnostic::error(path, ignored_message(&e)));
+    }
+    diagnostics
+}
+
+/// Reports a `schema_version` other than the current one: older files
+/// should be migrated, and newer ones may use fields this build ignores.
+fn schema_version_diagnostic(path: &Path, value: &Value) -> Option<Diagnostic> {
+    let version = value.get("schema_version")?.as_u64()?;
+    let current = u64::from(config::SCHEMA_VERSION);
+    if version < current {
+        Some(Diagnostic::warning(
+            path,
+            format!(
+                "schema_version {} is out of date (current is {}); run `jumble migrate`",
+                version, current
+            ),
+        ))
+    } else if version > current {
+        Some(Diagnostic::error(
+            path,
+            format!(
+                "schema_version {} is newer than this Jumble supports ({}); upgrade Jumble",
+                version, current
+            ),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Validates the Jumble config embedded in a package manifest
+/// (`Cargo.toml` or `package.json`) as a project config.
+pub fn validate_manifest_project(path: &Path) -> Vec<Diagnostic> {
+    let value = match config::manifest_project_value(path) {
+        Ok(Some(value)) => value,
+        Ok(None) => return Vec::new(),
+        Err(e) => return vec![Diagnostic::error(path, ignored_message(&e))],
+    };
+
+    let label = config::manifest_config_label(path).unwrap_or("jumble");
+    let mut diagnostics: Vec<Diagnostic> = unknown_fields(&ConfigKind::Project.schema(), &value)
+        .into_iter()
+        .map(|message| Diagnostic::error(path, format!("{}: {}", label, message)))
+        .collect();
+
+    if let Err(e) = config::load_project_config(path) {
+        diagnostics.push(Diagnostic::error(path, ignored_message(&e)));
+    }
+    diagnostics
+}
+
+/// Loaders fall back to defaults (or skip the project) when a file fails to
+/// parse, so say so rather than leave authors guessing.
+pub fn ignored_message(error: &anyhow::Error) -> String {
+    format!("file ignored: {:#}", error)
+}
+
+/// Lists keys in `value` that `schema` does not define, with suggestions.
+pub fn unknown_fields(schema: &Value, value: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    check_object(schema, schema, value, "", &mut problems);
+    problems
+}
+
+fn check_object(root: &Value, schema: &Value, value: &Value, path: &str, out: &mut Vec<String>) {
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    // Untagged enums accept any of several tables; judge the value against
+    // the one it fits best.
+    let branches: Vec<&Value> = branches(root, schema)
+        .into_iter()
+        .filter(|b| resolve(root, b).get("type").is_none_or(|t| t == "object"))
+        .collect();
+    if branches.len() > 1 {
+        let best = branches
+            .into_iter()
+            .map(|branch| {
+                let mut problems = Vec::new();
+                check_object(root, branch, value, path, &mut problems);
+                problems
+            })
+            .min_by_key(|problems| problems.len())
+            .unwrap_or_default();
+        out.extend(best);
+        return;
+    }
+    let schema = resolve(root, schema);
+
+    let additional = schema.get("additionalProperties").filter(|s| s.is_object());
+    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+        for (key, child) in object {
+            match properties.get(key).or(additional) {
+                Some(child_schema) => {
+                    check_object(root, child_schema, child, &join(path, key), out)
+                }
+                // `extends` is handled by the loader for every kind of file.
+                None if path.is_empty() && key == "extends" => {}
+                None => out.push(unknown_field_message(path, key, properties.keys())),
+            }
+        }
+    } else if let Some(item_schema) = additional {
+        for (key, child) in object {
+            check_object(root, item_schema, child, &join(path, key), out);
+        }
+    }
+}
+
+/// The non-null `anyOf`/`oneOf` branches of `schema`, after following `$ref`s.
+fn branches<'a>(root: &'a Value, schema: &'a Value) -> Vec<&'a Value> {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        if let Some(target) = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+        {
+            return branches(root, target);
+        }
+    }
+    ["anyOf", "oneOf"]
+        .into_iter()
+        .filter_map(|keyword| schema.get(keyword).and_then(|b| b.as_array()))
+        .flatten()
+        .filter(|b| b.get("type") != Some(&json!("null")))
+        .collect()
+}
+
+/// Follows `$ref`s and picks the non-null branch of `Option<T>` schemas.
+fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer));
+        if let Some(target) = target {
+            return resolve(root, target);
+        }
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(|b| b.as_array()) {
+            if let Some(branch) = branches
+                .iter()
+                .find(|b| b.get("type") != Some(&json!("null")))
+            {
+                return resolve(root, branch);
+            }
+        }
+    }
+    schema
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn unknown_field_message<'a>(
+    path: &str,
+    key: &str,
+    known: impl Iterator<Item = &'a String>,
+) -> String {
+    let mut message = if path.is_empty() {
+        format!("unknown field '{}'", key)
+    } else {
+        format!("unknown field '{}' in '{}'", key, path)
+    };
+    if let Some(suggestion) = fuzzy::closest_match(key, known.map(|k| k.as_str())) {
+        message.push_str(&format!(", did you mean '{}'?", suggestion));
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_unknown_fields_suggests_closest_key() {
+        let value = json!({
+            "project": {"name": "x", "description": "y", "langauge": "rust"},
+            "comands": {"build": "cargo build"},
+            "commands": {"test": {"cmd": "cargo test", "descripton": "Unit tests"}},
+            "concepts": {"auth": {"files": [], "summary": "s", "sumary": "typo"}},
+            "zzz": 1
+        });
+        let problems = unknown_fields(&ConfigKind::Project.schema(), &value);
+        assert!(problems.contains(&"unknown field 'comands', did you mean 'commands'?".to_string()));
+        assert!(problems.contains(
+            &"unknown field 'langauge' in 'project', did you mean 'language'?".to_string()
+        ));
+        assert!(problems.contains(
+            &"unknown field 'sumary' in 'concepts.auth', did you mean 'summary'?".to_string()
+        ));
+        assert!(problems.contains(
+            &"unknown field 'descripton' in 'commands.test', did you mean 'description'?"
+                .to_string()
+        ));
+        assert!(problems.contains(&"unknown field 'zzz'".to_string()));
+        assert_eq!(problems.len(), 5);
+    }
+
+    #[test]
+    fn test_unknown_fields_accepts_valid_config() {
+        let value = json!({
+            "extends": "../base.toml",
+            "project": {"name": "x", "description": "y"},
+            "commands": {
+                "build": "cargo build",
+                "test": {"cmd": "cargo test", "depends_on": ["build"]},
+                "ci": {"steps": ["build", "test"]}
+            },
+            "api": {"openapi": "api.yaml"},
+            "env": {"TARGET": "target"}
+        });
+        assert!(unknown_fields(&ConfigKind::Project.schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_fields_allows_custom_convention_categories() {
+        let value = json!({
+            "conventions": {"naming": "snake_case"},
+            "security": {"secrets": "Never log tokens"}
+        });
+        assert!(unknown_fields(&ConfigKind::Conventions.schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn test_validate_config_file_reports_missing_fields() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("project.toml");
+        std::fs::write(&path, "[project]\nname = \"x\"\n[gotchas]\n").unwrap();
+
+        let diagnostics = validate_config_file(&path, ConfigKind::Project);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("unknown field 'gotchas'"));
+        assert!(diagnostics[1].message.contains("description"));
+    }
+
+    #[test]
+    fn test_validate_config_file_checks_schema_version() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("docs.toml");
+        let check = |version: u32| {
+            std::fs::write(&path, format!("schema_version = {}\n", version)).unwrap();
+            validate_config_file(&path, ConfigKind::Docs)
+        };
+
+        assert!(check(config::SCHEMA_VERSION).is_empty());
+        let old = check(1);
+        assert_eq!(old[0].severity, crate::diagnostics::Severity::Warning);
+        assert!(old[0].message.contains("run `jumble migrate`"));
+        let new = check(config::SCHEMA_VERSION + 1);
+        assert_eq!(new[0].severity, crate::diagnostics::Severity::Error);
+    }
+
+    #[test]
+    fn test_all_schemas() {
+        let schemas = all_schemas();
+        for kind in ConfigKind::ALL {
+            assert!(schemas[kind.file_stem()]["properties"].is_object());
+        }
+    }
+}
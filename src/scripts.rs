@@ -0,0 +1,397 @@
+//! `jumble import-commands`: proposes `[commands]` entries from the build
+//! orchestration a project already has, so Jumble does not have to repeat
+//! it by hand.
+//!
+//! Recipes come from a `justfile`, targets from a `Makefile`, and scripts
+//! from `package.json`. When two sources define the same name, the first
+//! in that order wins. Prerequisites that are themselves imported become
+//! `depends_on`, and a comment directly above a recipe or target (or a
+//! trailing `## text` on a Makefile target) becomes its description.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::config::{CommandDetails, CommandSpec};
+
+const JUSTFILES: &[&str] = &["justfile", "Justfile", ".justfile"];
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+
+/// Lockfiles that pick the runner for npm scripts, checked in order.
+const RUNNERS: &[(&str, &str)] = &[
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lockb", "bun run"),
+    ("bun.lock", "bun run"),
+];
+
+/// Keywords that start a justfile line that is not a recipe.
+const JUST_KEYWORDS: &[&str] = &["set", "alias", "export", "import", "mod"];
+
+/// A proposed command and the file it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Imported {
+    pub name: String,
+    pub spec: CommandSpec,
+    pub source: &'static str,
+}
+
+/// A recipe, target, or script before it is turned into a command.
+struct Task {
+    name: String,
+    cmd: String,
+    description: Option<String>,
+    prerequisites: Vec<String>,
+}
+
+/// Commands for the project at `project_dir` that `existing` does not
+/// already define, in source order and then by name.
+pub fn import_commands(
+    project_dir: &Path,
+    existing: &HashMap<String, CommandSpec>,
+) -> Vec<Imported> {
+    let sources = [
+        first_file(project_dir, JUSTFILES).map(|(file, content)| (file, parse_justfile(&content))),
+        first_file(project_dir, MAKEFILES).map(|(file, content)| (file, parse_makefile(&content))),
+        first_file(project_dir, &["package.json"])
+            .map(|(file, content)| (file, parse_npm_scripts(project_dir, &content))),
+    ];
+
+    let mut imported: Vec<Imported> = Vec::new();
+    for (source, mut tasks) in sources.into_iter().flatten() {
+        tasks.sort_by(|a, b| a.name.cmp(&b.name));
+        let taken = |name: &str, imported: &[Imported]| {
+            existing.contains_key(name) || imported.iter().any(|i| i.name == name)
+        };
+        let names: Vec<String> = tasks
+            .iter()
+            .filter(|task| !taken(&task.name, &imported))
+            .map(|task| task.name.clone())
+            .collect();
+        for task in tasks {
+            if !names.contains(&task.name) {
+                continue;
+            }
+            let depends_on: Vec<String> = task
+                .prerequisites
+                .into_iter()
+                .filter(|name| names.contains(name))
+                .collect();
+            let spec = if task.description.is_none() && depends_on.is_empty() {
+                CommandSpec::Line(task.cmd)
+            } else {
+                CommandSpec::Detailed(CommandDetails {
+                    cmd: task.cmd,
+                    description: task.description,
+                    depends_on,
+                    ..Default::default()
+                })
+            };
+            imported.push(Imported {
+                name: task.name,
+                spec,
+                source,
+            });
+        }
+    }
+    imported
+}
+
+fn first_file(project_dir: &Path, names: &[&'static str]) -> Option<(&'static str, String)> {
+    names.iter().find_map(|name| {
+        let content = std::fs::read_to_string(project_dir.join(name)).ok()?;
+        Some((*name, content))
+    })
+}
+
+/// Recipe headers such as `test *args: build lint` at the start of a line.
+/// Private recipes (`_helper` or `[private]`) are skipped.
+fn parse_justfile(content: &str) -> Vec<Task> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tasks = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.starts_with([' ', '\t', '#', '[']) || line.trim().is_empty() {
+            continue;
+        }
+        let Some((header, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') {
+            continue;
+        }
+        let mut words = header.split_whitespace();
+        let Some(name) = words.next().map(|name| name.trim_start_matches('@')) else {
+            continue;
+        };
+        if JUST_KEYWORDS.contains(&name) || !is_identifier(name) || name.starts_with('_') {
+            continue;
+        }
+
+        // Attributes and comments sit on the lines directly above.
+        let mut description = None;
+        let mut private = false;
+        for above in lines[..index].iter().rev().map(|l| l.trim()) {
+            if let Some(attribute) = above.strip_prefix('[') {
+                private |= attribute.starts_with("private");
+                if let Some(doc) = attribute.strip_prefix("doc(") {
+                    description = Some(doc.trim_end_matches(")]").trim_matches(['"', '\'']));
+                }
+            } else if let Some(comment) = above.strip_prefix('#') {
+                description = description.or(Some(comment.trim()));
+                break;
+            } else {
+                break;
+            }
+        }
+        if private {
+            continue;
+        }
+
+        tasks.push(Task {
+            name: name.to_string(),
+            cmd: format!("just {}", name),
+            description: description.filter(|d| !d.is_empty()).map(str::to_string),
+            prerequisites: prerequisites(rest),
+        });
+    }
+    tasks
+}
+
+/// Rules such as `build: generate ## Build the app`. Special targets
+/// (`.PHONY`), pattern rules, variables, and file targets are skipped.
+fn parse_makefile(content: &str) -> Vec<Task> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tasks: Vec<Task> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.starts_with([' ', '\t', '#', '.']) {
+            continue;
+        }
+        let Some((targets, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let rest = rest.trim_start_matches(':');
+        if rest.starts_with('=') || targets.contains(['=', '$', '%']) {
+            continue;
+        }
+        let (rest, description) = match rest.split_once("##") {
+            Some((rest, text)) => (rest, Some(text.trim())),
+            None => {
+                let comment = index
+                    .checked_sub(1)
+                    .and_then(|above| lines[above].strip_prefix('#'))
+                    .map(|comment| comment.trim_start_matches('#').trim());
+                (rest, comment)
+            }
+        };
+        for name in targets.split_whitespace() {
+            if !is_identifier(name) || tasks.iter().any(|t| t.name == name) {
+                continue;
+            }
+            tasks.push(Task {
+                name: name.to_string(),
+                cmd: format!("make {}", name),
+                description: description.filter(|d| !d.is_empty()).map(str::to_string),
+                prerequisites: prerequisites(rest),
+            });
+        }
+    }
+    tasks
+}
+
+/// `scripts` from `package.json`, run with the package manager whose
+/// lockfile is present. `pre` and `post` hooks of other scripts are skipped.
+fn parse_npm_scripts(project_dir: &Path, content: &str) -> Vec<Task> {
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(scripts) = manifest.get("scripts").and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+    let runner = RUNNERS
+        .iter()
+        .find(|(lockfile, _)| project_dir.join(lockfile).is_file())
+        .map_or("npm run", |(_, runner)| runner);
+    scripts
+        .keys()
+        .filter(|name| {
+            let hook = ["pre", "post"]
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix));
+            hook.is_none_or(|script| !scripts.contains_key(script))
+        })
+        .map(|name| Task {
+            name: name.clone(),
+            cmd: format!("{} {}", runner, name),
+            description: None,
+            prerequisites: Vec::new(),
+        })
+        .collect()
+}
+
+/// Names after a rule's colon, up to a comment. Order-only markers and
+/// parameterized dependencies such as `(build "release")` are dropped.
+fn prerequisites(rest: &str) -> Vec<String> {
+    rest.split('#')
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter(|word| is_identifier(word))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const JUSTFILE: &str = r#"set dotenv-load
+version := "1.0"
+
+# Run the test suite
+test *args: build
+    cargo test {{args}}
+
+[doc("Compile everything")]
+build:
+    cargo build
+
+[private]
+setup:
+    ./setup.sh
+
+_helper:
+    echo hidden
+
+@lint: (build) fmt
+    cargo clippy
+
+fmt:
+    cargo fmt
+"#;
+
+    const MAKEFILE: &str = "\
+.PHONY: build test deploy
+CC := gcc
+
+build: generate ## Build the binary
+\tgo build ./...
+
+# Regenerate protobuf code
+generate:
+\tbuf generate
+
+deploy test: build
+\t./deploy.sh
+
+%.o: %.c
+\t$(CC) -c $<
+
+main.o: main.c
+";
+
+    fn names(imported: &[Imported]) -> Vec<(&str, &str)> {
+        imported
+            .iter()
+            .map(|i| (i.name.as_str(), i.source))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_justfile() {
+        let tasks = parse_justfile(JUSTFILE);
+        let summary: Vec<(&str, Option<&str>, Vec<String>)> = tasks
+            .iter()
+            .map(|t| {
+                (
+                    t.name.as_str(),
+                    t.description.as_deref(),
+                    t.prerequisites.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "test",
+                    Some("Run the test suite"),
+                    vec!["build".to_string()]
+                ),
+                ("build", Some("Compile everything"), vec![]),
+                ("lint", None, vec!["fmt".to_string()]),
+                ("fmt", None, vec![]),
+            ]
+        );
+        assert_eq!(tasks[0].cmd, "just test");
+    }
+
+    #[test]
+    fn test_parse_makefile() {
+        let tasks = parse_makefile(MAKEFILE);
+        let summary: Vec<(&str, Option<&str>, Vec<String>)> = tasks
+            .iter()
+            .map(|t| {
+                (
+                    t.name.as_str(),
+                    t.description.as_deref(),
+                    t.prerequisites.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "build",
+                    Some("Build the binary"),
+                    vec!["generate".to_string()]
+                ),
+                ("generate", Some("Regenerate protobuf code"), vec![]),
+                ("deploy", None, vec!["build".to_string()]),
+                ("test", None, vec!["build".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_commands() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        std::fs::write(dir.join("Makefile"), MAKEFILE).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"scripts": {"dev": "vite", "build": "vite build", "pretest": "tsc", "test": "vitest", "lint": "eslint ."}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("pnpm-lock.yaml"), "").unwrap();
+
+        let existing = HashMap::from([("lint".to_string(), CommandSpec::from("eslint"))]);
+        let imported = import_commands(dir, &existing);
+        assert_eq!(
+            names(&imported),
+            [
+                ("build", "Makefile"),
+                ("deploy", "Makefile"),
+                ("generate", "Makefile"),
+                ("test", "Makefile"),
+                ("dev", "package.json"),
+            ]
+        );
+        assert_eq!(
+            imported[1].spec,
+            CommandSpec::Detailed(CommandDetails {
+                cmd: "make deploy".to_string(),
+                depends_on: vec!["build".to_string()],
+                ..Default::default()
+            })
+        );
+        assert_eq!(imported[4].spec, CommandSpec::from("pnpm dev"));
+    }
+}
@@ -1,87 +1,494 @@
 //! MCP Server implementation.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 
+use crate::audit::{self, AuditEntry};
+use crate::authoring;
 use crate::config::{
-    JumbleConfig, ProjectConfig, ProjectConventions, ProjectDocs, ProjectSkills, SkillFrontmatter,
-    SkillInfo, WorkspaceConfig,
+    self, extract_skill_frontmatter_and_preview, CustomTool, DocEntry, JumbleConfig, MemoryConfig,
+    PathStyle, PluginConfig, PluginTool, ProjectConfig, ProjectConventions, ProjectDocs,
+    ProjectSkills, SingleFileSections, SkillFrontmatter, SkillInfo, WorkspaceConfig,
 };
-use crate::memory;
-use crate::protocol::{JsonRpcError, JsonRpcRequest, JsonRpcResponse};
+use crate::deps;
+use crate::diagnostics::{self, Diagnostic};
+use crate::format;
+use crate::fuzzy;
+use crate::language;
+use crate::members;
+use crate::memory::{self, MemoryDatabase};
+use crate::plugins;
+use crate::protocol::{
+    file_uri_to_path, negotiate_protocol_version, JsonRpcClientResponse, JsonRpcError,
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, PendingRequests, INVALID_PARAMS,
+    INVALID_REQUEST, METHOD_NOT_FOUND, TOOL_ERROR,
+};
+use crate::remote;
+use crate::schema::{self, ConfigKind};
+use crate::setup;
+use crate::skill_packs::{self, SkillSource};
+use crate::stats::{self, StatsDatabase};
 use crate::tools::{self, ProjectData};
 
+/// Sends server-initiated notifications (e.g. progress) to the client.
+pub type Notifier = Box<dyn Fn(JsonRpcNotification) + Send + Sync>;
+
+/// Tools that change server state or allocate numbered files on disk, and so
+/// run with exclusive access instead of alongside other requests.
+const EXCLUSIVE_TOOLS: &[&str] = &[
+    "reload_workspace",
+    "add_concept",
+    "add_convention",
+    "promote_memory",
+    "create_skill",
+    "record_decision",
+    "add_task",
+    "complete_task",
+];
+
+/// Methods that update server state, handled with exclusive access.
+/// Discovery can be rescoped by MCP roots, which the client reports after
+/// `initialize` and again on `notifications/roots/list_changed`.
+const EXCLUSIVE_METHODS: &[&str] = &[
+    "initialize",
+    "initialized",
+    "notifications/initialized",
+    "notifications/roots/list_changed",
+];
+
+/// Tools that write into a project's directory, refused for projects from a
+/// remote source.
+const WRITE_TOOLS: &[&str] = &[
+    "add_concept",
+    "add_convention",
+    "promote_memory",
+    "create_skill",
+    "record_decision",
+    "add_task",
+    "complete_task",
+    "store_memory",
+    "delete_memory",
+    "clear_memories",
+];
+
+/// How long a tool call waits for the client to answer a sampling request,
+/// which usually needs the user's approval.
+const SAMPLING_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Most values in one `completion/complete` answer, the limit MCP sets.
+const MAX_COMPLETIONS: usize = 100;
+
+/// Emit a progress notification after this many directories have been scanned.
+const PROGRESS_INTERVAL_DIRS: u64 = 100;
+
+/// What the client has told us about MCP roots.
+#[derive(Debug, Default)]
+struct ClientRoots {
+    /// The client declared the `roots` capability in `initialize`.
+    supported: bool,
+    /// The id of the `roots/list` request awaiting an answer.
+    pending: Option<Value>,
+    /// Requests sent so far, used to number the next one.
+    sent: u64,
+    /// Roots after the first, scanned for projects alongside [`Server::root`].
+    extra: Vec<PathBuf>,
+}
+
 /// MCP Server state
 pub struct Server {
+    /// The workspace root: the client's first MCP root, else `--root`.
     pub root: PathBuf,
+    /// The `--root` directory, used again when the client clears its roots.
+    launch_root: PathBuf,
+    client_roots: ClientRoots,
+    /// The client declared the `sampling` capability in `initialize`.
+    client_sampling: bool,
+    /// Requests to the client that a tool call is waiting on.
+    pending: Arc<PendingRequests>,
+    /// Sampling requests sent so far, used to number the next one.
+    samples_sent: AtomicU64,
+    /// Drafts from `draft_project_config` awaiting confirmation, by directory.
+    drafts: Mutex<HashMap<PathBuf, String>>,
     pub workspace: Option<WorkspaceConfig>,
     pub projects: HashMap<String, ProjectData>,
     /// Global Jumble configuration loaded from `~/.jumble/jumble.toml`.
-    #[allow(dead_code)]
     pub jumble_config: Option<JumbleConfig>,
+    /// Problems found during the last discovery pass (broken skills, etc.).
+    pub diagnostics: Vec<Diagnostic>,
+    /// Cached directories of remote skill packs from `[skill_sources]`.
+    skill_packs: Vec<PathBuf>,
+    /// Projects loaded from `[remote_sources]`, mapped to their source name.
+    remote_projects: HashMap<String, String>,
+    /// User-level skills, scanned once per discovery pass and shared by every project.
+    global_skills: GlobalSkills,
+    /// Set by `--no-global-skills`; leaves the user-level skill directories unscanned.
+    skip_global_skills: bool,
+    /// Channel for notifications; unset when nothing is listening.
+    notifier: Option<Notifier>,
+    /// Where tool usage is recorded; unset when stats are not kept.
+    stats: Option<StatsDatabase>,
+    /// User-level memories shared by every project; unset without a home directory.
+    global_memory: Option<MemoryDatabase>,
+    /// Set by a `shutdown` request; later requests other than `exit` are refused.
+    shutting_down: AtomicBool,
+    /// Set by an `exit` notification; the transport loop should stop.
+    exit_requested: AtomicBool,
 }
 
 impl Server {
     pub fn new(root: PathBuf) -> Result<Self> {
+        Self::with_global_skills(root, true)
+    }
+
+    /// Like [`Server::new`], but with `global_skills` unset the user-level skill
+    /// directories (`~/.jumble/skills`, `~/.claude/skills`, `~/.codex/skills`)
+    /// are never scanned.
+    pub fn with_global_skills(root: PathBuf, global_skills: bool) -> Result<Self> {
         let mut server = Server {
+            launch_root: root.clone(),
             root,
+            client_roots: ClientRoots::default(),
+            client_sampling: false,
+            pending: Arc::default(),
+            samples_sent: AtomicU64::new(0),
+            drafts: Mutex::default(),
             workspace: None,
             projects: HashMap::new(),
             jumble_config: load_jumble_config(),
+            diagnostics: Vec::new(),
+            skill_packs: Vec::new(),
+            remote_projects: HashMap::new(),
+            global_skills: GlobalSkills::default(),
+            skip_global_skills: !global_skills,
+            notifier: None,
+            stats: None,
+            global_memory: None,
+            shutting_down: AtomicBool::new(false),
+            exit_requested: AtomicBool::new(false),
         };
-        server.reload_workspace_and_projects()?;
+        server.reload_workspace_and_projects(None)?;
         Ok(server)
     }
 
-    fn reload_workspace_and_projects(&mut self) -> Result<()> {
+    /// Installs the callback used to send notifications to the client.
+    pub fn set_notifier(&mut self, notifier: Notifier) {
+        self.notifier = Some(notifier);
+    }
+
+    /// Whether the client has sent `exit` and the server should stop reading.
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested.load(Ordering::SeqCst)
+    }
+
+    /// Installs the database that tool calls are counted in.
+    pub fn set_stats(&mut self, stats: StatsDatabase) {
+        self.stats = Some(stats);
+    }
+
+    /// Installs the user-level memory store used by the global memory tools.
+    pub fn set_global_memory(&mut self, global_memory: MemoryDatabase) {
+        self.global_memory = Some(global_memory);
+    }
+
+    /// Rescans the workspace. When the triggering request carried a
+    /// `progressToken`, scan progress is reported as `notifications/progress`.
+    fn reload_workspace_and_projects(&mut self, progress_token: Option<Value>) -> Result<()> {
+        let started = Instant::now();
+        let mut diagnostics = Vec::new();
         self.workspace = Self::load_workspace_static(&self.root);
-        self.projects = self.discover_projects()?;
+        if let Some(path) = config::find_config_file(&self.root.join(".jumble"), "workspace") {
+            diagnostics.extend(schema::validate_config_file(&path, ConfigKind::Workspace));
+        }
+        self.skill_packs = self
+            .fetch_skill_packs(false)
+            .into_iter()
+            .filter_map(|(source, result)| match result {
+                Ok(dir) => Some(dir),
+                Err(e) => {
+                    warn!(source = %source.name, error = %e, "failed to fetch skill source");
+                    diagnostics.push(Diagnostic::error(
+                        Path::new(&source.url),
+                        format!("failed to fetch skill source '{}': {}", source.name, e),
+                    ));
+                    None
+                }
+            })
+            .collect();
+        self.global_skills = self.scan_global_skills(&mut diagnostics);
+        let mut notifier = self.notifier.take();
+        let mut progress = DiscoveryProgress::new(progress_token, notifier.as_mut());
+        let mut discovered = self.discover_projects(&self.root, &mut diagnostics, &mut progress);
+        for root in &self.client_roots.extra {
+            let Ok(projects) = &mut discovered else {
+                break;
+            };
+            // A project found under an earlier root wins.
+            match self.discover_projects(root, &mut diagnostics, &mut progress) {
+                Ok(more) => {
+                    for (name, data) in more {
+                        projects.entry(name).or_insert(data);
+                    }
+                }
+                Err(e) => discovered = Err(e),
+            }
+        }
+        if let Ok(projects) = &discovered {
+            progress.finish(projects.len());
+        }
+        self.notifier = notifier;
+        self.projects = discovered?;
+        self.add_remote_projects(&mut diagnostics);
+        diagnostics.extend(self.dangling_reference_diagnostics());
+        diagnostics.extend(self.command_dependency_diagnostics());
+        diagnostics.extend(member_coverage_diagnostics(&self.root));
+        diagnostics.extend(jumble_config_diagnostics());
+        diagnostics.extend(self.tool_name_diagnostics());
+        diagnostics.sort();
+        diagnostics.dedup();
+        self.diagnostics = diagnostics;
+        info!(
+            root = %self.root.display(),
+            projects = self.projects.len(),
+            diagnostics = self.diagnostics.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "workspace discovery complete"
+        );
         Ok(())
     }
 
-    fn load_workspace_static(root: &Path) -> Option<WorkspaceConfig> {
-        let workspace_path = root.join(".jumble/workspace.toml");
-        if workspace_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&workspace_path) {
-                if let Ok(config) = toml::from_str(&content) {
-                    return Some(config);
+    /// Fetches remote skill packs and rediscovers projects so the refreshed
+    /// skills take effect. Returns the outcome for each configured source.
+    pub fn sync_skill_packs(&mut self) -> Result<Vec<(SkillSource, Result<PathBuf, String>)>> {
+        let results = self.fetch_skill_packs(true);
+        self.reload_workspace_and_projects(None)?;
+        Ok(results)
+    }
+
+    /// Ensures every `[skill_sources]` entry is in the local cache, refreshing
+    /// already-cached packs only when `refresh` is set.
+    fn fetch_skill_packs(&self, refresh: bool) -> Vec<(SkillSource, Result<PathBuf, String>)> {
+        let sources = skill_packs::merge_sources(
+            self.workspace.as_ref().map(|w| &w.skill_sources),
+            self.jumble_config.as_ref().map(|c| &c.skill_sources),
+        );
+        if sources.is_empty() {
+            return Vec::new();
+        }
+        let Some(home_dir) = resolve_home_dir() else {
+            return Vec::new();
+        };
+
+        let cache_root = skill_packs::cache_root(&home_dir);
+        sources
+            .into_iter()
+            .map(|source| {
+                let result = skill_packs::ensure_pack(&cache_root, &source, refresh);
+                (source, result)
+            })
+            .collect()
+    }
+
+    /// Fetches stale `[remote_sources]` and adds their projects. Local projects
+    /// win name clashes; a source that cannot be fetched is reported and skipped.
+    fn add_remote_projects(&mut self, diagnostics: &mut Vec<Diagnostic>) {
+        self.remote_projects.clear();
+        let Some(config) = &self.jumble_config else {
+            return;
+        };
+        let sources = remote::sources(&config.remote_sources);
+        if sources.is_empty() {
+            return;
+        }
+        let Some(home_dir) = resolve_home_dir() else {
+            return;
+        };
+        let hours = config
+            .jumble
+            .remote_refresh_hours
+            .unwrap_or(remote::DEFAULT_REFRESH_HOURS);
+        let max_age = Duration::from_secs(hours * 3600);
+
+        let cache_root = remote::cache_root(&home_dir);
+        for source in sources {
+            let dir = match remote::ensure_source(&cache_root, &source, max_age) {
+                Ok(dir) => dir,
+                Err(e) => {
+                    warn!(source = %source.name, error = %e, "failed to fetch remote source");
+                    diagnostics.push(Diagnostic::error(
+                        Path::new(&source.url),
+                        format!("failed to fetch remote source '{}': {}", source.name, e),
+                    ));
+                    continue;
                 }
+            };
+            let mut progress = DiscoveryProgress::new(None, None);
+            let Ok(projects) = self.discover_projects(&dir, diagnostics, &mut progress) else {
+                continue;
+            };
+            for (name, data) in projects {
+                if self.projects.contains_key(&name) {
+                    diagnostics.push(Diagnostic::warning(
+                        &data.0,
+                        format!(
+                            "project '{}' from remote source '{}' is shadowed by a local project",
+                            name, source.name
+                        ),
+                    ));
+                    continue;
+                }
+                self.remote_projects
+                    .insert(name.clone(), source.name.clone());
+                self.projects.insert(name, data);
             }
         }
-        None
     }
 
-    fn discover_projects(&self) -> Result<HashMap<String, ProjectData>> {
+    /// The error for a write tool aimed at a project from a remote source.
+    fn read_only_error(&self, tool: &str, arguments: &Value) -> Option<String> {
+        if !WRITE_TOOLS.contains(&tool) {
+            return None;
+        }
+        let project = arguments.get("project").and_then(|v| v.as_str())?;
+        let source = self.remote_projects.get(project)?;
+        Some(format!(
+            "Project '{}' comes from remote source '{}' and is read-only",
+            project, source
+        ))
+    }
+
+    /// Reports `depends_on` and sequence steps that name unknown commands or
+    /// form a cycle.
+    fn command_dependency_diagnostics(&self) -> Vec<Diagnostic> {
+        let projects = self.projects.values().map(|(dir, config, ..)| {
+            let path = config::find_project_config(dir).unwrap_or_else(|| dir.join(".jumble"));
+            (path, &config.commands)
+        });
+        let workspace = self.workspace.as_ref().and_then(|w| {
+            let path = config::find_config_file(&self.root.join(".jumble"), "workspace")?;
+            Some((path, &w.commands))
+        });
+        projects
+            .chain(workspace)
+            .flat_map(|(path, commands)| {
+                tools::command_dependency_problems(commands)
+                    .into_iter()
+                    .map(move |problem| Diagnostic::error(&path, problem))
+            })
+            .collect()
+    }
+
+    /// Reports `related_projects` entries that name undiscovered projects.
+    fn dangling_reference_diagnostics(&self) -> Vec<Diagnostic> {
+        tools::dangling_related_projects(&self.projects)
+            .into_iter()
+            .map(|reference| {
+                let project_dir = &self.projects[&reference.project].0;
+                let path = config::find_project_config(project_dir)
+                    .unwrap_or_else(|| project_dir.join(".jumble"));
+                Diagnostic::warning(
+                    &path,
+                    format!(
+                        "related_projects.{} references unknown project '{}'",
+                        reference.field, reference.target
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the workspace `expose`/`hide` lists allow `project` to be served.
+    fn is_exposed(&self, project: &str) -> bool {
+        self.workspace
+            .as_ref()
+            .map(|w| w.workspace.exposes(project))
+            .unwrap_or(true)
+    }
+
+    fn load_workspace_static(root: &Path) -> Option<WorkspaceConfig> {
+        let workspace_path = config::find_config_file(&root.join(".jumble"), "workspace")?;
+        config::load_config_file(&workspace_path).ok()
+    }
+
+    fn discover_projects(
+        &self,
+        root: &Path,
+        diagnostics: &mut Vec<Diagnostic>,
+        progress: &mut DiscoveryProgress,
+    ) -> Result<HashMap<String, ProjectData>> {
         let mut projects = HashMap::new();
-        for entry in WalkDir::new(&self.root)
-            .follow_links(true)
+        let settings = self
+            .workspace
+            .as_ref()
+            .map(|w| w.discovery.clone())
+            .unwrap_or_default();
+        // A directory reached again through a symlink (a cycle or an alias)
+        // is pruned, so its projects are neither revisited nor duplicated.
+        let mut visited = HashSet::new();
+        for entry in WalkDir::new(root)
+            .follow_links(settings.follow_links)
+            .same_file_system(settings.same_file_system)
             .into_iter()
+            .filter_entry(|e| {
+                !e.file_type().is_dir()
+                    || directory_id(e.path()).is_none_or(|id| visited.insert(id))
+            })
             .filter_map(|e| e.ok())
         {
+            if entry.file_type().is_dir() {
+                progress.directory_scanned(projects.len());
+            }
             let path = entry.path();
-            if path.ends_with(".jumble/project.toml") {
-                if let Ok(config) = self.load_project(path) {
-                    let project_dir = path
-                        .parent()
-                        .and_then(|p| p.parent())
-                        .unwrap_or(path)
-                        .to_path_buf();
+            if let Some((jumble_dir, project_dir)) = project_location(path) {
+                let loaded = self.load_project(path);
+                if let Ok(config) = &loaded {
+                    // Hidden projects are skipped entirely, including their diagnostics.
+                    if !self.is_exposed(&config.project.name) {
+                        debug!(project = %config.project.name, "project hidden by workspace config");
+                        continue;
+                    }
+                }
+
+                let single_file = config::is_single_file_config(path).then_some(path);
+                if is_manifest(path) {
+                    diagnostics.extend(schema::validate_manifest_project(path));
+                } else if single_file.is_some() {
+                    diagnostics.extend(schema::validate_config_file(path, ConfigKind::SingleFile));
+                } else {
+                    diagnostics.extend(schema::validate_config_file(path, ConfigKind::Project));
+                }
+                for kind in [ConfigKind::Conventions, ConfigKind::Docs] {
+                    if let Some(file) = config::find_config_file(&jumble_dir, kind.file_stem()) {
+                        diagnostics.extend(schema::validate_config_file(&file, kind));
+                    }
+                }
 
+                if let Ok(mut config) = loaded {
+                    if config.project.language.is_none() {
+                        config.project.language = language::detect(&project_dir);
+                    }
+                    config::infer_project_info(&project_dir, &mut config.project);
+                    deps::sync_on_load(&project_dir, &mut config);
                     // Discover skills, conventions, and docs
-                    let skills = self.discover_skills(path.parent().unwrap());
-                    let conventions = self.load_conventions(path.parent().unwrap());
-                    let docs = self.load_docs(path.parent().unwrap());
+                    let skills = self.discover_skills(&jumble_dir, diagnostics);
+                    let sections = single_file.and_then(|path| SingleFileSections::load(path).ok());
+                    let conventions = self.load_conventions(&jumble_dir, sections.as_ref());
+                    let docs = self.load_docs(&jumble_dir, sections.as_ref());
 
                     // Load or create memory database
                     let memory_db = match memory::open_or_create_memory_db(&project_dir) {
                         Ok(db) => db,
                         Err(e) => {
-                            eprintln!(
-                                "jumble: warning: failed to load memory for project '{}': {}",
-                                config.project.name, e
+                            warn!(
+                                project = %config.project.name,
+                                error = %e,
+                                "failed to load memory database"
                             );
                             // Create an in-memory database as fallback
                             memory::open_or_create_memory_db(&project_dir)
@@ -99,245 +506,1184 @@ impl Server {
         Ok(projects)
     }
 
-    fn discover_skills(&self, jumble_dir: &Path) -> ProjectSkills {
+    /// Scans the user-level skill locations and cached skill packs. Done once
+    /// per discovery pass; [`Server::discover_skills`] merges the result into
+    /// each project.
+    fn scan_global_skills(&self, diagnostics: &mut Vec<Diagnostic>) -> GlobalSkills {
+        let mut global = GlobalSkills::default();
+        for pack_dir in &self.skill_packs {
+            discover_structured_skills_in_dir(pack_dir, &mut global.packs, diagnostics);
+        }
+        if !self.global_skills_enabled() {
+            return global;
+        }
+        let Some(home_dir) = resolve_home_dir() else {
+            return global;
+        };
+
+        discover_flat_skills_in_dir(
+            &home_dir.join(".jumble").join("skills"),
+            &mut global.jumble,
+            diagnostics,
+        );
+        for (dir, skills) in [
+            (".claude/skills", &mut global.claude),
+            (".codex/skills", &mut global.codex),
+        ] {
+            let dir = home_dir.join(dir);
+            if dir.is_dir() {
+                discover_structured_skills_in_dir(&dir, skills, diagnostics);
+            }
+        }
+        global
+    }
+
+    /// Whether user-level skills are scanned: not disabled by
+    /// `--no-global-skills` or `no_global_skills` in `~/.jumble/jumble.toml`.
+    fn global_skills_enabled(&self) -> bool {
+        !self.skip_global_skills
+            && !self
+                .jumble_config
+                .as_ref()
+                .is_some_and(|c| c.jumble.no_global_skills)
+    }
+
+    fn discover_skills(
+        &self,
+        jumble_dir: &Path,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> ProjectSkills {
         let mut skills = ProjectSkills::default();
         let skills_dir = jumble_dir.join("skills");
 
         // Traditional project-local .jumble/skills/*.md files
-        if skills_dir.is_dir() {
-            if let Ok(entries) = std::fs::read_dir(&skills_dir) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    let path = entry.path();
-                    if path.extension().map(|e| e == "md").unwrap_or(false) {
-                        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                            let (frontmatter, preview) = match std::fs::read_to_string(&path) {
-                                Ok(content) => extract_skill_frontmatter_and_preview(&content),
-                                Err(_) => (None, String::new()),
-                            };
-
-                            skills.skills.insert(
-                                stem.to_string(),
-                                SkillInfo {
-                                    path: path.clone(),
-                                    skill_dir: None, // Flat skills have no companion directory
-                                    frontmatter,
-                                    preview,
-                                },
-                            );
-                        }
-                    }
-                }
-            }
+        discover_flat_skills_in_dir(&skills_dir, &mut skills, diagnostics);
+        let prompts_dir = jumble_dir.join("prompts");
+        if prompts_dir.is_dir() {
+            diagnostics.push(Diagnostic::warning(
+                &prompts_dir,
+                "skills in .jumble/prompts/ are no longer read; move them to .jumble/skills/",
+            ));
         }
 
         // Personal/global Jumble skills: <home>/.jumble/skills/*.md
-        if let Some(home_dir) = resolve_home_dir() {
-            let global_skills_dir = home_dir.join(".jumble").join("skills");
-            if global_skills_dir.is_dir() {
-                if let Ok(entries) = std::fs::read_dir(&global_skills_dir) {
-                    for entry in entries.filter_map(|e| e.ok()) {
-                        let path = entry.path();
-                        if path.extension().map(|e| e == "md").unwrap_or(false) {
-                            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                                // Don't override project-local skills with global ones.
-                                if skills.skills.contains_key(stem) {
-                                    continue;
-                                }
-
-                                let (frontmatter, preview) = match std::fs::read_to_string(&path) {
-                                    Ok(content) => extract_skill_frontmatter_and_preview(&content),
-                                    Err(_) => (None, String::new()),
-                                };
-
-                                skills.skills.insert(
-                                    stem.to_string(),
-                                    SkillInfo {
-                                        path: path.clone(),
-                                        skill_dir: None,
-                                        frontmatter,
-                                        preview,
-                                    },
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        merge_skills(&mut skills, &self.global_skills.jumble, diagnostics);
+
+        // Remote skill packs: <home>/.jumble/skill-cache/<name>/**/SKILL.md
+        merge_skills(&mut skills, &self.global_skills.packs, diagnostics);
 
         // Project-local Claude skills: <project_root>/.claude/skills/**/SKILL.md
         if let Some(project_root) = jumble_dir.parent() {
             let claude_skills_dir = project_root.join(".claude/skills");
             if claude_skills_dir.is_dir() {
-                discover_structured_skills_in_dir(&claude_skills_dir, &mut skills);
+                discover_structured_skills_in_dir(&claude_skills_dir, &mut skills, diagnostics);
             }
         }
 
         // Personal/global Claude skills: <home>/.claude/skills/**/SKILL.md
-        if let Some(home_dir) = resolve_home_dir() {
-            let personal_skills_dir = home_dir.join(".claude/skills");
-            if personal_skills_dir.is_dir() {
-                discover_structured_skills_in_dir(&personal_skills_dir, &mut skills);
-            }
-        }
+        merge_skills(&mut skills, &self.global_skills.claude, diagnostics);
 
         // Project-local Codex skills: <project_root>/.codex/skills/**/SKILL.md
         if let Some(project_root) = jumble_dir.parent() {
             let codex_skills_dir = project_root.join(".codex/skills");
             if codex_skills_dir.is_dir() {
-                discover_structured_skills_in_dir(&codex_skills_dir, &mut skills);
+                discover_structured_skills_in_dir(&codex_skills_dir, &mut skills, diagnostics);
             }
         }
 
         // Personal/global Codex skills: <home>/.codex/skills/**/SKILL.md
-        if let Some(home_dir) = resolve_home_dir() {
-            let personal_codex_dir = home_dir.join(".codex/skills");
-            if personal_codex_dir.is_dir() {
-                discover_structured_skills_in_dir(&personal_codex_dir, &mut skills);
-            }
-        }
+        merge_skills(&mut skills, &self.global_skills.codex, diagnostics);
 
         skills
     }
 
-    fn load_conventions(&self, jumble_dir: &Path) -> ProjectConventions {
-        let conventions_path = jumble_dir.join("conventions.toml");
+    /// Conventions from `.jumble/conventions.*`, layered over the sections of a
+    /// single-file `jumble.toml` when the project has one.
+    fn load_conventions(
+        &self,
+        jumble_dir: &Path,
+        sections: Option<&SingleFileSections>,
+    ) -> ProjectConventions {
+        let mut conventions = sections
+            .map(SingleFileSections::project_conventions)
+            .unwrap_or_default();
+        if let Some(file) = config::find_config_file(jumble_dir, "conventions")
+            .and_then(|path| config::load_config_file(&path).ok())
+        {
+            conventions.extend(file);
+        }
+        conventions
+    }
 
-        if conventions_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&conventions_path) {
-                if let Ok(conventions) = toml::from_str(&content) {
-                    return conventions;
-                }
+    fn load_docs(&self, jumble_dir: &Path, sections: Option<&SingleFileSections>) -> ProjectDocs {
+        let single_file = sections
+            .map(SingleFileSections::project_docs)
+            .filter(|docs| !docs.docs.is_empty());
+        match (config::find_config_file(jumble_dir, "docs"), single_file) {
+            (Some(path), single_file) => {
+                let mut docs = single_file.unwrap_or_default();
+                let file: ProjectDocs = config::load_config_file(&path).unwrap_or_default();
+                docs.docs.extend(file.docs);
+                docs
             }
+            (None, Some(docs)) => docs,
+            // Without a hand-written index, fall back to whatever lives in `docs/`.
+            (None, None) => jumble_dir.parent().map(index_docs_dir).unwrap_or_default(),
         }
+    }
 
-        ProjectConventions::default()
+    fn load_project(&self, path: &Path) -> Result<ProjectConfig> {
+        config::load_project_config(path)
     }
 
-    fn load_docs(&self, jumble_dir: &Path) -> ProjectDocs {
-        let docs_path = jumble_dir.join("docs.toml");
+    /// Whether handling `request` needs exclusive access to the server
+    /// ([`Server::handle_request`]) rather than shared access
+    /// ([`Server::handle_shared_request`]).
+    pub fn needs_exclusive(request: &JsonRpcRequest) -> bool {
+        EXCLUSIVE_METHODS.contains(&request.method.as_str())
+            || request.method == "tools/call"
+                && request
+                    .params
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|name| EXCLUSIVE_TOOLS.contains(&name))
+    }
 
-        if docs_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&docs_path) {
-                if let Ok(docs) = toml::from_str(&content) {
-                    return docs;
-                }
+    /// Handles a single JSON-RPC message, returning the response to send.
+    ///
+    /// Notifications (messages without an `id`) are processed but never
+    /// answered, not even with an error, as required by JSON-RPC.
+    pub fn handle_request(&mut self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        if !Self::needs_exclusive(&request) || self.shutting_down.load(Ordering::SeqCst) {
+            return self.handle_shared_request(request);
+        }
+        let started = Instant::now();
+        let result = match request.method.as_str() {
+            "initialize" => self.handle_initialize(&request.params),
+            "initialized" | "notifications/initialized" | "notifications/roots/list_changed" => {
+                self.request_roots();
+                Ok(json!({}))
             }
+            _ => self.handle_exclusive_tools_call(&request.params),
+        };
+        self.respond(request, started, result)
+    }
+
+    /// Handles the client's answer to a request the server sent. Only
+    /// `roots/list` answers are expected; anything else is logged and dropped.
+    pub fn handle_response(&mut self, response: JsonRpcClientResponse) {
+        if self.client_roots.pending.as_ref() != Some(&response.id) {
+            debug!(id = %response.id, "ignoring response to unknown request");
+            return;
+        }
+        self.client_roots.pending = None;
+        if let Some(error) = response.error {
+            warn!(%error, "client failed to list roots");
+            return;
         }
+        let roots = response
+            .result
+            .as_ref()
+            .and_then(|r| r.get("roots"))
+            .and_then(|r| r.as_array())
+            .map(|roots| roots_to_paths(roots))
+            .unwrap_or_default();
+        self.set_roots(roots);
+    }
 
-        ProjectDocs::default()
+    /// Asks the client for its roots, if it supports them and nothing is
+    /// listening for the answer yet.
+    fn request_roots(&mut self) {
+        if !self.client_roots.supported || self.client_roots.pending.is_some() {
+            return;
+        }
+        let Some(notifier) = self.notifier.as_mut() else {
+            return;
+        };
+        self.client_roots.sent += 1;
+        let id = json!(format!("jumble-roots-{}", self.client_roots.sent));
+        notifier(JsonRpcNotification::request(
+            id.clone(),
+            "roots/list",
+            json!({}),
+        ));
+        self.client_roots.pending = Some(id);
     }
 
-    fn load_project(&self, path: &Path) -> Result<ProjectConfig> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read {}", path.display()))?;
-        let config: ProjectConfig = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", path.display()))?;
-        Ok(config)
+    /// Rescopes discovery to the client's roots: the first becomes the
+    /// workspace root and the rest are scanned for projects too. No roots
+    /// means back to `--root`.
+    fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        let mut roots = roots.into_iter();
+        let root = roots.next().unwrap_or_else(|| self.launch_root.clone());
+        let extra: Vec<PathBuf> = roots.collect();
+        if root == self.root && extra == self.client_roots.extra {
+            return;
+        }
+        info!(root = %root.display(), extra = extra.len(), "rescoping to client roots");
+        self.root = root;
+        self.client_roots.extra = extra;
+        if let Err(e) = self.reload_workspace_and_projects(None) {
+            warn!(error = %e, "failed to rescan client roots");
+        }
     }
 
-    pub fn handle_request(&mut self, request: JsonRpcRequest) -> JsonRpcResponse {
+    /// Handles a message that does not need exclusive access, so that several
+    /// can run at once. Messages for which [`Server::needs_exclusive`] is true
+    /// must go through [`Server::handle_request`] instead.
+    pub fn handle_shared_request(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        let started = Instant::now();
         let result = match request.method.as_str() {
-            "initialize" => self.handle_initialize(&request.params),
-            "initialized" => Ok(json!({})),
+            "exit" => {
+                self.exit_requested.store(true, Ordering::SeqCst);
+                Ok(Value::Null)
+            }
+            _ if self.shutting_down.load(Ordering::SeqCst) => Err(JsonRpcError::new(
+                INVALID_REQUEST,
+                "Server is shutting down",
+            )),
+            "shutdown" => {
+                self.shutting_down.store(true, Ordering::SeqCst);
+                info!("shutdown requested");
+                Ok(Value::Null)
+            }
+            "ping" => Ok(json!({})),
+            "notifications/cancelled" => Ok(json!({})),
             "tools/list" => self.handle_tools_list(),
             "tools/call" => self.handle_tools_call(&request.params),
-            _ => Err(JsonRpcError {
-                code: -32601,
-                message: format!("Method not found: {}", request.method),
-                data: None,
-            }),
+            "completion/complete" => self.handle_completion(&request.params),
+            _ => Err(JsonRpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", request.method),
+            )
+            .with_data(json!({ "method": request.method }))),
         };
+        self.respond(request, started, result)
+    }
 
-        match result {
-            Ok(value) => JsonRpcResponse::success(request.id, value),
-            Err(error) => JsonRpcResponse::error(request.id, error),
-        }
+    fn respond(
+        &self,
+        request: JsonRpcRequest,
+        started: Instant,
+        result: Result<Value, JsonRpcError>,
+    ) -> Option<JsonRpcResponse> {
+        debug!(
+            method = %request.method,
+            id = ?request.id,
+            ok = result.is_ok(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "handled request"
+        );
+        let id = Some(request.id?);
+        Some(match result {
+            Ok(value) => JsonRpcResponse::success(id, value),
+            Err(error) => JsonRpcResponse::error(id, error),
+        })
     }
 
-    fn handle_initialize(&self, _params: &Value) -> Result<Value, JsonRpcError> {
+    fn handle_initialize(&mut self, params: &Value) -> Result<Value, JsonRpcError> {
+        let requested = params.get("protocolVersion").and_then(|v| v.as_str());
+        let protocol_version = negotiate_protocol_version(requested)?;
+        let capabilities = params.get("capabilities");
+        self.client_roots.supported = capabilities.and_then(|c| c.get("roots")).is_some();
+        self.client_sampling = capabilities.and_then(|c| c.get("sampling")).is_some();
+        // Some clients send their roots up front instead of waiting for
+        // `roots/list`.
+        if let Some(roots) = params.get("roots").and_then(|r| r.as_array()) {
+            self.set_roots(roots_to_paths(roots));
+        }
         Ok(json!({
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": protocol_version,
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "completions": {}
             },
             "serverInfo": {
                 "name": "jumble",
                 "version": env!("CARGO_PKG_VERSION")
-            }
+            },
+            "instructions": setup::SERVER_INSTRUCTIONS
         }))
     }
 
     fn handle_tools_list(&self) -> Result<Value, JsonRpcError> {
-        Ok(tools::tools_list())
+        Ok(json!({ "tools": self.enabled_tools() }))
     }
 
-    fn handle_tools_call(&mut self, params: &Value) -> Result<Value, JsonRpcError> {
-        let name = params
-            .get("name")
+    /// `completion/complete`: suggests values for the `project`, `concept`,
+    /// `topic`, and `key` arguments from the loaded context. Concepts, topics,
+    /// and memory keys come from the project named in `context.arguments`,
+    /// or from every project when none is named yet.
+    fn handle_completion(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let argument = params
+            .get("argument")
+            .and_then(|a| a.get("name"))
+            .and_then(|n| n.as_str())
+            .ok_or_else(|| JsonRpcError::new(INVALID_PARAMS, "Missing argument.name"))?;
+        let partial = params
+            .get("argument")
+            .and_then(|a| a.get("value"))
             .and_then(|v| v.as_str())
-            .ok_or_else(|| JsonRpcError {
-                code: -32602,
-                message: "Missing 'name' parameter".to_string(),
-                data: None,
+            .unwrap_or_default();
+        let project = params
+            .get("context")
+            .and_then(|c| c.get("arguments"))
+            .and_then(|a| a.get("project"))
+            .and_then(|p| p.as_str())
+            .and_then(|p| fuzzy::resolve(p, self.projects.keys().map(|k| k.as_str())));
+        let projects: Vec<&ProjectData> = match project {
+            Some(name) => vec![&self.projects[name]],
+            None => self.projects.values().collect(),
+        };
+
+        let candidates: Vec<String> = match argument {
+            "project" => self.projects.keys().cloned().collect(),
+            "concept" => projects
+                .iter()
+                .flat_map(|(_, config, ..)| config.concepts.keys().cloned())
+                .collect(),
+            "topic" => projects
+                .iter()
+                .flat_map(|(_, _, skills, _, docs, _)| {
+                    skills.skills.keys().chain(docs.docs.keys()).cloned()
+                })
+                .collect(),
+            "key" => {
+                let mut dbs: Vec<&MemoryDatabase> = projects.iter().map(|data| &data.5).collect();
+                if project.is_none() {
+                    dbs.extend(&self.global_memory);
+                }
+                dbs.iter()
+                    .filter_map(|db| db.read(|db| db.keys().cloned().collect::<Vec<_>>()).ok())
+                    .flatten()
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let values = fuzzy::complete(partial, candidates.iter().map(|c| c.as_str()));
+        Ok(json!({
+            "completion": {
+                "values": values.iter().take(MAX_COMPLETIONS).collect::<Vec<_>>(),
+                "total": values.len(),
+                "hasMore": values.len() > MAX_COMPLETIONS
+            }
+        }))
+    }
+
+    /// Tool definitions not switched off by a global or workspace `[tools]` table.
+    fn enabled_tools(&self) -> Vec<Value> {
+        let list = tools::tools_list();
+        let plugin = self.plugin_tools().into_iter().map(|(name, (_, _, tool))| {
+            tools::tool_definition(name, &tool.description, &tool.arguments, &tool.required)
+        });
+        // Custom tools only render text; plugins may do anything, so they
+        // keep the MCP defaults.
+        let custom = self.custom_tools().into_iter().map(|(name, tool)| {
+            let mut definition =
+                tools::tool_definition(name, &tool.description, &tool.arguments, &tool.required);
+            definition["annotations"]["readOnlyHint"] = json!(true);
+            definition
+        });
+        list["tools"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .cloned()
+            .chain(plugin.collect::<Vec<_>>())
+            .chain(custom.collect::<Vec<_>>())
+            .filter(|tool| tool["name"].as_str().is_some_and(|n| self.tool_enabled(n)))
+            .collect()
+    }
+
+    /// Tools handled by `[plugins]` in the global config, with the plugin
+    /// serving each. Names of built-in tools are skipped.
+    fn plugin_tools(&self) -> BTreeMap<&str, (&str, &PluginConfig, &PluginTool)> {
+        self.jumble_config
+            .iter()
+            .flat_map(|c| &c.plugins)
+            .flat_map(|(plugin_name, plugin)| {
+                plugin
+                    .tools
+                    .iter()
+                    .map(move |(name, tool)| (name.as_str(), (plugin_name.as_str(), plugin, tool)))
+            })
+            .filter(|(name, _)| !tools::is_builtin_tool(name))
+            .collect()
+    }
+
+    /// Tools from `[custom_tools]` in the global config and the workspace,
+    /// the workspace winning on a name clash. Names of built-in and plugin
+    /// tools are skipped; `tool_name_diagnostics` reports them.
+    fn custom_tools(&self) -> BTreeMap<&str, &CustomTool> {
+        let plugin_tools = self.plugin_tools();
+        let global = self.jumble_config.as_ref().map(|c| &c.custom_tools);
+        let workspace = self.workspace.as_ref().map(|w| &w.custom_tools);
+        [global, workspace]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|(name, _)| {
+                !tools::is_builtin_tool(name) && !plugin_tools.contains_key(name.as_str())
+            })
+            .map(|(name, tool)| (name.as_str(), tool))
+            .collect()
+    }
+
+    /// Warns about custom and plugin tools that another tool shadows.
+    fn tool_name_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let plugin_tools = self.plugin_tools();
+        let global_path = resolve_home_dir().map(|home| home.join(".jumble").join("jumble.toml"));
+        if let (Some(config), Some(path)) = (&self.jumble_config, &global_path) {
+            for (plugin_name, plugin) in &config.plugins {
+                for name in plugin.tools.keys().filter(|n| tools::is_builtin_tool(n)) {
+                    diagnostics.push(Diagnostic::warning(
+                        path,
+                        format!(
+                            "tool '{}' of plugin '{}' has the name of a built-in tool and is ignored",
+                            name, plugin_name
+                        ),
+                    ));
+                }
+            }
+        }
+        let global = self
+            .jumble_config
+            .as_ref()
+            .zip(global_path)
+            .map(|(c, path)| (path, &c.custom_tools));
+        let workspace = self.workspace.as_ref().and_then(|w| {
+            let path = config::find_config_file(&self.root.join(".jumble"), "workspace")?;
+            Some((path, &w.custom_tools))
+        });
+        for (path, custom_tools) in [global, workspace].into_iter().flatten() {
+            for name in custom_tools.keys() {
+                let shadowed_by = if tools::is_builtin_tool(name) {
+                    "a built-in tool"
+                } else if plugin_tools.contains_key(name.as_str()) {
+                    "a plugin tool"
+                } else {
+                    continue;
+                };
+                diagnostics.push(Diagnostic::warning(
+                    &path,
+                    format!(
+                        "custom tool '{}' has the name of {} and is ignored",
+                        name, shadowed_by
+                    ),
+                ));
+            }
+        }
+        diagnostics
+    }
+
+    /// Sends a call for a plugin tool to its plugin, along with the
+    /// workspace root and, when known, the project it names.
+    fn call_plugin_tool(
+        &self,
+        name: &str,
+        plugin_name: &str,
+        plugin: &PluginConfig,
+        arguments: &Value,
+    ) -> Result<String, String> {
+        let mut request = json!({
+            "tool": name,
+            "arguments": arguments,
+            "root": self.root,
+        });
+        if let Some(project) = arguments.get("project").and_then(|v| v.as_str()) {
+            if let Some((path, ..)) = self.projects.get(project) {
+                request["project"] = json!({"name": project, "path": path});
+            }
+        }
+        plugins::call(plugin_name, plugin, &request)
+    }
+
+    fn tool_enabled(&self, name: &str) -> bool {
+        let global = self.jumble_config.as_ref().map(|c| &c.tools);
+        let workspace = self.workspace.as_ref().map(|w| &w.tools);
+        [global, workspace]
+            .into_iter()
+            .flatten()
+            .all(|tools| tools.enables(name))
+    }
+
+    /// Where the transport loop delivers answers to requests a tool call sent.
+    pub fn pending_requests(&self) -> Arc<PendingRequests> {
+        Arc::clone(&self.pending)
+    }
+
+    /// Sends `sampling/createMessage` to the client. The answer is awaited
+    /// with [`SamplingCall::wait`], which needs no access to the server.
+    fn start_sampling(&self, params: Value) -> Result<SamplingCall, String> {
+        if !self.client_sampling {
+            return Err("The connected client does not support MCP sampling. Use get_jumble_authoring_prompt to write the config by hand instead.".to_string());
+        }
+        let notifier = self
+            .notifier
+            .as_ref()
+            .ok_or("No client connection to send a sampling request on")?;
+        let n = self.samples_sent.fetch_add(1, Ordering::SeqCst) + 1;
+        let id = json!(format!("jumble-sampling-{}", n));
+        let answer = self.pending.register(&id);
+        notifier(JsonRpcNotification::request(
+            id.clone(),
+            "sampling/createMessage",
+            params,
+        ));
+        Ok(SamplingCall {
+            id,
+            answer,
+            pending: Arc::clone(&self.pending),
+        })
+    }
+
+    /// `draft_project_config`: asks the client's model for a draft, or with
+    /// `confirm` writes the draft shown earlier.
+    fn draft_project_config(&self, args: &Value) -> Result<String, String> {
+        match self.start_draft(args)? {
+            DraftStep::Done(message) => Ok(message),
+            DraftStep::Sampling(dir, call) => self.finish_draft(dir, call.wait()),
+        }
+    }
+
+    /// Writes a confirmed draft, or sends the sampling request for a new one.
+    fn start_draft(&self, args: &Value) -> Result<DraftStep, String> {
+        let dir = authoring::draft_target(&self.root, args)?;
+        let confirm = args
+            .get("confirm")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if confirm {
+            let mut drafts = self.drafts.lock().unwrap_or_else(PoisonError::into_inner);
+            let draft = drafts.get(&dir).ok_or_else(|| {
+                format!(
+                    "No draft to confirm for '{}'. Call draft_project_config without 'confirm' first.",
+                    dir.display()
+                )
             })?;
+            let file = authoring::write_draft(&dir, draft)?;
+            drafts.remove(&dir);
+            return Ok(DraftStep::Done(format!(
+                "Wrote {}. Call reload_workspace to load the project.",
+                file.display()
+            )));
+        }
+        let call = self.start_sampling(authoring::sampling_request(&dir))?;
+        Ok(DraftStep::Sampling(dir, call))
+    }
+
+    /// Keeps the model's draft for `dir` until it is confirmed.
+    fn finish_draft(&self, dir: PathBuf, result: Result<Value, String>) -> Result<String, String> {
+        let draft = authoring::parse_draft(&result?)?;
+        let preview = format!(
+            "Draft .jumble/project.toml for {}:\n\n```toml\n{}```\n\nReview it with the user, then call draft_project_config with confirm: true to write it.",
+            dir.display(),
+            draft
+        );
+        self.drafts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(dir, draft);
+        Ok(preview)
+    }
+
+    /// Whether `request` is a call that waits on the client's model, to be
+    /// handled by [`Server::handle_sampling_request`] instead of under a lock.
+    pub fn needs_sampling(request: &JsonRpcRequest) -> bool {
+        request.method == "tools/call"
+            && request.params.get("name").and_then(|v| v.as_str()) == Some("draft_project_config")
+    }
+
+    /// Handles a `draft_project_config` call, holding `server`'s lock only to
+    /// send the sampling request and to keep the draft. The client may take
+    /// minutes to answer, and exclusive requests must not wait on it.
+    pub fn handle_sampling_request(
+        server: &RwLock<Server>,
+        request: JsonRpcRequest,
+    ) -> Option<JsonRpcResponse> {
+        let read = || server.read().unwrap_or_else(PoisonError::into_inner);
+        let guard = read();
+        if guard.shutting_down.load(Ordering::SeqCst) {
+            return guard.handle_shared_request(request);
+        }
+        let started = Instant::now();
+        let (name, arguments) = match guard.tool_call_target(&request.params) {
+            Ok((name, arguments)) => (name.to_string(), arguments),
+            Err(error) => return guard.respond(request, started, Err(error)),
+        };
+        let step = match guard.read_only_error(&name, &arguments) {
+            Some(error) => Err(error),
+            None => guard.start_draft(&arguments),
+        };
+        drop(guard);
+
+        let result = match step {
+            Ok(DraftStep::Sampling(dir, call)) => {
+                let answer = call.wait();
+                read().finish_draft(dir, answer)
+            }
+            Ok(DraftStep::Done(message)) => Ok(message),
+            Err(error) => Err(error),
+        };
+        let guard = read();
+        let result = guard.finish_tool_call(&name, &arguments, started, result);
+        guard.respond(request, started, result)
+    }
+
+    /// Formats the recorded tool usage for `get_usage_stats`.
+    fn usage_stats(&self) -> Result<String, String> {
+        let db = self
+            .stats
+            .as_ref()
+            .ok_or("Usage statistics are not being recorded by this server")?;
+        let stats = db
+            .read(|stats| stats.clone())
+            .map_err(|e| format!("Failed to read stats database: {}", e))?;
+        Ok(stats::format_stats(&stats))
+    }
+
+    /// Protocol-level error for a `tools/call` naming a tool that does not
+    /// exist or has been disabled.
+    fn unknown_tool_error(&self, name: &str) -> JsonRpcError {
+        let available: Vec<Value> = self
+            .enabled_tools()
+            .iter()
+            .map(|t| t["name"].clone())
+            .collect();
+        let message = if self.tool_enabled(name) {
+            format!("Unknown tool: {}", name)
+        } else {
+            format!("Tool '{}' is disabled by configuration", name)
+        };
+        JsonRpcError::new(INVALID_PARAMS, message).with_data(json!({
+            "tool": name,
+            "available_tools": available,
+        }))
+    }
+
+    fn structured_errors(&self) -> bool {
+        self.jumble_config
+            .as_ref()
+            .map(|c| c.jumble.structured_errors)
+            .unwrap_or(false)
+    }
+
+    /// Per-source memory limits from the global `[memory]` table.
+    fn memory_quotas(&self) -> Option<&MemoryConfig> {
+        self.jumble_config.as_ref().map(|c| &c.memory)
+    }
+
+    /// Whether tool calls should be written to the audit log, as enabled by
+    /// either the workspace or the global configuration.
+    fn audit_log_enabled(&self) -> bool {
+        self.workspace
+            .as_ref()
+            .is_some_and(|ws| ws.workspace.audit_log)
+            || self
+                .jumble_config
+                .as_ref()
+                .is_some_and(|c| c.jumble.audit_log)
+    }
+
+    /// The configured default for the `paths` argument: the workspace setting,
+    /// else the global one.
+    fn default_path_style(&self) -> Option<PathStyle> {
+        self.workspace
+            .as_ref()
+            .and_then(|ws| ws.workspace.paths)
+            .or_else(|| self.jumble_config.as_ref().and_then(|c| c.jumble.paths))
+    }
+
+    fn unknown_project_error(&self, tool: &str, project: &str) -> JsonRpcError {
+        let mut available: Vec<&str> = self.projects.keys().map(|k| k.as_str()).collect();
+        available.sort();
+        let message = fuzzy::not_found("Project", project, available.iter().copied());
+        JsonRpcError::new(INVALID_PARAMS, message).with_data(json!({
+            "tool": tool,
+            "project": project,
+            "available_projects": available,
+        }))
+    }
+
+    fn handle_tools_call(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let (name, arguments) = self.tool_call_target(params)?;
+        let started = Instant::now();
+        let result = match self.read_only_error(name, &arguments) {
+            Some(error) => Err(error),
+            None => self
+                .call_tool(name, &arguments)
+                .ok_or_else(|| self.unknown_tool_error(name))?,
+        };
+        self.finish_tool_call(name, &arguments, started, result)
+    }
+
+    /// Runs one of the [`EXCLUSIVE_TOOLS`].
+    fn handle_exclusive_tools_call(&mut self, params: &Value) -> Result<Value, JsonRpcError> {
+        let (name, arguments) = self.tool_call_target(params)?;
+        let started = Instant::now();
+        if let Some(error) = self.read_only_error(name, &arguments) {
+            return self.finish_tool_call(name, &arguments, started, Err(error));
+        }
+        let result = match name {
+            "reload_workspace" => {
+                match self.reload_workspace_and_projects(progress_token(params)) {
+                    Ok(()) => Ok("Workspace and projects reloaded from disk.".to_string()),
+                    Err(e) => Err(format!("Failed to reload workspace: {}", e)),
+                }
+            }
+            "add_concept" => tools::add_concept(&mut self.projects, &arguments),
+            "create_skill" => tools::create_skill(&mut self.projects, &arguments),
+            "add_convention" => tools::add_convention(&mut self.projects, &arguments),
+            "promote_memory" => tools::promote_memory(&mut self.projects, &arguments),
+            _ => self
+                .call_tool(name, &arguments)
+                .ok_or_else(|| self.unknown_tool_error(name))?,
+        };
+        self.finish_tool_call(name, &arguments, started, result)
+    }
+
+    /// Resolves the tool name and arguments of a `tools/call`, rejecting
+    /// disabled tools and (with structured errors) unknown projects.
+    fn tool_call_target<'p>(&self, params: &'p Value) -> Result<(&'p str, Value), JsonRpcError> {
+        let name = params.get("name").and_then(|v| v.as_str()).ok_or_else(|| {
+            JsonRpcError::new(INVALID_PARAMS, "Missing 'name' parameter")
+                .with_data(json!({ "missing": "name" }))
+        })?;
+
+        if !self.tool_enabled(name) {
+            return Err(self.unknown_tool_error(name));
+        }
+
+        let mut arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+        if let (Some(style), Some(object)) = (self.default_path_style(), arguments.as_object_mut())
+        {
+            if tools::PATH_TOOLS.contains(&name) {
+                object.entry("paths").or_insert(json!(style));
+            }
+        }
+
+        // Read-only tools accept a loosely typed project name; writes must
+        // name their target exactly.
+        if !WRITE_TOOLS.contains(&name) {
+            if let Some(project) = arguments.get("project").and_then(|v| v.as_str()) {
+                if !self.projects.contains_key(project) {
+                    let keys = self.projects.keys().map(|k| k.as_str());
+                    if let Some(resolved) = fuzzy::resolve(project, keys) {
+                        arguments["project"] = json!(resolved);
+                    }
+                }
+            }
+        }
+
+        if self.structured_errors() {
+            if let Some(project) = arguments.get("project").and_then(|v| v.as_str()) {
+                if !self.projects.contains_key(project) {
+                    return Err(self.unknown_project_error(name, project));
+                }
+            }
+        }
+        Ok((name, arguments))
+    }
+
+    /// Runs a tool that only needs shared access, or returns `None` for an
+    /// unknown tool.
+    fn call_tool(&self, name: &str, arguments: &Value) -> Option<Result<String, String>> {
+        let result = match name {
+            "list_projects" => tools::list_projects(&self.projects),
+            "get_project_info" => tools::get_project_info(&self.projects, arguments),
+            "get_commands" => tools::get_commands(&self.projects, arguments),
+            "get_entry_point" => tools::get_entry_point(&self.projects, arguments),
+            "get_architecture" => tools::get_architecture(&self.root, &self.projects, arguments),
+            "get_related_files" => tools::get_related_files(&self.root, &self.projects, arguments),
+            "who_owns" => tools::who_owns(&self.root, &self.projects, arguments),
+            "get_tests_for_file" => tools::get_tests_for_file(&self.projects, arguments),
+            "get_coverage" => tools::get_coverage(&self.projects, arguments),
+            "get_environment" => tools::get_environment(&self.projects, arguments),
+            "get_services" => tools::get_services(&self.projects, arguments),
+            "get_ci_info" => tools::get_ci_info(&self.root, &self.projects, arguments),
+            "get_changelog" => tools::get_changelog(&self.projects, arguments),
+            "get_version_history" => tools::get_version_history(&self.projects, arguments),
+            "get_recent_changes" => tools::get_recent_changes(&self.projects, arguments),
+            "list_skills" => tools::list_skills(&self.projects, arguments),
+            "get_skill" => tools::get_skill(&self.projects, arguments),
+            "search_skills" => tools::search_skills(&self.projects, arguments),
+            "search_context" => tools::search_context(&self.projects, arguments),
+            "get_conventions" => tools::get_conventions(&self.projects, arguments),
+            "record_decision" => tools::record_decision(&self.projects, arguments),
+            "list_decisions" => tools::list_decisions(&self.projects, arguments),
+            "add_task" => tools::add_task(&self.projects, arguments),
+            "list_tasks" => tools::list_tasks(&self.projects, arguments),
+            "complete_task" => tools::complete_task(&self.projects, arguments),
+            "get_docs" => tools::get_docs(&self.root, &self.projects, arguments),
+            "search_docs" => tools::search_docs(&self.projects, arguments),
+            "get_workspace_overview" => {
+                tools::get_workspace_overview(&self.root, &self.workspace, &self.projects)
+            }
+            "get_session_briefing" => {
+                tools::get_session_briefing(&self.workspace, &self.projects, arguments)
+            }
+            "get_dependency_graph" => tools::get_dependency_graph(&self.projects, arguments),
+            "get_workspace_commands" => tools::get_workspace_commands(&self.workspace, arguments),
+            "get_workspace_conventions" => {
+                tools::get_workspace_conventions(&self.workspace, arguments)
+            }
+            "get_jumble_authoring_prompt" => tools::get_jumble_authoring_prompt(),
+            "get_diagnostics" => Ok(diagnostics::format_diagnostics(&self.diagnostics)),
+            "init_project" => tools::init_project(&self.root, arguments),
+            "get_context_bundle" => {
+                tools::get_context_bundle(&self.root, &self.projects, arguments)
+            }
+            "get_context_for_file" => {
+                tools::get_context_for_file(&self.root, &self.workspace, &self.projects, arguments)
+            }
+            "find_project_for_file" => {
+                tools::find_project_for_file(&self.root, &self.projects, arguments)
+            }
+            "store_memory" => tools::store_memory(&self.projects, self.memory_quotas(), arguments),
+            "store_global_memory" => tools::store_global_memory(
+                self.global_memory.as_ref(),
+                self.memory_quotas(),
+                arguments,
+            ),
+            "get_global_memory" => tools::get_global_memory(self.global_memory.as_ref(), arguments),
+            "get_memory" => tools::get_memory(&self.projects, arguments),
+            "get_memories" => tools::get_memories(&self.projects, arguments),
+            "list_memories" => tools::list_memories(&self.projects, arguments),
+            "search_memories" => tools::search_memories(&self.projects, arguments),
+            "search_all_memories" => {
+                tools::search_all_memories(&self.projects, self.global_memory.as_ref(), arguments)
+            }
+            "delete_memory" => tools::delete_memory(&self.projects, arguments),
+            "clear_memories" => tools::clear_memories(&self.projects, arguments),
+            "get_memory_audit" => {
+                tools::get_memory_audit(&self.projects, self.global_memory.as_ref(), arguments)
+            }
+            "get_usage_stats" => self.usage_stats(),
+            "draft_project_config" => self.draft_project_config(arguments),
+            _ => {
+                if let Some((plugin_name, plugin, _)) = self.plugin_tools().get(name) {
+                    self.call_plugin_tool(name, plugin_name, plugin, arguments)
+                } else {
+                    let tool = *self.custom_tools().get(name)?;
+                    tools::call_custom_tool(&self.root, name, tool, arguments)
+                }
+            }
+        };
+        Some(result)
+    }
+
+    /// Records, truncates, and audits a tool result and shapes it as a
+    /// `tools/call` response.
+    fn finish_tool_call(
+        &self,
+        name: &str,
+        arguments: &Value,
+        started: Instant,
+        result: Result<String, String>,
+    ) -> Result<Value, JsonRpcError> {
+        if let Some(db) = &self.stats {
+            if let Err(e) = stats::record_call(db, name, started.elapsed(), result.is_ok()) {
+                warn!(tool = name, error = %e, "failed to record tool usage");
+            }
+        }
+
+        let result = match tools::max_chars(arguments) {
+            Some(limit) => result.map(|content| format::truncate_output(&content, limit)),
+            None => result,
+        };
+
+        if self.audit_log_enabled() {
+            let path = audit::audit_log_path(&self.root);
+            if let Err(e) = audit::append(&path, &AuditEntry::new(name, arguments, &result)) {
+                warn!(tool = name, error = %e, "failed to write audit log");
+            }
+        }
+
+        match result {
+            Ok(content) => Ok(json!({
+                "content": [{
+                    "type": "text",
+                    "text": content
+                }]
+            })),
+            Err(msg) => {
+                warn!(tool = name, error = %msg, "tool call failed");
+                if self.structured_errors() {
+                    return Err(
+                        JsonRpcError::new(TOOL_ERROR, msg).with_data(json!({ "tool": name }))
+                    );
+                }
+                Ok(json!({
+                    "content": [{
+                        "type": "text",
+                        "text": format!("Error: {}", msg)
+                    }],
+                    "isError": true
+                }))
+            }
+        }
+    }
+}
+
+/// The `progressToken` a client attached to a request via `params._meta`.
+fn progress_token(params: &Value) -> Option<Value> {
+    params
+        .get("_meta")
+        .and_then(|meta| meta.get("progressToken"))
+        .cloned()
+}
+
+/// Skills found outside any project, grouped by location so each project can
+/// merge them in precedence order.
+#[derive(Debug, Default)]
+struct GlobalSkills {
+    /// `~/.jumble/skills/*.md`
+    jumble: ProjectSkills,
+    /// `~/.jumble/skill-cache/<name>/**/SKILL.md`
+    packs: ProjectSkills,
+    /// `~/.claude/skills/**/SKILL.md`
+    claude: ProjectSkills,
+    /// `~/.codex/skills/**/SKILL.md`
+    codex: ProjectSkills,
+}
+
+/// A `sampling/createMessage` request sent to the client, awaiting its answer.
+struct SamplingCall {
+    id: Value,
+    answer: mpsc::Receiver<JsonRpcClientResponse>,
+    pending: Arc<PendingRequests>,
+}
+
+impl SamplingCall {
+    /// Waits up to [`SAMPLING_TIMEOUT`] for the model's reply.
+    fn wait(self) -> Result<Value, String> {
+        let response = self.answer.recv_timeout(SAMPLING_TIMEOUT).map_err(|_| {
+            self.pending.forget(&self.id);
+            format!(
+                "The client did not answer the sampling request within {} seconds",
+                SAMPLING_TIMEOUT.as_secs()
+            )
+        })?;
+        if let Some(error) = response.error {
+            let message = error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .map_or_else(|| error.to_string(), str::to_string);
+            return Err(format!(
+                "The client declined the sampling request: {}",
+                message
+            ));
+        }
+        response
+            .result
+            .ok_or_else(|| "The client returned an empty sampling result".to_string())
+    }
+}
+
+/// Where a `draft_project_config` call stands after its first step.
+enum DraftStep {
+    /// Finished, with the message for the caller.
+    Done(String),
+    /// Waiting on the client's model to draft the config for the directory.
+    Sampling(PathBuf, SamplingCall),
+}
+
+/// Tracks directories scanned during discovery and reports them to the client.
+///
+/// The total number of directories is not known up front, so notifications
+/// carry a running count and message but no `total`.
+struct DiscoveryProgress<'a> {
+    token: Option<Value>,
+    notifier: Option<&'a mut Notifier>,
+    directories: u64,
+}
+
+impl<'a> DiscoveryProgress<'a> {
+    fn new(token: Option<Value>, notifier: Option<&'a mut Notifier>) -> Self {
+        Self {
+            token,
+            notifier,
+            directories: 0,
+        }
+    }
+
+    fn directory_scanned(&mut self, projects_found: usize) {
+        self.directories += 1;
+        if self.directories.is_multiple_of(PROGRESS_INTERVAL_DIRS) {
+            self.notify(format!(
+                "Scanned {} directories, found {} project(s)",
+                self.directories, projects_found
+            ));
+        }
+    }
+
+    fn finish(&mut self, projects_found: usize) {
+        self.notify(format!(
+            "Discovery complete: scanned {} directories, found {} project(s)",
+            self.directories, projects_found
+        ));
+    }
+
+    fn notify(&mut self, message: String) {
+        if let (Some(token), Some(notifier)) = (&self.token, self.notifier.as_mut()) {
+            notifier(JsonRpcNotification::progress(
+                token.clone(),
+                self.directories,
+                None,
+                message,
+            ));
+        }
+    }
+}
+
+/// Whether `path` is the project config of a `.jumble` directory.
+///
+/// Any supported format matches, but when a directory holds several (say
+/// `project.toml` and `project.yaml`) only the preferred one counts, so the
+/// project is not loaded twice.
+fn is_project_config(path: &Path) -> bool {
+    let Some(jumble_dir) = path.parent() else {
+        return false;
+    };
+    if jumble_dir
+        .file_name()
+        .map(|n| n != ".jumble")
+        .unwrap_or(true)
+    {
+        return false;
+    }
+    config::find_config_file(jumble_dir, "project").as_deref() == Some(path)
+}
+
+/// The `.jumble` directory and project root for a project config at `path`.
+/// Each project counts once, through the first of: `.jumble/project.*`, a
+/// single-file `jumble.toml`, or the preferred package manifest embedding a
+/// Jumble config. The first two layer a manifest underneath themselves.
+fn project_location(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    if is_project_config(path) {
+        let jumble_dir = path.parent()?;
+        let project_dir = jumble_dir.parent().unwrap_or(jumble_dir);
+        return Some((jumble_dir.to_path_buf(), project_dir.to_path_buf()));
+    }
+    // Installed dependencies carry manifests too; never read those.
+    let single_file = config::is_single_file_config(path);
+    if !(single_file || is_manifest(path))
+        || path.components().any(|c| c.as_os_str() == "node_modules")
+    {
+        return None;
+    }
+    let project_dir = path.parent()?;
+    let jumble_dir = project_dir.join(".jumble");
+    if config::find_config_file(&jumble_dir, "project").is_some() {
+        return None;
+    }
+    if !single_file
+        && (config::find_single_file_config(project_dir).is_some()
+            || config::find_manifest_project(project_dir).as_deref() != Some(path))
+    {
+        return None;
+    }
+    Some((jumble_dir, project_dir.to_path_buf()))
+}
+
+fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| config::PROJECT_MANIFESTS.contains(&n))
+}
+
+/// Reports workspace manifest members under `root` that have no Jumble context.
+fn member_coverage_diagnostics(root: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for listed in members::workspace_members(root) {
+        let manifest = listed
+            .manifest
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let missing: Vec<&PathBuf> = listed
+            .members
+            .iter()
+            .filter(|dir| config::find_project_config(dir).is_none())
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        for dir in &missing {
+            diagnostics.push(Diagnostic::info(
+                dir,
+                format!(
+                    "workspace member listed in {} has no Jumble context",
+                    manifest
+                ),
+            ));
+        }
+        diagnostics.push(Diagnostic::info(
+            &listed.manifest,
+            format!(
+                "{} of {} workspace members have Jumble context",
+                listed.members.len() - missing.len(),
+                listed.members.len()
+            ),
+        ));
+    }
+    diagnostics
+}
 
-        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+/// Reports a `~/.jumble/jumble.toml` that [`load_jumble_config`] could not
+/// parse and is therefore ignoring.
+fn jumble_config_diagnostics() -> Vec<Diagnostic> {
+    let Some(path) = resolve_home_dir().map(|home| home.join(".jumble").join("jumble.toml")) else {
+        return Vec::new();
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match toml::from_str::<JumbleConfig>(&content) {
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            let error =
+                anyhow::Error::from(e).context(format!("Failed to parse {}", path.display()));
+            vec![Diagnostic::error(&path, schema::ignored_message(&error))]
+        }
+    }
+}
 
-        let result = match name {
-            "reload_workspace" => match self.reload_workspace_and_projects() {
-                Ok(()) => Ok("Workspace and projects reloaded from disk.".to_string()),
-                Err(e) => Err(format!("Failed to reload workspace: {}", e)),
-            },
-            "list_projects" => tools::list_projects(&self.projects),
-            "get_project_info" => tools::get_project_info(&self.projects, &arguments),
-            "get_commands" => tools::get_commands(&self.projects, &arguments),
-            "get_architecture" => tools::get_architecture(&self.projects, &arguments),
-            "get_related_files" => tools::get_related_files(&self.projects, &arguments),
-            "list_skills" => tools::list_skills(&self.projects, &arguments),
-            "get_skill" => tools::get_skill(&self.projects, &arguments),
-            "get_conventions" => tools::get_conventions(&self.projects, &arguments),
-            "get_docs" => tools::get_docs(&self.projects, &arguments),
-            "get_workspace_overview" => {
-                tools::get_workspace_overview(&self.root, &self.workspace, &self.projects)
+/// The local directories among MCP `Root` objects (`{"uri": "file://..."}`).
+fn roots_to_paths(roots: &[Value]) -> Vec<PathBuf> {
+    roots
+        .iter()
+        .filter_map(|root| root.get("uri").and_then(|u| u.as_str()))
+        .filter_map(|uri| {
+            let path = file_uri_to_path(uri);
+            if path.is_none() {
+                warn!(uri, "ignoring root that is not a local file URI");
             }
-            "get_workspace_conventions" => {
-                tools::get_workspace_conventions(&self.workspace, &arguments)
-            }
-            "get_jumble_authoring_prompt" => tools::get_jumble_authoring_prompt(),
-            "init_project" => tools::init_project(&self.root, &arguments),
-            "store_memory" => tools::store_memory(&self.projects, &arguments),
-            "get_memory" => tools::get_memory(&self.projects, &arguments),
-            "list_memories" => tools::list_memories(&self.projects, &arguments),
-            "search_memories" => tools::search_memories(&self.projects, &arguments),
-            "delete_memory" => tools::delete_memory(&self.projects, &arguments),
-            "clear_memories" => tools::clear_memories(&self.projects, &arguments),
-            _ => Err(format!("Unknown tool: {}", name)),
-        };
+            path
+        })
+        .collect()
+}
 
-        match result {
-            Ok(content) => Ok(json!({
-                "content": [{
-                    "type": "text",
-                    "text": content
-                }]
-            })),
-            Err(msg) => Ok(json!({
-                "content": [{
-                    "type": "text",
-                    "text": format!("Error: {}", msg)
-                }],
-                "isError": true
-            })),
-        }
-    }
+/// Identifies a directory independently of the path it was reached by.
+#[cfg(unix)]
+fn directory_id(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Identifies a directory independently of the path it was reached by.
+#[cfg(not(unix))]
+fn directory_id(path: &Path) -> Option<PathBuf> {
+    std::fs::canonicalize(path).ok()
 }
 
 /// Resolve the current user's home directory in a cross-platform way.
 ///
 /// On Unix-like systems this prefers the `HOME` environment variable. On
 /// Windows it falls back to `USERPROFILE`, then `HOMEDRIVE` + `HOMEPATH`.
-fn resolve_home_dir() -> Option<PathBuf> {
+pub fn resolve_home_dir() -> Option<PathBuf> {
     if let Ok(home) = std::env::var("HOME") {
         if !home.is_empty() {
             return Some(PathBuf::from(home));
@@ -362,7 +1708,7 @@ fn resolve_home_dir() -> Option<PathBuf> {
 
 /// Load global Jumble configuration from `~/.jumble/jumble.toml`, creating a
 /// default file if it does not exist. Failures to read or parse the file are
-/// logged to stderr but do not prevent the server from starting.
+/// logged but do not prevent the server from starting.
 fn load_jumble_config() -> Option<JumbleConfig> {
     let home_dir = resolve_home_dir()?;
     let jumble_dir = home_dir.join(".jumble");
@@ -370,20 +1716,20 @@ fn load_jumble_config() -> Option<JumbleConfig> {
 
     if !config_path.exists() {
         if let Err(e) = std::fs::create_dir_all(&jumble_dir) {
-            eprintln!(
-                "jumble: failed to create global config directory at {}: {}",
-                jumble_dir.display(),
-                e
+            warn!(
+                path = %jumble_dir.display(),
+                error = %e,
+                "failed to create global config directory"
             );
             return None;
         }
 
         let default_content = "# Global configuration for the Jumble MCP server.\n\n[jumble]\n";
         if let Err(e) = std::fs::write(&config_path, default_content) {
-            eprintln!(
-                "jumble: failed to create default config at {}: {}",
-                config_path.display(),
-                e
+            warn!(
+                path = %config_path.display(),
+                error = %e,
+                "failed to create default global config"
             );
             return None;
         }
@@ -392,10 +1738,10 @@ fn load_jumble_config() -> Option<JumbleConfig> {
     let content = match std::fs::read_to_string(&config_path) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!(
-                "jumble: failed to read global config at {}: {}",
-                config_path.display(),
-                e
+            warn!(
+                path = %config_path.display(),
+                error = %e,
+                "failed to read global config"
             );
             return None;
         }
@@ -404,18 +1750,74 @@ fn load_jumble_config() -> Option<JumbleConfig> {
     match toml::from_str::<JumbleConfig>(&content) {
         Ok(cfg) => Some(cfg),
         Err(e) => {
-            eprintln!(
-                "jumble: failed to parse global config at {}: {}",
-                config_path.display(),
-                e
+            warn!(
+                path = %config_path.display(),
+                error = %e,
+                "failed to parse global config"
             );
             None
         }
     }
 }
 
+/// Adds flat `*.md` skills from `dir`, keyed by file stem. Names already in
+/// `skills` are kept and the newcomer reported as shadowed.
+fn discover_flat_skills_in_dir(
+    dir: &Path,
+    skills: &mut ProjectSkills,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.extension().map(|e| e == "md").unwrap_or(false) {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if let Some(existing) = skills.skills.get(stem) {
+            diagnostics.push(shadowed_skill(stem, &path, &existing.path));
+            continue;
+        }
+
+        let (frontmatter, preview) = read_skill_file(&path, diagnostics);
+        skills.skills.insert(
+            stem.to_string(),
+            SkillInfo {
+                path: path.clone(),
+                skill_dir: None, // Flat skills have no companion directory
+                frontmatter,
+                preview,
+            },
+        );
+    }
+}
+
+/// Adds the cached skills of one global location, leaving names the project
+/// already defines in place and reporting the global copy as shadowed.
+fn merge_skills(
+    skills: &mut ProjectSkills,
+    source: &ProjectSkills,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (name, info) in &source.skills {
+        if let Some(existing) = skills.skills.get(name) {
+            diagnostics.push(shadowed_skill(name, &info.path, &existing.path));
+            continue;
+        }
+        skills.skills.insert(name.clone(), info.clone());
+    }
+}
+
 /// Discover structured skills (Claude/Codex-style) with SKILL.md files and companion resources.
-fn discover_structured_skills_in_dir(root: &Path, skills: &mut ProjectSkills) {
+fn discover_structured_skills_in_dir(
+    root: &Path,
+    skills: &mut ProjectSkills,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
     for entry in WalkDir::new(root)
         .follow_links(true)
         .into_iter()
@@ -436,10 +1838,7 @@ fn discover_structured_skills_in_dir(root: &Path, skills: &mut ProjectSkills) {
             continue;
         }
 
-        let (frontmatter, preview) = match std::fs::read_to_string(path) {
-            Ok(content) => extract_skill_frontmatter_and_preview(&content),
-            Err(_) => (None, String::new()),
-        };
+        let (frontmatter, preview) = read_skill_file(path, diagnostics);
 
         // Determine the skill key. Prefer the frontmatter `name` field when present,
         // otherwise fall back to the containing directory name.
@@ -457,8 +1856,12 @@ fn discover_structured_skills_in_dir(root: &Path, skills: &mut ProjectSkills) {
                 .to_string();
         }
 
-        if key.is_empty() || skills.skills.contains_key(&key) {
-            // Skip empty keys and avoid overwriting existing skills from .jumble/skills.
+        if key.is_empty() {
+            continue;
+        }
+        if let Some(existing) = skills.skills.get(&key) {
+            // Avoid overwriting existing skills from .jumble/skills.
+            diagnostics.push(shadowed_skill(&key, path, &existing.path));
             continue;
         }
 
@@ -477,85 +1880,1267 @@ fn discover_structured_skills_in_dir(root: &Path, skills: &mut ProjectSkills) {
     }
 }
 
-/// Extract optional YAML frontmatter and a preview snippet from a skill file.
-///
-/// Frontmatter is only recognized when the file starts with a line containing only `---`.
-/// Everything between the first and second such markers is treated as YAML.
-/// The preview is taken from the body that follows the frontmatter (or from the
-/// top of the file when no frontmatter is present).
-fn extract_skill_frontmatter_and_preview(content: &str) -> (Option<SkillFrontmatter>, String) {
-    const PREVIEW_MAX_LINES: usize = 16;
+/// Frontmatter keys understood by Jumble or by the Claude/Codex skill formats.
+const KNOWN_SKILL_FRONTMATTER_KEYS: &[&str] = &[
+    "name",
+    "description",
+    "tags",
+    "allowed-tools",
+    "license",
+    "metadata",
+];
+
+/// Read a skill file, returning its frontmatter and preview and recording any
+/// problems with the file in `diagnostics`.
+fn read_skill_file(
+    path: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> (Option<SkillFrontmatter>, String) {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            diagnostics.extend(check_skill_frontmatter(path, &content));
+            extract_skill_frontmatter_and_preview(&content)
+        }
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!("failed to read skill: {}", e),
+            ));
+            (None, String::new())
+        }
+    }
+}
 
-    // Helper to build a preview from a body slice.
-    fn build_preview(body: &str) -> String {
-        body.lines()
-            .take(PREVIEW_MAX_LINES)
-            .collect::<Vec<_>>()
-            .join("\n")
+fn shadowed_skill(name: &str, path: &Path, existing: &Path) -> Diagnostic {
+    Diagnostic::warning(
+        path,
+        format!(
+            "skill '{}' is shadowed by {} and will be ignored",
+            name,
+            existing.display()
+        ),
+    )
+}
+
+/// Validate a skill's frontmatter, reporting problems that
+/// `extract_skill_frontmatter_and_preview` would otherwise silently ignore.
+fn check_skill_frontmatter(path: &Path, content: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if !content.starts_with("---\n") {
+        diagnostics.push(Diagnostic::info(
+            path,
+            "skill has no frontmatter; add a `description` so agents know when to use it",
+        ));
+        return diagnostics;
     }
+    let Some((frontmatter, _)) = config::split_frontmatter(content) else {
+        diagnostics.push(Diagnostic::error(
+            path,
+            "frontmatter starts with `---` but has no closing `---` line",
+        ));
+        return diagnostics;
+    };
 
-    // Detect YAML frontmatter only if the file starts with `---` on the first line.
-    if let Some(rest) = content.strip_prefix("---\n") {
-        // Skip the initial `---\n`.
-        if let Some(end_idx) = rest.find("\n---\n") {
-            let frontmatter_str = &rest[..end_idx];
-            let body_start = end_idx + "\n---\n".len();
-            let body = &rest[body_start..];
+    let mapping = match serde_yaml::from_str::<serde_yaml::Value>(frontmatter) {
+        Ok(serde_yaml::Value::Mapping(mapping)) => mapping,
+        Ok(_) => {
+            diagnostics.push(Diagnostic::error(
+                path,
+                "frontmatter must be a YAML mapping",
+            ));
+            return diagnostics;
+        }
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(
+                path,
+                format!("invalid frontmatter: {}", e),
+            ));
+            return diagnostics;
+        }
+    };
 
-            let frontmatter = serde_yaml::from_str::<SkillFrontmatter>(frontmatter_str).ok();
-            let preview = build_preview(body);
-            return (frontmatter, preview);
+    for key in mapping.keys() {
+        let key = key.as_str().unwrap_or_default();
+        if !KNOWN_SKILL_FRONTMATTER_KEYS.contains(&key) {
+            diagnostics.push(Diagnostic::warning(
+                path,
+                format!("unknown frontmatter key '{}'", key),
+            ));
         }
     }
 
-    // No valid frontmatter header found; fall back to using the first lines of the file.
-    (None, build_preview(content))
+    if let Err(e) =
+        serde_yaml::from_value::<SkillFrontmatter>(serde_yaml::Value::Mapping(mapping.clone()))
+    {
+        diagnostics.push(Diagnostic::error(
+            path,
+            format!("invalid frontmatter: {}", e),
+        ));
+    }
+
+    let description = mapping
+        .get("description")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if description.trim().is_empty() {
+        diagnostics.push(Diagnostic::warning(
+            path,
+            "frontmatter has no `description`; agents won't know when to use this skill",
+        ));
+    }
+
+    diagnostics
+}
+
+/// Builds a doc index from the markdown files under `<project>/docs`.
+///
+/// Each file is keyed by its path below `docs/` without the extension
+/// (`guides/setup` for `docs/guides/setup.md`) and summarized by its first
+/// heading, or by that key when it has none. Hidden files and directories are
+/// skipped.
+fn index_docs_dir(project_dir: &Path) -> ProjectDocs {
+    let docs_dir = project_dir.join("docs");
+    let mut docs = HashMap::new();
+    if !docs_dir.is_dir() {
+        return ProjectDocs {
+            docs,
+            ..Default::default()
+        };
+    }
+
+    let entries = WalkDir::new(&docs_dir)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|e| e.ok());
+    for entry in entries {
+        let path = entry.path();
+        let is_markdown = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"))
+            .unwrap_or(false);
+        if !entry.file_type().is_file() || !is_markdown {
+            continue;
+        }
+        let Ok(relative) = path.strip_prefix(&docs_dir) else {
+            continue;
+        };
+
+        let key = relative
+            .with_extension("")
+            .to_string_lossy()
+            .replace('\\', "/");
+        let summary = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| {
+                content
+                    .lines()
+                    .find_map(format::markdown_heading)
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| key.clone());
+        let doc_path = format!("docs/{}", relative.to_string_lossy().replace('\\', "/"));
+        docs.insert(
+            key,
+            DocEntry {
+                path: doc_path,
+                summary,
+            },
+        );
+    }
+    ProjectDocs {
+        docs,
+        ..Default::default()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{JumbleSection, ToolsConfig};
     use std::collections::HashMap;
 
+    fn server_with_errors(structured_errors: bool) -> Server {
+        Server {
+            root: PathBuf::from("/workspace"),
+            launch_root: PathBuf::from("/workspace"),
+            client_roots: ClientRoots::default(),
+            client_sampling: false,
+            pending: Arc::default(),
+            samples_sent: AtomicU64::new(0),
+            drafts: Mutex::default(),
+            workspace: None,
+            projects: HashMap::new(),
+            jumble_config: Some(JumbleConfig {
+                jumble: JumbleSection {
+                    structured_errors,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            diagnostics: Vec::new(),
+            skill_packs: Vec::new(),
+            remote_projects: HashMap::new(),
+            global_skills: GlobalSkills::default(),
+            skip_global_skills: false,
+            notifier: None,
+            stats: None,
+            global_memory: None,
+            shutting_down: AtomicBool::new(false),
+            exit_requested: AtomicBool::new(false),
+        }
+    }
+
+    fn request(id: Option<Value>, method: &str, params: Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id,
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    fn call_tool(server: &mut Server, params: Value) -> JsonRpcResponse {
+        server
+            .handle_request(request(Some(json!(1)), "tools/call", params))
+            .expect("requests with an id get a response")
+    }
+
+    #[test]
+    fn test_initialize_negotiates_protocol_version() {
+        let mut server = server_with_errors(false);
+        let params = json!({"protocolVersion": "2025-03-26", "capabilities": {}});
+        let response = server
+            .handle_request(request(Some(json!(1)), "initialize", params))
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        assert!(result["instructions"]
+            .as_str()
+            .unwrap()
+            .contains("get_workspace_overview()"));
+
+        let params = json!({"protocolVersion": "2020-01-01"});
+        let response = server
+            .handle_request(request(Some(json!(2)), "initialize", params))
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_reload_workspace_reports_progress() {
+        use std::sync::{Arc, Mutex};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let jumble_dir = temp.path().join("app/.jumble");
+        std::fs::create_dir_all(&jumble_dir).unwrap();
+        std::fs::write(
+            jumble_dir.join("project.toml"),
+            "[project]\nname = \"app\"\ndescription = \"App\"\n",
+        )
+        .unwrap();
+
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&sent);
+        server.set_notifier(Box::new(move |n| sink.lock().unwrap().push(n)));
+
+        // Without a progress token nothing is sent.
+        call_tool(&mut server, json!({"name": "reload_workspace"}));
+        assert!(sent.lock().unwrap().is_empty());
+
+        let params = json!({"name": "reload_workspace", "_meta": {"progressToken": 42}});
+        call_tool(&mut server, params);
+        let sent = sent.lock().unwrap();
+        let last = sent.last().expect("expected a progress notification");
+        assert_eq!(last.method, "notifications/progress");
+        assert_eq!(last.params["progressToken"], 42);
+        assert!(last.params["message"]
+            .as_str()
+            .unwrap()
+            .contains("found 1 project(s)"));
+        assert_eq!(server.projects.len(), 1);
+    }
+
+    #[test]
+    fn test_dangling_related_projects_are_diagnosed() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let jumble_dir = temp.path().join("web/.jumble");
+        std::fs::create_dir_all(&jumble_dir).unwrap();
+        std::fs::write(
+            jumble_dir.join("project.toml"),
+            "[project]\nname = \"web\"\ndescription = \"x\"\n[related_projects]\nupstream = [\"api\"]\n",
+        )
+        .unwrap();
+
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let diagnostic = server
+            .diagnostics
+            .iter()
+            .find(|d| d.message.contains("unknown project 'api'"))
+            .expect("expected a dangling reference diagnostic");
+        assert_eq!(diagnostic.severity, diagnostics::Severity::Warning);
+        assert_eq!(diagnostic.path, jumble_dir.join("project.toml"));
+    }
+
+    #[test]
+    fn test_hidden_projects_are_not_discovered() {
+        let temp = tempfile::TempDir::new().unwrap();
+        for name in ["api", "vendored-lib", "secret"] {
+            let jumble_dir = temp.path().join(name).join(".jumble");
+            std::fs::create_dir_all(&jumble_dir).unwrap();
+            std::fs::write(
+                jumble_dir.join("project.toml"),
+                format!("[project]\nname = \"{}\"\ndescription = \"x\"\n", name),
+            )
+            .unwrap();
+        }
+        std::fs::create_dir_all(temp.path().join(".jumble")).unwrap();
+        std::fs::write(
+            temp.path().join(".jumble/workspace.toml"),
+            "[workspace]\nhide = [\"vendored-*\", \"secret\"]\n",
+        )
+        .unwrap();
+
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let names: Vec<&String> = server.projects.keys().collect();
+        assert_eq!(names, vec!["api"]);
+        assert!(!temp.path().join("secret/.jumble/memory.ron").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discovery_survives_symlink_cycles() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        for (dir, name) in [
+            (temp.path().join("api"), "api"),
+            (outside.path().join("ext"), "ext"),
+        ] {
+            std::fs::create_dir_all(dir.join(".jumble")).unwrap();
+            std::fs::write(
+                dir.join(".jumble/project.toml"),
+                format!("[project]\nname = \"{}\"\ndescription = \"x\"\n", name),
+            )
+            .unwrap();
+        }
+        // A cycle back to the root, and two aliases of the same outside tree.
+        std::os::unix::fs::symlink(temp.path(), temp.path().join("api/loop")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("link-a")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("link-b")).unwrap();
+
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        let mut diagnostics = Vec::new();
+        let mut progress = DiscoveryProgress::new(None, None);
+        let projects = server
+            .discover_projects(&server.root, &mut diagnostics, &mut progress)
+            .unwrap();
+        let mut names: Vec<&String> = projects.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["api", "ext"]);
+        assert_eq!(projects["api"].0, temp.path().join("api"));
+        // Each directory is walked once, however many links lead to it.
+        assert_eq!(progress.directories, 6);
+
+        std::fs::create_dir_all(temp.path().join(".jumble")).unwrap();
+        std::fs::write(
+            temp.path().join(".jumble/workspace.toml"),
+            "[discovery]\nfollow_links = false\n",
+        )
+        .unwrap();
+        server.reload_workspace_and_projects(None).unwrap();
+        let names: Vec<&String> = server.projects.keys().collect();
+        assert_eq!(names, vec!["api"]);
+    }
+
+    #[test]
+    fn test_manifest_projects_are_discovered() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let tiny = temp.path().join("tiny");
+        std::fs::create_dir_all(&tiny).unwrap();
+        std::fs::write(
+            tiny.join("Cargo.toml"),
+            "[package]\nname = \"tiny\"\ndescription = \"A tiny crate\"\n\n[package.metadata.jumble]\nunknown = 1\n",
+        )
+        .unwrap();
+        let plain = temp.path().join("plain");
+        std::fs::create_dir_all(&plain).unwrap();
+        std::fs::write(plain.join("Cargo.toml"), "[package]\nname = \"plain\"\n").unwrap();
+        let web = temp.path().join("web");
+        std::fs::create_dir_all(web.join("node_modules/dep")).unwrap();
+        std::fs::write(
+            web.join("package.json"),
+            r#"{"name": "web", "description": "Frontend", "jumble": {"commands": {"test": "npm test"}}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            web.join("node_modules/dep/package.json"),
+            r#"{"name": "dep", "description": "Vendored", "jumble": {}}"#,
+        )
+        .unwrap();
+
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let mut names: Vec<&String> = server.projects.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["tiny", "web"]);
+        assert_eq!(server.projects["web"].1.commands["test"], "npm test".into());
+        let (path, config, ..) = &server.projects["tiny"];
+        assert_eq!(path, &tiny);
+        assert_eq!(config.project.description, "A tiny crate");
+        assert!(server.diagnostics.iter().any(|d| {
+            d.path == tiny.join("Cargo.toml") && d.message.contains("unknown field 'unknown'")
+        }));
+    }
+
+    #[test]
+    fn test_single_file_projects_are_discovered() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let app = temp.path().join("app");
+        std::fs::create_dir_all(app.join(".jumble")).unwrap();
+        std::fs::write(
+            app.join("jumble.toml"),
+            r#"
+            [project]
+            name = "app"
+            description = "One file"
+
+            [concepts.auth]
+            files = ["src/auth.rs"]
+            summary = "Sessions"
+
+            [conventions]
+            errors = "Use anyhow"
+
+            [gotchas]
+            async = "Never block"
+
+            [categories.security]
+            secrets = "Never log tokens"
+
+            [docs.guide]
+            path = "docs/guide.md"
+            summary = "User guide"
+
+            [comands]
+            build = "make"
+            "#,
+        )
+        .unwrap();
+        // Entries added later by add_convention land in `.jumble/` and win.
+        std::fs::write(
+            app.join(".jumble/conventions.toml"),
+            "[gotchas]\nasync = \"Never block the runtime\"\n",
+        )
+        .unwrap();
+        // A project with `.jumble/project.toml` ignores a stray `jumble.toml`.
+        let other = temp.path().join("other");
+        std::fs::create_dir_all(other.join(".jumble")).unwrap();
+        std::fs::write(
+            other.join(".jumble/project.toml"),
+            "[project]\nname = \"other\"\ndescription = \"x\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            other.join("jumble.toml"),
+            "[project]\nname = \"duplicate\"\ndescription = \"x\"\n",
+        )
+        .unwrap();
+
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let mut names: Vec<&String> = server.projects.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["app", "other"]);
+        let (path, config, _, conventions, docs, _) = &server.projects["app"];
+        assert_eq!(path, &app);
+        assert!(config.concepts.contains_key("auth"));
+        assert_eq!(conventions.conventions["errors"], "Use anyhow".into());
+        assert_eq!(
+            conventions.gotchas["async"],
+            "Never block the runtime".into()
+        );
+        assert_eq!(
+            conventions.custom["security"]["secrets"],
+            "Never log tokens".into()
+        );
+        assert_eq!(docs.docs["guide"].path, "docs/guide.md");
+        assert!(server.diagnostics.iter().any(|d| {
+            d.path == app.join("jumble.toml") && d.message.contains("did you mean 'commands'")
+        }));
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let mut server = server_with_errors(false);
+        for method in [
+            "initialized",
+            "notifications/initialized",
+            "notifications/cancelled",
+            "notifications/unknown",
+        ] {
+            assert!(server
+                .handle_request(request(None, method, json!({})))
+                .is_none());
+        }
+
+        // The same method with an id is a request and is answered.
+        let response = server.handle_request(request(Some(json!(7)), "tools/list", Value::Null));
+        assert_eq!(response.unwrap().id, Some(json!(7)));
+    }
+
+    #[test]
+    fn test_client_roots_rescope_discovery() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut uris = Vec::new();
+        for name in ["web", "api"] {
+            let dir = temp.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("jumble.toml"),
+                format!("[project]\nname = \"{}\"\ndescription = \"x\"\n", name),
+            )
+            .unwrap();
+            uris.push(json!({"uri": format!("file://{}", dir.display())}));
+        }
+        let mut server = server_with_errors(false);
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&sent);
+        server.set_notifier(Box::new(move |n| sink.lock().unwrap().push(n)));
+
+        let params = json!({"protocolVersion": "2025-03-26", "capabilities": {"roots": {}}});
+        server.handle_request(request(Some(json!(1)), "initialize", params));
+        server.handle_request(request(None, "notifications/initialized", json!({})));
+        let id = {
+            let sent = sent.lock().unwrap();
+            let roots_request = sent.last().expect("expected a roots/list request");
+            assert_eq!(roots_request.method, "roots/list");
+            roots_request.id.clone().unwrap()
+        };
+
+        let answer = format!(
+            r#"{{"jsonrpc": "2.0", "id": {}, "result": {{"roots": {}}}}}"#,
+            id,
+            json!(uris)
+        );
+        server.handle_response(JsonRpcClientResponse::parse(&answer).unwrap());
+        assert_eq!(server.root, temp.path().join("web"));
+        let mut names: Vec<&String> = server.projects.keys().collect();
+        names.sort();
+        assert_eq!(names, ["api", "web"]);
+
+        // A change notification asks again; an empty answer restores `--root`.
+        server.handle_request(request(None, "notifications/roots/list_changed", json!({})));
+        let id = sent.lock().unwrap().last().unwrap().id.clone().unwrap();
+        assert_ne!(id, json!("jumble-roots-1"));
+        let answer = format!(
+            r#"{{"jsonrpc": "2.0", "id": {}, "result": {{"roots": []}}}}"#,
+            id
+        );
+        server.handle_response(JsonRpcClientResponse::parse(&answer).unwrap());
+        assert_eq!(server.root, PathBuf::from("/workspace"));
+        assert!(server.projects.is_empty());
+    }
+
+    #[test]
+    fn test_roots_are_not_requested_without_the_capability() {
+        let mut server = server_with_errors(false);
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&sent);
+        server.set_notifier(Box::new(move |n| sink.lock().unwrap().push(n)));
+        let params = json!({"protocolVersion": "2025-03-26", "capabilities": {}});
+        server.handle_request(request(Some(json!(1)), "initialize", params));
+        server.handle_request(request(None, "notifications/initialized", json!({})));
+        assert!(sent.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_draft_project_config_samples_then_writes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("app/src")).unwrap();
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        let call = |server: &mut Server, arguments: Value| {
+            let args = json!({"name": "draft_project_config", "arguments": arguments});
+            let result = call_tool(server, args).result.unwrap();
+            (
+                result["content"][0]["text"].as_str().unwrap().to_string(),
+                result["isError"] == json!(true),
+            )
+        };
+
+        let (text, is_error) = call(&mut server, json!({"directory": "app"}));
+        assert!(is_error);
+        assert!(text.contains("does not support MCP sampling"));
+
+        // The client answers the sampling request as soon as it is sent.
+        server.client_sampling = true;
+        let pending = server.pending_requests();
+        server.set_notifier(Box::new(move |n| {
+            assert_eq!(n.method, "sampling/createMessage");
+            let answer = json!({"jsonrpc": "2.0", "id": n.id, "result": {
+                "role": "assistant",
+                "content": {"type": "text", "text": "[project]\nname = \"app\"\ndescription = \"An app\""}
+            }});
+            let response = JsonRpcClientResponse::parse(&answer.to_string()).unwrap();
+            assert!(pending.deliver(response).is_none());
+        }));
+
+        let (text, is_error) = call(&mut server, json!({"directory": "app", "confirm": true}));
+        assert!(is_error);
+        assert!(text.contains("No draft to confirm"));
+
+        let (text, is_error) = call(&mut server, json!({"directory": "app"}));
+        assert!(!is_error);
+        assert!(text.contains("```toml\n[project]\nname = \"app\""));
+        let file = temp.path().join("app/.jumble/project.toml");
+        assert!(!file.exists());
+
+        let (text, is_error) = call(&mut server, json!({"directory": "app", "confirm": true}));
+        assert!(!is_error);
+        assert!(text.contains("reload_workspace"));
+        assert_eq!(
+            std::fs::read_to_string(file).unwrap(),
+            "[project]\nname = \"app\"\ndescription = \"An app\"\n"
+        );
+    }
+
+    #[test]
+    fn test_sampling_request_releases_server_lock() {
+        use std::sync::mpsc;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("app")).unwrap();
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.client_sampling = true;
+        let pending = server.pending_requests();
+        let (sent, requests) = mpsc::channel();
+        let sent = Mutex::new(sent);
+        server.set_notifier(Box::new(move |n| sent.lock().unwrap().send(n).unwrap()));
+        let server = Arc::new(RwLock::new(server));
+
+        let params = json!({"name": "draft_project_config", "arguments": {"directory": "app"}});
+        let request = request(Some(json!(1)), "tools/call", params);
+        assert!(Server::needs_sampling(&request));
+        let caller = {
+            let server = Arc::clone(&server);
+            std::thread::spawn(move || Server::handle_sampling_request(&server, request))
+        };
+
+        // While the client has the request, exclusive access is available.
+        let sampling = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(sampling.method, "sampling/createMessage");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.try_write().is_err() {
+            assert!(
+                Instant::now() < deadline,
+                "server lock held during sampling"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let answer = json!({"jsonrpc": "2.0", "id": sampling.id, "result": {
+            "role": "assistant",
+            "content": {"type": "text", "text": "[project]\nname = \"app\"\ndescription = \"An app\""}
+        }});
+        let response = JsonRpcClientResponse::parse(&answer.to_string()).unwrap();
+        assert!(pending.deliver(response).is_none());
+
+        let response = caller.join().unwrap().unwrap();
+        let result = response.result.unwrap();
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("name = \"app\""));
+    }
+
+    #[test]
+    fn test_completion_suggests_argument_values() {
+        let temp = tempfile::TempDir::new().unwrap();
+        for (name, concept) in [("web", "auth_flow"), ("webhooks", "authorization")] {
+            let dir = temp.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join("jumble.toml"),
+                format!(
+                    "[project]\nname = \"{}\"\ndescription = \"x\"\n\n[concepts.{}]\nsummary = \"x\"\nfiles = []\n",
+                    name, concept
+                ),
+            )
+            .unwrap();
+        }
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+        let complete = |server: &mut Server, params: Value| {
+            let response = server
+                .handle_request(request(Some(json!(1)), "completion/complete", params))
+                .unwrap();
+            response.result.unwrap()["completion"].clone()
+        };
+
+        let completion = complete(
+            &mut server,
+            json!({
+                "ref": {"type": "ref/prompt", "name": "get_architecture"},
+                "argument": {"name": "project", "value": "we"}
+            }),
+        );
+        assert_eq!(completion["values"], json!(["web", "webhooks"]));
+        assert_eq!(completion["total"], 2);
+        assert_eq!(completion["hasMore"], false);
+
+        // Concepts come from the project already chosen, when there is one.
+        let completion = complete(
+            &mut server,
+            json!({
+                "ref": {"type": "ref/prompt", "name": "get_architecture"},
+                "argument": {"name": "concept", "value": "auth"},
+                "context": {"arguments": {"project": "webhooks"}}
+            }),
+        );
+        assert_eq!(completion["values"], json!(["authorization"]));
+        let completion = complete(
+            &mut server,
+            json!({"argument": {"name": "concept", "value": "auth"}}),
+        );
+        assert_eq!(completion["values"], json!(["auth_flow", "authorization"]));
+
+        let completion = complete(
+            &mut server,
+            json!({"argument": {"name": "max_chars", "value": "1"}}),
+        );
+        assert_eq!(completion["values"], json!([]));
+        let response = server
+            .handle_request(request(Some(json!(1)), "completion/complete", json!({})))
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_members_without_context_are_reported() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        for name in ["core", "cli"] {
+            let dir = temp.path().join("crates").join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("Cargo.toml"), "[package]\n").unwrap();
+        }
+        std::fs::write(
+            temp.path().join("crates/core/jumble.toml"),
+            "[project]\nname = \"core\"\ndescription = \"x\"\n",
+        )
+        .unwrap();
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let coverage: Vec<String> = server
+            .diagnostics
+            .iter()
+            .filter(|d| d.message.contains("workspace member"))
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            coverage,
+            [
+                format!(
+                    "info: {}: 1 of 2 workspace members have Jumble context",
+                    temp.path().join("Cargo.toml").display()
+                ),
+                format!(
+                    "info: {}: workspace member listed in Cargo.toml has no Jumble context",
+                    temp.path().join("crates/cli").display()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discovery_detects_unset_language() {
+        let temp = tempfile::TempDir::new().unwrap();
+        for (name, language) in [("api", ""), ("web", "language = \"elm\"\n")] {
+            let dir = temp.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("go.mod"), "module api\n").unwrap();
+            std::fs::write(
+                dir.join("jumble.toml"),
+                format!(
+                    "[project]\nname = \"{}\"\ndescription = \"x\"\n{}",
+                    name, language
+                ),
+            )
+            .unwrap();
+        }
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let language = |name: &str| server.projects[name].1.project.language.clone();
+        assert_eq!(language("api").as_deref(), Some("go"));
+        assert_eq!(language("web").as_deref(), Some("elm"));
+    }
+
+    #[test]
+    fn test_ping_returns_empty_result() {
+        let mut server = server_with_errors(false);
+        let response = server
+            .handle_request(request(Some(json!(7)), "ping", Value::Null))
+            .unwrap();
+        assert_eq!(response.result, Some(json!({})));
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_unknown_tool_is_protocol_error() {
+        let mut server = server_with_errors(false);
+        let response = call_tool(&mut server, json!({"name": "no_such_tool"}));
+        let error = response.error.expect("expected JSON-RPC error");
+        assert_eq!(error.code, INVALID_PARAMS);
+        let data = error.data.unwrap();
+        assert!(data["available_tools"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("list_projects")));
+    }
+
+    #[test]
+    fn test_max_chars_truncates_tool_response() {
+        let mut server = server_with_errors(false);
+        let args = json!({"name": "get_jumble_authoring_prompt", "arguments": {"max_chars": 80}});
+        let response = call_tool(&mut server, args);
+        let text = response.result.unwrap()["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(text.ends_with("characters, refine your query]"));
+        assert!(text.len() < 160);
+    }
+
+    #[test]
+    fn test_remote_projects_are_read_only() {
+        let mut server = server_with_errors(false);
+        server
+            .remote_projects
+            .insert("api".to_string(), "platform".to_string());
+
+        for name in ["store_memory", "add_concept"] {
+            let args = json!({"name": name, "arguments": {"project": "api", "key": "k"}});
+            let result = call_tool(&mut server, args).result.unwrap();
+            assert_eq!(result["isError"], json!(true));
+            assert_eq!(
+                result["content"][0]["text"],
+                "Error: Project 'api' comes from remote source 'platform' and is read-only"
+            );
+        }
+
+        // Read tools are unaffected and fail only because the project is absent.
+        let args = json!({"name": "get_memory", "arguments": {"project": "api", "key": "k"}});
+        let result = call_tool(&mut server, args).result.unwrap();
+        assert!(!result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("read-only"));
+    }
+
+    #[test]
+    fn test_project_names_resolve_fuzzily_for_reads() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path().join("billing");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("jumble.toml"),
+            "[project]\nname = \"billing-service\"\ndescription = \"x\"\n",
+        )
+        .unwrap();
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let params =
+            json!({"name": "get_project_info", "arguments": {"project": "Billing Service"}});
+        let (_, arguments) = server.tool_call_target(&params).unwrap();
+        assert_eq!(arguments["project"], "billing-service");
+        let params = json!({"name": "get_project_info", "arguments": {"project": "biling-servce"}});
+        let (_, arguments) = server.tool_call_target(&params).unwrap();
+        assert_eq!(arguments["project"], "billing-service");
+
+        let params = json!({"name": "store_memory", "arguments": {"project": "billing service"}});
+        let (_, arguments) = server.tool_call_target(&params).unwrap();
+        assert_eq!(arguments["project"], "billing service");
+    }
+
+    #[test]
+    fn test_default_path_style_is_injected() {
+        let mut server = server_with_errors(false);
+        let params = json!({"name": "get_docs", "arguments": {"project": "api"}});
+        let (_, arguments) = server.tool_call_target(&params).unwrap();
+        assert!(arguments.get("paths").is_none());
+
+        server.workspace = Some(WorkspaceConfig {
+            workspace: config::WorkspaceInfo {
+                paths: Some(PathStyle::Project),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let (_, arguments) = server.tool_call_target(&params).unwrap();
+        assert_eq!(arguments["paths"], "project");
+
+        // An explicit argument wins, and unrelated tools are left alone.
+        let params = json!({"name": "get_docs", "arguments": {"paths": "absolute"}});
+        assert_eq!(
+            server.tool_call_target(&params).unwrap().1["paths"],
+            "absolute"
+        );
+        let params = json!({"name": "list_projects"});
+        assert!(server
+            .tool_call_target(&params)
+            .unwrap()
+            .1
+            .get("paths")
+            .is_none());
+    }
+
+    #[test]
+    fn test_tool_calls_are_recorded_in_stats() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut server = server_with_errors(false);
+        let response = call_tool(&mut server, json!({"name": "get_usage_stats"}));
+        assert_eq!(response.result.unwrap()["isError"], json!(true));
+
+        server.set_stats(stats::open_or_create_stats_db(&temp.path().join("stats.ron")).unwrap());
+        call_tool(&mut server, json!({"name": "list_projects"}));
+        call_tool(
+            &mut server,
+            json!({"name": "get_commands", "arguments": {"project": "missing"}}),
+        );
+        let response = call_tool(&mut server, json!({"name": "get_usage_stats"}));
+        let text = response.result.unwrap()["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(text.contains("| list_projects | 1 | 0 (0%) |"));
+        assert!(text.contains("| get_commands | 1 | 1 (100%) |"));
+    }
+
+    #[test]
+    fn test_audit_log_is_opt_in() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        let log = audit::audit_log_path(temp.path());
+
+        call_tool(&mut server, json!({"name": "list_projects"}));
+        assert!(!log.exists());
+
+        server.workspace = Some(WorkspaceConfig {
+            workspace: config::WorkspaceInfo {
+                audit_log: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        call_tool(
+            &mut server,
+            json!({"name": "get_commands", "arguments": {"project": "missing"}}),
+        );
+        let content = std::fs::read_to_string(&log).unwrap();
+        let entry: Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+        assert_eq!(entry["tool"], "get_commands");
+        assert_eq!(entry["arguments"]["project"], "missing");
+        assert_eq!(entry["ok"], false);
+        assert!(entry["result"].as_str().unwrap().contains("missing"));
+    }
+
+    #[test]
+    fn test_shutdown_and_exit() {
+        let mut server = server_with_errors(false);
+        let response = server
+            .handle_request(request(Some(json!(1)), "shutdown", Value::Null))
+            .unwrap();
+        assert_eq!(response.result, Some(Value::Null));
+        assert!(!server.exit_requested());
+
+        let response = call_tool(&mut server, json!({"name": "list_projects"}));
+        assert_eq!(response.error.unwrap().code, INVALID_REQUEST);
+
+        assert!(server
+            .handle_request(request(None, "exit", Value::Null))
+            .is_none());
+        assert!(server.exit_requested());
+    }
+
     #[test]
-    fn test_extract_frontmatter_and_preview_with_valid_frontmatter() {
-        let content = "---\nname: bootstrap\ndescription: Test description\ntags: [a, b]\n---\n# Title\nBody line 1\nBody line 2\n";
+    fn test_needs_exclusive() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Server>();
+
+        let call = |name: &str| request(Some(json!(1)), "tools/call", json!({ "name": name }));
+        assert!(Server::needs_exclusive(&call("reload_workspace")));
+        assert!(Server::needs_exclusive(&call("add_task")));
+        assert!(!Server::needs_exclusive(&call("get_commands")));
+        assert!(!Server::needs_exclusive(&request(
+            Some(json!(1)),
+            "tools/list",
+            Value::Null
+        )));
+
+        // Shared requests are answered through `&Server`.
+        let server = server_with_errors(false);
+        let response = server.handle_shared_request(call("list_projects")).unwrap();
+        assert!(response.result.unwrap()["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("No projects found"));
+    }
 
-        let (frontmatter, preview) = extract_skill_frontmatter_and_preview(content);
+    #[test]
+    fn test_index_docs_dir() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let docs_dir = temp.path().join("docs");
+        std::fs::create_dir_all(docs_dir.join("guides")).unwrap();
+        std::fs::create_dir_all(docs_dir.join(".drafts")).unwrap();
+        std::fs::write(
+            docs_dir.join("architecture.md"),
+            "#notes\n\n# System Architecture\n\nBody",
+        )
+        .unwrap();
+        std::fs::write(docs_dir.join("guides/setup.md"), "No heading here").unwrap();
+        std::fs::write(docs_dir.join(".drafts/wip.md"), "# WIP").unwrap();
+        std::fs::write(docs_dir.join("diagram.png"), "").unwrap();
+
+        let docs = index_docs_dir(temp.path()).docs;
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs["architecture"].summary, "System Architecture");
+        assert_eq!(docs["architecture"].path, "docs/architecture.md");
+        assert_eq!(docs["guides/setup"].summary, "guides/setup");
+        assert_eq!(docs["guides/setup"].path, "docs/guides/setup.md");
+    }
 
-        let fm = frontmatter.expect("expected some frontmatter");
-        assert_eq!(fm.name.as_deref(), Some("bootstrap"));
-        assert_eq!(fm.description.as_deref(), Some("Test description"));
-        assert_eq!(fm.tags, vec!["a", "b"]);
+    #[test]
+    fn test_tools_config_filters_tools() {
+        let mut server = server_with_errors(false);
+        server.jumble_config.as_mut().unwrap().tools.disabled =
+            vec!["*_memory".to_string(), "clear_memories".to_string()];
+        server.workspace = Some(WorkspaceConfig {
+            tools: ToolsConfig {
+                disabled: vec!["get_diagnostics".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let response = server.handle_request(request(Some(json!(1)), "tools/list", Value::Null));
+        let result = response.unwrap().result.unwrap();
+        let names: Vec<&str> = result["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"list_projects"));
+        assert!(names.contains(&"list_memories"));
+        for disabled in ["store_memory", "clear_memories", "get_diagnostics"] {
+            assert!(!names.contains(&disabled));
+        }
 
-        // Preview should be built from the body after the closing `---`.
-        assert!(preview.starts_with("# Title"));
-        assert!(preview.contains("Body line 1"));
+        let response = call_tool(&mut server, json!({"name": "clear_memories"}));
+        let error = response.error.expect("expected JSON-RPC error");
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert!(error.message.contains("disabled"));
+        assert_eq!(
+            error.data.unwrap()["available_tools"]
+                .as_array()
+                .unwrap()
+                .len(),
+            names.len()
+        );
     }
 
     #[test]
-    fn test_extract_frontmatter_and_preview_without_frontmatter() {
-        let content = "# Title\nLine 1\nLine 2\n";
+    fn test_custom_tools_are_listed_and_called() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join(".jumble")).unwrap();
+        std::fs::write(
+            temp.path().join(".jumble/workspace.toml"),
+            r##"
+            [custom_tools.release_checklist]
+            description = "Steps for cutting a release"
+            response = "# Releasing {{version}}\n\n1. Tag from {{root}}"
+            arguments = { version = "The version being released" }
+            required = ["version"]
+
+            [custom_tools.list_projects]
+            description = "Shadows a built-in"
+            response = "nope"
+            "##,
+        )
+        .unwrap();
+        let mut server = server_with_errors(false);
+        server.root = temp.path().to_path_buf();
+        server.jumble_config.as_mut().unwrap().custom_tools.insert(
+            "release_checklist".to_string(),
+            CustomTool {
+                description: "Overridden by the workspace".to_string(),
+                response: "global".to_string(),
+                ..Default::default()
+            },
+        );
+        server.reload_workspace_and_projects(None).unwrap();
+
+        let tools = server.enabled_tools();
+        let custom = tools
+            .iter()
+            .find(|t| t["name"] == "release_checklist")
+            .unwrap();
+        assert_eq!(custom["description"], "Steps for cutting a release");
+        assert_eq!(custom["inputSchema"]["required"], json!(["version"]));
+        assert_eq!(
+            custom["annotations"],
+            json!({"title": "Release checklist", "readOnlyHint": true})
+        );
+        assert_eq!(
+            tools
+                .iter()
+                .filter(|t| t["name"] == "list_projects")
+                .count(),
+            1
+        );
+        assert!(server.diagnostics.iter().any(|d| d
+            .message
+            .contains("custom tool 'list_projects' has the name of a built-in tool")));
+
+        let args = json!({"name": "release_checklist", "arguments": {"version": "2.1.0"}});
+        let result = call_tool(&mut server, args).result.unwrap();
+        assert_eq!(
+            result["content"][0]["text"],
+            format!("# Releasing 2.1.0\n\n1. Tag from {}", temp.path().display())
+        );
+        let args = json!({"name": "release_checklist", "arguments": {}});
+        let result = call_tool(&mut server, args).result.unwrap();
+        assert_eq!(result["isError"], json!(true));
+    }
 
-        let (frontmatter, preview) = extract_skill_frontmatter_and_preview(content);
+    #[test]
+    fn test_plugin_tools_are_listed_and_called() {
+        let mut server = server_with_errors(false);
+        let mut plugin = PluginConfig {
+            command: "sh".to_string(),
+            // Reply with the request itself so the test can inspect it.
+            args: vec![
+                "-c".to_string(),
+                r#"read line; printf '{"content": %s}' "$(printf '%s' "$line" | sed 's/"/\\"/g; s/^/"/; s/$/"/')""#.to_string(),
+            ],
+            ..Default::default()
+        };
+        for name in ["unsafe_report", "list_projects"] {
+            plugin.tools.insert(
+                name.to_string(),
+                PluginTool {
+                    description: "Lists unsafe blocks".to_string(),
+                    required: vec!["project".to_string()],
+                    ..Default::default()
+                },
+            );
+        }
+        let config = server.jumble_config.as_mut().unwrap();
+        config.plugins.insert("rust".to_string(), plugin);
+        config.custom_tools.insert(
+            "unsafe_report".to_string(),
+            CustomTool {
+                description: "Shadowed by the plugin".to_string(),
+                response: "custom".to_string(),
+                ..Default::default()
+            },
+        );
 
-        assert!(frontmatter.is_none());
-        // Preview should include the top of the file when no frontmatter exists.
-        assert!(preview.starts_with("# Title"));
-        assert!(preview.contains("Line 1"));
+        let tools = server.enabled_tools();
+        let listed: Vec<&Value> = tools
+            .iter()
+            .filter(|t| t["name"] == "unsafe_report")
+            .collect();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0]["description"], "Lists unsafe blocks");
+        assert!(server.tool_name_diagnostics().iter().any(|d| d
+            .message
+            .contains("tool 'list_projects' of plugin 'rust' has the name of a built-in tool")));
+
+        let args = json!({"name": "unsafe_report", "arguments": {"project": "api"}});
+        let result = call_tool(&mut server, args).result.unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap();
+        let request: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(request["tool"], "unsafe_report");
+        assert_eq!(request["arguments"]["project"], "api");
+        assert_eq!(request["root"], json!(server.root));
     }
 
     #[test]
-    fn test_extract_frontmatter_and_preview_with_unclosed_frontmatter() {
-        // Starts with `---` but has no closing marker; this should fall back to no frontmatter.
-        let content = "---\nname: broken\n# Title\nLine 1\n";
+    fn test_structured_errors_report_available_projects() {
+        let mut server = server_with_errors(true);
+        let args = json!({"name": "get_commands", "arguments": {"project": "missing"}});
+        let response = call_tool(&mut server, args.clone());
+        let error = response.error.expect("expected JSON-RPC error");
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert_eq!(error.data.unwrap()["available_projects"], json!([]));
+
+        // Without the option the failure stays an `isError` tool result.
+        let mut server = server_with_errors(false);
+        let response = call_tool(&mut server, args);
+        assert!(response.error.is_none());
+        assert_eq!(response.result.unwrap()["isError"], json!(true));
+    }
 
-        let (frontmatter, preview) = extract_skill_frontmatter_and_preview(content);
+    #[test]
+    fn test_check_skill_frontmatter_valid() {
+        let content = "---\nname: bootstrap\ndescription: Sets things up\ntags: [a]\n---\nBody";
+        assert!(check_skill_frontmatter(Path::new("s.md"), content).is_empty());
+    }
 
-        assert!(frontmatter.is_none());
-        // In this failure mode we currently treat the whole file as body for the preview.
-        assert!(preview.starts_with("---"));
-        assert!(preview.contains("name: broken"));
+    #[test]
+    fn test_check_skill_frontmatter_reports_problems() {
+        let path = Path::new("s.md");
+
+        let unknown = "---\nname: x\ndescription: ''\ntgas: [a]\n---\nBody";
+        let diagnostics = check_skill_frontmatter(path, unknown);
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("unknown frontmatter key 'tgas'")));
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("no `description`")));
+
+        let invalid = "---\nname: [unclosed\n---\nBody";
+        let diagnostics = check_skill_frontmatter(path, invalid);
+        assert_eq!(diagnostics[0].severity, crate::diagnostics::Severity::Error);
+
+        let wrong_type = "---\ndescription: ok\ntags: not-a-list\n---\nBody";
+        let diagnostics = check_skill_frontmatter(path, wrong_type);
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.starts_with("invalid frontmatter")));
+
+        let unclosed = "---\nname: broken\n# Title\n";
+        let diagnostics = check_skill_frontmatter(path, unclosed);
+        assert!(diagnostics[0].message.contains("no closing"));
     }
 
     #[test]
@@ -572,7 +3157,7 @@ mod tests {
         std::fs::write(&skill_path, content).unwrap();
 
         let mut skills = ProjectSkills::default();
-        discover_structured_skills_in_dir(&tmp_root, &mut skills);
+        discover_structured_skills_in_dir(&tmp_root, &mut skills, &mut Vec::new());
 
         // Clean up best-effort; ignore failures.
         let _ = std::fs::remove_dir_all(&tmp_root);
@@ -606,7 +3191,7 @@ mod tests {
         std::fs::write(&skill_path, content).unwrap();
 
         let mut skills = ProjectSkills::default();
-        discover_structured_skills_in_dir(&tmp_root, &mut skills);
+        discover_structured_skills_in_dir(&tmp_root, &mut skills, &mut Vec::new());
 
         let _ = std::fs::remove_dir_all(&tmp_root);
 
@@ -670,14 +3255,32 @@ mod tests {
         let global_conflict_path = global_skills_dir.join("local-first.md");
         std::fs::write(&global_conflict_path, "# Global Conflict\\nBody").unwrap();
 
-        let server = Server {
+        let mut server = Server {
             root: project_root.clone(),
+            launch_root: project_root.clone(),
+            client_roots: ClientRoots::default(),
+            client_sampling: false,
+            pending: Arc::default(),
+            samples_sent: AtomicU64::new(0),
+            drafts: Mutex::default(),
             workspace: None,
             projects: HashMap::new(),
             jumble_config: cfg,
+            diagnostics: Vec::new(),
+            skill_packs: Vec::new(),
+            remote_projects: HashMap::new(),
+            global_skills: GlobalSkills::default(),
+            skip_global_skills: false,
+            notifier: None,
+            stats: None,
+            global_memory: None,
+            shutting_down: AtomicBool::new(false),
+            exit_requested: AtomicBool::new(false),
         };
 
-        let skills = server.discover_skills(&jumble_dir);
+        let mut diagnostics = Vec::new();
+        server.global_skills = server.scan_global_skills(&mut diagnostics);
+        let skills = server.discover_skills(&jumble_dir, &mut diagnostics);
 
         // Global-only skill should be present and loaded from the global path.
         let global_info = skills
@@ -693,6 +3296,26 @@ mod tests {
             .expect("expected local-first skill discovered");
         assert_eq!(local_info.path, local_first_path);
 
+        // The shadowed global skill is reported rather than silently dropped.
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.path == global_conflict_path && d.message.contains("shadowed")));
+
+        // `--no-global-skills` leaves the home directory unscanned.
+        server.skip_global_skills = true;
+        server.global_skills = server.scan_global_skills(&mut diagnostics);
+        let skills = server.discover_skills(&jumble_dir, &mut diagnostics);
+        assert!(skills.skills.contains_key("local-first"));
+        assert!(!skills.skills.contains_key("global-skill"));
+
+        // So does `no_global_skills` in the global config.
+        server.skip_global_skills = false;
+        if let Some(cfg) = server.jumble_config.as_mut() {
+            cfg.jumble.no_global_skills = true;
+        }
+        server.global_skills = server.scan_global_skills(&mut diagnostics);
+        assert!(server.global_skills.jumble.skills.is_empty());
+
         // Best-effort cleanup; ignore failures.
         let _ = std::fs::remove_dir_all(&tmp_root);
 
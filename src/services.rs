@@ -0,0 +1,147 @@
+//! Services defined in docker-compose files, merged with a project's
+//! `[services]` section for `get_services`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Compose file names looked for in the project root, in the order Docker
+/// Compose prefers them.
+pub const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// The parts of a compose service that describe its topology.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComposeService {
+    pub image: Option<String>,
+    /// Published host ports.
+    pub ports: Vec<u16>,
+    pub depends_on: Vec<String>,
+}
+
+/// The default compose file in `project_dir`, if there is one.
+pub fn find_compose_file(project_dir: &Path) -> Option<&'static str> {
+    COMPOSE_FILES
+        .iter()
+        .copied()
+        .find(|name| project_dir.join(name).is_file())
+}
+
+/// Reads the `services` of a compose file.
+pub fn load_compose(path: &Path) -> Result<BTreeMap<String, ComposeService>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_compose(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn parse_compose(content: &str) -> Result<BTreeMap<String, ComposeService>, serde_yaml::Error> {
+    let value: serde_yaml::Value = serde_yaml::from_str(content)?;
+    let mut services = BTreeMap::new();
+    let Some(entries) = value.get("services").and_then(|s| s.as_mapping()) else {
+        return Ok(services);
+    };
+    for (name, service) in entries {
+        let Some(name) = name.as_str() else {
+            continue;
+        };
+        let ports = service
+            .get("ports")
+            .and_then(|p| p.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(published_port)
+            .collect();
+        // `depends_on` is a list of names or a map of name to condition.
+        let depends_on = match service.get("depends_on") {
+            Some(serde_yaml::Value::Sequence(names)) => names
+                .iter()
+                .filter_map(|n| n.as_str().map(str::to_string))
+                .collect(),
+            Some(serde_yaml::Value::Mapping(names)) => names
+                .keys()
+                .filter_map(|n| n.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        services.insert(
+            name.to_string(),
+            ComposeService {
+                image: service
+                    .get("image")
+                    .and_then(|i| i.as_str())
+                    .map(str::to_string),
+                ports,
+                depends_on,
+            },
+        );
+    }
+    Ok(services)
+}
+
+/// The host port of a `ports` entry: `"8080:80"`, `"127.0.0.1:5432:5432"`,
+/// `"6379"`, `"9000-9001:9000-9001"` (first port), or the long form with
+/// `published`. Container-only entries publish on a random port and are
+/// skipped.
+fn published_port(entry: &serde_yaml::Value) -> Option<u16> {
+    if let Some(published) = entry.get("published") {
+        return published
+            .as_u64()
+            .and_then(|p| u16::try_from(p).ok())
+            .or_else(|| published.as_str().and_then(|p| p.parse().ok()));
+    }
+    let entry = match entry {
+        serde_yaml::Value::String(s) => s.clone(),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    let entry = entry.split('/').next().unwrap_or_default();
+    let parts: Vec<&str> = entry.rsplitn(3, ':').collect();
+    let host = match parts.len() {
+        1 => return None,
+        _ => parts[1],
+    };
+    host.split('-').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_compose() {
+        let services = parse_compose(
+            r#"
+services:
+  api:
+    build: .
+    ports: ["8080:80", "127.0.0.1:9229:9229/tcp", "3000"]
+    depends_on: [db, cache]
+  db:
+    image: postgres:16
+    ports:
+      - target: 5432
+        published: "5433"
+  cache:
+    image: redis:7
+    depends_on:
+      db:
+        condition: service_healthy
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            services["api"],
+            ComposeService {
+                image: None,
+                ports: vec![8080, 9229],
+                depends_on: vec!["db".to_string(), "cache".to_string()],
+            }
+        );
+        assert_eq!(services["db"].image.as_deref(), Some("postgres:16"));
+        assert_eq!(services["db"].ports, [5433]);
+        assert_eq!(services["cache"].depends_on, ["db"]);
+    }
+}
@@ -31,6 +31,7 @@ description = "A brief description of your project"
 
 # [entry_points]
 # main = "src/main.rs"
+# api = { path = "src/api/mod.rs", description = "HTTP routes and handlers" }
 "#;
         fs::write(&project_toml, default_project)
             .context("Failed to create .jumble/project.toml")?;
@@ -108,11 +109,21 @@ See `.ai/constitution.md` for project-specific guidelines, conventions, and any
 
 - `list_projects` - List all projects in workspace
 - `get_workspace_overview` - Workspace structure and dependencies
+- `get_workspace_commands` - Root-level workspace commands
 - `get_workspace_conventions` - Workspace-level conventions/gotchas
 - `get_project_info` - Project metadata and structure
 - `get_commands` - Build/test/lint/run commands
 - `get_architecture` - Architectural concepts and files
 - `get_related_files` - Find files by concept
+- `who_owns` - CODEOWNERS owners of a path
+- `get_tests_for_file` - Tests covering a source file
+- `get_coverage` - Test coverage per concept
+- `get_environment` - Required tools, env vars, and setup steps
+- `get_services` - Databases, queues, and ports the project uses
+- `get_ci_info` - CI workflows, triggers, and required checks
+- `get_changelog` - Changelog sections and release process
+- `get_version_history` - Recent git tags and commits since the latest
+- `search_context` - Search all project context at once
 - `get_conventions` - Project conventions and gotchas
 - `get_docs` - Documentation index
 - `list_skills` / `get_skill` - Task-specific guidance
@@ -353,16 +364,37 @@ If jumble returns "No projects found":
 
 - `list_projects` - List all projects in workspace
 - `get_workspace_overview` - Workspace structure and dependencies
+- `get_workspace_commands` - Root-level workspace commands
 - `get_workspace_conventions` - Workspace-level conventions/gotchas
 - `get_project_info` - Project metadata and structure
 - `get_commands` - Build/test/lint/run commands
 - `get_architecture` - Architectural concepts and files
 - `get_related_files` - Find files by concept
+- `who_owns` - CODEOWNERS owners of a path
+- `get_tests_for_file` - Tests covering a source file
+- `get_coverage` - Test coverage per concept
+- `get_environment` - Required tools, env vars, and setup steps
+- `get_services` - Databases, queues, and ports the project uses
+- `get_ci_info` - CI workflows, triggers, and required checks
+- `get_changelog` - Changelog sections and release process
+- `get_version_history` - Recent git tags and commits since the latest
+- `search_context` - Search all project context at once
 - `get_conventions` - Project conventions and gotchas
 - `get_docs` - Documentation index
 - `list_skills` / `get_skill` - Task-specific guidance
 "#;
 
+/// Sent as `instructions` in the `initialize` result, so hosts that pass it to
+/// their model get the usage guide without running a setup command.
+pub const SERVER_INSTRUCTIONS: &str = "Jumble serves on-demand context about the projects in this workspace.
+
+- Start by calling get_workspace_overview() to learn the projects and how they relate; get_session_briefing(project) returns the essentials for one project in a single call.
+- Before suggesting commands, call get_commands(project, type) for the exact build/test/lint/run command instead of guessing.
+- Before architectural changes, call get_architecture(project, concept) and get_related_files(project, query).
+- Before writing code, check get_conventions(project) and get_workspace_conventions(), including gotchas.
+- For documentation call get_docs(project); for task-specific guidance call list_skills(project) and get_skill(project, topic). search_context(project, query) searches all of these at once.
+- If no projects are found, offer to create .jumble/project.toml: draft_project_config(directory) drafts one, or get_jumble_authoring_prompt() explains how to write it.";
+
 /// Setup Claude Desktop integration
 pub fn setup_claude(workspace_root: &Path, global: bool) -> Result<()> {
     let config_dir = if global {
@@ -0,0 +1,225 @@
+//! Remote skill packs.
+//!
+//! A `[skill_sources]` table in `.jumble/workspace.toml` or
+//! `~/.jumble/jumble.toml` maps pack names to locations:
+//!
+//! ```toml
+//! [skill_sources]
+//! team = "https://github.com/acme/skills.git"
+//! deploy = "https://example.com/skills/deploy.md"
+//! ```
+//!
+//! Git repositories are cloned and single markdown files are downloaded into
+//! `~/.jumble/skill-cache/<name>`. Packs are fetched the first time they are
+//! needed and only refreshed on request (`jumble sync-skills`), so startup
+//! never waits on the network once the cache is warm.
+
+use crate::git;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A skill pack declared in configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillSource {
+    pub name: String,
+    pub url: String,
+}
+
+/// Merges workspace and global `[skill_sources]` tables.
+///
+/// Workspace entries win over global entries with the same name. The result is
+/// sorted by name so packs are always discovered in a stable order.
+pub fn merge_sources(
+    workspace: Option<&HashMap<String, String>>,
+    global: Option<&HashMap<String, String>>,
+) -> Vec<SkillSource> {
+    let mut merged: HashMap<&String, &String> = HashMap::new();
+    for sources in [global, workspace].into_iter().flatten() {
+        merged.extend(sources.iter());
+    }
+
+    let mut sources: Vec<SkillSource> = merged
+        .into_iter()
+        .map(|(name, url)| SkillSource {
+            name: name.clone(),
+            url: url.clone(),
+        })
+        .collect();
+    sources.sort_by(|a, b| a.name.cmp(&b.name));
+    sources
+}
+
+/// Directory under which all packs are cached.
+pub fn cache_root(home_dir: &Path) -> PathBuf {
+    home_dir.join(".jumble").join("skill-cache")
+}
+
+/// Makes sure `source` is present in the cache, returning its directory.
+///
+/// Already-cached packs are left alone unless `refresh` is set, in which case
+/// git packs are pulled and file packs re-downloaded.
+pub fn ensure_pack(
+    cache_root: &Path,
+    source: &SkillSource,
+    refresh: bool,
+) -> Result<PathBuf, String> {
+    validate_name(&source.name)?;
+    let pack_dir = cache_root.join(&source.name);
+    let cached = pack_dir.is_dir();
+    if cached && !refresh {
+        return Ok(pack_dir);
+    }
+
+    if is_single_file(&source.url) {
+        std::fs::create_dir_all(&pack_dir)
+            .map_err(|e| format!("Failed to create {}: {}", pack_dir.display(), e))?;
+        // Stored as SKILL.md so the pack is picked up like any structured skill.
+        download(&source.url, &pack_dir.join("SKILL.md"))?;
+    } else if cached && pack_dir.join(".git").exists() {
+        git::run_git(&pack_dir, &["pull", "--ff-only", "-q"])?;
+    } else {
+        if cached {
+            std::fs::remove_dir_all(&pack_dir)
+                .map_err(|e| format!("Failed to clear {}: {}", pack_dir.display(), e))?;
+        }
+        std::fs::create_dir_all(cache_root)
+            .map_err(|e| format!("Failed to create {}: {}", cache_root.display(), e))?;
+        let target = pack_dir.to_string_lossy().into_owned();
+        git::run_git(
+            cache_root,
+            &["clone", "--depth", "1", "-q", "--", &source.url, &target],
+        )?;
+    }
+
+    Ok(pack_dir)
+}
+
+/// Pack names become directory names, so keep them to a safe character set.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid source name '{}': use letters, digits, '-', '_' or '.'",
+            name
+        ))
+    }
+}
+
+/// HTTP(S) URLs pointing at a markdown file are fetched as a single skill;
+/// everything else is treated as a git repository.
+fn is_single_file(url: &str) -> bool {
+    (url.starts_with("https://") || url.starts_with("http://"))
+        && url
+            .split(['?', '#'])
+            .next()
+            .map(|path| path.to_ascii_lowercase().ends_with(".md"))
+            .unwrap_or(false)
+}
+
+/// Downloads `url` to `dest` using the `curl` binary.
+pub fn download(url: &str, dest: &Path) -> Result<(), String> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", "30", "-o"])
+        .arg(dest)
+        .arg("--url")
+        .arg(url)
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to download {}: {}", url, stderr.trim()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_sources_workspace_wins() {
+        let mut global = HashMap::new();
+        global.insert("team".to_string(), "https://a.example/team.git".to_string());
+        global.insert(
+            "shared".to_string(),
+            "https://a.example/shared.git".to_string(),
+        );
+        let mut workspace = HashMap::new();
+        workspace.insert("team".to_string(), "https://b.example/team.git".to_string());
+
+        let sources = merge_sources(Some(&workspace), Some(&global));
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].name, "shared");
+        assert_eq!(sources[1].name, "team");
+        assert_eq!(sources[1].url, "https://b.example/team.git");
+    }
+
+    #[test]
+    fn test_is_single_file() {
+        assert!(is_single_file("https://example.com/skills/deploy.md"));
+        assert!(is_single_file("https://example.com/deploy.MD?token=abc"));
+        assert!(!is_single_file("https://github.com/acme/skills.git"));
+        assert!(!is_single_file("git@github.com:acme/skills.md"));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("team-skills_v2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("a/b").is_err());
+    }
+
+    #[test]
+    fn test_ensure_pack_clones_local_git_repo() {
+        let upstream = TempDir::new().unwrap();
+        let repo = upstream.path();
+        let git = |args: &[&str]| {
+            let mut full = vec!["-c", "user.name=test", "-c", "user.email=test@example.com"];
+            full.extend_from_slice(args);
+            git::run_git(repo, &full).unwrap();
+        };
+        git(&["init", "-q"]);
+        std::fs::create_dir_all(repo.join("lint")).unwrap();
+        std::fs::write(repo.join("lint/SKILL.md"), "# Lint").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "add skill"]);
+
+        let cache = TempDir::new().unwrap();
+        let source = SkillSource {
+            name: "team".to_string(),
+            url: repo.to_string_lossy().into_owned(),
+        };
+        let dir = ensure_pack(cache.path(), &source, false).unwrap();
+        assert!(dir.join("lint/SKILL.md").exists());
+
+        // A refresh pulls into the existing clone.
+        std::fs::write(repo.join("lint/SKILL.md"), "# Lint v2").unwrap();
+        git(&["commit", "-q", "-am", "update skill"]);
+        ensure_pack(cache.path(), &source, true).unwrap();
+        let content = std::fs::read_to_string(dir.join("lint/SKILL.md")).unwrap();
+        assert_eq!(content, "# Lint v2");
+    }
+
+    #[test]
+    fn test_ensure_pack_treats_url_as_repository() {
+        let cache = TempDir::new().unwrap();
+        let marker = cache.path().join("injected");
+        let source = SkillSource {
+            name: "team".to_string(),
+            url: format!("--upload-pack=touch {}", marker.display()),
+        };
+        let err = ensure_pack(cache.path(), &source, false).unwrap_err();
+        assert!(err.contains("repository '--upload-pack="), "{}", err);
+        assert!(!marker.exists());
+    }
+}
@@ -0,0 +1,238 @@
+//! Tool usage statistics.
+//!
+//! The server counts calls, errors, and latency per tool and keeps the totals
+//! in `~/.jumble/stats.ron`, so maintainers can see which context agents
+//! actually ask for. Totals accumulate across server runs.
+
+use fd_lock::RwLock;
+use rustbreak::{deser::Ron, FileDatabase};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Call counts and timings for one tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub calls: u64,
+    pub errors: u64,
+    /// Sum of call durations, in milliseconds.
+    pub total_ms: u64,
+    /// Slowest call, in milliseconds.
+    pub max_ms: u64,
+}
+
+impl ToolStats {
+    pub fn record(&mut self, elapsed: Duration, ok: bool) {
+        let ms = elapsed.as_millis() as u64;
+        self.calls += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    pub fn avg_ms(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.total_ms as f64 / self.calls as f64
+        }
+    }
+
+    /// Fraction of calls that failed, from 0.0 to 1.0.
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.calls as f64
+        }
+    }
+}
+
+/// Usage totals for every tool that has been called.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    /// ISO 8601 timestamp of the first recorded call.
+    #[serde(default)]
+    pub since: Option<String>,
+    pub tools: BTreeMap<String, ToolStats>,
+}
+
+impl UsageStats {
+    pub fn record(&mut self, tool: &str, elapsed: Duration, ok: bool) {
+        if self.since.is_none() {
+            self.since = Some(crate::memory::current_timestamp());
+        }
+        self.tools
+            .entry(tool.to_string())
+            .or_default()
+            .record(elapsed, ok);
+    }
+}
+
+/// The usage statistics file, with `stats.lock` beside it so that servers
+/// sharing a home directory take turns updating it.
+pub struct StatsDatabase {
+    db: FileDatabase<UsageStats, Ron>,
+    /// Held while reloading, updating, and saving the totals. The lock file
+    /// is never written, so every process locks the same file.
+    lock_file: Mutex<RwLock<File>>,
+}
+
+impl StatsDatabase {
+    /// Runs `task` on the totals as last loaded.
+    pub fn read<T>(&self, task: impl FnOnce(&UsageStats) -> T) -> rustbreak::Result<T> {
+        self.db.read(task)
+    }
+}
+
+/// Default stats location: `~/.jumble/stats.ron`.
+pub fn default_stats_file() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".jumble").join("stats.ron"))
+}
+
+/// Opens or creates the stats database at `path`.
+pub fn open_or_create_stats_db(path: &Path) -> Result<StatsDatabase, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let db = FileDatabase::load_from_path_or(path, UsageStats::default())
+        .map_err(|e| format!("Failed to open stats database: {}", e))?;
+    let lock_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path.with_file_name("stats.lock"))
+        .map_err(|e| format!("Failed to open stats lock file: {}", e))?;
+    Ok(StatsDatabase {
+        db,
+        lock_file: Mutex::new(RwLock::new(lock_file)),
+    })
+}
+
+/// Adds one call to the stored totals.
+///
+/// The file is reloaded under `stats.lock` first, so that several servers
+/// sharing a home directory don't overwrite each other's counts.
+pub fn record_call(
+    db: &StatsDatabase,
+    tool: &str,
+    elapsed: Duration,
+    ok: bool,
+) -> Result<(), String> {
+    let mut lock_file = db.lock_file.lock().unwrap_or_else(|e| e.into_inner());
+    let _guard = lock_file
+        .write()
+        .map_err(|e| format!("Failed to lock stats database: {}", e))?;
+    db.db
+        .load()
+        .map_err(|e| format!("Failed to reload stats database: {}", e))?;
+    db.db
+        .write(|stats| stats.record(tool, elapsed, ok))
+        .map_err(|e| format!("Failed to update stats database: {}", e))?;
+    db.db
+        .save()
+        .map_err(|e| format!("Failed to save stats database: {}", e))
+}
+
+/// Renders the totals as a markdown table, most-called tools first.
+pub fn format_stats(stats: &UsageStats) -> String {
+    if stats.tools.is_empty() {
+        return "No tool calls recorded yet.".to_string();
+    }
+
+    let mut tools: Vec<(&String, &ToolStats)> = stats.tools.iter().collect();
+    tools.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then(a.0.cmp(b.0)));
+
+    let total: u64 = tools.iter().map(|(_, t)| t.calls).sum();
+    let mut output = String::from("# Tool Usage\n\n");
+    if let Some(since) = &stats.since {
+        output.push_str(&format!("{} calls since {}\n\n", total, since));
+    }
+    output.push_str("| Tool | Calls | Errors | Avg ms | Max ms |\n");
+    output.push_str("|------|------:|-------:|-------:|-------:|\n");
+    for (name, tool) in tools {
+        output.push_str(&format!(
+            "| {} | {} | {} ({:.0}%) | {:.1} | {} |\n",
+            name,
+            tool.calls,
+            tool.errors,
+            tool.error_rate() * 100.0,
+            tool.avg_ms(),
+            tool.max_ms
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_call_persists_totals() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("stats.ron");
+        let db = open_or_create_stats_db(&path).unwrap();
+        record_call(&db, "get_commands", Duration::from_millis(4), true).unwrap();
+        record_call(&db, "get_commands", Duration::from_millis(8), false).unwrap();
+        record_call(&db, "list_projects", Duration::from_millis(1), true).unwrap();
+
+        let reopened = open_or_create_stats_db(&path).unwrap();
+        let stats = reopened.read(|stats| stats.clone()).unwrap();
+        let commands = &stats.tools["get_commands"];
+        assert_eq!(commands.calls, 2);
+        assert_eq!(commands.errors, 1);
+        assert_eq!(commands.max_ms, 8);
+        assert_eq!(commands.avg_ms(), 6.0);
+        assert_eq!(commands.error_rate(), 0.5);
+        assert!(stats.since.is_some());
+
+        let table = format_stats(&stats);
+        assert!(table.contains("3 calls since"));
+        let commands_row = table
+            .find("| get_commands | 2 | 1 (50%) | 6.0 | 8 |")
+            .unwrap();
+        assert!(commands_row < table.find("| list_projects |").unwrap());
+    }
+
+    #[test]
+    fn test_instances_sharing_a_file_keep_all_calls() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("stats.ron");
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                let db = open_or_create_stats_db(&path).unwrap();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        record_call(&db, "get_commands", Duration::ZERO, true).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let db = open_or_create_stats_db(&path).unwrap();
+        let calls = db.read(|stats| stats.tools["get_commands"].calls).unwrap();
+        assert_eq!(calls, 50);
+        assert!(temp.path().join("stats.lock").exists());
+    }
+
+    #[test]
+    fn test_format_stats_empty() {
+        assert_eq!(
+            format_stats(&UsageStats::default()),
+            "No tool calls recorded yet."
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! `jumble suggest-concepts`: proposes candidate concepts for a project from
+//! its source layout, as TOML ready to paste into `project.toml`.
+//!
+//! Each subdirectory of a source root (`src/`, `lib/`, `pkg/`, ...) becomes a
+//! candidate, as does each top-level module file in a source root. Summaries
+//! come from the module's leading doc comment when there is one; otherwise
+//! they are left as a TODO for the author.
+
+use std::collections::HashMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::config::{self, Concept};
+
+/// Directories whose children are treated as modules.
+const SOURCE_ROOTS: &[&str] = &["src", "lib", "app", "pkg", "internal", "cmd", "packages"];
+
+/// File extensions counted as source code.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "ts", "tsx", "js", "jsx", "go", "java", "kt", "rb", "swift", "c", "cc", "cpp", "h",
+    "hpp", "cs", "ex", "php", "scala",
+];
+
+/// Entry-point and glue files that rarely make a concept of their own.
+const SKIPPED_STEMS: &[&str] = &["main", "lib", "mod", "index", "__init__", "app"];
+
+/// Most files listed for one directory concept.
+const MAX_FILES: usize = 10;
+
+/// Candidate concepts for the project at `project_dir`, sorted by name.
+/// Names already present in `existing` are left out.
+pub fn suggest_concepts(
+    project_dir: &Path,
+    existing: &HashMap<String, Concept>,
+) -> Vec<(String, Concept)> {
+    let mut suggestions: Vec<(String, Concept)> = Vec::new();
+    for root in SOURCE_ROOTS {
+        let Ok(entries) = std::fs::read_dir(project_dir.join(root)) else {
+            continue;
+        };
+        let mut entries: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            let Some(name) = path.file_stem().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if name.starts_with('.') || existing.contains_key(name) {
+                continue;
+            }
+            if suggestions.iter().any(|(existing, _)| existing == name) {
+                continue;
+            }
+            let concept = if path.is_dir() {
+                directory_concept(project_dir, &path, name)
+            } else if is_source_file(&path) && !SKIPPED_STEMS.contains(&name) {
+                Some(Concept {
+                    files: vec![relative(project_dir, &path)],
+                    summary: summary_for(&path, name),
+                    related: Vec::new(),
+                    owner: None,
+                    contact: None,
+                })
+            } else {
+                None
+            };
+            if let Some(concept) = concept {
+                suggestions.push((name.to_string(), concept));
+            }
+        }
+    }
+    suggestions.sort_by(|a, b| a.0.cmp(&b.0));
+    suggestions
+}
+
+/// Renders suggestions as `[concepts.<name>]` tables.
+pub fn render(suggestions: &[(String, Concept)]) -> String {
+    suggestions
+        .iter()
+        .map(|(name, concept)| config::concept_snippet(name, concept))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn directory_concept(project_dir: &Path, dir: &Path, name: &str) -> Option<Concept> {
+    let mut files: Vec<_> = WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .flatten()
+        .filter(|e| e.file_type().is_file() && is_source_file(e.path()))
+        .map(|e| e.into_path())
+        .collect();
+    if files.is_empty() {
+        return None;
+    }
+    files.sort();
+
+    // Prefer the module's own entry file for the summary, e.g. `auth/mod.rs`.
+    let summary = files
+        .iter()
+        .find(|f| {
+            f.file_stem()
+                .and_then(|s| s.to_str())
+                .is_some_and(|s| SKIPPED_STEMS.contains(&s))
+        })
+        .and_then(|f| doc_comment(f))
+        .unwrap_or_else(|| format!("TODO: describe {}", name));
+
+    let files = if files.len() > MAX_FILES {
+        vec![format!("{}/", relative(project_dir, dir))]
+    } else {
+        files.iter().map(|f| relative(project_dir, f)).collect()
+    };
+    Some(Concept {
+        files,
+        summary,
+        related: Vec::new(),
+        owner: None,
+        contact: None,
+    })
+}
+
+fn summary_for(path: &Path, name: &str) -> String {
+    doc_comment(path).unwrap_or_else(|| format!("TODO: describe {}", name))
+}
+
+/// First line of a file's leading doc comment: `//!` in Rust, a module
+/// docstring in Python, or a leading `/** ... */` block elsewhere.
+fn doc_comment(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let first = content.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let text = if let Some(rest) = first.strip_prefix("//!") {
+        rest
+    } else if let Some(rest) = first
+        .strip_prefix("\"\"\"")
+        .or_else(|| first.strip_prefix("/**"))
+    {
+        rest.trim_end_matches("\"\"\"").trim_end_matches("*/")
+    } else {
+        return None;
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e))
+}
+
+fn relative(project_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(project_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_suggest_concepts() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for (file, content) in [
+            ("src/main.rs", "fn main() {}"),
+            ("src/cache.rs", "//! In-memory LRU cache.\n"),
+            ("src/auth/mod.rs", "//! Token checks and sessions\n"),
+            ("src/auth/tokens.rs", ""),
+            ("src/billing.rs", ""),
+            ("src/assets/logo.png", ""),
+            ("README.md", ""),
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let mut existing = HashMap::new();
+        existing.insert(
+            "billing".to_string(),
+            Concept {
+                files: vec!["src/billing.rs".to_string()],
+                summary: "Invoices".to_string(),
+                related: Vec::new(),
+                owner: None,
+                contact: None,
+            },
+        );
+
+        let suggestions = suggest_concepts(root, &existing);
+        let names: Vec<&str> = suggestions.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["auth", "cache"]);
+        let (_, auth) = &suggestions[0];
+        assert_eq!(auth.files, ["src/auth/mod.rs", "src/auth/tokens.rs"]);
+        assert_eq!(auth.summary, "Token checks and sessions");
+        assert_eq!(suggestions[1].1.summary, "In-memory LRU cache.");
+
+        let toml = render(&suggestions);
+        assert!(toml.contains("[concepts.auth]"));
+        let parsed: toml::Value = toml::from_str(&toml).unwrap();
+        assert_eq!(
+            parsed["concepts"]["cache"]["files"][0].as_str(),
+            Some("src/cache.rs")
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Task tracking for multi-session agent work.
+//!
+//! Open items are stored per project in `<project_root>/.jumble/tasks.ron`, so
+//! an agent can hand work off to the next session without encoding it in
+//! memory keys.
+
+use rustbreak::{deser::Ron, FileDatabase};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Whether a task still needs doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Open,
+    Done,
+}
+
+/// How urgent a task is. Variants are ordered from least to most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    pub const NAMES: [&'static str; 3] = ["low", "medium", "high"];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "medium" => Ok(Priority::Medium),
+            "high" => Ok(Priority::High),
+            _ => Err(format!(
+                "Invalid priority '{}'. Use one of: {}",
+                name,
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A single tracked task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: u64,
+    pub title: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    pub status: TaskStatus,
+    pub priority: Priority,
+    /// Agent or tool that created the task.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// ISO 8601 timestamp when the task was added.
+    pub created: String,
+    /// ISO 8601 timestamp when the task was completed.
+    #[serde(default)]
+    pub completed: Option<String>,
+}
+
+/// All tasks for a project, plus the id to hand out next.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskList {
+    pub next_id: u64,
+    pub tasks: Vec<Task>,
+}
+
+/// Type alias for the FileDatabase used in task storage.
+pub type TaskDatabase = FileDatabase<TaskList, Ron>;
+
+/// Opens or creates the task database at `<project_root>/.jumble/tasks.ron`.
+pub fn open_or_create_task_db(project_root: &Path) -> Result<TaskDatabase, String> {
+    let tasks_path = project_root.join(".jumble/tasks.ron");
+
+    if let Some(parent) = tasks_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .jumble directory: {}", e))?;
+    }
+
+    TaskDatabase::load_from_path_or(tasks_path, TaskList::default())
+        .map_err(|e| format!("Failed to open task database: {}", e))
+}
+
+/// Reads the tasks for a project without creating the database file.
+pub fn load_tasks(project_root: &Path) -> Result<TaskList, String> {
+    if !project_root.join(".jumble/tasks.ron").exists() {
+        return Ok(TaskList::default());
+    }
+    let db = open_or_create_task_db(project_root)?;
+    db.read(|list| list.clone())
+        .map_err(|e| format!("Failed to read task database: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::current_timestamp;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_task_db_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load_tasks(temp_dir.path()).unwrap().tasks.is_empty());
+        assert!(!temp_dir.path().join(".jumble/tasks.ron").exists());
+
+        let db = open_or_create_task_db(temp_dir.path()).unwrap();
+        db.write(|list| {
+            list.next_id = 2;
+            list.tasks.push(Task {
+                id: 1,
+                title: "Port the parser".to_string(),
+                notes: None,
+                status: TaskStatus::Open,
+                priority: Priority::High,
+                source: Some("agent".to_string()),
+                created: current_timestamp(),
+                completed: None,
+            });
+        })
+        .unwrap();
+        db.save().unwrap();
+
+        let list = load_tasks(temp_dir.path()).unwrap();
+        assert_eq!(list.next_id, 2);
+        assert_eq!(list.tasks[0].title, "Port the parser");
+        assert_eq!(list.tasks[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn test_priority_parse() {
+        assert_eq!(Priority::parse("HIGH").unwrap(), Priority::High);
+        assert!(Priority::parse("urgent").is_err());
+        assert!(Priority::High > Priority::Low);
+    }
+}
@@ -0,0 +1,208 @@
+//! Starter templates for `jumble template`: each context file with inline
+//! comments that follow AUTHORING.md, so a new file can be written without
+//! the guide open.
+
+use clap::ValueEnum;
+
+/// The files `jumble template` can generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TemplateKind {
+    Project,
+    Conventions,
+    Docs,
+    Workspace,
+    Skill,
+}
+
+impl TemplateKind {
+    /// Where `--write` puts the file, relative to the project or workspace
+    /// root.
+    pub fn default_path(self) -> &'static str {
+        match self {
+            TemplateKind::Project => ".jumble/project.toml",
+            TemplateKind::Conventions => ".jumble/conventions.toml",
+            TemplateKind::Docs => ".jumble/docs.toml",
+            TemplateKind::Workspace => ".jumble/workspace.toml",
+            TemplateKind::Skill => ".jumble/skills/new-skill.md",
+        }
+    }
+
+    pub fn content(self) -> &'static str {
+        match self {
+            TemplateKind::Project => PROJECT,
+            TemplateKind::Conventions => CONVENTIONS,
+            TemplateKind::Docs => DOCS,
+            TemplateKind::Workspace => WORKSPACE,
+            TemplateKind::Skill => SKILL,
+        }
+    }
+}
+
+const PROJECT: &str = r#"# Project context for Jumble. Only [project] is required; the rest helps
+# agents find their way around. See AUTHORING.md for detection heuristics.
+
+schema_version = 2             # config format; `jumble migrate` upgrades older files
+# extends = "../shared/base-project.toml"   # merge a shared base underneath
+
+[project]
+name = "my-project"            # canonical name from Cargo.toml, package.json, go.mod, ...
+description = "One-line description of what this project does"
+language = "rust"              # primary language
+# version = "0.1.0"
+# repository = "https://github.com/org/my-project"
+
+# Commands in the project's preferred tooling. Prefer Makefile or justfile
+# targets when they exist (`jumble import-commands` can propose them).
+[commands]
+build = "cargo build"
+test = "cargo test"
+lint = "cargo clippy && cargo fmt --check"
+# run = "cargo run"
+# ci = { steps = ["lint", "build", "test"], description = "Everything CI runs" }
+
+# The 3-5 files that explain the codebase best.
+[entry_points]
+main = "src/main.rs"
+# config = "src/config.rs"
+# api = { path = "src/api/mod.rs", description = "HTTP routes and handlers" }
+
+[dependencies]
+internal = []                  # other projects in this workspace
+external = []                  # the 5-10 third-party crates or packages that shape the architecture
+
+# [related_projects]
+# upstream = []                # projects this one consumes
+# downstream = []              # projects that consume this one
+
+# Concepts map architectural areas to files; they are the most useful part of
+# this file. Aim for 3-5, one focused responsibility each, with a one-sentence
+# summary. Files are relative to the project root; directories and line
+# ranges (`src/server.rs:120-210`) are allowed.
+[concepts.core]
+files = ["src/main.rs"]
+summary = "What this area does, in one sentence"
+# related = ["other_concept"]
+# owner = "@team"
+"#;
+
+const CONVENTIONS: &str = r#"# Conventions and gotchas for this project. Keep each entry to one rule
+# or pitfall, and 3-7 entries per section. Longer explanations belong in a
+# doc or skill.
+
+schema_version = 2
+# Architectural patterns and standards to follow. An entry may be a table
+# with a level: "must" (hard rule), "should" (preference), or "info".
+[conventions]
+error_handling = { description = "Return Result from fallible functions; never unwrap outside tests.", level = "must" }
+# naming = "Plain strings work too when the level doesn't matter."
+
+# Common mistakes and non-obvious behaviour.
+[gotchas]
+example_gotcha = """
+Describe the trap, why it happens, and what to do instead."""
+
+# Any other table is a category of its own, e.g. [security] or [testing],
+# retrievable with get_conventions(category: "security").
+# [security]
+# secrets = "Never log request headers; they carry bearer tokens."
+"#;
+
+const DOCS: &str = r#"# Documentation index. Without this file, Jumble indexes the markdown files
+# under docs/ using their first heading; write it for better summaries or for
+# docs that live elsewhere.
+#
+# Keys are short topic names (lowercase, hyphens). Summaries should help an
+# agent decide whether the doc answers its question, so include the keywords
+# a query would use. Don't index generated API docs.
+
+schema_version = 2
+[docs.getting-started]
+path = "docs/getting-started.md"
+summary = "Installation, quick start, and minimal configuration"
+
+# [docs.architecture]
+# path = "docs/architecture.md"
+# summary = "Components, data flow, and deployment topology"
+"#;
+
+const WORKSPACE: &str = r#"# Workspace context at the root of a monorepo. Keep it to what spans
+# projects; each project has its own conventions.toml.
+
+schema_version = 2
+[workspace]
+name = "My Workspace"
+description = "What lives in this repository"
+# expose = ["api-*"]           # only serve matching projects
+# hide = ["scratch"]           # never serve these
+
+# Patterns that apply across all projects.
+[conventions]
+error_handling = "Use anyhow in binaries and thiserror in libraries."
+
+# Pitfalls that catch people in any project.
+[gotchas]
+shared_dependencies = "Shared dependency versions live in the root manifest; don't pin them per project."
+
+# Orchestration commands that belong to no single project.
+# [commands]
+# bootstrap = "./scripts/bootstrap.sh"
+# test-all = "cargo test --workspace"
+"#;
+
+const SKILL: &str = r#"---
+name: new-skill
+description: When to use this skill, in one sentence an agent can match against a task
+tags: []
+---
+
+# Task Name
+
+One task per skill. Use concrete steps that reference real files, and keep it
+well under 500 lines.
+
+## Steps
+1. First step, naming the file to change (e.g. `src/routes/mod.rs`)
+2. Second step
+3. How to test it
+
+## Conventions
+- Patterns the change must follow
+
+## Related Files
+- `path/to/example.rs` - An existing example to copy from
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        ProjectConfig, ProjectConventions, ProjectDocs, WorkspaceConfig, SCHEMA_VERSION,
+    };
+
+    #[test]
+    fn test_templates_parse() {
+        let project: ProjectConfig = toml::from_str(TemplateKind::Project.content()).unwrap();
+        assert_eq!(project.project.name, "my-project");
+        assert_eq!(project.concepts.len(), 1);
+        assert_eq!(project.schema_version, Some(SCHEMA_VERSION));
+
+        let conventions: ProjectConventions =
+            toml::from_str(TemplateKind::Conventions.content()).unwrap();
+        assert_eq!(conventions.conventions.len(), 1);
+        assert_eq!(conventions.gotchas.len(), 1);
+        assert!(conventions.custom.is_empty());
+        assert_eq!(conventions.schema_version, Some(SCHEMA_VERSION));
+
+        let docs: ProjectDocs = toml::from_str(TemplateKind::Docs.content()).unwrap();
+        assert_eq!(docs.docs.len(), 1);
+        assert_eq!(docs.schema_version, Some(SCHEMA_VERSION));
+
+        let workspace: WorkspaceConfig = toml::from_str(TemplateKind::Workspace.content()).unwrap();
+        assert_eq!(workspace.workspace.name.as_deref(), Some("My Workspace"));
+        assert_eq!(workspace.schema_version, Some(SCHEMA_VERSION));
+
+        let (frontmatter, _) =
+            crate::config::extract_skill_frontmatter_and_preview(TemplateKind::Skill.content());
+        assert!(frontmatter.unwrap().description.is_some());
+    }
+}
@@ -0,0 +1,186 @@
+//! Finds the tests for a source file: first from the project's `[test_map]`
+//! table, then by common naming conventions (`foo_test.go`, `foo.spec.ts`,
+//! `tests/test_foo.py`, `src/test/java/.../FooTest.java`).
+
+use globset::GlobBuilder;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Top-level directories that hold tests mirroring the source tree.
+const TEST_DIRS: &[&str] = &["tests", "test", "spec", "__tests__"];
+
+/// Source roots stripped before mirroring a path into a test directory.
+const SOURCE_DIRS: &[&str] = &["src", "lib", "app"];
+
+/// Test paths that `[test_map]` assigns to `path`: the values of every key
+/// whose glob matches it, in key order.
+pub fn configured(test_map: &HashMap<String, Vec<String>>, path: &str) -> Vec<String> {
+    let mut patterns: Vec<&String> = test_map.keys().collect();
+    patterns.sort();
+    let mut tests = Vec::new();
+    for pattern in patterns {
+        let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() else {
+            continue;
+        };
+        if glob.compile_matcher().is_match(path) {
+            for test in &test_map[pattern] {
+                if !tests.contains(test) {
+                    tests.push(test.clone());
+                }
+            }
+        }
+    }
+    tests
+}
+
+/// Existing files under `project_dir` named like tests for `path`.
+pub fn conventional(project_dir: &Path, path: &str) -> Vec<String> {
+    let path = path.trim_start_matches("./");
+    let file = Path::new(path);
+    let (Some(stem), Some(ext)) = (
+        file.file_stem().and_then(|s| s.to_str()),
+        file.extension().and_then(|e| e.to_str()),
+    ) else {
+        return Vec::new();
+    };
+    let dir = file
+        .parent()
+        .and_then(|d| d.to_str())
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    let names = [
+        format!("{}_test.{}", stem, ext),
+        format!("{}.test.{}", stem, ext),
+        format!("{}.spec.{}", stem, ext),
+        format!("test_{}.{}", stem, ext),
+        format!("{}_spec.{}", stem, ext),
+        format!("{}Test.{}", stem, ext),
+        format!("{}.{}", stem, ext),
+    ];
+    let test_names = &names[..names.len() - 1];
+
+    let mut dirs: Vec<(String, &[String])> = vec![
+        (dir.to_string(), test_names),
+        (join(dir, "__tests__"), &names[..]),
+    ];
+    let mirrored = SOURCE_DIRS
+        .iter()
+        .find_map(|source| {
+            dir.strip_prefix(source)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .unwrap_or(dir)
+        .trim_start_matches('/');
+    for test_dir in TEST_DIRS {
+        dirs.push((join(test_dir, mirrored), &names[..]));
+        if !mirrored.is_empty() {
+            dirs.push((test_dir.to_string(), &names[..]));
+        }
+    }
+    // Maven and Gradle keep tests in a parallel `src/test` tree.
+    if let Some(rest) = dir.strip_prefix("src/main/") {
+        dirs.push((join("src/test", rest), &names[..]));
+    }
+
+    let mut tests = Vec::new();
+    for (dir, names) in dirs {
+        for name in names {
+            let candidate = join(&dir, name);
+            if candidate != path
+                && !tests.contains(&candidate)
+                && project_dir.join(&candidate).is_file()
+            {
+                tests.push(candidate);
+            }
+        }
+    }
+    tests
+}
+
+/// Whether a Rust source file carries its own `#[cfg(test)]` tests.
+pub fn has_inline_tests(project_dir: &Path, path: &str) -> bool {
+    path.ends_with(".rs")
+        && std::fs::read_to_string(project_dir.join(path))
+            .is_ok_and(|content| content.contains("#[cfg(test)]"))
+}
+
+fn join(dir: &str, name: &str) -> String {
+    match (dir.is_empty(), name.is_empty()) {
+        (true, _) => name.to_string(),
+        (false, true) => dir.to_string(),
+        (false, false) => format!("{}/{}", dir, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project(files: &[&str]) -> TempDir {
+        let temp = TempDir::new().unwrap();
+        for file in files {
+            let path = temp.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        temp
+    }
+
+    #[test]
+    fn test_configured_matches_globs() {
+        let map: HashMap<String, Vec<String>> = [
+            (
+                "src/billing/**".to_string(),
+                vec!["tests/billing.rs".to_string(), "tests/smoke.rs".to_string()],
+            ),
+            (
+                "src/**/*.rs".to_string(),
+                vec!["tests/smoke.rs".to_string()],
+            ),
+        ]
+        .into();
+        assert_eq!(
+            configured(&map, "src/billing/invoice.rs"),
+            ["tests/smoke.rs", "tests/billing.rs"]
+        );
+        assert_eq!(configured(&map, "src/main.rs"), ["tests/smoke.rs"]);
+        assert!(configured(&map, "build.rs").is_empty());
+    }
+
+    #[test]
+    fn test_conventional_naming() {
+        let temp = project(&[
+            "src/billing/invoice.ts",
+            "src/billing/invoice.spec.ts",
+            "src/billing/__tests__/invoice.test.ts",
+            "tests/billing/invoice.ts",
+            "pkg/store/store_test.go",
+            "tests/test_models.py",
+            "src/test/java/com/acme/OrderTest.java",
+        ]);
+        let root = temp.path();
+        assert_eq!(
+            conventional(root, "src/billing/invoice.ts"),
+            [
+                "src/billing/invoice.spec.ts",
+                "src/billing/__tests__/invoice.test.ts",
+                "tests/billing/invoice.ts",
+            ]
+        );
+        assert_eq!(
+            conventional(root, "pkg/store/store.go"),
+            ["pkg/store/store_test.go"]
+        );
+        assert_eq!(
+            conventional(root, "app/models.py"),
+            ["tests/test_models.py"]
+        );
+        assert_eq!(
+            conventional(root, "src/main/java/com/acme/Order.java"),
+            ["src/test/java/com/acme/OrderTest.java"]
+        );
+        assert!(conventional(root, "src/billing/invoice.spec.ts").is_empty());
+        assert!(conventional(root, "Makefile").is_empty());
+    }
+}
@@ -1,16 +1,35 @@
 //! MCP tool implementations.
 
+use crate::changelog;
+use crate::ci;
+use crate::codeowners::{self, CodeOwners};
 use crate::config::{
-    Concept, ProjectConfig, ProjectConventions, ProjectDocs, ProjectSkills, WorkspaceConfig,
+    self, extract_skill_frontmatter_and_preview, CommandDetails, CommandSpec, Concept, Convention,
+    ConventionDetails, ConventionLevel, Conventions, CustomTool, FileRef, MemoryConfig, PathStyle,
+    ProjectConfig, ProjectConventions, ProjectDocs, ProjectSkills, Service, SkillFrontmatter,
+    SkillInfo, WorkspaceConfig,
 };
+use crate::coverage;
+use crate::decisions;
+use crate::environment;
 use crate::format::{
-    format_api, format_commands, format_concept, format_dependencies, format_entry_points,
-    format_related_projects,
+    command_notes, display_path, format_api, format_command, format_commands, format_concept,
+    format_concept_file, format_dependencies, format_dot_graph, format_entry_points,
+    format_mermaid_graph, format_related_projects, format_service, format_workflow,
+    interpolate_env, markdown_heading, markdown_headings, markdown_section, render_template,
 };
-use crate::memory::MemoryDatabase;
+use crate::fuzzy;
+use crate::git::{self, ChangeRange};
+use crate::memory::{self, MemoryDatabase};
+use crate::memory_audit::{self, MemoryAction};
+use crate::services;
+use crate::skill_packs;
+use crate::tasks::{self, Priority, Task, TaskStatus};
+use crate::test_map;
 use serde_json::{json, Value};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
 
 /// Type alias for project data stored in the server
 pub type ProjectData = (
@@ -22,9 +41,55 @@ pub type ProjectData = (
     MemoryDatabase,
 );
 
+/// Tools that list project files and accept a `paths` argument.
+pub const PATH_TOOLS: &[&str] = &[
+    "get_architecture",
+    "get_related_files",
+    "get_docs",
+    "get_context_bundle",
+];
+
+/// Tools that write to disk, whether project files, stored memories, or the
+/// skill cache; every other tool only reads.
+const MUTATING_TOOLS: &[&str] = &[
+    "reload_workspace",
+    "init_project",
+    "add_concept",
+    "add_convention",
+    "promote_memory",
+    "create_skill",
+    "record_decision",
+    "add_task",
+    "complete_task",
+    "store_memory",
+    "store_global_memory",
+    "delete_memory",
+    "clear_memories",
+    "draft_project_config",
+];
+
+/// Mutating tools that can overwrite or remove existing data.
+const DESTRUCTIVE_TOOLS: &[&str] = &[
+    "store_memory",
+    "store_global_memory",
+    "delete_memory",
+    "clear_memories",
+];
+
+/// Mutating tools that have no further effect when repeated with the same
+/// arguments.
+const IDEMPOTENT_TOOLS: &[&str] = &[
+    "reload_workspace",
+    "init_project",
+    "store_memory",
+    "store_global_memory",
+    "delete_memory",
+    "clear_memories",
+];
+
 /// Returns the JSON schema for all available tools
 pub fn tools_list() -> Value {
-    json!({
+    let mut list = json!({
         "tools": [
             {
                 "name": "list_projects",
@@ -54,9 +119,55 @@ pub fn tools_list() -> Value {
                     "required": ["project"]
                 }
             },
+            {
+                "name": "find_project_for_file",
+                "description": "Finds the project that owns a file, given an absolute or workspace-relative path (e.g. from a stack trace or diff), and returns its name and summary.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Absolute path, or a path relative to the workspace root"
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "get_context_for_file",
+                "description": "One-shot answer to 'what do I need to know to edit this file': the owning project, concepts whose files or globs include the file, and the project and workspace conventions and gotchas.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {
+                            "type": "string",
+                            "description": "Absolute path, or a path relative to the workspace root"
+                        }
+                    },
+                    "required": ["path"]
+                }
+            },
+            {
+                "name": "get_context_bundle",
+                "description": "Gathers what an agent needs to start a task in one call: the project skill that best matches the task description, concepts whose name or summary mention it, and the project's conventions and gotchas.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "task": {
+                            "type": "string",
+                            "description": "What you are about to do, e.g. 'add a database migration'"
+                        }
+                    },
+                    "required": ["project", "task"]
+                }
+            },
             {
                 "name": "get_commands",
-                "description": "Returns executable commands for a project (build, test, lint, run, dev, etc.)",
+                "description": "Returns executable commands for a project (build, test, lint, run, dev, etc.). A single command with dependencies, or a sequence, includes the order to run its steps in. ${VAR} placeholders are resolved from the environment or the project's [env] section.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -72,9 +183,27 @@ pub fn tools_list() -> Value {
                     "required": ["project"]
                 }
             },
+            {
+                "name": "get_entry_point",
+                "description": "Returns the contents of a project's entry point file (e.g. 'main', 'lib') with line numbers. Large files are truncated.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "The entry point name as listed in [entry_points]"
+                        }
+                    },
+                    "required": ["project", "name"]
+                }
+            },
             {
                 "name": "get_architecture",
-                "description": "Returns architectural info for a specific concept/area of a project, including relevant files and a summary.",
+                "description": "Returns architectural info for a specific concept/area of a project, including relevant files, a summary, and related concepts.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -90,9 +219,45 @@ pub fn tools_list() -> Value {
                     "required": ["project", "concept"]
                 }
             },
+            {
+                "name": "add_concept",
+                "description": "Creates or updates a concept (summary, files, related concepts) in the project's .jumble/project.toml, keeping the rest of the file's formatting. Omitted fields keep their current values when updating. Without confirm: true it only previews the TOML that would be written.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "The concept name, e.g. 'authentication'"
+                        },
+                        "summary": {
+                            "type": "string",
+                            "description": "One-sentence summary (required for new concepts)"
+                        },
+                        "files": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Files or directories relative to the project root, optionally with line ranges like 'src/server.rs:120-210' (required for new concepts)"
+                        },
+                        "related": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Optional: names of related concepts"
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Set to true to write the concept; otherwise a preview is returned"
+                        }
+                    },
+                    "required": ["project", "name"]
+                }
+            },
             {
                 "name": "get_related_files",
-                "description": "Finds files related to a concept or feature by searching through all defined concepts.",
+                "description": "Finds files related to a concept or feature by searching through all defined concepts. Results are ranked by relevance, best first.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -109,22 +274,26 @@ pub fn tools_list() -> Value {
                 }
             },
             {
-                "name": "list_skills",
-                "description": "Lists available task-specific skills for a project. Skills provide focused context for specific tasks like adding endpoints, debugging, etc.",
+                "name": "get_tests_for_file",
+                "description": "Lists the tests that cover a source file, from the project's [test_map] and common naming conventions (foo_test.go, foo.spec.ts, tests/test_foo.py, ...). Use it to decide which tests to run or extend after a change.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "project": {
                             "type": "string",
                             "description": "The project name"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Source file path, relative to the project root"
                         }
                     },
-                    "required": ["project"]
+                    "required": ["project", "path"]
                 }
             },
             {
-                "name": "get_skill",
-                "description": "Retrieves a task-specific skill containing focused context and instructions for a particular task.",
+                "name": "get_coverage",
+                "description": "Reports line coverage from the project's lcov or Cobertura report (configured in [coverage]) for a concept's files, or per concept when no concept is given. Thinnest coverage is listed first, to show where tests are most needed.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -132,78 +301,81 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
-                        "topic": {
+                        "concept": {
                             "type": "string",
-                            "description": "The skill topic (e.g., 'add-endpoint', 'debug-auth')"
+                            "description": "Optional concept name; omit for a summary of every concept"
                         }
                     },
-                    "required": ["project", "topic"]
+                    "required": ["project"]
                 }
             },
             {
-                "name": "get_conventions",
-                "description": "Returns project-specific coding conventions and gotchas. Conventions are architectural patterns and standards; gotchas are common mistakes to avoid.",
+                "name": "get_environment",
+                "description": "Returns a project's environment requirements from [environment]: tool versions, environment variables, and setup steps. Each tool and variable is checked on this machine, so use it to diagnose 'works on my machine' problems.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "project": {
                             "type": "string",
                             "description": "The project name"
-                        },
-                        "category": {
-                            "type": "string",
-                            "description": "Optional: 'conventions' or 'gotchas' to filter results",
-                            "enum": ["conventions", "gotchas"]
                         }
                     },
                     "required": ["project"]
                 }
             },
             {
-                "name": "get_docs",
-                "description": "Returns a documentation index for a project, listing available docs with summaries. Optionally retrieves the path to a specific doc.",
+                "name": "get_services",
+                "description": "Returns the databases, queues, caches, and other services a project talks to at runtime, with images, local ports, connection URLs, and the env vars that configure them. Combines the project's [services] section with its docker-compose file. Use it before writing integration code.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
                         "project": {
                             "type": "string",
                             "description": "The project name"
-                        },
-                        "topic": {
-                            "type": "string",
-                            "description": "Optional: specific doc topic to get the path for"
                         }
                     },
                     "required": ["project"]
                 }
             },
             {
-                "name": "get_workspace_overview",
-                "description": "Returns a high-level overview of the entire workspace: workspace info, all projects with descriptions, and their dependency relationships. Call this first to understand the workspace structure.",
+                "name": "get_ci_info",
+                "description": "Returns a project's CI workflows with their summaries from [ci], the checks required before merging, and, for GitHub Actions, each workflow's triggers and jobs with their run steps. Use it to see what checks a pull request must pass and how to reproduce them locally.",
                 "inputSchema": {
                     "type": "object",
-                    "properties": {},
-                    "required": []
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "parse": {
+                            "type": "boolean",
+                            "description": "Read triggers and jobs from GitHub Actions workflow files (default: true)"
+                        }
+                    },
+                    "required": ["project"]
                 }
             },
             {
-                "name": "get_workspace_conventions",
-                "description": "Returns workspace-level conventions and gotchas that apply across all projects in the workspace.",
+                "name": "get_changelog",
+                "description": "Returns a release section from the project's changelog, or, without a version, the unreleased changes and the latest release along with the list of versions, the versioning scheme, and the release command from [releases]. Use it to write release notes or see what changed recently.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "category": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "version": {
                             "type": "string",
-                            "description": "Optional: 'conventions' or 'gotchas' to filter results",
-                            "enum": ["conventions", "gotchas"]
+                            "description": "Optional version such as '1.2.0' or 'v1.2.0', 'unreleased', or 'latest'"
                         }
                     },
-                    "required": []
+                    "required": ["project"]
                 }
             },
             {
-                "name": "store_memory",
-                "description": "Stores a memory entry (key-value pair) for a project. AI agents can use this to persist learned information, preferences, or context over time.",
+                "name": "get_version_history",
+                "description": "Returns the project's recent git tags with their dates, commits, and subjects, the configured version next to the latest tag, and how many commits have touched the project since. In a monorepo, tags prefixed with the project name (such as 'api-v1.2.0' or 'api@1.2.0') are picked out automatically. Use it to see what has been released and whether changes are waiting for a release.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -211,25 +383,39 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
-                        "key": {
-                            "type": "string",
-                            "description": "The memory key (identifier)"
+                        "limit": {
+                            "type": "integer",
+                            "description": "Most tags to list (default: 10)"
                         },
-                        "value": {
+                        "pattern": {
                             "type": "string",
-                            "description": "The memory value to store"
+                            "description": "Optional glob the tag names must match, such as 'v*' or 'api/*'"
+                        }
+                    },
+                    "required": ["project"]
+                }
+            },
+            {
+                "name": "who_owns",
+                "description": "Returns the owners of a file or directory from the project's CODEOWNERS file, and the rule that assigns them. Use it to find reviewers or who to ask about an area.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
                         },
-                        "source": {
+                        "path": {
                             "type": "string",
-                            "description": "Optional: identifier for the agent/tool storing this memory"
+                            "description": "File or directory path, relative to the project root"
                         }
                     },
-                    "required": ["project", "key", "value"]
+                    "required": ["project", "path"]
                 }
             },
             {
-                "name": "get_memory",
-                "description": "Retrieves a specific memory entry by key for a project.",
+                "name": "get_recent_changes",
+                "description": "Summarizes files changed in recent git commits for a project and cross-references them against defined concepts, highlighting actively evolving areas.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -237,17 +423,35 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
-                        "key": {
+                        "commits": {
+                            "type": "integer",
+                            "description": "Optional: number of recent commits to inspect (default: 20)"
+                        },
+                        "since": {
                             "type": "string",
-                            "description": "The memory key to retrieve"
+                            "description": "Optional: git ref (tag, branch, or commit) to inspect changes since; overrides 'commits'"
                         }
                     },
-                    "required": ["project", "key"]
+                    "required": ["project"]
                 }
             },
             {
-                "name": "list_memories",
-                "description": "Lists all stored memories for a project, optionally filtered by a key pattern.",
+                "name": "list_skills",
+                "description": "Lists available task-specific skills for a project. Skills provide focused context for specific tasks like adding endpoints, debugging, etc.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        }
+                    },
+                    "required": ["project"]
+                }
+            },
+            {
+                "name": "get_skill",
+                "description": "Retrieves a task-specific skill containing focused context and instructions for a particular task.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -255,17 +459,24 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
-                        "pattern": {
+                        "topic": {
                             "type": "string",
-                            "description": "Optional: filter keys by this substring (case-insensitive)"
+                            "description": "The skill topic (e.g., 'add-endpoint', 'debug-auth')"
+                        },
+                        "variables": {
+                            "type": "object",
+                            "description": "Optional: values for {{name}} placeholders in the skill. {{project}} and {{root}} are always available.",
+                            "additionalProperties": {
+                                "type": "string"
+                            }
                         }
                     },
-                    "required": ["project"]
+                    "required": ["project", "topic"]
                 }
             },
             {
-                "name": "search_memories",
-                "description": "Searches memory keys and values for a query string (case-insensitive substring match).",
+                "name": "create_skill",
+                "description": "Writes a new skill (markdown with YAML frontmatter) to the project's .jumble/skills/ directory, so a procedure worked out in this session can be reused later. Without confirm: true it only previews the file.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -273,17 +484,48 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
+                        "name": {
+                            "type": "string",
+                            "description": "Skill name, used as the file name (letters, digits, '-' or '_')"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "One-line description of when to use the skill"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "Markdown body of the skill: the steps, commands, and pitfalls"
+                        },
+                        "tags": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Optional: tags used by search_skills"
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Set to true to write the file; otherwise a preview is returned"
+                        }
+                    },
+                    "required": ["project", "name", "description", "content"]
+                }
+            },
+            {
+                "name": "search_skills",
+                "description": "Searches skill names, descriptions, tags, and previews across all projects and global skills, returning ranked matches. Use this when you don't know which project or topic holds the guidance you need.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
                         "query": {
                             "type": "string",
-                            "description": "Search query to match against keys and values"
+                            "description": "Search terms to match against skills"
                         }
                     },
-                    "required": ["project", "query"]
+                    "required": ["query"]
                 }
             },
             {
-                "name": "delete_memory",
-                "description": "Deletes a specific memory entry by key for a project.",
+                "name": "get_conventions",
+                "description": "Returns project-specific coding conventions and gotchas. Conventions are architectural patterns and standards; gotchas are common mistakes to avoid. Projects may also define custom categories such as security or testing. Entries may carry a [MUST], [SHOULD], or [INFO] badge separating hard rules from preferences and background; hard rules are listed first.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -291,17 +533,17 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
-                        "key": {
+                        "category": {
                             "type": "string",
-                            "description": "The memory key to delete"
+                            "description": "Optional: 'conventions', 'gotchas', or a custom category from conventions.toml (e.g. 'security') to filter results"
                         }
                     },
-                    "required": ["project", "key"]
+                    "required": ["project"]
                 }
             },
             {
-                "name": "clear_memories",
-                "description": "Clears all memories for a project, optionally filtered by pattern or age. Use with caution!",
+                "name": "add_convention",
+                "description": "Adds a convention, gotcha, or custom-category entry to the project's .jumble/conventions.toml so a learning from this session becomes durable project context. Without confirm: true it only previews the TOML that would be written.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
@@ -309,196 +551,4343 @@ pub fn tools_list() -> Value {
                             "type": "string",
                             "description": "The project name"
                         },
-                        "pattern": {
+                        "category": {
                             "type": "string",
-                            "description": "Optional: only delete memories with keys matching this pattern (case-insensitive)"
+                            "description": "'conventions', 'gotchas', or a custom category such as 'security'"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Short key for the entry, e.g. 'error_handling'"
+                        },
+                        "description": {
+                            "type": "string",
+                            "description": "The convention or gotcha text"
+                        },
+                        "level": {
+                            "type": "string",
+                            "enum": ["must", "should", "info"],
+                            "description": "Optional: how strictly the entry applies - 'must' for hard rules, 'should' for preferences, 'info' for background"
                         },
                         "confirm": {
                             "type": "boolean",
-                            "description": "Must be set to true to confirm deletion"
+                            "description": "Set to true to write the entry; otherwise a preview is returned"
                         }
                     },
-                    "required": ["project", "confirm"]
+                    "required": ["project", "category", "name", "description"]
                 }
             },
             {
-                "name": "reload_workspace",
-                "description": "Reloads workspace and project metadata from disk. Use this after editing .jumble files to pick up changes without restarting the server.",
+                "name": "promote_memory",
+                "description": "Turns a memory entry into durable project context: writes it to conventions.toml as a convention or gotcha, or to project.toml as a concept, then deletes the memory (or marks it as promoted). Without confirm: true it only previews the change.",
                 "inputSchema": {
                     "type": "object",
-                    "properties": {},
-                    "required": []
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "key": {
+                            "type": "string",
+                            "description": "The memory key to promote"
+                        },
+                        "to": {
+                            "type": "string",
+                            "description": "What to promote the memory to",
+                            "enum": ["convention", "gotcha", "concept"]
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Optional: name for the new entry (defaults to the memory key)"
+                        },
+                        "files": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Files for the concept (required when promoting to a new concept)"
+                        },
+                        "level": {
+                            "type": "string",
+                            "enum": ["must", "should", "info"],
+                            "description": "Optional: level for a promoted convention or gotcha"
+                        },
+                        "keep": {
+                            "type": "boolean",
+                            "description": "Keep the memory and mark it as promoted instead of deleting it (default: false)"
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Set to true to write the change; otherwise a preview is returned"
+                        }
+                    },
+                    "required": ["project", "key", "to"]
                 }
             },
             {
-                "name": "get_jumble_authoring_prompt",
-                "description": "Returns a canonical prompt and guidance for creating .jumble context files (project, workspace, conventions, docs) in any project.",
+                "name": "get_docs",
+                "description": "Returns a documentation index for a project, listing available docs with summaries. Optionally retrieves the path to a specific doc, or the text of one section of it.",
                 "inputSchema": {
                     "type": "object",
-                    "properties": {},
-                    "required": []
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "topic": {
+                            "type": "string",
+                            "description": "Optional: specific doc topic to get the path for"
+                        },
+                        "section": {
+                            "type": "string",
+                            "description": "Optional: a markdown heading in the topic's doc; returns only that section's text"
+                        }
+                    },
+                    "required": ["project"]
                 }
             },
             {
-                "name": "init_project",
-                "description": "Initializes a new jumble project by creating the necessary directories and configuration files. Creates .jumble, .ai, docs directories and initializes project.toml, constitution.md, AGENTS.md, and .gitignore.",
+                "name": "search_docs",
+                "description": "Searches the content of indexed docs across all projects (or one project) and returns matching docs with the headings and snippets around each match. Use this when you don't know which doc covers a topic.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "directory": {
+                        "query": {
                             "type": "string",
-                            "description": "The directory path to initialize the project in. Supports both absolute and relative paths."
+                            "description": "Search terms to look for in doc names, summaries, and content"
+                        },
+                        "project": {
+                            "type": "string",
+                            "description": "Optional: limit the search to one project"
                         }
                     },
-                    "required": ["directory"]
+                    "required": ["query"]
                 }
+            },
+            {
+                "name": "search_context",
+                "description": "Searches one project's concepts, conventions, gotchas, doc summaries, skills, and memories in a single call and returns ranked results grouped by type, each with the tool to call for details. Start here when you don't know where the answer lives.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Search terms to match against names and descriptions"
+                        }
+                    },
+                    "required": ["project", "query"]
+                }
+            },
+            {
+                "name": "get_workspace_overview",
+                "description": "Returns a high-level overview of the entire workspace: workspace info, all projects with descriptions, and their dependency relationships. Call this first to understand the workspace structure.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "get_session_briefing",
+                "description": "Returns the context needed at the start of a session in one call: workspace overview, the project's info, its main conventions and gotchas, and recent memories, trimmed to a token budget.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project you will be working on"
+                        },
+                        "max_tokens": {
+                            "type": "integer",
+                            "description": "Approximate size limit for the briefing (default: 2000)"
+                        }
+                    },
+                    "required": ["project"]
+                }
+            },
+            {
+                "name": "get_dependency_graph",
+                "description": "Returns the workspace dependency graph (from related_projects and internal dependencies) as Mermaid or Graphviz DOT source, ready to render or analyze.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "format": {
+                            "type": "string",
+                            "description": "Output format (default: 'mermaid')",
+                            "enum": ["mermaid", "dot"]
+                        }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "get_workspace_commands",
+                "description": "Returns root-level workspace commands (bootstrap, test-all, release, etc.) from the [commands] table of workspace.toml. Use get_commands for a single project's commands. ${VAR} placeholders are resolved from the environment.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "command_type": {
+                            "type": "string",
+                            "description": "Optional specific command, e.g. 'bootstrap' or 'test-all'"
+                        }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "get_workspace_conventions",
+                "description": "Returns workspace-level conventions and gotchas that apply across all projects in the workspace.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "category": {
+                            "type": "string",
+                            "description": "Optional: 'conventions', 'gotchas', or a custom category from conventions.toml (e.g. 'security') to filter results"
+                        }
+                    },
+                    "required": []
+                }
+            },
+            {
+                "name": "record_decision",
+                "description": "Records an architecture decision (ADR) as .jumble/decisions/NNNN-title.md with date, status, context, decision, and consequences. Use this for durable, reviewable decisions rather than free-form memory. Without confirm: true it only previews the file.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "Short title of the decision"
+                        },
+                        "context": {
+                            "type": "string",
+                            "description": "The forces and constraints that led to the decision"
+                        },
+                        "decision": {
+                            "type": "string",
+                            "description": "What was decided"
+                        },
+                        "consequences": {
+                            "type": "string",
+                            "description": "Optional: the resulting trade-offs"
+                        },
+                        "status": {
+                            "type": "string",
+                            "description": "Decision status (default: accepted)",
+                            "enum": decisions::STATUSES
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Set to true to write the record; otherwise a preview is returned"
+                        }
+                    },
+                    "required": ["project", "title", "context", "decision"]
+                }
+            },
+            {
+                "name": "list_decisions",
+                "description": "Lists a project's architecture decision records with number, status, date, and title, or returns one decision in full when a number is given.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "status": {
+                            "type": "string",
+                            "description": "Optional: only list decisions with this status"
+                        },
+                        "number": {
+                            "type": "integer",
+                            "description": "Optional: decision number to return in full"
+                        }
+                    },
+                    "required": ["project"]
+                }
+            },
+            {
+                "name": "add_task",
+                "description": "Adds an open task for a project, stored in .jumble/tasks.ron. Use this to hand off unfinished work to a later session instead of storing it as a memory.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "What needs to be done"
+                        },
+                        "notes": {
+                            "type": "string",
+                            "description": "Optional: details, links, or where the work stopped"
+                        },
+                        "priority": {
+                            "type": "string",
+                            "description": "Task priority (default: medium)",
+                            "enum": Priority::NAMES
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Optional: identifier for the agent/tool adding this task"
+                        }
+                    },
+                    "required": ["project", "title"]
+                }
+            },
+            {
+                "name": "list_tasks",
+                "description": "Lists a project's tasks, open ones first and most urgent first.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "status": {
+                            "type": "string",
+                            "description": "Which tasks to list (default: open)",
+                            "enum": ["open", "done", "all"]
+                        },
+                        "priority": {
+                            "type": "string",
+                            "description": "Optional: only list tasks with this priority",
+                            "enum": Priority::NAMES
+                        }
+                    },
+                    "required": ["project"]
+                }
+            },
+            {
+                "name": "complete_task",
+                "description": "Marks a task as done.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "id": {
+                            "type": "integer",
+                            "description": "The task number shown by list_tasks"
+                        }
+                    },
+                    "required": ["project", "id"]
+                }
+            },
+            {
+                "name": "store_global_memory",
+                "description": "Stores a user-level memory shared by every project, e.g. editor, code style, or naming preferences that apply across repositories.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "key": {
+                            "type": "string",
+                            "description": "The memory key (identifier)"
+                        },
+                        "value": {
+                            "type": "string",
+                            "description": "The memory value to store"
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Optional: identifier for the agent/tool storing this memory"
+                        }
+                    },
+                    "required": ["key", "value"]
+                }
+            },
+            {
+                "name": "get_global_memory",
+                "description": "Retrieves a user-level memory by key, or lists all user-level memories when no key is given.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "key": {
+                            "type": "string",
+                            "description": "Optional: the memory key to retrieve"
+                        }
+                    }
+                }
+            },
+            {
+                "name": "store_memory",
+                "description": "Stores a memory entry (key-value pair) for a project. AI agents can use this to persist learned information, preferences, or context over time.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "key": {
+                            "type": "string",
+                            "description": "The memory key (identifier)"
+                        },
+                        "value": {
+                            "type": "string",
+                            "description": "The memory value to store"
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Optional: identifier for the agent/tool storing this memory. The global config can cap how many entries each source may store."
+                        }
+                    },
+                    "required": ["project", "key", "value"]
+                }
+            },
+            {
+                "name": "get_memory",
+                "description": "Retrieves a specific memory entry by key for a project.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "key": {
+                            "type": "string",
+                            "description": "The memory key to retrieve"
+                        }
+                    },
+                    "required": ["project", "key"]
+                }
+            },
+            {
+                "name": "get_memories",
+                "description": "Retrieves several memory entries by key in one call, e.g. to restore related notes at session start.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "keys": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "The memory keys to retrieve"
+                        }
+                    },
+                    "required": ["project", "keys"]
+                }
+            },
+            {
+                "name": "list_memories",
+                "description": "Lists all stored memories for a project, optionally filtered by a key pattern or time range and sorted by key or recency.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "pattern": {
+                            "type": "string",
+                            "description": "Optional: filter keys by this substring (case-insensitive)"
+                        },
+                        "regex": {
+                            "type": "boolean",
+                            "description": "Treat 'pattern' as a regular expression (default: false)"
+                        },
+                        "sort_by": {
+                            "type": "string",
+                            "description": "Order by key (default) or by timestamp, newest first",
+                            "enum": ["key", "timestamp"]
+                        },
+                        "since": {
+                            "type": "string",
+                            "description": "Optional: only memories updated at or after this RFC 3339 timestamp or YYYY-MM-DD date"
+                        },
+                        "before": {
+                            "type": "string",
+                            "description": "Optional: only memories updated before this RFC 3339 timestamp or YYYY-MM-DD date"
+                        }
+                    },
+                    "required": ["project"]
+                }
+            },
+            {
+                "name": "search_memories",
+                "description": "Searches memory keys and values for a query string (case-insensitive substring match, or a regular expression with regex: true).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "query": {
+                            "type": "string",
+                            "description": "Search query to match against keys and values"
+                        },
+                        "regex": {
+                            "type": "boolean",
+                            "description": "Treat 'query' as a regular expression (default: false)"
+                        }
+                    },
+                    "required": ["project", "query"]
+                }
+            },
+            {
+                "name": "search_all_memories",
+                "description": "Searches memory keys and values in every project and in the global store, naming the store each match came from. Useful when working across services in a monorepo.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Search query to match against keys and values"
+                        },
+                        "regex": {
+                            "type": "boolean",
+                            "description": "Treat 'query' as a regular expression (default: false)"
+                        }
+                    },
+                    "required": ["query"]
+                }
+            },
+            {
+                "name": "delete_memory",
+                "description": "Deletes a specific memory entry by key for a project.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "key": {
+                            "type": "string",
+                            "description": "The memory key to delete"
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Optional: identifier for the agent/tool making this change, recorded in the memory audit log"
+                        }
+                    },
+                    "required": ["project", "key"]
+                }
+            },
+            {
+                "name": "clear_memories",
+                "description": "Clears all memories for a project, optionally filtered by pattern or age. Use with caution!",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name"
+                        },
+                        "pattern": {
+                            "type": "string",
+                            "description": "Optional: only delete memories with keys matching this pattern (case-insensitive)"
+                        },
+                        "regex": {
+                            "type": "boolean",
+                            "description": "Treat 'pattern' as a regular expression (default: false)"
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Must be set to true to confirm deletion"
+                        },
+                        "source": {
+                            "type": "string",
+                            "description": "Optional: identifier for the agent/tool making this change, recorded in the memory audit log"
+                        }
+                    },
+                    "required": ["project", "confirm"]
+                }
+            },
+            {
+                "name": "get_memory_audit",
+                "description": "Returns the audit trail of memory changes for a project (or the global store): every store, delete, clear, and promote, newest first, with the value before and after. Use it to review what agents changed and to recover an overwritten or deleted memory.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "project": {
+                            "type": "string",
+                            "description": "The project name (omit with global: true)"
+                        },
+                        "global": {
+                            "type": "boolean",
+                            "description": "Read the audit trail of the global memory store instead (default: false)"
+                        },
+                        "key": {
+                            "type": "string",
+                            "description": "Optional: only changes to this key"
+                        },
+                        "action": {
+                            "type": "string",
+                            "description": "Optional: only this kind of change",
+                            "enum": ["store", "delete", "clear", "promote"]
+                        },
+                        "limit": {
+                            "type": "integer",
+                            "description": "Most changes to return (default: 20)"
+                        }
+                    }
+                }
+            },
+            {
+                "name": "reload_workspace",
+                "description": "Reloads workspace and project metadata from disk. Use this after editing .jumble files to pick up changes without restarting the server.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "get_jumble_authoring_prompt",
+                "description": "Returns a canonical prompt and guidance for creating .jumble context files (project, workspace, conventions, docs) in any project.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "draft_project_config",
+                "description": "Drafts .jumble/project.toml for a project that has no Jumble context by asking the client's model (MCP sampling) to read the repository listing and manifests. Returns the draft for review; call again with confirm: true to write it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "directory": {
+                            "type": "string",
+                            "description": "Project directory, relative to the workspace root"
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Write the previously returned draft (default: false)"
+                        }
+                    },
+                    "required": ["directory"]
+                }
+            },
+            {
+                "name": "get_diagnostics",
+                "description": "Reports problems found while loading .jumble context and skills (invalid frontmatter, unknown keys, empty descriptions, shadowed skills). Use this when context seems to be missing.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "get_usage_stats",
+                "description": "Reports how often each jumble tool has been called, with error rates and latency, so maintainers can see which context agents actually use.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }
+            },
+            {
+                "name": "init_project",
+                "description": "Initializes a new jumble project by creating the necessary directories and configuration files. Creates .jumble, .ai, docs directories and initializes project.toml, constitution.md, AGENTS.md, and .gitignore.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "directory": {
+                            "type": "string",
+                            "description": "The directory path to initialize the project in. Supports both absolute and relative paths."
+                        }
+                    },
+                    "required": ["directory"]
+                }
+            }
+        ]
+    });
+
+    for tool in list["tools"].as_array_mut().unwrap() {
+        if PATH_TOOLS.contains(&tool["name"].as_str().unwrap_or_default()) {
+            tool["inputSchema"]["properties"]["paths"] = json!({
+                "type": "string",
+                "enum": ["absolute", "workspace", "project"],
+                "description": "Optional: write file paths as absolute (default), relative to the workspace root, or relative to the project"
+            });
+        }
+        add_annotations(tool);
+        add_max_chars(tool);
+    }
+    list
+}
+
+/// MCP tool annotations, so hosts can ask before destructive calls and run
+/// read-only ones in parallel.
+fn add_annotations(tool: &mut Value) {
+    let name = tool["name"].as_str().unwrap_or_default();
+    let mut annotations = json!({"title": tool_title(name)});
+    if MUTATING_TOOLS.contains(&name) {
+        annotations["readOnlyHint"] = json!(false);
+        annotations["destructiveHint"] = json!(DESTRUCTIVE_TOOLS.contains(&name));
+        annotations["idempotentHint"] = json!(IDEMPOTENT_TOOLS.contains(&name));
+    } else {
+        annotations["readOnlyHint"] = json!(true);
+    }
+    tool["annotations"] = annotations;
+}
+
+/// A human-readable title for a tool: `get_project_info` becomes
+/// "Get project info".
+fn tool_title(name: &str) -> String {
+    let words = name.replace(['_', '-'], " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Every tool accepts `max_chars`; the server truncates the response to fit.
+fn add_max_chars(tool: &mut Value) {
+    tool["inputSchema"]["properties"]["max_chars"] = json!({
+        "type": "integer",
+        "description": "Optional: truncate the response to about this many characters"
+    });
+}
+
+/// Whether `name` is one of the tools in [`tools_list`].
+pub fn is_builtin_tool(name: &str) -> bool {
+    static TOOLS: OnceLock<Value> = OnceLock::new();
+    static NAMES: OnceLock<HashSet<&str>> = OnceLock::new();
+    NAMES
+        .get_or_init(|| {
+            TOOLS.get_or_init(tools_list)["tools"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|tool| tool["name"].as_str())
+                .collect()
+        })
+        .contains(name)
+}
+
+/// The `tools/list` entry for a tool defined in config, under
+/// `[custom_tools]` or `[plugins]`, whose arguments are all strings.
+pub fn tool_definition(
+    name: &str,
+    description: &str,
+    arguments: &BTreeMap<String, String>,
+    required: &[String],
+) -> Value {
+    let properties: serde_json::Map<String, Value> = arguments
+        .iter()
+        .map(|(arg, description)| {
+            (
+                arg.clone(),
+                json!({"type": "string", "description": description}),
+            )
+        })
+        .collect();
+    let mut definition = json!({
+        "name": name,
+        "description": description,
+        "inputSchema": {
+            "type": "object",
+            "properties": properties,
+            "required": required
+        },
+        "annotations": {"title": tool_title(name)}
+    });
+    add_max_chars(&mut definition);
+    definition
+}
+
+/// Renders a `[custom_tools]` response, filling `{{name}}` placeholders from
+/// the call's arguments and `{{root}}` with the workspace root.
+pub fn call_custom_tool(
+    root: &Path,
+    name: &str,
+    tool: &CustomTool,
+    args: &Value,
+) -> Result<String, String> {
+    let mut variables = HashMap::new();
+    variables.insert("root".to_string(), root.display().to_string());
+    if let Some(args) = args.as_object() {
+        for (arg, value) in args {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            variables.insert(arg.clone(), value);
+        }
+    }
+    if let Some(missing) = tool
+        .required
+        .iter()
+        .find(|arg| !variables.contains_key(*arg))
+    {
+        return Err(format!(
+            "Missing '{}' argument for tool '{}'",
+            missing, name
+        ));
+    }
+    Ok(render_template(&tool.response, &variables))
+}
+
+/// The prefix for file paths in `project_dir` requested by the `paths`
+/// argument: the project directory itself (`absolute`, the default), its
+/// location within the workspace `root` (`workspace`), or nothing (`project`).
+/// Projects outside the workspace keep absolute paths.
+pub fn path_base(root: &Path, project_dir: &Path, args: &Value) -> Result<PathBuf, String> {
+    let style = match args.get("paths") {
+        Some(value) => serde_json::from_value(value.clone()).map_err(|_| {
+            format!(
+                "Invalid 'paths' value {}: expected \"absolute\", \"workspace\", or \"project\"",
+                value
+            )
+        })?,
+        None => PathStyle::Absolute,
+    };
+    Ok(match style {
+        PathStyle::Absolute => project_dir.to_path_buf(),
+        PathStyle::Workspace => project_dir
+            .strip_prefix(root)
+            .unwrap_or(project_dir)
+            .to_path_buf(),
+        PathStyle::Project => PathBuf::new(),
+    })
+}
+
+/// The `max_chars` response limit requested by a tool call, if any.
+pub fn max_chars(args: &Value) -> Option<usize> {
+    args.get("max_chars")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+}
+
+pub fn get_jumble_authoring_prompt() -> Result<String, String> {
+    let prompt = r#"# Jumble authoring prompt
+
+Use this prompt with an AI assistant to create Jumble context files for a project or workspace.
+
+## Full prompt
+
+```
+Create jumble context for this project.
+
+Read the AUTHORING.md guide at https://github.com/velvet-tiger/jumble/blob/main/AUTHORING.md, then examine this project's structure to create:
+
+1. `.jumble/project.toml` (required)
+   - Extract name, description, language from manifest files
+   - Identify build/test/lint commands
+   - Map 3–5 architectural concepts to their files
+   - Note upstream/downstream project relationships
+
+2. `.jumble/conventions.toml`
+   - Capture coding patterns to follow (look at existing code)
+   - Document gotchas and non-obvious behaviors
+   - Check for constitution.md, CONTRIBUTING.md, or similar guides
+
+3. `.jumble/docs.toml`
+   - Index the docs/ directory if it exists
+   - Write one-line summaries that help find the right doc
+
+Focus on what helps an AI understand this codebase quickly. Don't over-document:
+- 3–5 concepts
+- 5–7 conventions/gotchas
+- Index only human-written docs, not generated API docs
+```
+
+## Minimal prompt
+
+```
+Create jumble context for this project following the guide at https://github.com/velvet-tiger/jumble/blob/main/AUTHORING.md
+```
+
+## Workspace-level usage
+
+For monorepos or multi-project workspaces, you can ask the AI to:
+
+- Create `.jumble/workspace.toml` at the workspace root with:
+  - Workspace name and description
+  - Cross-project conventions (coding standards, tooling)
+  - Common gotchas that span multiple projects
+- Then, for each important project, create `.jumble/project.toml` with:
+  - Project metadata and commands
+  - Key concepts mapped to files
+  - Upstream/downstream relationships to other workspace projects
+
+Start with the most important projects. Use `related_projects` to show how they connect.
+"#;
+
+    Ok(prompt.to_string())
+}
+
+// ============================================================================
+// Tool Implementations
+// ============================================================================
+
+/// Looks up a project by name, suggesting the closest name when it is unknown.
+fn get_project<'a>(
+    projects: &'a HashMap<String, ProjectData>,
+    name: &str,
+) -> Result<&'a ProjectData, String> {
+    projects
+        .get(name)
+        .ok_or_else(|| fuzzy::not_found("Project", name, projects.keys().map(|k| k.as_str())))
+}
+
+/// Appends the list of valid names to a not-found message.
+fn with_available(message: String, label: &str, available: &[&str]) -> String {
+    let separator = if message.ends_with('?') { " " } else { ". " };
+    format!(
+        "{}{}{}: {}",
+        message,
+        separator,
+        label,
+        available.join(", ")
+    )
+}
+
+pub fn list_projects(projects: &HashMap<String, ProjectData>) -> Result<String, String> {
+    if projects.is_empty() {
+        return Ok(
+            "No projects found. Make sure .jumble/project.toml files exist in your workspace."
+                .to_string(),
+        );
+    }
+
+    let mut output = String::new();
+    for (name, (path, config, _skills, _conventions, _docs, _memory)) in projects {
+        if config.project.archived {
+            continue;
+        }
+        let lang = config.project.language.as_deref().unwrap_or("unknown");
+        output.push_str(&format!(
+            "- **{}** ({}): {}\n  Path: {}\n",
+            name,
+            lang,
+            config.project.description,
+            path.display()
+        ));
+    }
+    output.push_str(&archived_note(projects));
+    Ok(output)
+}
+
+/// `jumble list`: a table of every discovered project with its language,
+/// path, and how much context it carries.
+pub fn format_project_table(projects: &HashMap<String, ProjectData>) -> String {
+    if projects.is_empty() {
+        return "No projects found.".to_string();
+    }
+    let mut names: Vec<&String> = projects.keys().collect();
+    names.sort();
+
+    let mut output = String::from(
+        "| Project | Language | Path | Concepts | Skills | Docs | Memories |\n\
+         |---------|----------|------|---------:|-------:|-----:|---------:|\n",
+    );
+    for name in names {
+        let (path, config, skills, _, docs, memory_db) = &projects[name];
+        let archived = if config.project.archived {
+            " (archived)"
+        } else {
+            ""
+        };
+        let memories = memory_db
+            .read(|db| db.len().to_string())
+            .unwrap_or_else(|_| "?".to_string());
+        output.push_str(&format!(
+            "| {}{} | {} | {} | {} | {} | {} | {} |\n",
+            name,
+            archived,
+            config.project.language.as_deref().unwrap_or("unknown"),
+            path.display(),
+            config.concepts.len(),
+            skills.skills.len(),
+            docs.docs.len(),
+            memories
+        ));
+    }
+    output
+}
+
+/// A closing note counting the archived projects left out of a listing, or
+/// nothing when there are none.
+fn archived_note(projects: &HashMap<String, ProjectData>) -> String {
+    let archived = projects
+        .values()
+        .filter(|(_, config, ..)| config.project.archived)
+        .count();
+    match archived {
+        0 => String::new(),
+        1 => "\n*1 archived project not shown; use it by name.*\n".to_string(),
+        n => format!("\n*{} archived projects not shown; use them by name.*\n", n),
+    }
+}
+
+pub fn get_project_info(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (path, config, _skills, _conventions, _docs, _memory) =
+        get_project(projects, project_name)?;
+
+    let field = args.get("field").and_then(|v| v.as_str());
+
+    match field {
+        Some("commands") => Ok(format_commands(&resolve_commands(config))),
+        Some("entry_points") => Ok(format_entry_points(&config.entry_points)),
+        Some("dependencies") => {
+            let mut output = format_dependencies(&config.dependencies);
+            if let Some(source) = config.project.inferred.get("dependencies.external") {
+                output.push_str(&format!(
+                    "\n*External dependencies read from {}.*\n",
+                    source
+                ));
+            }
+            Ok(output)
+        }
+        Some("api") => Ok(format_api(&config.api)),
+        Some("related_projects") => Ok(format_related_projects(&config.related_projects)),
+        Some(f) => Err(format!("Unknown field: {}", f)),
+        None => {
+            let mut output = format!("# {}\n\n", config.project.name);
+            output.push_str(&format!(
+                "**Description:** {}\n",
+                config.project.description
+            ));
+            if let Some(lang) = &config.project.language {
+                output.push_str(&format!("**Language:** {}\n", lang));
+            }
+            let inferred = |field: &str| {
+                config
+                    .project
+                    .inferred
+                    .get(field)
+                    .map(|source| format!(" *(inferred from {})*", source))
+                    .unwrap_or_default()
+            };
+            if let Some(version) = &config.project.version {
+                output.push_str(&format!(
+                    "**Version:** {}{}\n",
+                    version,
+                    inferred("version")
+                ));
+            }
+            if let Some(repo) = &config.project.repository {
+                output.push_str(&format!(
+                    "**Repository:** {}{}\n",
+                    repo,
+                    inferred("repository")
+                ));
+            }
+            output.push_str(&format!("**Path:** {}\n", path.display()));
+
+            if !config.entry_points.is_empty() {
+                output.push_str("\n## Entry Points\n");
+                output.push_str(&format_entry_points(&config.entry_points));
+            }
+
+            if !config.concepts.is_empty() {
+                output.push_str("\n## Concepts\n");
+                for (name, concept) in &config.concepts {
+                    output.push_str(&format!("- **{}**: {}\n", name, concept.summary));
+                }
+            }
+
+            Ok(output)
+        }
+    }
+}
+
+pub fn find_project_for_file(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let file = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'path' argument")?;
+
+    let (name, (path, config, ..), relative) = owning_project(root, projects, file)?;
+
+    let mut output = format!("# {}\n\n", name);
+    output.push_str(&format!(
+        "**Description:** {}\n",
+        config.project.description
+    ));
+    if let Some(lang) = &config.project.language {
+        output.push_str(&format!("**Language:** {}\n", lang));
+    }
+    output.push_str(&format!("**Path:** {}\n", path.display()));
+    output.push_str(&format!("**File:** {}\n", relative.display()));
+    Ok(output)
+}
+
+pub fn get_context_for_file(
+    root: &Path,
+    workspace: &Option<WorkspaceConfig>,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let mut output = find_project_for_file(root, projects, args)?;
+    let file = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let (_, (_, config, _, conventions, _, _), relative) = owning_project(root, projects, file)?;
+
+    let mut concepts: Vec<(&String, &Concept)> = config
+        .concepts
+        .iter()
+        .filter(|(_, concept)| {
+            concept
+                .files
+                .iter()
+                .any(|pattern| file_matches(pattern, &relative))
+        })
+        .collect();
+    concepts.sort_by_key(|(name, _)| *name);
+    if !concepts.is_empty() {
+        output.push_str("\n## Concepts\n\n");
+        for (name, concept) in concepts {
+            output.push_str(&format!("- **{}**: {}", name, concept.summary));
+            if !concept.related.is_empty() {
+                output.push_str(&format!(" (related: {})", concept.related.join(", ")));
+            }
+            output.push('\n');
+        }
+    }
+
+    // Conventions aren't scoped to files, so all of them apply; gotchas first.
+    let mut categories = conventions.categories();
+    categories.sort_by_key(|(name, _)| *name != "gotchas");
+    for (category, entries) in categories {
+        push_context_entries(&mut output, &capitalize(category), entries);
+    }
+    if let Some(ws) = workspace {
+        push_context_entries(&mut output, "Workspace Gotchas", &ws.gotchas);
+        push_context_entries(&mut output, "Workspace Conventions", &ws.conventions);
+    }
+    Ok(output)
+}
+
+/// Task words shorter than this (e.g. "a", "to") are ignored when matching.
+const MIN_TASK_TERM_LEN: usize = 3;
+
+pub fn get_context_bundle(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let task = args
+        .get("task")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'task' argument")?;
+
+    let terms: Vec<String> = task
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= MIN_TASK_TERM_LEN)
+        .map(|t| t.to_lowercase())
+        .collect();
+    if terms.is_empty() {
+        return Err("Task must contain at least one word to match on".to_string());
+    }
+
+    let (path, config, skills, conventions, _, _) = get_project(projects, project_name)?;
+    let path = &path_base(root, path, args)?;
+    let mut output = format!("# Context for '{}' in {}\n", task, project_name);
+
+    let skill = skills
+        .skills
+        .iter()
+        .map(|(name, info)| (score_skill(name, info, &terms), name))
+        .filter(|(score, _)| *score > 0)
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(a.1)));
+    match skill {
+        Some((_, name)) => {
+            let content = get_skill(projects, &json!({ "project": project_name, "topic": name }))?;
+            output.push_str(&format!("\n## Skill: {}\n\n{}\n", name, content.trim_end()));
+        }
+        None => output.push_str("\n_No skill matches this task._\n"),
+    }
+
+    let mut concepts: Vec<(usize, &String, &Concept)> = config
+        .concepts
+        .iter()
+        .map(|(name, concept)| {
+            let name_lower = name.to_lowercase();
+            let summary = concept.summary.to_lowercase();
+            let score = terms
+                .iter()
+                .map(|term| {
+                    2 * usize::from(name_lower.contains(term.as_str()))
+                        + usize::from(summary.contains(term.as_str()))
+                })
+                .sum();
+            (score, name, concept)
+        })
+        .filter(|(score, _, _)| *score > 0)
+        .collect();
+    concepts.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+    if !concepts.is_empty() {
+        output.push_str("\n## Concepts\n");
+        for (_, name, concept) in concepts {
+            // Nest each concept's `##` heading under "Concepts".
+            output.push_str(&format!(
+                "\n#{}",
+                format_concept(path, name, concept, &config.concepts)
+            ));
+        }
+    }
+
+    let mut categories = conventions.categories();
+    categories.sort_by_key(|(name, _)| *name != "gotchas");
+    for (category, entries) in categories {
+        push_context_entries(&mut output, &capitalize(category), entries);
+    }
+    Ok(output)
+}
+
+fn push_context_entries(output: &mut String, heading: &str, entries: &Conventions) {
+    if entries.is_empty() {
+        return;
+    }
+    let mut entries: Vec<_> = entries.iter().collect();
+    entries.sort_by_key(|(name, _)| *name);
+    output.push_str(&format!("\n## {}\n\n", heading));
+    for (name, convention) in entries {
+        output.push_str(&format!("- **{}**: {}\n", name, convention.with_badge()));
+    }
+}
+
+/// Whether a concept's `files` entry covers `file` (relative to the project):
+/// the same path, a directory containing it, or a glob such as `src/**/*.rs`.
+fn file_matches(pattern: &str, file: &Path) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.is_empty() {
+        return false;
+    }
+    let dir = Path::new(pattern.trim_end_matches('/'));
+    if file.starts_with(dir) {
+        return true;
+    }
+    globset::Glob::new(pattern).is_ok_and(|glob| glob.compile_matcher().is_match(file))
+}
+
+/// The project whose directory contains `file`, with the file's path
+/// relative to it. Nested projects are possible, so the deepest wins.
+fn owning_project<'a>(
+    root: &Path,
+    projects: &'a HashMap<String, ProjectData>,
+    file: &str,
+) -> Result<(&'a String, &'a ProjectData, PathBuf), String> {
+    let file_path = normalize_path(&root.join(file));
+    projects
+        .iter()
+        .filter_map(|(name, data)| {
+            let relative = file_path.strip_prefix(normalize_path(&data.0)).ok()?;
+            Some((name, data, relative.to_path_buf()))
+        })
+        .min_by_key(|(_, _, relative)| relative.components().count())
+        .ok_or_else(|| format!("No project contains '{}'", file))
+}
+
+/// Removes `.` and `..` components and resolves symlinks in the longest
+/// existing ancestor, so paths from stack traces of deleted or generated
+/// files still compare with project directories.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut lexical = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                lexical.pop();
+            }
+            other => lexical.push(other),
+        }
+    }
+    for ancestor in lexical.ancestors() {
+        if let Ok(canonical) = std::fs::canonicalize(ancestor) {
+            let rest = lexical.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return canonical.join(rest);
+        }
+    }
+    lexical
+}
+
+pub fn get_commands(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (_, config, _, _, _, _) = get_project(projects, project_name)?;
+
+    let command_type = args.get("command_type").and_then(|v| v.as_str());
+    let commands = resolve_commands(config);
+
+    match command_type {
+        Some(cmd_type) if commands.contains_key(cmd_type) => {
+            format_command_plan(&commands, cmd_type)
+        }
+        Some(cmd_type) => {
+            let mut message = format!(
+                "Command '{}' not found for project '{}'",
+                cmd_type, project_name
+            );
+            let names = commands.keys().map(|k| k.as_str());
+            if let Some(suggestion) = fuzzy::closest_match(cmd_type, names) {
+                message.push_str(&format!(", did you mean '{}'?", suggestion));
+            }
+            Err(message)
+        }
+        None => Ok(format_commands(&commands)),
+    }
+}
+
+/// Returns the project's commands with `${VAR}` placeholders resolved.
+///
+/// Only variables named in the project's `[env]` section are substituted.
+/// A process environment variable of the same name overrides the default, so
+/// shared configs can ship defaults that individual developers override
+/// locally; other variables, such as tokens, are never read.
+pub fn resolve_commands(config: &ProjectConfig) -> HashMap<String, CommandDetails> {
+    resolve_commands_with(config, |name| std::env::var(name).ok())
+}
+
+/// [`resolve_commands`] with `env` standing in for the process environment.
+fn resolve_commands_with(
+    config: &ProjectConfig,
+    env: impl Fn(&str) -> Option<String>,
+) -> HashMap<String, CommandDetails> {
+    let lookup = |name: &str| {
+        let default = config.env.get(name)?;
+        Some(env(name).unwrap_or_else(|| default.clone()))
+    };
+    config
+        .commands
+        .iter()
+        .map(|(name, spec)| (name.clone(), resolve_command(spec, lookup)))
+        .collect()
+}
+
+/// `spec` as a table, with `${VAR}` placeholders in its command line,
+/// working directory, and environment values resolved through `lookup`.
+fn resolve_command(spec: &CommandSpec, lookup: impl Fn(&str) -> Option<String>) -> CommandDetails {
+    let mut details = spec.details();
+    details.cmd = interpolate_env(&details.cmd, &lookup);
+    details.cwd = details.cwd.map(|cwd| interpolate_env(&cwd, &lookup));
+    for value in details.env.values_mut() {
+        *value = interpolate_env(value, &lookup);
+    }
+    details
+}
+
+/// The commands that run for `name`, dependencies first and each once.
+/// Sequences contribute their steps but no command line of their own.
+fn command_order<'a>(
+    commands: &'a HashMap<String, CommandDetails>,
+    name: &str,
+) -> Result<Vec<&'a str>, String> {
+    let Some((name, _)) = commands.get_key_value(name) else {
+        return Err(format!("command '{}' not found", name));
+    };
+    let mut order = Vec::new();
+    visit_command(commands, name, &mut Vec::new(), &mut order)?;
+    order.retain(|step| !commands[*step].cmd.is_empty());
+    Ok(order)
+}
+
+fn visit_command<'a>(
+    commands: &'a HashMap<String, CommandDetails>,
+    name: &'a str,
+    stack: &mut Vec<&'a str>,
+    order: &mut Vec<&'a str>,
+) -> Result<(), String> {
+    if order.contains(&name) {
+        return Ok(());
+    }
+    if let Some(start) = stack.iter().position(|step| *step == name) {
+        // Start the cycle at its first name so every entry point reports it
+        // the same way.
+        let mut cycle = stack[start..].to_vec();
+        let first = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap_or(0);
+        cycle.rotate_left(first);
+        cycle.push(cycle[0]);
+        return Err(format!("command dependency cycle: {}", cycle.join(" -> ")));
+    }
+    stack.push(name);
+    for dependency in &commands[name].depends_on {
+        let Some((dependency, _)) = commands.get_key_value(dependency.as_str()) else {
+            return Err(format!(
+                "command '{}' depends on unknown command '{}'",
+                name, dependency
+            ));
+        };
+        visit_command(commands, dependency, stack, order)?;
+    }
+    stack.pop();
+    order.push(name);
+    Ok(())
+}
+
+/// Unknown dependencies and cycles among `commands`, one message each.
+pub fn command_dependency_problems(commands: &HashMap<String, CommandSpec>) -> Vec<String> {
+    let commands: HashMap<String, CommandDetails> = commands
+        .iter()
+        .map(|(name, spec)| (name.clone(), spec.details()))
+        .collect();
+    // Unknown names are checked directly, since a cycle can stop the walk
+    // before it reaches them.
+    let unknown = commands.iter().flat_map(|(name, command)| {
+        command
+            .depends_on
+            .iter()
+            .filter(|dependency| !commands.contains_key(*dependency))
+            .map(move |dependency| {
+                format!(
+                    "command '{}' depends on unknown command '{}'",
+                    name, dependency
+                )
+            })
+    });
+    let problems: BTreeSet<String> = commands
+        .keys()
+        .filter_map(|name| command_order(&commands, name).err())
+        .chain(unknown)
+        .collect();
+    problems.into_iter().collect()
+}
+
+/// A single command, followed by the resolved run order when it has
+/// dependencies or is a sequence.
+fn format_command_plan(
+    commands: &HashMap<String, CommandDetails>,
+    name: &str,
+) -> Result<String, String> {
+    let command = &commands[name];
+    let mut output = format_command(name, command);
+    let order = command_order(commands, name).map_err(|e| capitalize(&e))?;
+    if command.cmd.is_empty() || order.len() > 1 {
+        output.push_str("\n\nRun in order:");
+        for (i, step) in order.iter().enumerate() {
+            output.push_str(&format!("\n{}. {}: `{}`", i + 1, step, commands[*step].cmd));
+            for note in command_notes(&commands[*step]) {
+                output.push_str(&format!("\n   - {}", note));
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// The file at `relative` in `project_dir`, refused when it resolves outside
+/// the project (through `..`, an absolute path, or a symlink).
+fn project_file(project_dir: &Path, relative: &str) -> Result<PathBuf, String> {
+    let joined = project_dir.join(relative);
+    let file = joined
+        .canonicalize()
+        .map_err(|e| format!("Failed to read {}: {}", joined.display(), e))?;
+    let root = project_dir
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", project_dir.display(), e))?;
+    if !file.starts_with(&root) {
+        return Err(format!("'{}' is outside the project directory", relative));
+    }
+    Ok(file)
+}
+
+pub fn get_entry_point(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'name' argument")?;
+
+    let (path, config, _, _, _, _) = get_project(projects, project_name)?;
+
+    let entry = config.entry_points.get(name).ok_or_else(|| {
+        let mut available: Vec<&str> = config.entry_points.keys().map(|s| s.as_str()).collect();
+        available.sort();
+        if available.is_empty() {
+            format!("No entry points defined for '{}'", project_name)
+        } else {
+            let message = fuzzy::not_found("Entry point", name, available.iter().copied());
+            with_available(message, "Available", &available)
+        }
+    })?;
+
+    let relative = entry.path();
+    let file_path = project_file(path, relative)?;
+    let bytes = std::fs::read(&file_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path.display(), e))?;
+    let content = String::from_utf8_lossy(&bytes);
+
+    let (shown, truncated) = if content.len() > MAX_ENTRY_POINT_BYTES {
+        let mut cut = MAX_ENTRY_POINT_BYTES;
+        while !content.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let cut = content[..cut].rfind('\n').map(|i| i + 1).unwrap_or(cut);
+        (&content[..cut], true)
+    } else {
+        (content.as_ref(), false)
+    };
+
+    let total_lines = content.lines().count();
+    let width = total_lines.max(1).to_string().len();
+    let mut output = format!("# {} ({})\n\n", name, relative);
+    if let Some(description) = entry.description() {
+        output.push_str(&format!("{}\n\n", description));
+    }
+    output.push_str("```\n");
+    let mut shown_lines = 0;
+    for (number, line) in shown.lines().enumerate() {
+        output.push_str(&format!(
+            "{:>width$}  {}\n",
+            number + 1,
+            line,
+            width = width
+        ));
+        shown_lines += 1;
+    }
+    output.push_str("```\n");
+    if truncated {
+        output.push_str(&format!(
+            "\n*Truncated: showing {} of {} lines ({} bytes). Read {} directly for the rest.*\n",
+            shown_lines,
+            total_lines,
+            bytes.len(),
+            file_path.display()
+        ));
+    }
+    Ok(output)
+}
+
+pub fn get_architecture(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let concept_name = args
+        .get("concept")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'concept' argument")?;
+
+    let (path, config, _, _, _, _) = get_project(projects, project_name)?;
+    let path = &path_base(root, path, args)?;
+
+    // Try exact match first
+    if let Some(concept) = config.concepts.get(concept_name) {
+        return Ok(format_concept(
+            path,
+            concept_name,
+            concept,
+            &config.concepts,
+        ));
+    }
+
+    // Try case-insensitive match
+    let concept_lower = concept_name.to_lowercase();
+    for (name, concept) in &config.concepts {
+        if name.to_lowercase() == concept_lower {
+            return Ok(format_concept(path, name, concept, &config.concepts));
+        }
+    }
+
+    // Try partial match: name matches rank above summary matches.
+    let mut matches: Vec<(bool, &String, &Concept)> = config
+        .concepts
+        .iter()
+        .filter_map(|(name, concept)| {
+            let in_name = name.to_lowercase().contains(&concept_lower);
+            (in_name || concept.summary.to_lowercase().contains(&concept_lower))
+                .then_some((!in_name, name, concept))
+        })
+        .collect();
+    matches.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+    match matches.as_slice() {
+        [] => {}
+        [(_, name, concept)] => return Ok(format_concept(path, name, concept, &config.concepts)),
+        _ => {
+            let mut output = format!("# {} concepts match '{}'\n\n", matches.len(), concept_name);
+            for (_, name, concept) in &matches {
+                output.push_str(&format!("- **{}**: {}\n", name, concept.summary));
+            }
+            output.push_str("\nCall get_architecture again with one of these concept names.");
+            return Ok(output);
+        }
+    }
+
+    // Try a fuzzy match for typos and separator differences.
+    if let Some((name, concept)) = fuzzy::lookup(&config.concepts, concept_name) {
+        return Ok(format_concept(path, name, concept, &config.concepts));
+    }
+
+    // List available concepts
+    let available: Vec<&str> = config.concepts.keys().map(|s| s.as_str()).collect();
+    let message = fuzzy::not_found("Concept", concept_name, available.iter().copied());
+    Err(with_available(message, "Available concepts", &available))
+}
+
+pub fn add_concept(
+    projects: &mut HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or("Missing 'name' argument")?;
+
+    let summary = args.get("summary").and_then(|v| v.as_str());
+    let files = string_list(args, "files")?;
+    let related = string_list(args, "related")?;
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    get_project(projects, project_name)?;
+    let (path, config, _, _, _, _) = projects.get_mut(project_name).unwrap();
+
+    let file = config::find_config_file(&path.join(".jumble"), "project")
+        .or_else(|| config::find_single_file_config(path))
+        .ok_or_else(|| format!("No project config found for '{}'", project_name))?;
+    if file.extension().and_then(|e| e.to_str()) != Some("toml") {
+        return Err(format!(
+            "add_concept only edits TOML files; update {} by hand",
+            file.display()
+        ));
+    }
+
+    // Fields that are not supplied keep their current values.
+    let concept = match config.concepts.get(name) {
+        Some(existing) => Concept {
+            files: files.unwrap_or_else(|| existing.files.clone()),
+            summary: summary.map_or_else(|| existing.summary.clone(), str::to_string),
+            related: related.unwrap_or_else(|| existing.related.clone()),
+            owner: existing.owner.clone(),
+            contact: existing.contact.clone(),
+        },
+        None => Concept {
+            files: files.ok_or("New concepts need a 'files' argument")?,
+            summary: summary
+                .ok_or("New concepts need a 'summary' argument")?
+                .to_string(),
+            related: related.unwrap_or_default(),
+            owner: None,
+            contact: None,
+        },
+    };
+
+    if !confirm {
+        return Ok(format!(
+            "Would write to {}:\n\n```toml\n{}```\n\nCall add_concept again with confirm: true to write it.",
+            file.display(),
+            config::concept_snippet(name, &concept)
+        ));
+    }
+
+    let created = config::upsert_concept(&file, name, &concept).map_err(|e| format!("{:#}", e))?;
+    config.concepts.insert(name.to_string(), concept);
+
+    Ok(format!(
+        "{} concept '{}' in {}",
+        if created { "Added" } else { "Updated" },
+        name,
+        file.display()
+    ))
+}
+
+/// Reads an optional array-of-strings argument.
+fn string_list(args: &Value, key: &str) -> Result<Option<Vec<String>>, String> {
+    let Some(value) = args.get(key) else {
+        return Ok(None);
+    };
+    value
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+        })
+        .map(Some)
+        .ok_or_else(|| format!("'{}' must be an array of strings", key))
+}
+
+pub fn get_related_files(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'query' argument")?;
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let path = &path_base(root, project_dir, args)?;
+
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Err("Query must not be empty".to_string());
+    }
+    let query_lower = terms.join(" ");
+
+    let mut matched_files: Vec<(u32, &str, &Concept)> = config
+        .concepts
+        .iter()
+        .filter_map(|(name, concept)| {
+            let score = related_score(name, &concept.summary, &query_lower, &terms);
+            (score > 0).then_some((score, name.as_str(), concept))
+        })
+        .collect();
+
+    if matched_files.is_empty() {
+        return Err(format!("No concepts matching '{}' found", query));
+    }
+
+    matched_files.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let codeowners = CodeOwners::find(project_dir, root);
+    let mut output = format!("Files related to '{}': \n\n", query);
+    for (score, name, concept) in &matched_files {
+        output.push_str(&format!(
+            "## {} (score {})\n{}\n\nFiles:\n",
+            name, score, concept.summary
+        ));
+        for file in &concept.files {
+            let line = format_concept_file(path, file);
+            let owners = codeowners
+                .as_ref()
+                .and_then(|c| c.rule_for(&project_dir.join(FileRef::parse(file).path)))
+                .filter(|rule| !rule.owners.is_empty());
+            match owners {
+                Some(rule) => output.push_str(&format!(
+                    "{} (owners: {})\n",
+                    line.trim_end(),
+                    rule.owners.join(", ")
+                )),
+                None => output.push_str(&line),
+            }
+        }
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Relevance of a concept to a `get_related_files` query. An exact name
+/// match outranks name substrings, which outrank summary substrings; each
+/// query term hit adds to the score. Zero means no match.
+fn related_score(name: &str, summary: &str, query: &str, terms: &[String]) -> u32 {
+    let name = name.to_lowercase();
+    let summary = summary.to_lowercase();
+    let mut score = if name == query { 100 } else { 0 };
+    for term in terms {
+        if name.contains(term.as_str()) {
+            score += 10;
+        } else if summary.contains(term.as_str()) {
+            score += 3;
+        }
+    }
+    score
+}
+
+/// `get_tests_for_file`: the tests covering a source file, from `[test_map]`
+/// and common test naming conventions.
+pub fn get_tests_for_file(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'path' argument")?;
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let configured = test_map::configured(&config.test_map, path);
+    let conventional: Vec<String> = test_map::conventional(project_dir, path)
+        .into_iter()
+        .filter(|test| !configured.contains(test))
+        .collect();
+    let inline = test_map::has_inline_tests(project_dir, path);
+
+    if configured.is_empty() && conventional.is_empty() && !inline {
+        return Ok(format!(
+            "No tests found for `{}`. Checked [test_map] and common naming conventions \
+             (foo_test, foo.test, foo.spec, test_foo, __tests__/, tests/). Add a [test_map] \
+             entry to project.toml if this project lays tests out differently.",
+            path
+        ));
+    }
+
+    let mut output = format!("# Tests for {}\n", path);
+    if !configured.is_empty() {
+        output.push_str("\n**From [test_map]:**\n");
+        for test in &configured {
+            let missing = if project_dir.join(test).exists() {
+                ""
+            } else {
+                " (missing)"
+            };
+            output.push_str(&format!("- {}{}\n", test, missing));
+        }
+    }
+    if !conventional.is_empty() {
+        output.push_str("\n**By naming convention:**\n");
+        for test in &conventional {
+            output.push_str(&format!("- {}\n", test));
+        }
+    }
+    if inline {
+        output.push_str(&format!(
+            "\n`{}` also has inline tests (`#[cfg(test)]`).\n",
+            path
+        ));
+    }
+    Ok(output)
+}
+
+/// `get_coverage`: line coverage from the project's `[coverage]` report, for
+/// one concept's files or per concept, thinnest first.
+pub fn get_coverage(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let coverage_config = config.coverage.as_ref().ok_or_else(|| {
+        format!(
+            "Project '{}' has no coverage report configured. Add a [coverage] section with report = \"coverage/lcov.info\" (or a Cobertura XML path) to its project.toml.",
+            project_name
+        )
+    })?;
+    let report = coverage::load(project_dir, coverage_config)?;
+    let summarize = |files: &[String]| {
+        files
+            .iter()
+            .filter_map(|file| report.summarize(project_dir, file))
+            .reduce(|mut total, summary| {
+                total.add(summary);
+                total
+            })
+    };
+    let row = |name: &str, summary: Option<coverage::Summary>| match summary {
+        Some(summary) => format!(
+            "| {} | {:.1}% | {}/{} |\n",
+            name,
+            summary.percent(),
+            summary.covered,
+            summary.total
+        ),
+        None => format!("| {} | no data | - |\n", name),
+    };
+    // Thinnest coverage first; entries without data last.
+    let rank = |summary: &Option<coverage::Summary>| {
+        summary.map_or(f64::INFINITY, |summary| summary.percent())
+    };
+
+    let mut output;
+    match args.get("concept").and_then(|v| v.as_str()) {
+        Some(concept_name) => {
+            let (name, concept) =
+                fuzzy::lookup(&config.concepts, concept_name).ok_or_else(|| {
+                    let names = config.concepts.keys().map(|k| k.as_str());
+                    fuzzy::not_found("Concept", concept_name, names)
+                })?;
+            output = format!(
+                "# Coverage for {}\n\nReport: {}\n\n",
+                name, coverage_config.report
+            );
+            match summarize(&concept.files) {
+                Some(total) => output.push_str(&format!(
+                    "**Total:** {:.1}% ({}/{} lines)\n\n",
+                    total.percent(),
+                    total.covered,
+                    total.total
+                )),
+                None => output.push_str("**Total:** no coverage data for these files\n\n"),
+            }
+            let mut files: Vec<(&String, Option<coverage::Summary>)> = concept
+                .files
+                .iter()
+                .map(|file| (file, report.summarize(project_dir, file)))
+                .collect();
+            files.sort_by(|a, b| rank(&a.1).total_cmp(&rank(&b.1)).then(a.0.cmp(b.0)));
+            output.push_str("| File | Coverage | Lines |\n|------|---------:|------:|\n");
+            for (file, summary) in files {
+                output.push_str(&row(file, summary));
+            }
+        }
+        None => {
+            output = format!(
+                "# Coverage for {}\n\nReport: {}\n\n",
+                project_name, coverage_config.report
+            );
+            let total = report.total();
+            output.push_str(&format!(
+                "**Total:** {:.1}% ({}/{} lines in {} files)\n\n",
+                total.percent(),
+                total.covered,
+                total.total,
+                report.files.len()
+            ));
+            if config.concepts.is_empty() {
+                output.push_str("No concepts defined. Add [concepts] to project.toml to see coverage per area.\n");
+                return Ok(output);
+            }
+            let mut concepts: Vec<(&String, Option<coverage::Summary>)> = config
+                .concepts
+                .iter()
+                .map(|(name, concept)| (name, summarize(&concept.files)))
+                .collect();
+            concepts.sort_by(|a, b| rank(&a.1).total_cmp(&rank(&b.1)).then(a.0.cmp(b.0)));
+            output.push_str("| Concept | Coverage | Lines |\n|---------|---------:|------:|\n");
+            for (name, summary) in concepts {
+                output.push_str(&row(name, summary));
+            }
+        }
+    }
+    Ok(output)
+}
+
+/// `get_environment`: the project's `[environment]` requirements, checked
+/// against the tools and variables available where Jumble runs.
+pub fn get_environment(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let env = config.environment.as_ref().ok_or_else(|| {
+        format!(
+            "Project '{}' has no environment requirements. Add an [environment] section with tools, variables, and setup steps to its project.toml.",
+            project_name
+        )
+    })?;
+
+    let mut problems = Vec::new();
+    let mut output = format!("# Environment for {}\n\n", project_name);
+
+    if !env.tools.is_empty() {
+        output.push_str(
+            "## Tools\n\n| Tool | Required | Installed |\n|------|----------|-----------|\n",
+        );
+        for (tool, requirement) in &env.tools {
+            let installed = match environment::check_tool(tool, requirement) {
+                environment::ToolCheck::Ok(version) => format!("{} ✓", version),
+                environment::ToolCheck::Mismatch(version) => {
+                    problems.push(format!(
+                        "{} {} does not satisfy {}",
+                        tool, version, requirement
+                    ));
+                    format!("{} ✗", version)
+                }
+                environment::ToolCheck::Unknown => "unknown version".to_string(),
+                environment::ToolCheck::Unsupported => "not checked".to_string(),
+                environment::ToolCheck::NotFound => {
+                    problems.push(format!("{} is not installed", tool));
+                    "not found ✗".to_string()
+                }
+            };
+            output.push_str(&format!("| {} | {} | {} |\n", tool, requirement, installed));
+        }
+        output.push('\n');
+    }
+
+    if !env.variables.is_empty() {
+        output.push_str("## Environment Variables\n\n");
+        for (name, variable) in &env.variables {
+            let status = match environment::find_variable(project_dir, name) {
+                Some(environment::VariableSource::Process) => "set",
+                Some(environment::VariableSource::DotEnv) => "set in .env",
+                None if variable.required() => {
+                    problems.push(format!("{} is not set", name));
+                    "missing"
+                }
+                None => "not set",
+            };
+            let required = if variable.required() {
+                "required"
+            } else {
+                "optional"
+            };
+            output.push_str(&format!("- `{}` ({}, {})", name, required, status));
+            if let Some(description) = variable.description() {
+                output.push_str(&format!(": {}", description));
+            }
+            if let Some(example) = variable.example() {
+                output.push_str(&format!(" (e.g. `{}`)", example));
+            }
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+
+    if !env.setup.is_empty() {
+        output.push_str("## Setup\n\n");
+        for (i, step) in env.setup.iter().enumerate() {
+            output.push_str(&format!("{}. {}\n", i + 1, step));
+        }
+        output.push('\n');
+    }
+
+    if problems.is_empty() {
+        output.push_str("All requirements are met on this machine.\n");
+    } else {
+        output.push_str("## Problems\n\n");
+        for problem in problems {
+            output.push_str(&format!("- {}\n", problem));
+        }
+    }
+    Ok(output)
+}
+
+/// `get_services`: the project's `[services]`, merged with the services in
+/// its docker-compose files.
+pub fn get_services(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let default_compose = services::find_compose_file(project_dir);
+    if config.services.is_empty() && default_compose.is_none() {
+        return Err(format!(
+            "Project '{}' has no services configured. Add [services.<name>] tables to its project.toml, or a docker-compose.yml to the project root.",
+            project_name
+        ));
+    }
+
+    let mut compose_files = BTreeMap::new();
+    let files = config
+        .services
+        .values()
+        .filter_map(|service| service.compose.as_deref())
+        .chain(default_compose);
+    for file in files {
+        compose_files
+            .entry(file)
+            .or_insert_with(|| services::load_compose(&project_dir.join(file)));
+    }
+
+    let mut output = format!("# Services for {}\n\n", project_name);
+    for (name, service) in &config.services {
+        let compose = service
+            .compose
+            .as_deref()
+            .or(default_compose)
+            .and_then(|file| {
+                let found = compose_files.get(file)?.as_ref().ok()?.get(name)?;
+                Some((file, found))
+            });
+        output.push_str(&format_service(name, service, compose));
+    }
+    for (file, found) in &compose_files {
+        match found {
+            Ok(found) => {
+                for (name, compose) in found {
+                    if !config.services.contains_key(name) {
+                        let service = Service::default();
+                        output.push_str(&format_service(name, &service, Some((file, compose))));
+                    }
+                }
+            }
+            Err(e) => output.push_str(&format!("*{}*\n\n", e)),
+        }
+    }
+    Ok(output.trim_end().to_string() + "\n")
+}
+
+/// `get_ci_info`: the workflows listed in `[ci]`, or the project's GitHub
+/// Actions workflows, with triggers and jobs when `parse` is not `false`.
+pub fn get_ci_info(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+    let parse = args.get("parse").and_then(|v| v.as_bool()).unwrap_or(true);
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let configured = config.ci.as_ref().map(|ci| &ci.workflows);
+    // (shown path, file, summary)
+    let workflows: Vec<(String, PathBuf, Option<&str>)> = match configured {
+        Some(configured) if !configured.is_empty() => configured
+            .iter()
+            .map(|(path, summary)| (path.clone(), project_dir.join(path), Some(summary.as_str())))
+            .collect(),
+        _ => ci::find_workflows(project_dir, root)
+            .map(|(base, files)| {
+                files
+                    .into_iter()
+                    .map(|file| {
+                        let shown = file
+                            .strip_prefix(&base)
+                            .unwrap_or(&file)
+                            .display()
+                            .to_string();
+                        (shown, file, None)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    if workflows.is_empty() {
+        return Err(format!(
+            "Project '{}' has no CI configured. Add a [ci] section listing its workflow files to its project.toml, or add GitHub Actions workflows under {}.",
+            project_name,
+            ci::WORKFLOW_DIR
+        ));
+    }
+
+    let mut output = format!("# CI for {}\n\n", project_name);
+    let required = config.ci.iter().flat_map(|ci| &ci.required_checks);
+    let required: Vec<&str> = required.map(String::as_str).collect();
+    if !required.is_empty() {
+        output.push_str(&format!("**Required checks:** {}\n\n", required.join(", ")));
+    }
+
+    let mut sections = String::new();
+    let mut pull_request_checks = Vec::new();
+    for (shown, file, summary) in &workflows {
+        sections.push_str(&format!("## {}\n\n", shown));
+        if let Some(summary) = summary {
+            sections.push_str(&format!("{}\n\n", summary));
+        }
+        // Only GitHub Actions files are parsed; others keep their summary.
+        let is_actions = file
+            .to_string_lossy()
+            .replace('\\', "/")
+            .contains(ci::WORKFLOW_DIR);
+        if !parse || !is_actions {
+            continue;
+        }
+        match ci::load_workflow(file) {
+            Ok(workflow) => {
+                if workflow.runs_on_pull_requests() {
+                    let prefix = workflow.name.as_deref().unwrap_or(shown);
+                    for job in &workflow.jobs {
+                        let job_name = job.name.as_deref().unwrap_or(&job.id);
+                        pull_request_checks.push(format!("{} / {}", prefix, job_name));
+                    }
+                }
+                sections.push_str(&format_workflow(&workflow));
+                sections.push('\n');
+            }
+            Err(e) => sections.push_str(&format!("*{}*\n\n", e)),
+        }
+    }
+    if !pull_request_checks.is_empty() {
+        output.push_str(&format!(
+            "**Runs on pull requests:** {}\n\n",
+            pull_request_checks.join(", ")
+        ));
+    }
+    output.push_str(&sections);
+    Ok(output.trim_end().to_string() + "\n")
+}
+
+/// Most versions listed in the `get_changelog` overview.
+const MAX_LISTED_VERSIONS: usize = 10;
+
+/// `get_changelog`: one release section of the project's changelog, or the
+/// pending and latest releases when no version is given.
+pub fn get_changelog(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let releases_config = config.releases.clone().unwrap_or_default();
+    let file = changelog::find(project_dir, releases_config.changelog.as_deref()).ok_or_else(|| {
+        format!(
+            "Project '{}' has no changelog. Add a CHANGELOG.md, or set changelog under [releases] in its project.toml.",
+            project_name
+        )
+    })?;
+    let path = project_file(project_dir, &file)?;
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let releases = changelog::releases(&content);
+
+    if let Some(version) = args.get("version").and_then(|v| v.as_str()) {
+        let release = if version.eq_ignore_ascii_case("latest") {
+            releases.iter().find(|r| !r.is_unreleased())
+        } else {
+            releases.iter().find(|r| changelog::matches(r, version))
+        };
+        return release.map(|r| r.text.clone()).ok_or_else(|| {
+            let versions = releases.iter().map(|r| r.version.as_str());
+            fuzzy::not_found("Version", version, versions)
+        });
+    }
+
+    let mut output = format!("# Changelog for {}\n\n**File:** {}\n", project_name, file);
+    if let Some(version) = &config.project.version {
+        output.push_str(&format!("**Current version:** {}\n", version));
+    }
+    if let Some(versioning) = &releases_config.versioning {
+        output.push_str(&format!("**Versioning:** {}\n", versioning));
+    }
+    if let Some(command) = &releases_config.command {
+        match resolve_commands(config).get(command) {
+            Some(details) => output.push_str(&format!(
+                "**Release command:** `{}` (`{}`)\n",
+                details.cmd, command
+            )),
+            None => output.push_str(&format!("**Release command:** `{}`\n", command)),
+        }
+    }
+    if releases.is_empty() {
+        output.push_str(&format!("\nNo release sections found in {}.\n", file));
+        return Ok(output);
+    }
+
+    let mut versions: Vec<String> = releases
+        .iter()
+        .take(MAX_LISTED_VERSIONS)
+        .map(changelog::Release::label)
+        .collect();
+    if releases.len() > MAX_LISTED_VERSIONS {
+        versions.push(format!(
+            "and {} older",
+            releases.len() - MAX_LISTED_VERSIONS
+        ));
+    }
+    output.push_str(&format!("**Versions:** {}\n", versions.join(", ")));
+
+    // Pending changes when there are any, and the latest release.
+    let pending = releases
+        .iter()
+        .find(|r| r.is_unreleased())
+        .filter(|r| r.has_entries());
+    let latest = releases.iter().find(|r| !r.is_unreleased());
+    for release in pending.into_iter().chain(latest) {
+        output.push_str(&format!("\n{}\n", release.text));
+    }
+    Ok(output)
+}
+
+/// Tags listed by `get_version_history` when no limit is given.
+const DEFAULT_LISTED_TAGS: usize = 10;
+
+/// `get_version_history`: recent git tags for a project, newest first.
+pub fn get_version_history(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_LISTED_TAGS, |n| n as usize);
+    let pattern = args.get("pattern").and_then(|v| v.as_str());
+
+    let (project_dir, config, _, _, _, _) = get_project(projects, project_name)?;
+    let all_tags = git::tags(project_dir)?;
+    let tags: Vec<&git::Tag> = match pattern {
+        Some(pattern) => {
+            let matcher = globset::Glob::new(pattern)
+                .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?
+                .compile_matcher();
+            all_tags
+                .iter()
+                .filter(|t| matcher.is_match(&t.name))
+                .collect()
+        }
+        None => {
+            // Monorepos tag each project separately, e.g. `api-v1.2.0`,
+            // `api/v1.2.0`, or `api@1.2.0`; use those when there are any.
+            let prefixes = ["-", "/", "@"].map(|sep| format!("{}{}", project_name, sep));
+            let own: Vec<&git::Tag> = all_tags
+                .iter()
+                .filter(|t| prefixes.iter().any(|p| t.name.starts_with(p.as_str())))
+                .collect();
+            if own.is_empty() {
+                all_tags.iter().collect()
+            } else {
+                own
+            }
+        }
+    };
+    if tags.is_empty() {
+        return Err(match pattern {
+            Some(pattern) => format!(
+                "No git tags matching '{}' found for project '{}'.",
+                pattern, project_name
+            ),
+            None => format!("No git tags found for project '{}'.", project_name),
+        });
+    }
+
+    let mut output = format!("# Version history for {}\n\n", project_name);
+    if let Some(version) = &config.project.version {
+        let bare = version.trim_start_matches('v');
+        // `1.0.0` is tagged as `1.0.0`, `v1.0.0`, `api-v1.0.0`, `api@1.0.0`, ...
+        let tagged = tags.iter().any(|t| {
+            t.name.strip_suffix(bare).is_some_and(|prefix| {
+                let prefix = prefix.strip_suffix(['v', 'V']).unwrap_or(prefix);
+                prefix.is_empty() || prefix.ends_with(['-', '/', '@'])
+            })
+        });
+        output.push_str(&format!("**Configured version:** {}", version));
+        if !tagged {
+            output.push_str(" (no tag yet)");
+        }
+        output.push('\n');
+    }
+    let latest = tags[0];
+    output.push_str(&format!(
+        "**Latest tag:** {} ({})\n",
+        latest.name, latest.date
+    ));
+    match git::commits_since(project_dir, &latest.name) {
+        Ok(0) => output.push_str("**Since then:** no commits touch this project\n"),
+        Ok(1) => output.push_str("**Since then:** 1 commit touches this project\n"),
+        Ok(n) => output.push_str(&format!(
+            "**Since then:** {} commits touch this project\n",
+            n
+        )),
+        Err(_) => {}
+    }
+
+    output.push_str("\n## Tags\n\n");
+    for tag in tags.iter().take(limit) {
+        output.push_str(&format!(
+            "- **{}** ({}, {})",
+            tag.name, tag.date, tag.commit
+        ));
+        if !tag.subject.is_empty() {
+            output.push_str(&format!(": {}", tag.subject));
+        }
+        output.push('\n');
+    }
+    if tags.len() > limit {
+        output.push_str(&format!(
+            "\n*{} older tags not shown.*\n",
+            tags.len() - limit
+        ));
+    }
+    Ok(output)
+}
+
+/// `who_owns`: the `CODEOWNERS` owners of a file or directory in a project.
+pub fn who_owns(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'path' argument")?;
+
+    let (project_dir, _, _, _, _, _) = get_project(projects, project_name)?;
+    let codeowners = CodeOwners::find(project_dir, root).ok_or_else(|| {
+        format!(
+            "No CODEOWNERS file found for project '{}' (looked for {})",
+            project_name,
+            codeowners::LOCATIONS.join(", ")
+        )
+    })?;
+    let file = codeowners
+        .file
+        .strip_prefix(root)
+        .unwrap_or(&codeowners.file)
+        .display()
+        .to_string();
+
+    Ok(match codeowners.rule_for(&project_dir.join(path)) {
+        Some(rule) if rule.owners.is_empty() => format!(
+            "`{}` has no owners: `{}` ({} line {}) lists none.",
+            path, rule.pattern, file, rule.line
+        ),
+        Some(rule) => format!(
+            "`{}` is owned by {}\n\nRule: `{}` ({} line {})",
+            path,
+            rule.owners.join(", "),
+            rule.pattern,
+            file,
+            rule.line
+        ),
+        None => format!("No rule in {} matches `{}`.", file, path),
+    })
+}
+
+/// Most results shown per group by `search_context`.
+const MAX_CONTEXT_RESULTS: usize = 5;
+
+/// One `search_context` hit: score, name, and a one-line description.
+type ContextHit = (u32, String, String);
+
+pub fn search_context(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'query' argument")?;
+
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Err("Query must not be empty".to_string());
+    }
+
+    let (_, config, skills, conventions, docs, memory_db) = get_project(projects, project_name)?;
+
+    let score_all = |entries: &mut dyn Iterator<Item = (String, &str)>| -> Vec<ContextHit> {
+        entries
+            .filter_map(|(name, text)| {
+                let score = score_text(&name, text, &terms);
+                (score > 0).then(|| (score, name, text.to_string()))
+            })
+            .collect()
+    };
+
+    let concepts = score_all(
+        &mut config
+            .concepts
+            .iter()
+            .map(|(name, c)| (name.clone(), c.summary.as_str())),
+    );
+    let mut convention_entries = Vec::new();
+    let mut gotcha_entries = Vec::new();
+    for (category, entries) in conventions.categories() {
+        for (key, value) in entries {
+            match category {
+                "conventions" => convention_entries.push((key.clone(), value.description())),
+                "gotchas" => gotcha_entries.push((key.clone(), value.description())),
+                _ => {
+                    convention_entries.push((format!("{}.{}", category, key), value.description()))
+                }
+            }
+        }
+    }
+    let convention_hits = score_all(&mut convention_entries.into_iter());
+    let gotcha_hits = score_all(&mut gotcha_entries.into_iter());
+    let doc_hits = score_all(
+        &mut docs
+            .docs
+            .iter()
+            .map(|(name, d)| (name.clone(), d.summary.as_str())),
+    );
+    let skill_hits: Vec<ContextHit> = skills
+        .skills
+        .iter()
+        .filter_map(|(name, info)| {
+            let score = score_skill(name, info, &terms);
+            let description = info
+                .frontmatter
+                .as_ref()
+                .and_then(|fm| fm.description.clone())
+                .unwrap_or_default();
+            (score > 0).then(|| (score, name.clone(), description))
+        })
+        .collect();
+    let memory_hits = memory_db.read(|db| {
+        score_all(
+            &mut db
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.value.as_str())),
+        )
+    })?;
+
+    let mut groups = vec![
+        ("Concepts", "get_architecture(project, concept)", concepts),
+        ("Conventions", "get_conventions(project)", convention_hits),
+        ("Gotchas", "get_conventions(project)", gotcha_hits),
+        ("Docs", "get_docs(project, topic)", doc_hits),
+        ("Skills", "get_skill(project, topic)", skill_hits),
+        ("Memories", "get_memory(project, key)", memory_hits),
+    ];
+    groups.retain(|(_, _, hits)| !hits.is_empty());
+    if groups.is_empty() {
+        return Ok(format!(
+            "No context matching '{}' found in '{}'.",
+            query, project_name
+        ));
+    }
+    for (_, _, hits) in &mut groups {
+        hits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    }
+    // The group holding the best hit comes first; ties keep the order above.
+    groups.sort_by(|a, b| b.2[0].0.cmp(&a.2[0].0));
+
+    let mut output = format!("# Context matching '{}' in '{}'\n", query, project_name);
+    for (label, follow_up, hits) in &groups {
+        output.push_str(&format!("\n## {} (use {})\n", label, follow_up));
+        for (score, name, text) in hits.iter().take(MAX_CONTEXT_RESULTS) {
+            output.push_str(&format!("- **{}** (score {})", name, score));
+            if let Some(line) = text.lines().find(|l| !l.trim().is_empty()) {
+                output.push_str(&format!(": {}", line.trim()));
+            }
+            output.push('\n');
+        }
+        if hits.len() > MAX_CONTEXT_RESULTS {
+            output.push_str(&format!(
+                "- ... and {} more\n",
+                hits.len() - MAX_CONTEXT_RESULTS
+            ));
+        }
+    }
+    Ok(output)
+}
+
+/// Scores a named entry against lowercase query terms, weighting the name
+/// like `score_skill` does and the text like a skill description.
+fn score_text(name: &str, text: &str, terms: &[String]) -> u32 {
+    let name = name.to_lowercase();
+    let text = text.to_lowercase();
+    let mut score = 0;
+    for term in terms {
+        if name == *term {
+            score += 100;
+        } else if name.contains(term.as_str()) {
+            score += 60;
+        }
+        if text.contains(term.as_str()) {
+            score += 30;
+        }
+    }
+    score
+}
+
+/// Default number of commits inspected by `get_recent_changes`.
+const DEFAULT_RECENT_COMMITS: usize = 20;
+
+/// Entry point files larger than this are truncated at a line boundary.
+const MAX_ENTRY_POINT_BYTES: usize = 64 * 1024;
+
+pub fn get_recent_changes(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (path, config, _, _, _, _) = get_project(projects, project_name)?;
+
+    let (range, range_label) = match args.get("since").and_then(|v| v.as_str()) {
+        Some(since) => (
+            ChangeRange::SinceRef(since.to_string()),
+            format!("since {}", since),
+        ),
+        None => {
+            let n = args
+                .get("commits")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize)
+                .unwrap_or(DEFAULT_RECENT_COMMITS);
+            (ChangeRange::LastCommits(n), format!("last {} commits", n))
+        }
+    };
+
+    let commits = git::changed_files_per_commit(path, &range)?;
+    if commits.is_empty() {
+        return Ok(format!(
+            "No changes found for '{}' ({}).",
+            project_name, range_label
+        ));
+    }
+
+    // Count how many commits touched each file.
+    let mut file_counts: HashMap<&str, usize> = HashMap::new();
+    for files in &commits {
+        for file in files {
+            *file_counts.entry(file.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    // Cross-reference against concepts: count distinct commits touching any concept file.
+    let mut concept_hits: Vec<(&str, usize, Vec<&str>)> = Vec::new();
+    for (name, concept) in &config.concepts {
+        let touching = commits
+            .iter()
+            .filter(|files| {
+                files
+                    .iter()
+                    .any(|f| concept.files.iter().any(|cf| concept_file_matches(cf, f)))
+            })
+            .count();
+        if touching > 0 {
+            let mut touched: Vec<&str> = file_counts
+                .keys()
+                .copied()
+                .filter(|f| concept.files.iter().any(|cf| concept_file_matches(cf, f)))
+                .collect();
+            touched.sort();
+            concept_hits.push((name.as_str(), touching, touched));
+        }
+    }
+    concept_hits.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    let mut output = format!(
+        "# Recent changes in '{}' ({})\n\n",
+        project_name, range_label
+    );
+    output.push_str(&format!(
+        "{} commit(s) touched {} file(s).\n\n",
+        commits.len(),
+        file_counts.len()
+    ));
+
+    output.push_str("## Concepts\n\n");
+    if concept_hits.is_empty() {
+        output.push_str("No defined concepts were touched.\n");
+    }
+    for (name, touching, files) in &concept_hits {
+        output.push_str(&format!(
+            "- **{}**: touched in {} commit(s) ({})\n",
+            name,
+            touching,
+            files.join(", ")
+        ));
+    }
+
+    let mut files: Vec<(&str, usize)> = file_counts.into_iter().collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    output.push_str("\n## Files\n\n");
+    for (file, count) in &files {
+        output.push_str(&format!("- {} ({})\n", file, count));
+    }
+
+    Ok(output)
+}
+
+/// Returns true when a changed file (relative to the project root) is covered
+/// by a concept file entry, which may name a file, a line range within a file,
+/// or a directory.
+fn concept_file_matches(concept_file: &str, changed: &str) -> bool {
+    let concept_file = FileRef::parse(concept_file)
+        .path
+        .trim_start_matches("./")
+        .trim_end_matches('/');
+    changed == concept_file || changed.starts_with(&format!("{}/", concept_file))
+}
+
+pub fn list_skills(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let (_, _, skills, _, _, _) = get_project(projects, project_name)?;
+
+    if skills.skills.is_empty() {
+        return Ok(format!(
+            "No skills found for '{}'. Create .jumble/skills/*.md files to add task-specific context.",
+            project_name
+        ));
+    }
+
+    let mut output = format!("Available skills for '{}':\n\n", project_name);
+
+    // Include any available frontmatter description or, as a fallback, the first
+    // line of the cached preview. This makes skill listings more informative
+    // and exercises the cached metadata so it is not considered dead code.
+    for (name, info) in &skills.skills {
+        let mut line = format!("- {}", name);
+
+        if let Some(fm) = &info.frontmatter {
+            if let Some(desc) = &fm.description {
+                if !desc.is_empty() {
+                    line.push_str(&format!(": {}", desc));
+                    output.push_str(&line);
+                    output.push('\n');
+                    continue;
+                }
+            }
+        }
+
+        let first_preview_line = info.preview.lines().next().unwrap_or("").trim();
+        if !first_preview_line.is_empty() {
+            line.push_str(&format!(": {}", first_preview_line));
+        }
+
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    output.push_str("\nUse get_skill(project, topic) to retrieve a specific skill.");
+    Ok(output)
+}
+
+/// Where a skill of the project at `project_dir` was found: `project`,
+/// `global` (`~/.jumble/skills`), `pack <name>`, `claude`, or `codex`, with
+/// `project claude` and `project codex` for a project's own `.claude` and
+/// `.codex` skills.
+pub fn skill_source(project_dir: &Path, home: Option<&Path>, skill: &Path) -> String {
+    for (dir, label) in [
+        (".jumble", "project"),
+        (".claude", "project claude"),
+        (".codex", "project codex"),
+    ] {
+        if skill.starts_with(project_dir.join(dir)) {
+            return label.to_string();
+        }
+    }
+    if let Some(home) = home {
+        if let Ok(rest) = skill.strip_prefix(skill_packs::cache_root(home)) {
+            let pack = rest.components().next();
+            return format!(
+                "pack {}",
+                pack.map_or_else(String::new, |c| c
+                    .as_os_str()
+                    .to_string_lossy()
+                    .into_owned())
+            );
+        }
+        for (dir, label) in [
+            (".jumble", "global"),
+            (".claude", "claude"),
+            (".codex", "codex"),
+        ] {
+            if skill.starts_with(home.join(dir)) {
+                return label.to_string();
+            }
+        }
+    }
+    "unknown".to_string()
+}
+
+pub fn get_skill(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let topic = args
+        .get("topic")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'topic' argument")?;
+
+    let (path, _, skills, _, _, _) = get_project(projects, project_name)?;
+
+    let (_, skill_info) = fuzzy::lookup(&skills.skills, topic).ok_or_else(|| {
+        let available: Vec<&str> = skills.skills.keys().map(|s| s.as_str()).collect();
+        if available.is_empty() {
+            format!("No skills found for '{}'", project_name)
+        } else {
+            let message = fuzzy::not_found("Skill", topic, available.iter().copied());
+            with_available(message, "Available", &available)
+        }
+    })?;
+
+    // Read the main skill file
+    let skill_content = std::fs::read_to_string(&skill_info.path)
+        .map_err(|e| format!("Failed to read skill: {}", e))?;
+
+    // Substitute {{project}}, {{root}}, and any caller-supplied variables.
+    let mut variables = HashMap::new();
+    variables.insert("project".to_string(), project_name.to_string());
+    variables.insert("root".to_string(), path.display().to_string());
+    if let Some(user_vars) = args.get("variables").and_then(|v| v.as_object()) {
+        for (name, value) in user_vars {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            variables.insert(name.clone(), value);
+        }
+    }
+    let skill_content = render_template(&skill_content, &variables);
+
+    // If this skill has a directory with companion files, include them
+    if let Some(skill_dir) = &skill_info.skill_dir {
+        let companions = discover_companion_files(skill_dir);
+        if !companions.is_empty() {
+            return Ok(format_skill_with_companions(&skill_content, &companions));
+        }
+    }
+
+    Ok(skill_content)
+}
+
+/// Scores how well a skill matches the lowercase query terms.
+///
+/// Name matches weigh most, followed by tags, the frontmatter description,
+/// and finally the cached preview. Returns 0 when nothing matches.
+fn score_skill(name: &str, info: &SkillInfo, terms: &[String]) -> u32 {
+    let name_lower = name.to_lowercase();
+    let (description, tags) = match &info.frontmatter {
+        Some(fm) => (
+            fm.description.as_deref().unwrap_or("").to_lowercase(),
+            fm.tags.iter().map(|t| t.to_lowercase()).collect(),
+        ),
+        None => (String::new(), Vec::new()),
+    };
+    let preview = info.preview.to_lowercase();
+
+    let mut score = 0;
+    for term in terms {
+        if name_lower == *term {
+            score += 100;
+        } else if name_lower.contains(term.as_str()) {
+            score += 60;
+        }
+        if tags.iter().any(|t: &String| t == term) {
+            score += 40;
+        }
+        if description.contains(term.as_str()) {
+            score += 30;
+        }
+        if preview.contains(term.as_str()) {
+            score += 10;
+        }
+    }
+    score
+}
+
+pub fn create_skill(
+    projects: &mut HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'name' argument")?;
+
+    let description = args
+        .get("description")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .ok_or("Missing 'description' argument")?;
+
+    let content = args
+        .get("content")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'content' argument")?;
+
+    let tags = string_list(args, "tags")?.unwrap_or_default();
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    // The name becomes the file name, so keep it to a safe character set.
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Err(format!(
+            "Invalid skill name '{}': use letters, digits, '-' or '_'",
+            name
+        ));
+    }
+
+    get_project(projects, project_name)?;
+    let (path, _, skills, _, _, _) = projects.get_mut(project_name).unwrap();
+
+    let file = path
+        .join(".jumble")
+        .join("skills")
+        .join(format!("{}.md", name));
+    if file.exists() {
+        return Err(format!("Skill file {} already exists", file.display()));
+    }
+
+    let frontmatter = SkillFrontmatter {
+        name: Some(name.to_string()),
+        description: Some(description.to_string()),
+        tags,
+    };
+    let yaml = serde_yaml::to_string(&frontmatter)
+        .map_err(|e| format!("Failed to serialize frontmatter: {}", e))?;
+    let markdown = format!("---\n{}---\n\n{}\n", yaml, content.trim_end());
+
+    if !confirm {
+        return Ok(format!(
+            "Would create {}:\n\n{}\nCall create_skill again with confirm: true to write it.",
+            file.display(),
+            markdown
+        ));
+    }
+
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&file, &markdown)
+        .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+
+    // Register the skill right away; it takes precedence over any global skill
+    // with the same name, just as it will after the next discovery.
+    let (frontmatter, preview) = extract_skill_frontmatter_and_preview(&markdown);
+    skills.skills.insert(
+        name.to_string(),
+        SkillInfo {
+            path: file.clone(),
+            skill_dir: None,
+            frontmatter,
+            preview,
+        },
+    );
+
+    Ok(format!("Created skill '{}' at {}", name, file.display()))
+}
+
+pub fn search_skills(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'query' argument")?;
+
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Err("Query must not be empty".to_string());
+    }
+
+    // Global skills are merged into every project, so dedupe by file path and
+    // remember which projects expose each skill.
+    let mut matches: HashMap<&std::path::Path, (u32, &str, &SkillInfo, Vec<&str>)> = HashMap::new();
+    for (project_name, (project_dir, _, skills, _, _, _)) in projects {
+        for (name, info) in &skills.skills {
+            let score = score_skill(name, info, &terms);
+            if score == 0 {
+                continue;
+            }
+            let entry = matches.entry(info.path.as_path()).or_insert((
+                score,
+                name.as_str(),
+                info,
+                Vec::new(),
+            ));
+            if info.path.starts_with(project_dir) {
+                entry.3.push(project_name.as_str());
+            }
+        }
+    }
+
+    if matches.is_empty() {
+        return Ok(format!("No skills matching '{}' found.", query));
+    }
+
+    let mut ranked: Vec<_> = matches.into_values().collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let mut output = format!("# Skills matching '{}'\n\n", query);
+    for (score, name, info, owners) in &ranked {
+        let source = if owners.is_empty() {
+            "global".to_string()
+        } else {
+            let mut owners = owners.clone();
+            owners.sort();
+            format!("project: {}", owners.join(", "))
+        };
+        output.push_str(&format!("- **{}** ({}, score {})", name, source, score));
+        if let Some(desc) = info
+            .frontmatter
+            .as_ref()
+            .and_then(|fm| fm.description.as_ref())
+        {
+            if !desc.is_empty() {
+                output.push_str(&format!(": {}", desc));
+            }
+        }
+        output.push('\n');
+    }
+    output.push_str("\nUse get_skill(project, topic) to retrieve a specific skill.");
+    Ok(output)
+}
+
+/// Companion file entry discovered in a skill directory
+#[derive(Debug)]
+struct CompanionFile {
+    relative_path: String,
+    is_dir: bool,
+}
+
+/// Discover companion files and directories in a skill folder.
+/// Looks for common subdirectories: scripts/, references/, docs/, assets/, examples/
+fn discover_companion_files(skill_dir: &std::path::Path) -> Vec<CompanionFile> {
+    let mut companions = Vec::new();
+
+    // Common companion directory names for Claude/Codex skills
+    let known_dirs = [
+        "scripts",
+        "references",
+        "docs",
+        "assets",
+        "examples",
+        "templates",
+    ];
+
+    for dir_name in &known_dirs {
+        let dir_path = skill_dir.join(dir_name);
+        if dir_path.is_dir() {
+            // Add the directory itself
+            companions.push(CompanionFile {
+                relative_path: dir_name.to_string(),
+                is_dir: true,
+            });
+
+            // List files in the directory (non-recursive for now)
+            if let Ok(entries) = std::fs::read_dir(&dir_path) {
+                for entry in entries.filter_map(|e| e.ok()) {
+                    if let Ok(file_type) = entry.file_type() {
+                        if file_type.is_file() {
+                            if let Some(file_name) = entry.file_name().to_str() {
+                                companions.push(CompanionFile {
+                                    relative_path: format!("{}/{}", dir_name, file_name),
+                                    is_dir: false,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    companions
+}
+
+/// Format skill content with companion files listed at the end
+fn format_skill_with_companions(skill_content: &str, companions: &[CompanionFile]) -> String {
+    let mut output = String::from(skill_content);
+
+    // Add companion files section
+    output.push_str("\n\n---\n\n");
+    output.push_str("## Companion Resources\n\n");
+    output.push_str("This skill includes additional resources:\n\n");
+
+    // Group by directory
+    let mut current_dir: Option<String> = None;
+    for companion in companions {
+        if companion.is_dir {
+            current_dir = Some(companion.relative_path.clone());
+            output.push_str(&format!("\n### {}\n", companion.relative_path));
+        } else {
+            // Extract directory and filename
+            if let Some(slash_pos) = companion.relative_path.rfind('/') {
+                let dir = &companion.relative_path[..slash_pos];
+                let file = &companion.relative_path[slash_pos + 1..];
+
+                if current_dir.as_deref() == Some(dir) {
+                    output.push_str(&format!("- `{}`\n", file));
+                } else {
+                    output.push_str(&format!("- `{}`\n", companion.relative_path));
+                }
+            } else {
+                output.push_str(&format!("- `{}`\n", companion.relative_path));
+            }
+        }
+    }
+
+    output
+}
+
+pub fn get_conventions(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let category = args.get("category").and_then(|v| v.as_str());
+
+    let (_, _, _, conventions, _, _) = get_project(projects, project_name)?;
+
+    let categories = conventions.categories();
+    if categories.iter().all(|(_, entries)| entries.is_empty()) {
+        return Ok(format!(
+            "No conventions found for '{}'. Create .jumble/conventions.toml to add project-specific conventions and gotchas.",
+            project_name
+        ));
+    }
+
+    let selected = match category {
+        Some(c) => {
+            let names: Vec<&str> = categories.iter().map(|(name, _)| *name).collect();
+            let found = categories
+                .iter()
+                .find(|(name, _)| *name == c)
+                .ok_or_else(|| {
+                    let message = fuzzy::not_found("Category", c, names.iter().copied());
+                    with_available(message, "Available", &names)
+                })?;
+            if found.1.is_empty() {
+                return Ok(format!("No {} defined.", c));
+            }
+            vec![*found]
+        }
+        None => categories,
+    };
+
+    let mut output = String::new();
+    for (name, entries) in selected.into_iter().filter(|(_, e)| !e.is_empty()) {
+        output.push_str(&format!(
+            "# {} for '{}'\n\n",
+            capitalize(name),
+            project_name
+        ));
+        push_convention_sections(&mut output, entries);
+    }
+
+    Ok(output)
+}
+
+/// Renders entries as `## name` sections, each with its level's badge: hard
+/// rules first, then preferences, unleveled entries, and background.
+fn push_convention_sections(output: &mut String, entries: &Conventions) {
+    let rank = |level: Option<ConventionLevel>| match level {
+        Some(ConventionLevel::Must) => 0,
+        Some(ConventionLevel::Should) => 1,
+        None => 2,
+        Some(ConventionLevel::Info) => 3,
+    };
+    let mut entries: Vec<_> = entries.iter().collect();
+    entries.sort_by_key(|(name, convention)| (rank(convention.level()), *name));
+    for (name, convention) in entries {
+        output.push_str(&format!("## {}\n{}\n\n", name, convention.with_badge()));
+    }
+}
+
+pub fn add_convention(
+    projects: &mut HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let category = args
+        .get("category")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or("Missing 'category' argument")?;
+
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or("Missing 'name' argument")?;
+
+    let description = args
+        .get("description")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'description' argument")?;
+
+    let level = match args.get("level").and_then(|v| v.as_str()) {
+        Some(level) => Some(ConventionLevel::parse(level).ok_or_else(|| {
+            format!(
+                "Unknown level '{}'. Use 'must', 'should', or 'info'.",
+                level
+            )
+        })?),
+        None => None,
+    };
+    let convention = match level {
+        Some(level) => Convention::Detailed(ConventionDetails {
+            description: description.to_string(),
+            level: Some(level),
+        }),
+        None => Convention::from(description),
+    };
+
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if category == "extends" {
+        return Err("'extends' is reserved and cannot be used as a category".to_string());
+    }
+
+    get_project(projects, project_name)?;
+    let (path, _, _, conventions, _, _) = projects.get_mut(project_name).unwrap();
+
+    let jumble_dir = path.join(".jumble");
+    let file = config::find_config_file(&jumble_dir, "conventions")
+        .unwrap_or_else(|| jumble_dir.join("conventions.toml"));
+    if file.extension().and_then(|e| e.to_str()) != Some("toml") {
+        return Err(format!(
+            "add_convention only edits TOML files; add the entry to {} by hand",
+            file.display()
+        ));
+    }
+
+    let existing = conventions
+        .categories()
+        .into_iter()
+        .find(|(c, _)| *c == category)
+        .is_some_and(|(_, entries)| entries.contains_key(name));
+    if existing {
+        return Err(format!("'{}' already exists in [{}]", name, category));
+    }
+
+    let snippet = config::convention_snippet(category, name, &convention);
+    if !confirm {
+        return Ok(format!(
+            "Would add to {}:\n\n```toml\n{}```\n\nCall add_convention again with confirm: true to write it.",
+            file.display(),
+            snippet
+        ));
+    }
+
+    config::append_convention(&file, category, name, &convention)
+        .map_err(|e| format!("{:#}", e))?;
+
+    let entries = match category {
+        "conventions" => &mut conventions.conventions,
+        "gotchas" => &mut conventions.gotchas,
+        custom => conventions.custom.entry(custom.to_string()).or_default(),
+    };
+    entries.insert(name.to_string(), convention);
+
+    Ok(format!(
+        "Added '{}' to [{}] in {}",
+        name,
+        category,
+        file.display()
+    ))
+}
+
+pub fn promote_memory(
+    projects: &mut HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let key = args
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'key' argument")?;
+
+    let to = args
+        .get("to")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'to' argument")?;
+
+    let name = args.get("name").and_then(|v| v.as_str()).unwrap_or(key);
+    let keep = args.get("keep").and_then(|v| v.as_bool()).unwrap_or(false);
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
+    let entry = memory_db.read(|db| {
+        db.get(key)
+            .cloned()
+            .ok_or_else(|| fuzzy::not_found("Memory key", key, db.keys().map(|k| k.as_str())))
+    })??;
+
+    // The promotion itself is an ordinary add_convention/add_concept call.
+    let (target, result) = match to {
+        "convention" | "gotcha" => {
+            let category = format!("{}s", to);
+            let args = json!({
+                "project": project_name,
+                "category": category,
+                "name": name,
+                "description": entry.value,
+                "level": args.get("level"),
+                "confirm": confirm,
+            });
+            (
+                format!("{}.{}", category, name),
+                add_convention(projects, &args)?,
+            )
+        }
+        "concept" => {
+            let mut concept_args = json!({
+                "project": project_name,
+                "name": name,
+                "summary": entry.value,
+                "confirm": confirm,
+            });
+            if let Some(files) = args.get("files") {
+                concept_args["files"] = files.clone();
+            }
+            (
+                format!("concepts.{}", name),
+                add_concept(projects, &concept_args)?,
+            )
+        }
+        other => {
+            return Err(format!(
+                "Invalid 'to' value '{}'. Use 'convention', 'gotcha', or 'concept'",
+                other
+            ))
+        }
+    };
+
+    let result = result.replace("Call add_convention again", "Call promote_memory again");
+    let result = result.replace("Call add_concept again", "Call promote_memory again");
+    if !confirm {
+        let afterwards = if keep {
+            "marked as promoted"
+        } else {
+            "deleted"
+        };
+        return Ok(format!(
+            "{}\n\nThe memory '{}' will then be {}.",
+            result, key, afterwards
+        ));
+    }
+
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
+    let source = args.get("source").and_then(|v| v.as_str());
+    memory_db.update_audited(MemoryAction::Promote, source, |db| {
+        if keep {
+            if let Some(entry) = db.get_mut(key) {
+                entry.promoted = Some(target.clone());
+            }
+        } else {
+            db.remove(key);
+        }
+    })?;
+
+    Ok(format!(
+        "{}\nMemory '{}' {}.",
+        result,
+        key,
+        if keep {
+            format!("marked as promoted to {}", target)
+        } else {
+            "deleted".to_string()
+        }
+    ))
+}
+
+/// Uppercases the first character, e.g. `gotchas` -> `Gotchas`.
+pub fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn get_docs(
+    root: &Path,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let topic = args.get("topic").and_then(|v| v.as_str());
+    let section = args.get("section").and_then(|v| v.as_str());
+
+    let (path, _, _, _, docs, _) = get_project(projects, project_name)?;
+    let base = path_base(root, path, args)?;
+
+    if docs.docs.is_empty() {
+        return Ok(format!(
+            "No documentation index found for '{}'. Create .jumble/docs.toml to index project documentation.",
+            project_name
+        ));
+    }
+
+    match topic {
+        Some(t) => {
+            // Return path to specific doc
+            let (t, doc) = fuzzy::lookup(&docs.docs, t).ok_or_else(|| {
+                let available: Vec<&str> = docs.docs.keys().map(|s| s.as_str()).collect();
+                let message = fuzzy::not_found("Doc", t, available.iter().copied());
+                with_available(message, "Available", &available)
+            })?;
+            let shown_path = display_path(&base, &doc.path);
+            let Some(section) = section else {
+                return Ok(format!(
+                    "## {}\n**Summary:** {}\n**Path:** {}",
+                    t, doc.summary, shown_path
+                ));
+            };
+
+            let full_path = project_file(path, &doc.path)?;
+            let content = std::fs::read_to_string(&full_path)
+                .map_err(|e| format!("Failed to read {}: {}", full_path.display(), e))?;
+            let text = markdown_section(&content, section).ok_or_else(|| {
+                let headings: Vec<&str> = markdown_headings(&content)
+                    .into_iter()
+                    .map(|(_, _, title)| title)
+                    .collect();
+                let message = fuzzy::not_found("Section", section, headings.iter().copied());
+                with_available(message, "Available", &headings)
+            })?;
+            Ok(format!("**Path:** {}\n\n{}", shown_path, text))
+        }
+        None if section.is_some() => Err("The 'section' argument requires 'topic'".to_string()),
+        None => {
+            // List all docs with summaries
+            let mut output = format!("# Documentation for '{}'\n\n", project_name);
+            for (name, doc) in &docs.docs {
+                output.push_str(&format!("- **{}**: {}\n", name, doc.summary));
+            }
+            output.push_str("\nUse get_docs(project, topic) to get the path to a specific doc.");
+            Ok(output)
+        }
+    }
+}
+
+/// Maximum number of snippets shown per doc by `search_docs`.
+const MAX_DOC_SNIPPETS: usize = 3;
+
+/// Lines of context shown on each side of a `search_docs` match.
+const SNIPPET_CONTEXT_LINES: usize = 1;
+
+/// A matching region of a doc, with the heading it falls under.
+struct DocSnippet {
+    heading: Option<String>,
+    line: usize,
+    text: String,
+}
+
+pub fn search_docs(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'query' argument")?;
+
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Err("Query must not be empty".to_string());
+    }
+
+    let mut project_names: Vec<&str> = match args.get("project").and_then(|v| v.as_str()) {
+        Some(name) => {
+            get_project(projects, name)?;
+            vec![name]
+        }
+        None => projects.keys().map(|k| k.as_str()).collect(),
+    };
+    project_names.sort();
+
+    let mut matches = Vec::new();
+    for project_name in project_names {
+        let (path, _, _, _, docs, _) = &projects[project_name];
+        for (name, doc) in &docs.docs {
+            let full_path = path.join(&doc.path);
+            // Unreadable docs, and docs outside the project, can still match
+            // on their name and summary.
+            let content = project_file(path, &doc.path)
+                .ok()
+                .and_then(|file| std::fs::read_to_string(file).ok())
+                .unwrap_or_default();
+            let (content_score, snippets) = search_doc_content(&content, &terms);
+
+            let name_lower = name.to_lowercase();
+            let summary_lower = doc.summary.to_lowercase();
+            let mut score = content_score;
+            for term in &terms {
+                if name_lower.contains(term.as_str()) {
+                    score += 60;
+                }
+                if summary_lower.contains(term.as_str()) {
+                    score += 30;
+                }
+            }
+            if score > 0 {
+                matches.push((score, project_name, name.as_str(), doc, full_path, snippets));
+            }
+        }
+    }
+
+    if matches.is_empty() {
+        return Ok(format!("No docs matching '{}' found.", query));
+    }
+
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)).then(a.2.cmp(b.2)));
+
+    let mut output = format!("# Docs matching '{}'\n\n", query);
+    for (score, project_name, name, doc, full_path, snippets) in &matches {
+        output.push_str(&format!(
+            "## {} (project: {}, score {})\n{}\n**Path:** {}\n",
+            name,
+            project_name,
+            score,
+            doc.summary,
+            full_path.display()
+        ));
+        for snippet in snippets {
+            match &snippet.heading {
+                Some(heading) => output.push_str(&format!(
+                    "\nLine {}, under \"{}\":\n",
+                    snippet.line, heading
+                )),
+                None => output.push_str(&format!("\nLine {}:\n", snippet.line)),
+            }
+            for line in snippet.text.lines() {
+                output.push_str(&format!("> {}\n", line));
+            }
+        }
+        output.push('\n');
+    }
+    output.push_str("Use get_docs(project, topic) to get the path to a specific doc.");
+    Ok(output)
+}
+
+/// Scores doc content against the lowercase query terms and collects up to
+/// [`MAX_DOC_SNIPPETS`] non-overlapping snippets around matching lines.
+///
+/// Each term earns 10 points per matching line, capped at five lines so long
+/// docs do not drown out focused ones.
+fn search_doc_content(content: &str, terms: &[String]) -> (u32, Vec<DocSnippet>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut heading: Option<&str> = None;
+    let mut hits = vec![0u32; terms.len()];
+    let mut snippets = Vec::new();
+    // Lines before this index have already been shown in a snippet.
+    let mut shown_until = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(title) = markdown_heading(line) {
+            heading = Some(title);
+        }
+        let lower = line.to_lowercase();
+        let mut matched = false;
+        for (term, count) in terms.iter().zip(hits.iter_mut()) {
+            if lower.contains(term.as_str()) {
+                *count += 1;
+                matched = true;
+            }
+        }
+        if matched && i >= shown_until && snippets.len() < MAX_DOC_SNIPPETS {
+            let start = i.saturating_sub(SNIPPET_CONTEXT_LINES).max(shown_until);
+            let end = (i + SNIPPET_CONTEXT_LINES + 1).min(lines.len());
+            snippets.push(DocSnippet {
+                heading: heading.map(str::to_string),
+                line: i + 1,
+                text: lines[start..end].join("\n"),
+            });
+            shown_until = end;
+        }
+    }
+
+    let score = hits.iter().map(|count| 10 * count.min(&5)).sum();
+    (score, snippets)
+}
+
+pub fn record_decision(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let required = |key: &str| {
+        args.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or(format!("Missing '{}' argument", key))
+    };
+    let title = required("title")?;
+    let context = required("context")?;
+    let decision = required("decision")?;
+    let consequences = args.get("consequences").and_then(|v| v.as_str());
+    let status = args
+        .get("status")
+        .and_then(|v| v.as_str())
+        .unwrap_or("accepted");
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let (path, _, _, _, _, _) = get_project(projects, project_name)?;
+
+    let date = decisions::today();
+    let record = decisions::NewDecision {
+        title,
+        status,
+        date: &date,
+        context,
+        decision,
+        consequences,
+    };
+
+    if !confirm {
+        return Ok(format!(
+            "Would create {}:\n\n{}\nCall record_decision again with confirm: true to write it.",
+            decisions::next_decision_path(path, title).display(),
+            decisions::render_decision(&record)?
+        ));
+    }
+
+    let file = decisions::write_decision(path, &record)?;
+    Ok(format!(
+        "Recorded decision '{}' at {}",
+        title,
+        file.display()
+    ))
+}
+
+pub fn list_decisions(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let status = args.get("status").and_then(|v| v.as_str());
+    let number = args.get("number").and_then(|v| v.as_u64());
+
+    let (path, _, _, _, _, _) = get_project(projects, project_name)?;
+    let all = decisions::load_decisions(path);
+
+    if let Some(number) = number {
+        let decision = all
+            .iter()
+            .find(|d| u64::from(d.number) == number)
+            .ok_or_else(|| format!("Decision {} not found for '{}'", number, project_name))?;
+        return Ok(format!(
+            "# ADR-{:04}: {}\n**Status:** {}\n**Date:** {}\n**Path:** {}\n\n{}",
+            decision.number,
+            decision.meta.title,
+            decision.meta.status,
+            decision.meta.date,
+            decision.path.display(),
+            decision.body
+        ));
+    }
+
+    let selected: Vec<_> = all
+        .iter()
+        .filter(|d| status.is_none_or(|s| d.meta.status.eq_ignore_ascii_case(s)))
+        .collect();
+    if selected.is_empty() {
+        return Ok(match status {
+            Some(s) => format!("No {} decisions recorded for '{}'.", s, project_name),
+            None => format!(
+                "No decisions recorded for '{}'. Use record_decision to add one.",
+                project_name
+            ),
+        });
+    }
+
+    let mut output = format!("# Decisions for '{}'\n\n", project_name);
+    for decision in selected {
+        output.push_str(&format!(
+            "- **ADR-{:04}** [{}] {}: {}\n",
+            decision.number, decision.meta.status, decision.meta.date, decision.meta.title
+        ));
+    }
+    output.push_str("\nUse list_decisions(project, number) to read a decision in full.");
+    Ok(output)
+}
+
+pub fn add_task(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let title = args
+        .get("title")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or("Missing 'title' argument")?;
+
+    let priority = match args.get("priority").and_then(|v| v.as_str()) {
+        Some(name) => Priority::parse(name)?,
+        None => Priority::Medium,
+    };
+    let notes = args.get("notes").and_then(|v| v.as_str());
+    let source = args.get("source").and_then(|v| v.as_str());
+
+    let (path, _, _, _, _, _) = get_project(projects, project_name)?;
+    let db = tasks::open_or_create_task_db(path)?;
+
+    let id = db
+        .write(|list| {
+            let id = list.next_id.max(1);
+            list.next_id = id + 1;
+            list.tasks.push(Task {
+                id,
+                title: title.to_string(),
+                notes: notes.map(str::to_string),
+                status: TaskStatus::Open,
+                priority,
+                source: source.map(str::to_string),
+                created: memory::current_timestamp(),
+                completed: None,
+            });
+            id
+        })
+        .map_err(|e| format!("Failed to write to task database: {}", e))?;
+
+    db.save()
+        .map_err(|e| format!("Failed to save task database: {}", e))?;
+
+    Ok(format!(
+        "Task #{} added for project '{}' ({} priority)",
+        id, project_name, priority
+    ))
+}
+
+pub fn list_tasks(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let status = match args
+        .get("status")
+        .and_then(|v| v.as_str())
+        .unwrap_or("open")
+    {
+        "open" => Some(TaskStatus::Open),
+        "done" => Some(TaskStatus::Done),
+        "all" => None,
+        other => {
+            return Err(format!(
+                "Invalid status '{}'. Use 'open', 'done', or 'all'",
+                other
+            ))
+        }
+    };
+    let priority = match args.get("priority").and_then(|v| v.as_str()) {
+        Some(name) => Some(Priority::parse(name)?),
+        None => None,
+    };
+
+    let (path, _, _, _, _, _) = get_project(projects, project_name)?;
+    let list = tasks::load_tasks(path)?;
+
+    let mut selected: Vec<&Task> = list
+        .tasks
+        .iter()
+        .filter(|t| status.is_none_or(|s| t.status == s))
+        .filter(|t| priority.is_none_or(|p| t.priority == p))
+        .collect();
+    if selected.is_empty() {
+        return Ok(format!("No matching tasks for '{}'.", project_name));
+    }
+    // Open work first, most urgent first, then oldest first.
+    selected.sort_by(|a, b| {
+        (a.status == TaskStatus::Done)
+            .cmp(&(b.status == TaskStatus::Done))
+            .then(b.priority.cmp(&a.priority))
+            .then(a.id.cmp(&b.id))
+    });
+
+    let mut output = format!("# Tasks for '{}'\n\n", project_name);
+    for task in selected {
+        let check = if task.status == TaskStatus::Done {
+            "x"
+        } else {
+            " "
+        };
+        output.push_str(&format!(
+            "- [{}] #{} ({}) {}",
+            check, task.id, task.priority, task.title
+        ));
+        if let Some(source) = &task.source {
+            output.push_str(&format!(" — from {}", source));
+        }
+        output.push('\n');
+        if let Some(notes) = &task.notes {
+            output.push_str(&format!("  {}\n", notes));
+        }
+    }
+    Ok(output)
+}
+
+pub fn complete_task(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let id = args
+        .get("id")
+        .and_then(|v| v.as_u64())
+        .ok_or("Missing 'id' argument")?;
+
+    let (path, _, _, _, _, _) = get_project(projects, project_name)?;
+    if tasks::load_tasks(path)?.tasks.is_empty() {
+        return Err(format!("Task #{} not found for '{}'", id, project_name));
+    }
+    let db = tasks::open_or_create_task_db(path)?;
+
+    let title = db
+        .write(|list| {
+            let task = list
+                .tasks
+                .iter_mut()
+                .find(|t| t.id == id)
+                .ok_or_else(|| format!("Task #{} not found for '{}'", id, project_name))?;
+            if task.status == TaskStatus::Done {
+                return Err(format!("Task #{} is already done", id));
             }
-        ]
-    })
+            task.status = TaskStatus::Done;
+            task.completed = Some(memory::current_timestamp());
+            Ok(task.title.clone())
+        })
+        .map_err(|e| format!("Failed to write to task database: {}", e))??;
+
+    db.save()
+        .map_err(|e| format!("Failed to save task database: {}", e))?;
+
+    Ok(format!("Task #{} completed: {}", id, title))
 }
 
-pub fn get_jumble_authoring_prompt() -> Result<String, String> {
-    let prompt = r#"# Jumble authoring prompt
+pub fn get_workspace_overview(
+    root: &std::path::Path,
+    workspace: &Option<WorkspaceConfig>,
+    projects: &HashMap<String, ProjectData>,
+) -> Result<String, String> {
+    let mut output = String::new();
 
-Use this prompt with an AI assistant to create Jumble context files for a project or workspace.
+    // Workspace info
+    if let Some(ws) = workspace {
+        if let Some(name) = &ws.workspace.name {
+            output.push_str(&format!("# {}\n\n", name));
+        } else {
+            output.push_str("# Workspace Overview\n\n");
+        }
+        if let Some(desc) = &ws.workspace.description {
+            output.push_str(&format!("{}\n\n", desc));
+        }
+    } else {
+        output.push_str("# Workspace Overview\n\n");
+    }
 
-## Full prompt
+    output.push_str(&format!("**Root:** {}\n\n", root.display()));
 
-```
-Create jumble context for this project.
+    // Projects list
+    if projects.is_empty() {
+        output.push_str("No projects found.\n");
+        return Ok(output);
+    }
 
-Read the AUTHORING.md guide at https://github.com/velvet-tiger/jumble/blob/main/AUTHORING.md, then examine this project's structure to create:
+    output.push_str("## Projects\n\n");
 
-1. `.jumble/project.toml` (required)
-   - Extract name, description, language from manifest files
-   - Identify build/test/lint commands
-   - Map 3–5 architectural concepts to their files
-   - Note upstream/downstream project relationships
+    // Collect and sort projects for consistent output
+    let mut project_names: Vec<&String> = projects
+        .iter()
+        .filter(|(_, (_, config, ..))| !config.project.archived)
+        .map(|(name, _)| name)
+        .collect();
+    project_names.sort();
 
-2. `.jumble/conventions.toml`
-   - Capture coding patterns to follow (look at existing code)
-   - Document gotchas and non-obvious behaviors
-   - Check for constitution.md, CONTRIBUTING.md, or similar guides
+    for name in &project_names {
+        let (_, config, _, _, _, _) = projects.get(*name).unwrap();
+        let lang = config.project.language.as_deref().unwrap_or("unknown");
+        output.push_str(&format!(
+            "- **{}** ({}): {}\n",
+            name, lang, config.project.description
+        ));
+    }
 
-3. `.jumble/docs.toml`
-   - Index the docs/ directory if it exists
-   - Write one-line summaries that help find the right doc
+    // Dependency graph
+    output.push_str("\n## Dependencies\n\n");
+    let mut has_deps = false;
 
-Focus on what helps an AI understand this codebase quickly. Don't over-document:
-- 3–5 concepts
-- 5–7 conventions/gotchas
-- Index only human-written docs, not generated API docs
-```
+    for name in &project_names {
+        let (_, config, _, _, _, _) = projects.get(*name).unwrap();
+        let upstream = &config.related_projects.upstream;
+        let downstream = &config.related_projects.downstream;
 
-## Minimal prompt
+        if !upstream.is_empty() || !downstream.is_empty() {
+            has_deps = true;
+            output.push_str(&format!("**{}**:\n", name));
+            if !upstream.is_empty() {
+                output.push_str(&format!("  ← depends on: {}\n", upstream.join(", ")));
+            }
+            if !downstream.is_empty() {
+                output.push_str(&format!("  → used by: {}\n", downstream.join(", ")));
+            }
+        }
+    }
 
-```
-Create jumble context for this project following the guide at https://github.com/velvet-tiger/jumble/blob/main/AUTHORING.md
-```
+    if !has_deps {
+        output.push_str("No cross-project dependencies defined.\n");
+    }
 
-## Workspace-level usage
+    let dangling: Vec<DanglingReference> = dangling_related_projects(projects)
+        .into_iter()
+        .filter(|reference| !projects[&reference.project].1.project.archived)
+        .collect();
+    if !dangling.is_empty() {
+        output.push_str("\n## Unknown Related Projects\n\n");
+        for reference in &dangling {
+            output.push_str(&format!("- {}\n", reference));
+        }
+    }
+    output.push_str(&archived_note(projects));
 
-For monorepos or multi-project workspaces, you can ask the AI to:
+    // Note about workspace conventions
+    if workspace.is_some() {
+        output.push_str("\n*Use get_workspace_conventions() for workspace-wide coding standards.*");
+    }
 
-- Create `.jumble/workspace.toml` at the workspace root with:
-  - Workspace name and description
-  - Cross-project conventions (coding standards, tooling)
-  - Common gotchas that span multiple projects
-- Then, for each important project, create `.jumble/project.toml` with:
-  - Project metadata and commands
-  - Key concepts mapped to files
-  - Upstream/downstream relationships to other workspace projects
+    Ok(output)
+}
 
-Start with the most important projects. Use `related_projects` to show how they connect.
-"#;
+/// Default token budget for `get_session_briefing`.
+const DEFAULT_BRIEFING_TOKENS: usize = 2000;
 
-    Ok(prompt.to_string())
+/// Rough characters-per-token ratio used to estimate briefing size.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Entries shown per convention category in a briefing.
+const BRIEFING_CONVENTIONS_PER_CATEGORY: usize = 5;
+
+/// Most recent memories shown in a briefing.
+const BRIEFING_MEMORIES: usize = 5;
+
+/// Longest memory preview shown in a briefing, in characters.
+const BRIEFING_MEMORY_PREVIEW: usize = 100;
+
+/// Bundles the start-of-session context an agent would otherwise fetch with
+/// several calls: workspace overview, project info, conventions and gotchas,
+/// and recent memories. Sections are added in that order until the token
+/// budget runs out; a section that does not fit is cut at a line boundary.
+pub fn get_session_briefing(
+    workspace: &Option<WorkspaceConfig>,
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let max_tokens = args
+        .get("max_tokens")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_BRIEFING_TOKENS);
+
+    let (_, _, _, conventions, _, memory_db) = get_project(projects, project_name)?;
+
+    let mut sections = vec![
+        briefing_workspace(workspace, projects),
+        get_project_info(projects, &json!({ "project": project_name }))?,
+    ];
+    if let Some(section) = briefing_conventions(conventions) {
+        sections.push(section);
+    }
+    let memories = memory_db.read(briefing_memories)?;
+    if let Some(section) = memories {
+        sections.push(section);
+    }
+
+    let mut budget = max_tokens * CHARS_PER_TOKEN;
+    let mut output = String::new();
+    let mut truncated = false;
+    for section in sections {
+        let section = format!("{}\n", section.trim_end());
+        if section.len() <= budget {
+            budget -= section.len();
+            output.push_str(&section);
+            output.push('\n');
+            continue;
+        }
+        for line in section.lines() {
+            if line.len() + 1 > budget {
+                break;
+            }
+            budget -= line.len() + 1;
+            output.push_str(line);
+            output.push('\n');
+        }
+        truncated = true;
+        break;
+    }
+
+    if truncated {
+        output.push_str(&format!(
+            "\n*Briefing truncated to ~{} tokens. Use get_project_info, get_conventions, or list_memories for the rest.*\n",
+            max_tokens
+        ));
+    }
+    Ok(output)
+}
+
+/// Workspace name and a one-line summary per project.
+fn briefing_workspace(
+    workspace: &Option<WorkspaceConfig>,
+    projects: &HashMap<String, ProjectData>,
+) -> String {
+    let info = workspace.as_ref().map(|ws| &ws.workspace);
+    let name = info
+        .and_then(|w| w.name.as_deref())
+        .unwrap_or("Workspace Overview");
+    let mut output = format!("# {}\n\n", name);
+    if let Some(desc) = info.and_then(|w| w.description.as_deref()) {
+        output.push_str(&format!("{}\n\n", desc));
+    }
+
+    let mut project_names: Vec<&String> = projects.keys().collect();
+    project_names.sort();
+    output.push_str("## Projects\n\n");
+    for name in project_names {
+        let (_, config, _, _, _, _) = &projects[name];
+        let lang = config.project.language.as_deref().unwrap_or("unknown");
+        output.push_str(&format!(
+            "- **{}** ({}): {}\n",
+            name, lang, config.project.description
+        ));
+    }
+    output
+}
+
+/// The first few entries of each convention category, gotchas first.
+fn briefing_conventions(conventions: &ProjectConventions) -> Option<String> {
+    let mut categories = conventions.categories();
+    categories.sort_by_key(|(name, _)| *name != "gotchas");
+
+    let mut output = String::new();
+    for (name, entries) in categories.into_iter().filter(|(_, e)| !e.is_empty()) {
+        let mut keys: Vec<&String> = entries.keys().collect();
+        keys.sort();
+        output.push_str(&format!("## {}\n\n", capitalize(name)));
+        for key in keys.iter().take(BRIEFING_CONVENTIONS_PER_CATEGORY) {
+            output.push_str(&format!("- **{}**: {}\n", key, entries[*key]));
+        }
+        if keys.len() > BRIEFING_CONVENTIONS_PER_CATEGORY {
+            output.push_str(&format!(
+                "- ...and {} more\n",
+                keys.len() - BRIEFING_CONVENTIONS_PER_CATEGORY
+            ));
+        }
+        output.push('\n');
+    }
+    (!output.is_empty()).then_some(output)
+}
+
+/// The most recently updated memories, with a short preview of each.
+fn briefing_memories(db: &memory::MemoryDb) -> Option<String> {
+    if db.is_empty() {
+        return None;
+    }
+    let mut entries: Vec<_> = db.iter().collect();
+    entries.sort_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp).then(a.0.cmp(b.0)));
+
+    let mut output = String::from("## Recent Memories\n\n");
+    for (key, entry) in entries.into_iter().take(BRIEFING_MEMORIES) {
+        let preview: String = entry.value.chars().take(BRIEFING_MEMORY_PREVIEW).collect();
+        let ellipsis = if preview.len() < entry.value.len() {
+            "..."
+        } else {
+            ""
+        };
+        output.push_str(&format!("- **{}**: {}{}\n", key, preview, ellipsis));
+    }
+    Some(output)
+}
+
+/// A `related_projects` entry naming a project that was not discovered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DanglingReference {
+    pub project: String,
+    /// `upstream` or `downstream`.
+    pub field: &'static str,
+    pub target: String,
+}
+
+impl std::fmt::Display for DanglingReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "**{}**: related_projects.{} references unknown project '{}'",
+            self.project, self.field, self.target
+        )
+    }
+}
+
+/// Finds `related_projects` entries that do not match any discovered project,
+/// sorted by project name.
+pub fn dangling_related_projects(
+    projects: &HashMap<String, ProjectData>,
+) -> Vec<DanglingReference> {
+    let mut dangling = Vec::new();
+    for (name, (_, config, _, _, _, _)) in projects {
+        let related = &config.related_projects;
+        for (field, targets) in [
+            ("upstream", &related.upstream),
+            ("downstream", &related.downstream),
+        ] {
+            for target in targets {
+                if !projects.contains_key(target) {
+                    dangling.push(DanglingReference {
+                        project: name.clone(),
+                        field,
+                        target: target.clone(),
+                    });
+                }
+            }
+        }
+    }
+    dangling
+        .sort_by(|a, b| (&a.project, a.field, &a.target).cmp(&(&b.project, b.field, &b.target)));
+    dangling
+}
+
+pub fn get_dependency_graph(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let format = args
+        .get("format")
+        .and_then(|v| v.as_str())
+        .unwrap_or("mermaid");
+
+    let edges = dependency_edges(projects);
+    let mut nodes: BTreeSet<&str> = projects.keys().map(|name| name.as_str()).collect();
+    for (from, to) in &edges {
+        nodes.insert(from);
+        nodes.insert(to);
+    }
+    let nodes: Vec<&str> = nodes.into_iter().collect();
+
+    match format {
+        "mermaid" => Ok(format_mermaid_graph(&nodes, &edges)),
+        "dot" => Ok(format_dot_graph(&nodes, &edges)),
+        other => Err(format!(
+            "Unknown format '{}'. Use 'mermaid' or 'dot'.",
+            other
+        )),
+    }
+}
+
+/// Collects `(dependent, dependency)` edges across all projects.
+///
+/// `related_projects.upstream` and `dependencies.internal` are things a project
+/// depends on; `related_projects.downstream` lists projects depending on it.
+fn dependency_edges(projects: &HashMap<String, ProjectData>) -> BTreeSet<(String, String)> {
+    let mut edges = BTreeSet::new();
+    for (name, (_, config, _, _, _, _)) in projects {
+        for dependency in config
+            .related_projects
+            .upstream
+            .iter()
+            .chain(&config.dependencies.internal)
+        {
+            edges.insert((name.clone(), dependency.clone()));
+        }
+        for dependent in &config.related_projects.downstream {
+            edges.insert((dependent.clone(), name.clone()));
+        }
+    }
+    edges
 }
 
-// ============================================================================
-// Tool Implementations
-// ============================================================================
-
-pub fn list_projects(projects: &HashMap<String, ProjectData>) -> Result<String, String> {
-    if projects.is_empty() {
+/// `get_workspace_commands`: the `[commands]` table of `workspace.toml`, with
+/// `${VAR}` placeholders resolved from the environment.
+pub fn get_workspace_commands(
+    workspace: &Option<WorkspaceConfig>,
+    args: &Value,
+) -> Result<String, String> {
+    let ws = workspace.as_ref().ok_or(
+        "No workspace.toml found. Create .jumble/workspace.toml at the workspace root to define workspace-level commands."
+    )?;
+    if ws.commands.is_empty() {
         return Ok(
-            "No projects found. Make sure .jumble/project.toml files exist in your workspace."
+            "No workspace commands defined. Add a [commands] table to .jumble/workspace.toml."
                 .to_string(),
         );
     }
+    let commands: HashMap<String, CommandDetails> = ws
+        .commands
+        .iter()
+        .map(|(name, spec)| {
+            (
+                name.clone(),
+                resolve_command(spec, |var| std::env::var(var).ok()),
+            )
+        })
+        .collect();
 
-    let mut output = String::new();
-    for (name, (path, config, _skills, _conventions, _docs, _memory)) in projects {
-        let lang = config.project.language.as_deref().unwrap_or("unknown");
-        output.push_str(&format!(
-            "- **{}** ({}): {}\n  Path: {}\n",
-            name,
-            lang,
-            config.project.description,
-            path.display()
-        ));
+    match args.get("command_type").and_then(|v| v.as_str()) {
+        Some(cmd_type) if commands.contains_key(cmd_type) => {
+            format_command_plan(&commands, cmd_type)
+        }
+        Some(cmd_type) => {
+            let names = commands.keys().map(|k| k.as_str());
+            Err(fuzzy::not_found("Workspace command", cmd_type, names))
+        }
+        None => Ok(format_commands(&commands)),
     }
-    Ok(output)
 }
 
-pub fn get_project_info(
-    projects: &HashMap<String, ProjectData>,
+pub fn get_workspace_conventions(
+    workspace: &Option<WorkspaceConfig>,
     args: &Value,
 ) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+    let ws = workspace.as_ref().ok_or(
+        "No workspace.toml found. Create .jumble/workspace.toml at the workspace root to define workspace-level conventions."
+    )?;
 
-    let (path, config, _skills, _conventions, _docs, _memory) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    let category = args.get("category").and_then(|v| v.as_str());
 
-    let field = args.get("field").and_then(|v| v.as_str());
+    let has_conventions = !ws.conventions.is_empty();
+    let has_gotchas = !ws.gotchas.is_empty();
 
-    match field {
-        Some("commands") => Ok(format_commands(&config.commands)),
-        Some("entry_points") => Ok(format_entry_points(&config.entry_points)),
-        Some("dependencies") => Ok(format_dependencies(&config.dependencies)),
-        Some("api") => Ok(format_api(&config.api)),
-        Some("related_projects") => Ok(format_related_projects(&config.related_projects)),
-        Some(f) => Err(format!("Unknown field: {}", f)),
-        None => {
-            let mut output = format!("# {}\n\n", config.project.name);
-            output.push_str(&format!(
-                "**Description:** {}\n",
-                config.project.description
-            ));
-            if let Some(lang) = &config.project.language {
-                output.push_str(&format!("**Language:** {}\n", lang));
-            }
-            if let Some(version) = &config.project.version {
-                output.push_str(&format!("**Version:** {}\n", version));
+    if !has_conventions && !has_gotchas {
+        return Ok("Workspace config exists but no conventions or gotchas defined.".to_string());
+    }
+
+    let mut output = String::new();
+    let ws_name = ws.workspace.name.as_deref().unwrap_or("Workspace");
+
+    match category {
+        Some("conventions") => {
+            if !has_conventions {
+                return Ok("No workspace conventions defined.".to_string());
             }
-            if let Some(repo) = &config.project.repository {
-                output.push_str(&format!("**Repository:** {}\n", repo));
+            output.push_str(&format!("# {} Conventions\n\n", ws_name));
+            push_convention_sections(&mut output, &ws.conventions);
+        }
+        Some("gotchas") => {
+            if !has_gotchas {
+                return Ok("No workspace gotchas defined.".to_string());
             }
-            output.push_str(&format!("**Path:** {}\n", path.display()));
-
-            if !config.entry_points.is_empty() {
-                output.push_str("\n## Entry Points\n");
-                output.push_str(&format_entry_points(&config.entry_points));
+            output.push_str(&format!("# {} Gotchas\n\n", ws_name));
+            push_convention_sections(&mut output, &ws.gotchas);
+        }
+        None => {
+            if has_conventions {
+                output.push_str(&format!("# {} Conventions\n\n", ws_name));
+                push_convention_sections(&mut output, &ws.conventions);
             }
-
-            if !config.concepts.is_empty() {
-                output.push_str("\n## Concepts\n");
-                for (name, concept) in &config.concepts {
-                    output.push_str(&format!("- **{}**: {}\n", name, concept.summary));
-                }
+            if has_gotchas {
+                output.push_str(&format!("# {} Gotchas\n\n", ws_name));
+                push_convention_sections(&mut output, &ws.gotchas);
             }
-
-            Ok(output)
+        }
+        Some(c) => {
+            return Err(format!(
+                "Unknown category '{}'. Use 'conventions' or 'gotchas'.",
+                c
+            ))
         }
     }
+
+    Ok(output)
 }
 
-pub fn get_commands(
+// ============================================================================
+// Memory Tool Implementations
+// ============================================================================
+
+pub fn store_memory(
     projects: &HashMap<String, ProjectData>,
+    quotas: Option<&MemoryConfig>,
     args: &Value,
 ) -> Result<String, String> {
     let project_name = args
@@ -506,122 +4895,201 @@ pub fn get_commands(
         .and_then(|v| v.as_str())
         .ok_or("Missing 'project' argument")?;
 
-    let (_, config, _, _, _, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    let key = args
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'key' argument")?;
+
+    let value = args
+        .get("value")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'value' argument")?;
 
-    let command_type = args.get("command_type").and_then(|v| v.as_str());
+    let source = args.get("source").and_then(|v| v.as_str());
 
-    match command_type {
-        Some(cmd_type) => config
-            .commands
-            .get(cmd_type)
-            .map(|cmd| format!("{}: {}", cmd_type, cmd))
-            .ok_or_else(|| {
-                format!(
-                    "Command '{}' not found for project '{}'",
-                    cmd_type, project_name
-                )
-            }),
-        None => Ok(format_commands(&config.commands)),
-    }
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
+
+    // Create memory entry
+    let entry = memory::MemoryEntry {
+        value: value.to_string(),
+        timestamp: memory::current_timestamp(),
+        source: source.map(|s| s.to_string()),
+        promoted: None,
+    };
+
+    // Store in database
+    let store = format!("project '{}'", project_name);
+    memory_db.update_audited(MemoryAction::Store, source, |db| {
+        check_memory_quota(db, quotas, key, source, &store).map(|()| {
+            db.insert(key.to_string(), entry);
+        })
+    })??;
+
+    Ok(format!(
+        "Memory stored: key='{}' for project '{}'",
+        key, project_name
+    ))
 }
 
-pub fn get_architecture(
-    projects: &HashMap<String, ProjectData>,
-    args: &Value,
-) -> Result<String, String> {
+pub fn get_memory(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
     let project_name = args
         .get("project")
         .and_then(|v| v.as_str())
         .ok_or("Missing 'project' argument")?;
 
-    let concept_name = args
-        .get("concept")
+    let key = args
+        .get("key")
         .and_then(|v| v.as_str())
-        .ok_or("Missing 'concept' argument")?;
+        .ok_or("Missing 'key' argument")?;
 
-    let (path, config, _, _, _, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
 
-    // Try exact match first
-    if let Some(concept) = config.concepts.get(concept_name) {
-        return Ok(format_concept(path, concept_name, concept));
-    }
+    // Read from database
+    let result = memory_db.read(|db| {
+        db.get(key)
+            .map(|entry| format_memory(key, entry))
+            .ok_or_else(|| fuzzy::not_found("Memory key", key, db.keys().map(|k| k.as_str())))
+    })?;
 
-    // Try case-insensitive match
-    let concept_lower = concept_name.to_lowercase();
-    for (name, concept) in &config.concepts {
-        if name.to_lowercase() == concept_lower {
-            return Ok(format_concept(path, name, concept));
-        }
-    }
+    result
+}
 
-    // Try partial match
-    for (name, concept) in &config.concepts {
-        if name.to_lowercase().contains(&concept_lower)
-            || concept.summary.to_lowercase().contains(&concept_lower)
-        {
-            return Ok(format_concept(path, name, concept));
-        }
+fn format_memory(key: &str, entry: &memory::MemoryEntry) -> String {
+    let mut output = format!("# Memory: {}\n\n", key);
+    output.push_str(&format!("**Value:** {}\n", entry.value));
+    output.push_str(&format!("**Timestamp:** {}\n", entry.timestamp));
+    if let Some(src) = &entry.source {
+        output.push_str(&format!("**Source:** {}\n", src));
     }
-
-    // List available concepts
-    let available: Vec<&str> = config.concepts.keys().map(|s| s.as_str()).collect();
-    Err(format!(
-        "Concept '{}' not found. Available concepts: {}",
-        concept_name,
-        available.join(", ")
-    ))
+    if let Some(target) = &entry.promoted {
+        output.push_str(&format!("**Promoted to:** {}\n", target));
+    }
+    output
 }
 
-pub fn get_related_files(
-    projects: &HashMap<String, ProjectData>,
+pub fn store_global_memory(
+    global_memory: Option<&MemoryDatabase>,
+    quotas: Option<&MemoryConfig>,
     args: &Value,
 ) -> Result<String, String> {
-    let project_name = args
-        .get("project")
+    let memory_db = global_memory.ok_or(GLOBAL_MEMORY_UNAVAILABLE)?;
+
+    let key = args
+        .get("key")
         .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+        .ok_or("Missing 'key' argument")?;
 
-    let query = args
-        .get("query")
+    let value = args
+        .get("value")
         .and_then(|v| v.as_str())
-        .ok_or("Missing 'query' argument")?;
+        .ok_or("Missing 'value' argument")?;
 
-    let (path, config, _, _, _, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    let entry = memory::MemoryEntry {
+        value: value.to_string(),
+        timestamp: memory::current_timestamp(),
+        source: args
+            .get("source")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        promoted: None,
+    };
+    let source = entry.source.clone();
+    memory_db.update_audited(MemoryAction::Store, source.as_deref(), |db| {
+        check_memory_quota(db, quotas, key, source.as_deref(), "global memory").map(|()| {
+            db.insert(key.to_string(), entry);
+        })
+    })??;
 
-    let query_lower = query.to_lowercase();
-    let mut matched_files: Vec<(String, &str, &Concept)> = Vec::new();
+    Ok(format!("Global memory stored: key='{}'", key))
+}
 
-    for (name, concept) in &config.concepts {
-        if name.to_lowercase().contains(&query_lower)
-            || concept.summary.to_lowercase().contains(&query_lower)
-        {
-            matched_files.push((name.clone(), name.as_str(), concept));
-        }
+/// Most of a source's keys listed in a quota error.
+const QUOTA_KEYS_SHOWN: usize = 20;
+
+/// Source that memories stored without one count against for quotas.
+const UNKNOWN_SOURCE: &str = "unknown";
+
+/// Refuses a store that would take `source` past its `[memory]` quota in
+/// `store`. Overwriting a key the source already owns never counts against
+/// it; stores without a source share the [`UNKNOWN_SOURCE`] quota.
+fn check_memory_quota(
+    db: &memory::MemoryDb,
+    quotas: Option<&MemoryConfig>,
+    key: &str,
+    source: Option<&str>,
+    store: &str,
+) -> Result<(), String> {
+    let Some(quotas) = quotas else {
+        return Ok(());
+    };
+    let source = source.unwrap_or(UNKNOWN_SOURCE);
+    let Some(limit) = quotas.quota_for(source) else {
+        return Ok(());
+    };
+    let mut owned: Vec<&str> = db
+        .iter()
+        .filter(|(_, entry)| entry.source.as_deref().unwrap_or(UNKNOWN_SOURCE) == source)
+        .map(|(k, _)| k.as_str())
+        .collect();
+    if owned.contains(&key) || owned.len() < limit {
+        return Ok(());
+    }
+    owned.sort_unstable();
+
+    let mut message = format!(
+        "Memory quota reached: source '{}' already stores {} entries in {} (limit {}).\n\
+         Consolidate related memories into one key, overwrite an existing key, or \
+         delete stale ones with delete_memory before storing more.\n\nKeys stored by '{}':\n",
+        source,
+        owned.len(),
+        store,
+        limit,
+        source
+    );
+    for owned_key in owned.iter().take(QUOTA_KEYS_SHOWN) {
+        message.push_str(&format!("- {}\n", owned_key));
+    }
+    if owned.len() > QUOTA_KEYS_SHOWN {
+        message.push_str(&format!(
+            "- ... and {} more\n",
+            owned.len() - QUOTA_KEYS_SHOWN
+        ));
     }
+    Err(message)
+}
 
-    if matched_files.is_empty() {
-        return Err(format!("No concepts matching '{}' found", query));
+pub fn get_global_memory(
+    global_memory: Option<&MemoryDatabase>,
+    args: &Value,
+) -> Result<String, String> {
+    let memory_db = global_memory.ok_or(GLOBAL_MEMORY_UNAVAILABLE)?;
+
+    if let Some(key) = args.get("key").and_then(|v| v.as_str()) {
+        return memory_db.read(|db| {
+            db.get(key)
+                .map(|entry| format_memory(key, entry))
+                .ok_or_else(|| {
+                    fuzzy::not_found("Global memory key", key, db.keys().map(|k| k.as_str()))
+                })
+        })?;
     }
 
-    let mut output = format!("Files related to '{}': \n\n", query);
-    for (_, name, concept) in &matched_files {
-        output.push_str(&format!("## {}\n{}\n\nFiles:\n", name, concept.summary));
-        for file in &concept.files {
-            output.push_str(&format!("- {}/{}\n", path.display(), file));
+    memory_db.read(|db| {
+        if db.is_empty() {
+            return "No global memories stored".to_string();
         }
-        output.push('\n');
-    }
+        let mut entries: Vec<_> = db.iter().collect();
+        entries.sort_by_key(|(k, _)| *k);
 
-    Ok(output)
+        let mut output = String::from("# Global Memories\n\n");
+        for (key, entry) in entries {
+            output.push_str(&format!("- **{}**: {}\n", key, entry.value));
+        }
+        output
+    })
 }
 
-pub fn list_skills(
+pub fn get_memories(
     projects: &HashMap<String, ProjectData>,
     args: &Value,
 ) -> Result<String, String> {
@@ -630,180 +5098,194 @@ pub fn list_skills(
         .and_then(|v| v.as_str())
         .ok_or("Missing 'project' argument")?;
 
-    let (_, _, skills, _, _, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
-
-    if skills.skills.is_empty() {
-        return Ok(format!(
-            "No skills found for '{}'. Create .jumble/skills/*.md files to add task-specific context.",
-            project_name
-        ));
+    let keys = string_list(args, "keys")?.ok_or("Missing 'keys' argument")?;
+    if keys.is_empty() {
+        return Err("'keys' must not be empty".to_string());
     }
 
-    let mut output = format!("Available skills for '{}':\n\n", project_name);
-
-    // Include any available frontmatter description or, as a fallback, the first
-    // line of the cached preview. This makes skill listings more informative
-    // and exercises the cached metadata so it is not considered dead code.
-    for (name, info) in &skills.skills {
-        let mut line = format!("- {}", name);
-
-        if let Some(fm) = &info.frontmatter {
-            if let Some(desc) = &fm.description {
-                if !desc.is_empty() {
-                    line.push_str(&format!(": {}", desc));
-                    output.push_str(&line);
-                    output.push('\n');
-                    continue;
-                }
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
+
+    memory_db.read(|db| {
+        let mut output = String::new();
+        let mut missing = Vec::new();
+        for key in &keys {
+            let Some(entry) = db.get(key) else {
+                missing.push(fuzzy::not_found(
+                    "Memory key",
+                    key,
+                    db.keys().map(|k| k.as_str()),
+                ));
+                continue;
+            };
+            output.push_str(&format!("## {}\n\n", key));
+            output.push_str(&format!("**Value:** {}\n", entry.value));
+            output.push_str(&format!("**Timestamp:** {}\n", entry.timestamp));
+            if let Some(src) = &entry.source {
+                output.push_str(&format!("**Source:** {}\n", src));
             }
+            output.push('\n');
         }
 
-        let first_preview_line = info.preview.lines().next().unwrap_or("").trim();
-        if !first_preview_line.is_empty() {
-            line.push_str(&format!(": {}", first_preview_line));
-        }
-
-        output.push_str(&line);
-        output.push('\n');
-    }
-
-    output.push_str("\nUse get_skill(project, topic) to retrieve a specific skill.");
-    Ok(output)
+        let mut header = format!(
+            "# Memories for '{}' ({} of {} found)\n\n",
+            project_name,
+            keys.len() - missing.len(),
+            keys.len()
+        );
+        if !missing.is_empty() {
+            header.push_str("**Not found:**\n");
+            for message in &missing {
+                header.push_str(&format!("- {}\n", message));
+            }
+            header.push('\n');
+        }
+        format!("{}{}", header, output).trim_end().to_string()
+    })
 }
 
-pub fn get_skill(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
+pub fn list_memories(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
     let project_name = args
         .get("project")
         .and_then(|v| v.as_str())
         .ok_or("Missing 'project' argument")?;
 
-    let topic = args
-        .get("topic")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'topic' argument")?;
-
-    let (_, _, skills, _, _, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
-
-    let skill_info = skills.skills.get(topic).ok_or_else(|| {
-        let available: Vec<&str> = skills.skills.keys().map(|s| s.as_str()).collect();
-        if available.is_empty() {
-            format!("No skills found for '{}'", project_name)
-        } else {
-            format!(
-                "Skill '{}' not found. Available: {}",
-                topic,
-                available.join(", ")
-            )
+    let pattern = args.get("pattern").and_then(|v| v.as_str());
+    let matcher = pattern
+        .map(|pat| MemoryMatcher::new(pat, args))
+        .transpose()?;
+    let by_timestamp = match args.get("sort_by").and_then(|v| v.as_str()) {
+        None | Some("key") => false,
+        Some("timestamp") => true,
+        Some(other) => {
+            return Err(format!(
+                "Invalid sort_by '{}'. Use 'key' or 'timestamp'",
+                other
+            ))
         }
-    })?;
+    };
+    let since = time_bound(args, "since")?;
+    let before = time_bound(args, "before")?;
 
-    // Read the main skill file
-    let skill_content = std::fs::read_to_string(&skill_info.path)
-        .map_err(|e| format!("Failed to read skill: {}", e))?;
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
 
-    // If this skill has a directory with companion files, include them
-    if let Some(skill_dir) = &skill_info.skill_dir {
-        let companions = discover_companion_files(skill_dir);
-        if !companions.is_empty() {
-            return Ok(format_skill_with_companions(&skill_content, &companions));
+    // Read from database
+    let result = memory_db.read(|db| {
+        if db.is_empty() {
+            return Ok(format!("No memories stored for project '{}'", project_name));
         }
-    }
-
-    Ok(skill_content)
-}
-
-/// Companion file entry discovered in a skill directory
-#[derive(Debug)]
-struct CompanionFile {
-    relative_path: String,
-    is_dir: bool,
-}
 
-/// Discover companion files and directories in a skill folder.
-/// Looks for common subdirectories: scripts/, references/, docs/, assets/, examples/
-fn discover_companion_files(skill_dir: &std::path::Path) -> Vec<CompanionFile> {
-    let mut companions = Vec::new();
+        let mut entries: Vec<(&String, &memory::MemoryEntry)> = db
+            .iter()
+            .filter(|(k, _)| matcher.as_ref().is_none_or(|m| m.is_match(k)))
+            .filter(|(_, entry)| {
+                if since.is_none() && before.is_none() {
+                    return true;
+                }
+                // Entries with an unreadable timestamp can't be placed in the range.
+                memory::parse_timestamp(&entry.timestamp).is_some_and(|time| {
+                    since.is_none_or(|s| time >= s) && before.is_none_or(|b| time < b)
+                })
+            })
+            .collect();
+        if by_timestamp {
+            entries.sort_by(|a, b| {
+                let time = |entry: &memory::MemoryEntry| memory::parse_timestamp(&entry.timestamp);
+                time(b.1).cmp(&time(a.1)).then(a.0.cmp(b.0))
+            });
+        } else {
+            entries.sort_by_key(|(k, _)| *k);
+        }
 
-    // Common companion directory names for Claude/Codex skills
-    let known_dirs = [
-        "scripts",
-        "references",
-        "docs",
-        "assets",
-        "examples",
-        "templates",
-    ];
+        if entries.is_empty() {
+            if since.is_some() || before.is_some() {
+                return Ok(format!(
+                    "No memories in the requested time range for project '{}'",
+                    project_name
+                ));
+            }
+            return Ok(format!(
+                "No memories matching pattern '{}' for project '{}'",
+                pattern.unwrap_or(""),
+                project_name
+            ));
+        }
 
-    for dir_name in &known_dirs {
-        let dir_path = skill_dir.join(dir_name);
-        if dir_path.is_dir() {
-            // Add the directory itself
-            companions.push(CompanionFile {
-                relative_path: dir_name.to_string(),
-                is_dir: true,
-            });
+        let mut output = format!("# Memories for '{}'\n\n", project_name);
+        if let Some(pat) = pattern {
+            output.push_str(&format!("Filtered by: {}\n\n", pat));
+        }
 
-            // List files in the directory (non-recursive for now)
-            if let Ok(entries) = std::fs::read_dir(&dir_path) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    if let Ok(file_type) = entry.file_type() {
-                        if file_type.is_file() {
-                            if let Some(file_name) = entry.file_name().to_str() {
-                                companions.push(CompanionFile {
-                                    relative_path: format!("{}/{}", dir_name, file_name),
-                                    is_dir: false,
-                                });
-                            }
-                        }
-                    }
-                }
+        for (key, entry) in entries {
+            output.push_str(&format!("- **{}**\n", key));
+            output.push_str(&format!("  Timestamp: {}\n", entry.timestamp));
+            if let Some(src) = &entry.source {
+                output.push_str(&format!("  Source: {}\n", src));
+            }
+            // Preview first 100 chars of value
+            let preview = if entry.value.len() > 100 {
+                format!("{}...", &entry.value[..100])
+            } else {
+                entry.value.clone()
+            };
+            output.push_str(&format!("  Preview: {}\n", preview));
+            if let Some(target) = &entry.promoted {
+                output.push_str(&format!("  Promoted to: {}\n", target));
             }
         }
-    }
 
-    companions
+        Ok(output)
+    })?;
+
+    result
 }
 
-/// Format skill content with companion files listed at the end
-fn format_skill_with_companions(skill_content: &str, companions: &[CompanionFile]) -> String {
-    let mut output = String::from(skill_content);
+const GLOBAL_MEMORY_UNAVAILABLE: &str =
+    "Global memory is not available on this server (no home directory)";
 
-    // Add companion files section
-    output.push_str("\n\n---\n\n");
-    output.push_str("## Companion Resources\n\n");
-    output.push_str("This skill includes additional resources:\n\n");
+/// Parses an optional `since`/`before` argument into a point in time.
+fn time_bound(args: &Value, key: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    let Some(value) = args.get(key).and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    memory::parse_timestamp(value).map(Some).ok_or_else(|| {
+        format!(
+            "Invalid '{}' value '{}'. Use an RFC 3339 timestamp or a YYYY-MM-DD date",
+            key, value
+        )
+    })
+}
 
-    // Group by directory
-    let mut current_dir: Option<String> = None;
-    for companion in companions {
-        if companion.is_dir {
-            current_dir = Some(companion.relative_path.clone());
-            output.push_str(&format!("\n### {}\n", companion.relative_path));
-        } else {
-            // Extract directory and filename
-            if let Some(slash_pos) = companion.relative_path.rfind('/') {
-                let dir = &companion.relative_path[..slash_pos];
-                let file = &companion.relative_path[slash_pos + 1..];
+/// Matches memory keys and values against a `pattern` or `query` argument:
+/// a case-insensitive substring, or a regular expression when the call sets
+/// `regex: true`.
+enum MemoryMatcher {
+    Substring(String),
+    Regex(regex::Regex),
+}
 
-                if current_dir.as_deref() == Some(dir) {
-                    output.push_str(&format!("- `{}`\n", file));
-                } else {
-                    output.push_str(&format!("- `{}`\n", companion.relative_path));
-                }
-            } else {
-                output.push_str(&format!("- `{}`\n", companion.relative_path));
-            }
+impl MemoryMatcher {
+    fn new(pattern: &str, args: &Value) -> Result<Self, String> {
+        let regex = args.get("regex").and_then(|v| v.as_bool()).unwrap_or(false);
+        if !regex {
+            return Ok(MemoryMatcher::Substring(pattern.to_lowercase()));
         }
+        regex::Regex::new(pattern)
+            .map(MemoryMatcher::Regex)
+            .map_err(|e| format!("Invalid regex '{}': {}", pattern, e))
     }
 
-    output
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            MemoryMatcher::Substring(needle) => text.to_lowercase().contains(needle),
+            MemoryMatcher::Regex(regex) => regex.is_match(text),
+        }
+    }
 }
 
-pub fn get_conventions(
+pub fn search_memories(
     projects: &HashMap<String, ProjectData>,
     args: &Value,
 ) -> Result<String, String> {
@@ -812,848 +5294,2134 @@ pub fn get_conventions(
         .and_then(|v| v.as_str())
         .ok_or("Missing 'project' argument")?;
 
-    let category = args.get("category").and_then(|v| v.as_str());
-
-    let (_, _, _, conventions, _, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'query' argument")?;
+    let matcher = MemoryMatcher::new(query, args)?;
 
-    let has_conventions = !conventions.conventions.is_empty();
-    let has_gotchas = !conventions.gotchas.is_empty();
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
 
-    if !has_conventions && !has_gotchas {
-        return Ok(format!(
-            "No conventions found for '{}'. Create .jumble/conventions.toml to add project-specific conventions and gotchas.",
-            project_name
-        ));
-    }
+    // Read from database
+    let result = memory_db.read(|db| {
+        if db.is_empty() {
+            return Ok(format!("No memories stored for project '{}'", project_name));
+        }
 
-    let mut output = String::new();
+        let mut matches: Vec<(&String, &memory::MemoryEntry)> = db
+            .iter()
+            .filter(|(k, v)| matcher.is_match(k) || matcher.is_match(&v.value))
+            .collect();
 
-    match category {
-        Some("conventions") => {
-            if !has_conventions {
-                return Ok("No conventions defined.".to_string());
-            }
-            output.push_str(&format!("# Conventions for '{}'\n\n", project_name));
-            for (name, desc) in &conventions.conventions {
-                output.push_str(&format!("## {}\n{}\n\n", name, desc));
-            }
+        if matches.is_empty() {
+            return Ok(format!(
+                "No memories matching query '{}' for project '{}'",
+                query, project_name
+            ));
         }
-        Some("gotchas") => {
-            if !has_gotchas {
-                return Ok("No gotchas defined.".to_string());
-            }
-            output.push_str(&format!("# Gotchas for '{}'\n\n", project_name));
-            for (name, desc) in &conventions.gotchas {
-                output.push_str(&format!("## {}\n{}\n\n", name, desc));
+
+        // Sort by key for consistent output
+        matches.sort_by_key(|(k, _)| *k);
+
+        let mut output = format!("# Search results for '{}' in '{}'\n\n", query, project_name);
+        output.push_str(&format!("Found {} match(es)\n\n", matches.len()));
+
+        for (key, entry) in matches {
+            output.push_str(&format!("## {}\n", key));
+            output.push_str(&format!("**Value:** {}\n", entry.value));
+            output.push_str(&format!("**Timestamp:** {}\n", entry.timestamp));
+            if let Some(src) = &entry.source {
+                output.push_str(&format!("**Source:** {}\n", src));
             }
+            output.push('\n');
         }
-        None => {
-            if has_conventions {
-                output.push_str(&format!("# Conventions for '{}'\n\n", project_name));
-                for (name, desc) in &conventions.conventions {
-                    output.push_str(&format!("## {}\n{}\n\n", name, desc));
-                }
+
+        Ok(output)
+    })?;
+
+    result
+}
+
+pub fn search_all_memories(
+    projects: &HashMap<String, ProjectData>,
+    global_memory: Option<&MemoryDatabase>,
+    args: &Value,
+) -> Result<String, String> {
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'query' argument")?;
+    let matcher = MemoryMatcher::new(query, args)?;
+
+    let mut names: Vec<&String> = projects.keys().collect();
+    names.sort();
+    let mut stores: Vec<(String, &MemoryDatabase)> = names
+        .into_iter()
+        .map(|name| (format!("Project '{}'", name), &projects[name].5))
+        .collect();
+    if let Some(global) = global_memory {
+        stores.push(("Global".to_string(), global));
+    }
+
+    let mut output = format!("# Search results for '{}' in all memories\n\n", query);
+    let mut total = 0;
+    for (label, memory_db) in stores {
+        let section = memory_db.read(|db| {
+            let mut matches: Vec<(&String, &memory::MemoryEntry)> = db
+                .iter()
+                .filter(|(k, v)| matcher.is_match(k) || matcher.is_match(&v.value))
+                .collect();
+            if matches.is_empty() {
+                return None;
             }
-            if has_gotchas {
-                output.push_str(&format!("# Gotchas for '{}'\n\n", project_name));
-                for (name, desc) in &conventions.gotchas {
-                    output.push_str(&format!("## {}\n{}\n\n", name, desc));
+            matches.sort_by_key(|(k, _)| *k);
+
+            let mut section = format!("## {} ({} match(es))\n\n", label, matches.len());
+            for (key, entry) in &matches {
+                section.push_str(&format!("### {}\n", key));
+                section.push_str(&format!("**Value:** {}\n", entry.value));
+                section.push_str(&format!("**Timestamp:** {}\n", entry.timestamp));
+                if let Some(src) = &entry.source {
+                    section.push_str(&format!("**Source:** {}\n", src));
                 }
+                section.push('\n');
             }
-        }
-        Some(c) => {
-            return Err(format!(
-                "Unknown category '{}'. Use 'conventions' or 'gotchas'.",
-                c
-            ))
+            Some((matches.len(), section))
+        })?;
+        if let Some((count, section)) = section {
+            total += count;
+            output.push_str(&section);
         }
     }
 
+    if total == 0 {
+        return Ok(format!(
+            "No memories matching query '{}' in any project",
+            query
+        ));
+    }
     Ok(output)
 }
 
-pub fn get_docs(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
+pub fn delete_memory(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
     let project_name = args
         .get("project")
         .and_then(|v| v.as_str())
         .ok_or("Missing 'project' argument")?;
 
-    let topic = args.get("topic").and_then(|v| v.as_str());
+    let key = args
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'key' argument")?;
 
-    let (path, _, _, _, docs, _) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    let source = args.get("source").and_then(|v| v.as_str());
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
 
-    if docs.docs.is_empty() {
-        return Ok(format!(
-            "No documentation index found for '{}'. Create .jumble/docs.toml to index project documentation.",
-            project_name
-        ));
+    // Delete from database
+    memory_db.update_audited(MemoryAction::Delete, source, |db| match db.remove(key) {
+        Some(_) => Ok(()),
+        None => Err(fuzzy::not_found(
+            "Memory key",
+            key,
+            db.keys().map(|k| k.as_str()),
+        )),
+    })??;
+
+    Ok(format!(
+        "Memory deleted: key='{}' for project '{}'",
+        key, project_name
+    ))
+}
+
+pub fn clear_memories(
+    projects: &HashMap<String, ProjectData>,
+    args: &Value,
+) -> Result<String, String> {
+    let project_name = args
+        .get("project")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'project' argument")?;
+
+    let confirm = args
+        .get("confirm")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    if !confirm {
+        return Err("Deletion not confirmed. Set 'confirm' to true to proceed.".to_string());
     }
 
-    match topic {
-        Some(t) => {
-            // Return path to specific doc
-            let doc = docs.docs.get(t).ok_or_else(|| {
-                let available: Vec<&str> = docs.docs.keys().map(|s| s.as_str()).collect();
-                format!("Doc '{}' not found. Available: {}", t, available.join(", "))
-            })?;
-            let full_path = path.join(&doc.path);
-            Ok(format!(
-                "## {}\n**Summary:** {}\n**Path:** {}",
-                t,
-                doc.summary,
-                full_path.display()
-            ))
-        }
-        None => {
-            // List all docs with summaries
-            let mut output = format!("# Documentation for '{}'\n\n", project_name);
-            for (name, doc) in &docs.docs {
-                output.push_str(&format!("- **{}**: {}\n", name, doc.summary));
+    let pattern = args.get("pattern").and_then(|v| v.as_str());
+    let matcher = pattern
+        .map(|pat| MemoryMatcher::new(pat, args))
+        .transpose()?;
+
+    let source = args.get("source").and_then(|v| v.as_str());
+    let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
+
+    // Delete from database
+    let deleted_count = memory_db.update_audited(MemoryAction::Clear, source, |db| {
+        if let Some(matcher) = &matcher {
+            let keys_to_delete: Vec<String> =
+                db.keys().filter(|k| matcher.is_match(k)).cloned().collect();
+
+            let count = keys_to_delete.len();
+            for key in keys_to_delete {
+                db.remove(&key);
             }
-            output.push_str("\nUse get_docs(project, topic) to get the path to a specific doc.");
-            Ok(output)
+            count
+        } else {
+            let count = db.len();
+            db.clear();
+            count
         }
+    })?;
+
+    if let Some(pat) = pattern {
+        Ok(format!(
+            "Cleared {} memor{} matching pattern '{}' for project '{}'",
+            deleted_count,
+            if deleted_count == 1 { "y" } else { "ies" },
+            pat,
+            project_name
+        ))
+    } else {
+        Ok(format!(
+            "Cleared all {} memor{} for project '{}'",
+            deleted_count,
+            if deleted_count == 1 { "y" } else { "ies" },
+            project_name
+        ))
     }
 }
 
-pub fn get_workspace_overview(
-    root: &std::path::Path,
-    workspace: &Option<WorkspaceConfig>,
+/// Changes listed by `get_memory_audit` when no limit is given.
+const DEFAULT_AUDIT_ENTRIES: usize = 20;
+
+/// `get_memory_audit`: recorded memory changes for a project or the global
+/// store, newest first.
+pub fn get_memory_audit(
     projects: &HashMap<String, ProjectData>,
+    global_memory: Option<&MemoryDatabase>,
+    args: &Value,
 ) -> Result<String, String> {
-    let mut output = String::new();
-
-    // Workspace info
-    if let Some(ws) = workspace {
-        if let Some(name) = &ws.workspace.name {
-            output.push_str(&format!("# {}\n\n", name));
-        } else {
-            output.push_str("# Workspace Overview\n\n");
-        }
-        if let Some(desc) = &ws.workspace.description {
-            output.push_str(&format!("{}\n\n", desc));
-        }
+    let global = args
+        .get("global")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let (label, memory_db) = if global {
+        (
+            "global memory".to_string(),
+            global_memory.ok_or(GLOBAL_MEMORY_UNAVAILABLE)?,
+        )
     } else {
-        output.push_str("# Workspace Overview\n\n");
+        let project_name = args
+            .get("project")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing 'project' argument (or set 'global' to true)")?;
+        let (_, _, _, _, _, memory_db) = get_project(projects, project_name)?;
+        (format!("project '{}'", project_name), memory_db)
+    };
+    let key = args.get("key").and_then(|v| v.as_str());
+    let action = args
+        .get("action")
+        .and_then(|v| v.as_str())
+        .map(|name| {
+            MemoryAction::parse(name).ok_or_else(|| {
+                format!(
+                    "Unknown action '{}'. Use 'store', 'delete', 'clear', or 'promote'.",
+                    name
+                )
+            })
+        })
+        .transpose()?;
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_AUDIT_ENTRIES, |n| n as usize);
+
+    let entries: Vec<memory_audit::MemoryAuditEntry> = memory_audit::read(memory_db.audit_log())?
+        .into_iter()
+        .rev()
+        .filter(|e| key.is_none_or(|k| e.key == k))
+        .filter(|e| action.is_none_or(|a| e.action == a))
+        .collect();
+    if entries.is_empty() {
+        return Ok(format!("No recorded memory changes for {}.", label));
     }
 
-    output.push_str(&format!("**Root:** {}\n\n", root.display()));
-
-    // Projects list
-    if projects.is_empty() {
-        output.push_str("No projects found.\n");
-        return Ok(output);
+    let mut output = format!("# Memory audit for {}\n\n", label);
+    for entry in entries.iter().take(limit) {
+        output.push_str(&memory_audit::format_entry(entry));
     }
-
-    output.push_str("## Projects\n\n");
-
-    // Collect and sort projects for consistent output
-    let mut project_names: Vec<&String> = projects.keys().collect();
-    project_names.sort();
-
-    for name in &project_names {
-        let (_, config, _, _, _, _) = projects.get(*name).unwrap();
-        let lang = config.project.language.as_deref().unwrap_or("unknown");
+    if entries.len() > limit {
         output.push_str(&format!(
-            "- **{}** ({}): {}\n",
-            name, lang, config.project.description
+            "\n*{} older change(s) not shown.*\n",
+            entries.len() - limit
         ));
     }
+    Ok(output)
+}
 
-    // Dependency graph
-    output.push_str("\n## Dependencies\n\n");
-    let mut has_deps = false;
-
-    for name in &project_names {
-        let (_, config, _, _, _, _) = projects.get(*name).unwrap();
-        let upstream = &config.related_projects.upstream;
-        let downstream = &config.related_projects.downstream;
-
-        if !upstream.is_empty() || !downstream.is_empty() {
-            has_deps = true;
-            output.push_str(&format!("**{}**:\n", name));
-            if !upstream.is_empty() {
-                output.push_str(&format!("  ← depends on: {}\n", upstream.join(", ")));
-            }
-            if !downstream.is_empty() {
-                output.push_str(&format!("  → used by: {}\n", downstream.join(", ")));
-            }
-        }
+pub fn init_project(_workspace_root: &std::path::PathBuf, args: &Value) -> Result<String, String> {
+    // Get the target directory from arguments (required)
+    let dir_str = args
+        .get("directory")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing 'directory' argument")?;
+    
+    let target_dir = std::path::PathBuf::from(dir_str);
+    
+    // Ensure the directory exists or can be created
+    if !target_dir.exists() {
+        std::fs::create_dir_all(&target_dir)
+            .map_err(|e| format!("Failed to create directory '{}': {}", target_dir.display(), e))?;
     }
-
-    if !has_deps {
-        output.push_str("No cross-project dependencies defined.\n");
+    
+    // Use the setup module's init function
+    match crate::setup::setup_init(&target_dir) {
+        Ok(()) => Ok(format!("Project initialized successfully in {}.", target_dir.display())),
+        Err(e) => Err(format!("Failed to initialize project: {}", e)),
     }
+}
 
-    // Note about workspace conventions
-    if workspace.is_some() {
-        output.push_str("\n*Use get_workspace_conventions() for workspace-wide coding standards.*");
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::*;
+    use crate::memory;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
 
-    Ok(output)
-}
+    fn create_test_project() -> (String, ProjectData) {
+        let config = ProjectConfig {
+            extends: None,
+            schema_version: None,
+            project: ProjectInfo {
+                name: "test-project".to_string(),
+                description: "A test project".to_string(),
+                language: Some("rust".to_string()),
+                version: Some("1.0.0".to_string()),
+                repository: None,
+                archived: false,
+                inferred: Default::default(),
+            },
+            commands: {
+                let mut map = HashMap::new();
+                map.insert("build".to_string(), "cargo build".into());
+                map.insert("test".to_string(), "cargo test".into());
+                map
+            },
+            entry_points: {
+                let mut map = HashMap::new();
+                map.insert("main".to_string(), "src/main.rs".into());
+                map
+            },
+            dependencies: Dependencies {
+                internal: vec!["shared".to_string()],
+                external: vec!["serde".to_string()],
+                sync: false,
+            },
+            related_projects: RelatedProjects {
+                upstream: vec!["core".to_string()],
+                downstream: vec![],
+            },
+            api: None,
+            concepts: {
+                let mut map = HashMap::new();
+                map.insert(
+                    "authentication".to_string(),
+                    Concept {
+                        files: vec!["src/auth.rs".to_string()],
+                        summary: "JWT auth".to_string(),
+                        related: Vec::new(),
+                        owner: None,
+                        contact: None,
+                    },
+                );
+                map
+            },
+            test_map: HashMap::new(),
+            coverage: None,
+            environment: None,
+            services: BTreeMap::new(),
+            ci: None,
+            releases: None,
+            env: HashMap::new(),
+        };
 
-pub fn get_workspace_conventions(
-    workspace: &Option<WorkspaceConfig>,
-    args: &Value,
-) -> Result<String, String> {
-    let ws = workspace.as_ref().ok_or(
-        "No workspace.toml found. Create .jumble/workspace.toml at the workspace root to define workspace-level conventions."
-    )?;
+        let skills = ProjectSkills::default();
+        let conventions = ProjectConventions {
+            conventions: {
+                let mut map = HashMap::new();
+                map.insert("naming".to_string(), "Use snake_case".into());
+                map
+            },
+            gotchas: {
+                let mut map = HashMap::new();
+                map.insert("async".to_string(), "Avoid blocking".into());
+                map
+            },
+            custom: {
+                let mut security = HashMap::new();
+                security.insert("secrets".to_string(), "Never log tokens".into());
+                let mut map = BTreeMap::new();
+                map.insert("security".to_string(), security);
+                map
+            },
+            ..Default::default()
+        };
+        let docs = ProjectDocs {
+            schema_version: None,
+            docs: {
+                let mut map = HashMap::new();
+                map.insert(
+                    "readme".to_string(),
+                    DocEntry {
+                        path: "README.md".to_string(),
+                        summary: "Project readme".to_string(),
+                    },
+                );
+                map
+            },
+        };
 
-    let category = args.get("category").and_then(|v| v.as_str());
+        // Create a temporary memory database for testing
+        let temp_dir = TempDir::new().unwrap();
+        let test_path = temp_dir.path().to_path_buf();
+        let memory_db = memory::open_or_create_memory_db(&test_path).unwrap();
 
-    let has_conventions = !ws.conventions.is_empty();
-    let has_gotchas = !ws.gotchas.is_empty();
+        (
+            "test-project".to_string(),
+            (
+                test_path.clone(),
+                config,
+                skills,
+                conventions,
+                docs,
+                memory_db,
+            ),
+        )
+    }
 
-    if !has_conventions && !has_gotchas {
-        return Ok("Workspace config exists but no conventions or gotchas defined.".to_string());
+    fn create_test_projects() -> HashMap<String, ProjectData> {
+        let mut projects = HashMap::new();
+        let (name, data) = create_test_project();
+        projects.insert(name, data);
+        projects
     }
 
-    let mut output = String::new();
-    let ws_name = ws.workspace.name.as_deref().unwrap_or("Workspace");
+    #[test]
+    fn test_list_projects_empty() {
+        let projects = HashMap::new();
+        let result = list_projects(&projects).unwrap();
+        assert!(result.contains("No projects found"));
+    }
 
-    match category {
-        Some("conventions") => {
-            if !has_conventions {
-                return Ok("No workspace conventions defined.".to_string());
-            }
-            output.push_str(&format!("# {} Conventions\n\n", ws_name));
-            for (name, desc) in &ws.conventions {
-                output.push_str(&format!("## {}\n{}\n\n", name, desc));
-            }
-        }
-        Some("gotchas") => {
-            if !has_gotchas {
-                return Ok("No workspace gotchas defined.".to_string());
-            }
-            output.push_str(&format!("# {} Gotchas\n\n", ws_name));
-            for (name, desc) in &ws.gotchas {
-                output.push_str(&format!("## {}\n{}\n\n", name, desc));
-            }
-        }
-        None => {
-            if has_conventions {
-                output.push_str(&format!("# {} Conventions\n\n", ws_name));
-                for (name, desc) in &ws.conventions {
-                    output.push_str(&format!("## {}\n{}\n\n", name, desc));
-                }
-            }
-            if has_gotchas {
-                output.push_str(&format!("# {} Gotchas\n\n", ws_name));
-                for (name, desc) in &ws.gotchas {
-                    output.push_str(&format!("## {}\n{}\n\n", name, desc));
-                }
-            }
-        }
-        Some(c) => {
-            return Err(format!(
-                "Unknown category '{}'. Use 'conventions' or 'gotchas'.",
-                c
-            ))
+    #[test]
+    fn test_list_projects() {
+        let projects = create_test_projects();
+        let result = list_projects(&projects).unwrap();
+        assert!(result.contains("test-project"));
+        assert!(result.contains("rust"));
+        assert!(result.contains("A test project"));
+    }
+
+    #[test]
+    fn test_archived_projects_are_not_listed() {
+        let (name, mut data) = create_test_project();
+        data.1.project.archived = true;
+        let mut projects = HashMap::from([(name, data)]);
+        let (_, mut active) = create_test_project();
+        active.1.project.name = "active".to_string();
+        projects.insert("active".to_string(), active);
+
+        let listing = list_projects(&projects).unwrap();
+        assert!(listing.contains("**active**"));
+        assert!(!listing.contains("test-project"));
+        assert!(listing.ends_with("*1 archived project not shown; use it by name.*\n"));
+        let overview = get_workspace_overview(Path::new("/workspace"), &None, &projects).unwrap();
+        assert!(overview.contains("**active**"));
+        assert!(!overview.contains("test-project"));
+
+        let args = json!({"project": "test-project"});
+        assert!(get_project_info(&projects, &args).is_ok());
+    }
+
+    #[test]
+    fn test_get_project_info_marks_inferred_fields() {
+        let (name, mut data) = create_test_project();
+        data.1.project.repository = Some("https://example.com/app.git".to_string());
+        data.1
+            .project
+            .inferred
+            .insert("repository".to_string(), "git remote origin".to_string());
+        let projects = HashMap::from([(name, data)]);
+        let info = get_project_info(&projects, &json!({"project": "test-project"})).unwrap();
+        assert!(info.contains("**Version:** 1.0.0\n"));
+        assert!(info.contains(
+            "**Repository:** https://example.com/app.git *(inferred from git remote origin)*\n"
+        ));
+
+        let mut projects = projects;
+        let config = &mut projects.get_mut("test-project").unwrap().1;
+        config.project.inferred.insert(
+            "dependencies.external".to_string(),
+            "Cargo.toml".to_string(),
+        );
+        let deps = get_project_info(
+            &projects,
+            &json!({"project": "test-project", "field": "dependencies"}),
+        )
+        .unwrap();
+        assert!(deps.ends_with("- serde\n\n*External dependencies read from Cargo.toml.*\n"));
+    }
+
+    #[test]
+    fn test_format_project_table() {
+        assert_eq!(format_project_table(&HashMap::new()), "No projects found.");
+
+        let (name, mut data) = create_test_project();
+        data.1.project.archived = true;
+        let projects = HashMap::from([(name, data)]);
+        let table = format_project_table(&projects);
+        let row = table.lines().nth(2).unwrap();
+        assert!(row.starts_with("| test-project (archived) | rust | "));
+        let counts: Vec<&str> = row.split(" | ").skip(3).collect();
+        let (_, config, skills, _, docs, _) = &projects["test-project"];
+        assert_eq!(counts[0], config.concepts.len().to_string());
+        assert_eq!(counts[1], skills.skills.len().to_string());
+        assert_eq!(counts[2], docs.docs.len().to_string());
+        assert_eq!(counts[3], "0 |");
+    }
+
+    #[test]
+    fn test_skill_source() {
+        let project = Path::new("/work/api");
+        let home = Some(Path::new("/home/dev"));
+        let cases = [
+            ("/work/api/.jumble/skills/deploy.md", "project"),
+            ("/work/api/.claude/skills/lint/SKILL.md", "project claude"),
+            ("/home/dev/.jumble/skills/style.md", "global"),
+            (
+                "/home/dev/.jumble/skill-cache/team/release/SKILL.md",
+                "pack team",
+            ),
+            ("/home/dev/.claude/skills/review/SKILL.md", "claude"),
+            ("/home/dev/.codex/skills/review/SKILL.md", "codex"),
+            ("/elsewhere/SKILL.md", "unknown"),
+        ];
+        for (path, source) in cases {
+            assert_eq!(
+                skill_source(project, home, Path::new(path)),
+                source,
+                "{}",
+                path
+            );
         }
     }
 
-    Ok(output)
-}
+    #[test]
+    fn test_get_project_info_not_found() {
+        let projects = create_test_projects();
+        let args = json!({"project": "nonexistent"});
+        let result = get_project_info(&projects, &args);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("not found"));
+    }
 
-// ============================================================================
-// Memory Tool Implementations
-// ============================================================================
+    #[test]
+    fn test_lookup_errors_suggest_closest_name() {
+        let projects = create_test_projects();
 
-pub fn store_memory(
-    projects: &HashMap<String, ProjectData>,
-    args: &Value,
-) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+        let err = get_project_info(&projects, &json!({"project": "test-projet"})).unwrap_err();
+        assert_eq!(
+            err,
+            "Project 'test-projet' not found, did you mean 'test-project'?"
+        );
 
-    let key = args
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'key' argument")?;
+        let args = json!({"project": "test-project", "command_type": "biuld"});
+        let err = get_commands(&projects, &args).unwrap_err();
+        assert!(err.ends_with("did you mean 'build'?"));
 
-    let value = args
-        .get("value")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'value' argument")?;
+        // Concepts and docs resolve typos outright, so only unrelated names fail.
+        let args = json!({"project": "test-project", "concept": "billing"});
+        let err = get_architecture(Path::new("/workspace"), &projects, &args).unwrap_err();
+        assert_eq!(
+            err,
+            "Concept 'billing' not found. Available concepts: authentication"
+        );
 
-    let source = args.get("source").and_then(|v| v.as_str());
+        let args = json!({"project": "test-project", "topic": "changelog"});
+        let err = get_docs(Path::new("/workspace"), &projects, &args).unwrap_err();
+        assert!(err.ends_with("Available: readme"));
+    }
 
-    let (_, _, _, _, _, memory_db) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    #[test]
+    fn test_get_project_info_full() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        let result = get_project_info(&projects, &args).unwrap();
+        assert!(result.contains("test-project"));
+        assert!(result.contains("A test project"));
+        assert!(result.contains("rust"));
+        assert!(result.contains("1.0.0"));
+    }
 
-    // Create memory entry
-    let entry = crate::memory::MemoryEntry {
-        value: value.to_string(),
-        timestamp: crate::memory::current_timestamp(),
-        source: source.map(|s| s.to_string()),
-    };
+    #[test]
+    fn test_get_project_info_commands_field() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "field": "commands"});
+        let result = get_project_info(&projects, &args).unwrap();
+        assert!(result.contains("build"));
+        assert!(result.contains("cargo build"));
+    }
 
-    // Store in database
-    memory_db
-        .write(|db| {
-            db.insert(key.to_string(), entry);
-        })
-        .map_err(|e| format!("Failed to write to memory database: {}", e))?;
+    #[test]
+    fn test_get_commands() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        let result = get_commands(&projects, &args).unwrap();
+        assert!(result.contains("build"));
+        assert!(result.contains("test"));
+    }
 
-    memory_db
-        .save()
-        .map_err(|e| format!("Failed to save memory database: {}", e))?;
+    #[test]
+    fn test_get_commands_specific() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "command_type": "build"});
+        let result = get_commands(&projects, &args).unwrap();
+        assert!(result.contains("cargo build"));
+    }
 
-    Ok(format!(
-        "Memory stored: key='{}' for project '{}'",
-        key, project_name
-    ))
-}
+    #[test]
+    fn test_resolve_commands_env_placeholders() {
+        let mut config = create_test_projects().remove("test-project").unwrap().1;
+        config.commands.insert(
+            "run".to_string(),
+            "${RUNNER} --data ${DATA_DIR} ${API_TOKEN} ${UNSET}".into(),
+        );
+        config
+            .env
+            .insert("RUNNER".to_string(), "cargo run".to_string());
+        config
+            .env
+            .insert("DATA_DIR".to_string(), "./data".to_string());
+        let env = |name: &str| match name {
+            "DATA_DIR" => Some("/srv/data".to_string()),
+            "API_TOKEN" => Some("s3cret".to_string()),
+            _ => None,
+        };
 
-pub fn get_memory(projects: &HashMap<String, ProjectData>, args: &Value) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+        // Variables missing from `[env]` stay unresolved, even when set.
+        let commands = resolve_commands_with(&config, env);
+        assert_eq!(
+            commands["run"].cmd,
+            "cargo run --data /srv/data ${API_TOKEN} ${UNSET}"
+        );
+    }
 
-    let key = args
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'key' argument")?;
+    #[test]
+    fn test_get_commands_structured() {
+        let mut projects = create_test_projects();
+        let (_, config, _, _, _, _) = projects.get_mut("test-project").unwrap();
+        let spec = CommandSpec::Detailed(CommandDetails {
+            cmd: "cargo test".to_string(),
+            description: Some("Integration tests".to_string()),
+            cwd: Some("crates/api".to_string()),
+            env: [("RUST_LOG".to_string(), "${JUMBLE_TEST_LOG}".to_string())].into(),
+            ..Default::default()
+        });
+        config
+            .env
+            .insert("JUMBLE_TEST_LOG".to_string(), "debug".to_string());
+        config.commands.insert("itest".to_string(), spec);
+
+        let args = json!({"project": "test-project", "command_type": "itest"});
+        assert_eq!(
+            get_commands(&projects, &args).unwrap(),
+            "itest: cargo test\nIntegration tests\ncwd: `crates/api`\nenv: `RUST_LOG=debug`"
+        );
+        let all = get_commands(&projects, &json!({"project": "test-project"})).unwrap();
+        assert!(all.contains(
+            "- **itest**: `cargo test` - Integration tests\n  - cwd: `crates/api`\n  - env: `RUST_LOG=debug`\n"
+        ));
+        assert!(all.contains("- **build**: `cargo build`\n"));
+    }
 
-    let (_, _, _, _, _, memory_db) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    #[test]
+    fn test_get_commands_resolves_dependencies_and_sequences() {
+        let mut projects = create_test_projects();
+        let (_, config, _, _, _, _) = projects.get_mut("test-project").unwrap();
+        config
+            .commands
+            .insert("codegen".to_string(), "buf generate".into());
+        config.commands.insert(
+            "build".to_string(),
+            CommandSpec::Detailed(CommandDetails {
+                cmd: "cargo build".to_string(),
+                depends_on: vec!["codegen".to_string()],
+                ..Default::default()
+            }),
+        );
+        config.commands.insert(
+            "test".to_string(),
+            CommandSpec::Detailed(CommandDetails {
+                cmd: "cargo test".to_string(),
+                cwd: Some("crates/api".to_string()),
+                depends_on: vec!["build".to_string()],
+                ..Default::default()
+            }),
+        );
+        config.commands.insert(
+            "ci".to_string(),
+            CommandSpec::Sequence(CommandSequence {
+                steps: vec!["build".to_string(), "test".to_string()],
+                description: Some("Everything CI runs".to_string()),
+            }),
+        );
 
-    // Read from database
-    let result = memory_db
-        .read(|db| {
-            db.get(key)
-                .map(|entry| {
-                    let mut output = format!("# Memory: {}\n\n", key);
-                    output.push_str(&format!("**Value:** {}\n", entry.value));
-                    output.push_str(&format!("**Timestamp:** {}\n", entry.timestamp));
-                    if let Some(src) = &entry.source {
-                        output.push_str(&format!("**Source:** {}\n", src));
-                    }
-                    output
-                })
-                .ok_or_else(|| format!("Memory key '{}' not found", key))
-        })
-        .map_err(|e| format!("Failed to read from memory database: {}", e))?;
+        let args = json!({"project": "test-project", "command_type": "test"});
+        assert_eq!(
+            get_commands(&projects, &args).unwrap(),
+            "test: cargo test\ndepends on: `build`\ncwd: `crates/api`\n\n\
+             Run in order:\n\
+             1. codegen: `buf generate`\n\
+             2. build: `cargo build`\n\
+             3. test: `cargo test`\n   - cwd: `crates/api`"
+        );
+        let args = json!({"project": "test-project", "command_type": "ci"});
+        let ci = get_commands(&projects, &args).unwrap();
+        assert!(ci.starts_with("ci: runs `build`, then `test`\nEverything CI runs\n"));
+        assert!(ci.contains("1. codegen: `buf generate`\n2. build: `cargo build`\n3. test:"));
+        let all = get_commands(&projects, &json!({"project": "test-project"})).unwrap();
+        assert!(all.contains("- **ci**: runs `build`, then `test` - Everything CI runs\n"));
+        assert!(all.contains("- **build**: `cargo build`\n  - depends on: `codegen`\n"));
+
+        let (_, config, _, _, _, _) = projects.get_mut("test-project").unwrap();
+        assert!(command_dependency_problems(&config.commands).is_empty());
+        config.commands.insert(
+            "codegen".to_string(),
+            CommandSpec::Detailed(CommandDetails {
+                cmd: "buf generate".to_string(),
+                depends_on: vec!["test".to_string(), "protos".to_string()],
+                ..Default::default()
+            }),
+        );
+        assert_eq!(
+            command_dependency_problems(&config.commands),
+            [
+                "command 'codegen' depends on unknown command 'protos'",
+                "command dependency cycle: build -> codegen -> test -> build",
+            ]
+        );
+        let args = json!({"project": "test-project", "command_type": "build"});
+        assert_eq!(
+            get_commands(&projects, &args).unwrap_err(),
+            "Command dependency cycle: build -> codegen -> test -> build"
+        );
+    }
 
-    result
-}
+    #[test]
+    fn test_get_commands_not_found() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "command_type": "deploy"});
+        let result = get_commands(&projects, &args);
+        assert!(result.is_err());
+    }
 
-pub fn list_memories(
-    projects: &HashMap<String, ProjectData>,
-    args: &Value,
-) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+    #[test]
+    fn test_get_architecture() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "concept": "authentication"});
+        let result = get_architecture(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("authentication"));
+        assert!(result.contains("JWT auth"));
+        assert!(result.contains("src/auth.rs"));
+    }
 
-    let pattern = args.get("pattern").and_then(|v| v.as_str());
+    #[test]
+    fn test_get_architecture_case_insensitive() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "concept": "AUTHENTICATION"});
+        let result = get_architecture(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("JWT auth"));
+    }
 
-    let (_, _, _, _, _, memory_db) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+    #[test]
+    fn test_get_architecture_partial_match() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "concept": "auth"});
+        let result = get_architecture(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("JWT auth"));
+    }
 
-    // Read from database
-    let result = memory_db
-        .read(|db| {
-            if db.is_empty() {
-                return Ok(format!("No memories stored for project '{}'", project_name));
-            }
+    #[test]
+    fn test_get_architecture_fuzzy_match() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "concept": "authntication"});
+        let result = get_architecture(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("JWT auth"));
+    }
 
-            let mut keys: Vec<&String> = db.keys().collect();
-            keys.sort();
+    #[test]
+    fn test_get_architecture_lists_all_partial_matches() {
+        let mut projects = create_test_projects();
+        let concepts = &mut projects.get_mut("test-project").unwrap().1.concepts;
+        for (name, summary) in [
+            ("sessions", "Cookie sessions backing auth"),
+            ("oauth", "Third-party login"),
+        ] {
+            concepts.insert(
+                name.to_string(),
+                Concept {
+                    files: vec![format!("src/{}.rs", name)],
+                    summary: summary.to_string(),
+                    related: Vec::new(),
+                    owner: None,
+                    contact: None,
+                },
+            );
+        }
 
-            // Filter by pattern if provided
-            let filtered_keys: Vec<&String> = if let Some(pat) = pattern {
-                let pat_lower = pat.to_lowercase();
-                keys.into_iter()
-                    .filter(|k| k.to_lowercase().contains(&pat_lower))
-                    .collect()
-            } else {
-                keys
-            };
+        let args = json!({"project": "test-project", "concept": "auth"});
+        let result = get_architecture(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.starts_with("# 3 concepts match 'auth'"));
+        // Name matches come first, then summary-only matches.
+        let auth = result.find("**authentication**").unwrap();
+        let oauth = result.find("**oauth**").unwrap();
+        let sessions = result.find("**sessions**").unwrap();
+        assert!(auth < oauth && oauth < sessions);
+        assert!(!result.contains("src/auth.rs"));
+    }
 
-            if filtered_keys.is_empty() {
-                return Ok(format!(
-                    "No memories matching pattern '{}' for project '{}'",
-                    pattern.unwrap_or(""),
-                    project_name
-                ));
-            }
+    #[test]
+    fn test_add_concept() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join(".jumble")).unwrap();
+        let file = temp.path().join(".jumble/project.toml");
+        std::fs::write(
+            &file,
+            "[project]\nname = \"test-project\"\ndescription = \"A test project\"\n",
+        )
+        .unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"project": "test-project", "name": "sessions", "summary": "Cookies"});
+        let err = add_concept(&mut projects, &args).unwrap_err();
+        assert!(err.contains("'files'"));
+
+        let args = json!({
+            "project": "test-project",
+            "name": "sessions",
+            "summary": "Cookie sessions",
+            "files": ["src/session.rs"],
+            "confirm": true
+        });
+        let result = add_concept(&mut projects, &args).unwrap();
+        assert!(result.starts_with("Added concept 'sessions'"));
+
+        // Updating only the summary keeps the files, in memory and on disk.
+        let args = json!({
+            "project": "test-project",
+            "name": "sessions",
+            "summary": "Signed cookie sessions",
+            "confirm": true
+        });
+        let result = add_concept(&mut projects, &args).unwrap();
+        assert!(result.starts_with("Updated concept 'sessions'"));
+        let written: ProjectConfig =
+            toml::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(written.concepts["sessions"].files, ["src/session.rs"]);
+        assert_eq!(
+            written.concepts["sessions"].summary,
+            "Signed cookie sessions"
+        );
 
-            let mut output = format!("# Memories for '{}'\n\n", project_name);
-            if let Some(pat) = pattern {
-                output.push_str(&format!("Filtered by: {}\n\n", pat));
-            }
+        let args = json!({"project": "test-project", "concept": "sessions"});
+        let result = get_architecture(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("Signed cookie sessions"));
+    }
 
-            for key in filtered_keys {
-                if let Some(entry) = db.get(key) {
-                    output.push_str(&format!("- **{}**\n", key));
-                    output.push_str(&format!("  Timestamp: {}\n", entry.timestamp));
-                    if let Some(src) = &entry.source {
-                        output.push_str(&format!("  Source: {}\n", src));
-                    }
-                    // Preview first 100 chars of value
-                    let preview = if entry.value.len() > 100 {
-                        format!("{}...", &entry.value[..100])
-                    } else {
-                        entry.value.clone()
-                    };
-                    output.push_str(&format!("  Preview: {}\n", preview));
-                }
-            }
+    #[test]
+    fn test_get_related_files() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "query": "auth"});
+        let result = get_related_files(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("authentication"));
+        assert!(result.contains("src/auth.rs"));
+    }
 
-            Ok(output)
-        })
-        .map_err(|e| format!("Failed to read from memory database: {}", e))?;
+    #[test]
+    fn test_get_related_files_ranking() {
+        let mut projects = create_test_projects();
+        let concepts = &mut projects.get_mut("test-project").unwrap().1.concepts;
+        for (name, summary) in [
+            ("auth", "Login entry points"),
+            ("sessions", "Cookie sessions backing auth tokens"),
+            ("billing", "Invoices"),
+        ] {
+            concepts.insert(
+                name.to_string(),
+                Concept {
+                    files: vec![format!("src/{}.rs", name)],
+                    summary: summary.to_string(),
+                    related: Vec::new(),
+                    owner: None,
+                    contact: None,
+                },
+            );
+        }
 
-    result
-}
+        let args = json!({"project": "test-project", "query": "auth tokens"});
+        let result = get_related_files(Path::new("/workspace"), &projects, &args).unwrap();
+        let headings: Vec<&str> = result.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(
+            headings,
+            [
+                "## auth (score 10)",
+                "## authentication (score 10)",
+                "## sessions (score 6)"
+            ]
+        );
 
-pub fn search_memories(
-    projects: &HashMap<String, ProjectData>,
-    args: &Value,
-) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+        let args = json!({"project": "test-project", "query": "Auth"});
+        let result = get_related_files(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("## auth (score 110)\n"));
+        assert!(!result.contains("billing"));
+    }
 
-    let query = args
-        .get("query")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'query' argument")?;
+    #[test]
+    fn test_who_owns() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("api");
+        std::fs::create_dir_all(temp.path().join(".github")).unwrap();
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            temp.path().join(".github/CODEOWNERS"),
+            "* @org/everyone
+/api/src/auth.rs @alice @org/security
+/api/vendor/
+",
+        )
+        .unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = project_dir;
 
-    let (_, _, _, _, _, memory_db) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+        let owners = |path: &str| {
+            let args = json!({"project": "test-project", "path": path});
+            who_owns(temp.path(), &projects, &args).unwrap()
+        };
+        assert_eq!(
+            owners("src/auth.rs"),
+            "`src/auth.rs` is owned by @alice, @org/security\n\n\
+             Rule: `/api/src/auth.rs` (.github/CODEOWNERS line 2)"
+        );
+        assert!(owners("Cargo.toml").starts_with("`Cargo.toml` is owned by @org/everyone"));
+        assert_eq!(
+            owners("vendor/lib.rs"),
+            "`vendor/lib.rs` has no owners: `/api/vendor/` (.github/CODEOWNERS line 3) lists none."
+        );
 
-    // Read from database
-    let result = memory_db
-        .read(|db| {
-            if db.is_empty() {
-                return Ok(format!("No memories stored for project '{}'", project_name));
-            }
+        let args =
+            json!({"project": "test-project", "query": "authentication", "paths": "project"});
+        let related = get_related_files(temp.path(), &projects, &args).unwrap();
+        assert!(related.contains("- src/auth.rs (owners: @alice, @org/security)\n"));
 
-            let query_lower = query.to_lowercase();
-            let mut matches: Vec<(&String, &crate::memory::MemoryEntry)> = db
-                .iter()
-                .filter(|(k, v)| {
-                    k.to_lowercase().contains(&query_lower)
-                        || v.value.to_lowercase().contains(&query_lower)
-                })
-                .collect();
+        std::fs::remove_file(temp.path().join(".github/CODEOWNERS")).unwrap();
+        let args = json!({"project": "test-project", "path": "src/auth.rs"});
+        let err = who_owns(temp.path(), &projects, &args).unwrap_err();
+        assert!(err.starts_with("No CODEOWNERS file found for project 'test-project'"));
+    }
 
-            if matches.is_empty() {
-                return Ok(format!(
-                    "No memories matching query '{}' for project '{}'",
-                    query, project_name
-                ));
-            }
+    #[test]
+    fn test_get_tests_for_file() {
+        let temp = TempDir::new().unwrap();
+        for (file, content) in [
+            (
+                "src/billing.rs",
+                "pub fn total() {}\n\n#[cfg(test)]\nmod tests {}\n",
+            ),
+            ("tests/billing.rs", ""),
+            ("tests/test_billing.rs", ""),
+            ("src/main.rs", "fn main() {}\n"),
+        ] {
+            let path = temp.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let mut projects = create_test_projects();
+        let (dir, config, _, _, _, _) = projects.get_mut("test-project").unwrap();
+        *dir = temp.path().to_path_buf();
+        config.test_map.insert(
+            "src/billing*.rs".to_string(),
+            vec![
+                "tests/billing.rs".to_string(),
+                "tests/e2e/pay.rs".to_string(),
+            ],
+        );
 
-            // Sort by key for consistent output
-            matches.sort_by_key(|(k, _)| *k);
+        let tests_for = |path: &str| {
+            let args = json!({"project": "test-project", "path": path});
+            get_tests_for_file(&projects, &args).unwrap()
+        };
+        assert_eq!(
+            tests_for("src/billing.rs"),
+            "# Tests for src/billing.rs\n\n\
+             **From [test_map]:**\n- tests/billing.rs\n- tests/e2e/pay.rs (missing)\n\n\
+             **By naming convention:**\n- tests/test_billing.rs\n\n\
+             `src/billing.rs` also has inline tests (`#[cfg(test)]`).\n"
+        );
+        assert!(tests_for("src/main.rs").starts_with("No tests found for `src/main.rs`."));
+    }
 
-            let mut output = format!("# Search results for '{}' in '{}'\n\n", query, project_name);
-            output.push_str(&format!("Found {} match(es)\n\n", matches.len()));
+    #[test]
+    fn test_get_coverage() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("coverage")).unwrap();
+        std::fs::write(
+            temp.path().join("coverage/lcov.info"),
+            "SF:src/auth.rs\nDA:1,1\nDA:2,1\nDA:3,1\nDA:4,0\nend_of_record\n\
+             SF:src/billing/invoice.rs\nDA:1,1\nDA:2,0\nDA:3,0\nDA:4,0\nend_of_record\n\
+             SF:src/billing/tax.rs\nDA:1,0\nDA:2,0\nend_of_record\n",
+        )
+        .unwrap();
+        let mut projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        assert!(get_coverage(&projects, &args)
+            .unwrap_err()
+            .contains("has no coverage report configured"));
+
+        let (dir, config, _, _, _, _) = projects.get_mut("test-project").unwrap();
+        *dir = temp.path().to_path_buf();
+        config.coverage = Some(CoverageConfig {
+            report: "coverage/lcov.info".to_string(),
+            format: None,
+        });
+        for (name, files) in [
+            (
+                "billing",
+                vec!["src/billing/invoice.rs", "src/billing/tax.rs:1-1"],
+            ),
+            ("cli", vec!["src/cli.rs"]),
+        ] {
+            config.concepts.insert(
+                name.to_string(),
+                Concept {
+                    files: files.into_iter().map(str::to_string).collect(),
+                    summary: format!("The {}", name),
+                    related: Vec::new(),
+                    owner: None,
+                    contact: None,
+                },
+            );
+        }
 
-            for (key, entry) in matches {
-                output.push_str(&format!("## {}\n", key));
-                output.push_str(&format!("**Value:** {}\n", entry.value));
-                output.push_str(&format!("**Timestamp:** {}\n", entry.timestamp));
-                if let Some(src) = &entry.source {
-                    output.push_str(&format!("**Source:** {}\n", src));
-                }
-                output.push('\n');
-            }
+        assert_eq!(
+            get_coverage(&projects, &args).unwrap(),
+            "# Coverage for test-project\n\nReport: coverage/lcov.info\n\n\
+             **Total:** 40.0% (4/10 lines in 3 files)\n\n\
+             | Concept | Coverage | Lines |\n|---------|---------:|------:|\n\
+             | billing | 20.0% | 1/5 |\n\
+             | authentication | 75.0% | 3/4 |\n\
+             | cli | no data | - |\n"
+        );
+        let args = json!({"project": "test-project", "concept": "billing"});
+        let billing = get_coverage(&projects, &args).unwrap();
+        assert!(billing.contains("**Total:** 20.0% (1/5 lines)\n"));
+        assert!(billing.ends_with(
+            "| src/billing/tax.rs:1-1 | 0.0% | 0/1 |\n| src/billing/invoice.rs | 25.0% | 1/4 |\n"
+        ));
 
-            Ok(output)
-        })
-        .map_err(|e| format!("Failed to read from memory database: {}", e))?;
+        std::fs::remove_file(temp.path().join("coverage/lcov.info")).unwrap();
+        let err = get_coverage(&projects, &args).unwrap_err();
+        assert!(err.starts_with("Failed to read coverage report"));
+    }
 
-    result
-}
+    #[test]
+    fn test_get_environment() {
+        let mut projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        assert!(get_environment(&projects, &args)
+            .unwrap_err()
+            .contains("has no environment requirements"));
+
+        let config = &mut projects.get_mut("test-project").unwrap().1;
+        config.environment = Some(EnvironmentConfig {
+            tools: BTreeMap::from([("jumble-missing-tool".to_string(), "1.0".to_string())]),
+            variables: BTreeMap::from([
+                (
+                    "PATH".to_string(),
+                    EnvVariable::Description("Search path".to_string()),
+                ),
+                (
+                    "JUMBLE_TEST_API_KEY".to_string(),
+                    EnvVariable::Detailed {
+                        description: Some("Sandbox API key".to_string()),
+                        required: true,
+                        example: Some("sk_test_123".to_string()),
+                    },
+                ),
+                (
+                    "JUMBLE_TEST_DEBUG".to_string(),
+                    EnvVariable::Detailed {
+                        description: None,
+                        required: false,
+                        example: None,
+                    },
+                ),
+            ]),
+            setup: vec!["cp .env.example .env".to_string()],
+        });
 
-pub fn delete_memory(
-    projects: &HashMap<String, ProjectData>,
-    args: &Value,
-) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+        assert_eq!(
+            get_environment(&projects, &args).unwrap(),
+            "# Environment for test-project\n\n\
+             ## Tools\n\n| Tool | Required | Installed |\n|------|----------|-----------|\n\
+             | jumble-missing-tool | 1.0 | not checked |\n\n\
+             ## Environment Variables\n\n\
+             - `JUMBLE_TEST_API_KEY` (required, missing): Sandbox API key (e.g. `sk_test_123`)\n\
+             - `JUMBLE_TEST_DEBUG` (optional, not set)\n\
+             - `PATH` (required, set): Search path\n\n\
+             ## Setup\n\n1. cp .env.example .env\n\n\
+             ## Problems\n\n\
+             - JUMBLE_TEST_API_KEY is not set\n"
+        );
+    }
 
-    let key = args
-        .get("key")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'key' argument")?;
+    #[test]
+    fn test_get_services() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        let (dir, ..) = projects.get_mut("test-project").unwrap();
+        *dir = temp.path().to_path_buf();
+        let args = json!({"project": "test-project"});
+        assert!(get_services(&projects, &args)
+            .unwrap_err()
+            .contains("has no services configured"));
 
-    let (_, _, _, _, _, memory_db) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+        std::fs::write(
+            temp.path().join("docker-compose.yml"),
+            "services:\n  db:\n    image: postgres:16\n    ports: [\"5432:5432\"]\n  cache:\n    image: redis:7\n    depends_on: [db]\n",
+        )
+        .unwrap();
+        let config = &mut projects.get_mut("test-project").unwrap().1;
+        config.services.insert(
+            "db".to_string(),
+            Service {
+                kind: Some("database".to_string()),
+                description: Some("Primary datastore".to_string()),
+                env: Some("DATABASE_URL".to_string()),
+                ..Default::default()
+            },
+        );
+        config.services.insert(
+            "payments".to_string(),
+            Service {
+                kind: Some("http".to_string()),
+                url: Some("https://api.stripe.com".to_string()),
+                ..Default::default()
+            },
+        );
 
-    // Delete from database
-    let deleted = memory_db
-        .write(|db| db.remove(key).is_some())
-        .map_err(|e| format!("Failed to write to memory database: {}", e))?;
+        assert_eq!(
+            get_services(&projects, &args).unwrap(),
+            "# Services for test-project\n\n\
+             ## db (database)\n\nPrimary datastore\n\n\
+             - **Image:** postgres:16\n- **Ports:** 5432\n- **Env:** `DATABASE_URL`\n\
+             - **Defined in:** docker-compose.yml\n\n\
+             ## payments (http)\n\n- **URL:** https://api.stripe.com\n\n\
+             ## cache\n\n- **Image:** redis:7\n- **Depends on:** db\n\
+             - **Defined in:** docker-compose.yml\n"
+        );
 
-    if !deleted {
-        return Err(format!("Memory key '{}' not found", key));
+        let config = &mut projects.get_mut("test-project").unwrap().1;
+        config.services.get_mut("payments").unwrap().compose = Some("missing.yml".to_string());
+        let output = get_services(&projects, &args).unwrap();
+        assert!(output.ends_with(&format!(
+            "*Failed to read {}: No such file or directory (os error 2)*\n",
+            temp.path().join("missing.yml").display()
+        )));
     }
 
-    memory_db
-        .save()
-        .map_err(|e| format!("Failed to save memory database: {}", e))?;
+    #[test]
+    fn test_get_ci_info() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        let (dir, ..) = projects.get_mut("test-project").unwrap();
+        *dir = temp.path().join("app");
+        std::fs::create_dir_all(temp.path().join("app")).unwrap();
+        let args = json!({"project": "test-project"});
+        assert!(get_ci_info(temp.path(), &projects, &args)
+            .unwrap_err()
+            .contains("has no CI configured"));
+
+        std::fs::create_dir_all(temp.path().join(".github/workflows")).unwrap();
+        std::fs::write(
+            temp.path().join(".github/workflows/ci.yml"),
+            "name: CI\non: [pull_request]\njobs:\n  test:\n    name: Test\n    runs-on: ubuntu-latest\n    steps:\n      - run: cargo test\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.path().join(".github/workflows/nightly.yml"),
+            "on:\n  schedule:\n    - cron: '0 3 * * *'\njobs:\n  audit:\n    runs-on: ubuntu-latest\n",
+        )
+        .unwrap();
+        assert_eq!(
+            get_ci_info(temp.path(), &projects, &args).unwrap(),
+            "# CI for test-project\n\n\
+             **Runs on pull requests:** CI / Test\n\n\
+             ## .github/workflows/ci.yml\n\n\
+             **Name:** CI\n**Triggers:** pull_request\n**Jobs:**\n\
+             - **test** (Test) - on ubuntu-latest\n  - `cargo test`\n\n\
+             ## .github/workflows/nightly.yml\n\n\
+             **Triggers:** schedule (0 3 * * *)\n**Jobs:**\n\
+             - **audit** - on ubuntu-latest\n"
+        );
 
-    Ok(format!(
-        "Memory deleted: key='{}' for project '{}'",
-        key, project_name
-    ))
-}
+        let config = &mut projects.get_mut("test-project").unwrap().1;
+        config.ci = Some(CiConfig {
+            workflows: BTreeMap::from([
+                (
+                    "../.github/workflows/ci.yml".to_string(),
+                    "Tests every pull request".to_string(),
+                ),
+                (
+                    ".gitlab-ci.yml".to_string(),
+                    "Deploys from the mirror".to_string(),
+                ),
+            ]),
+            required_checks: vec!["CI / Test".to_string()],
+        });
+        let args = json!({"project": "test-project", "parse": false});
+        assert_eq!(
+            get_ci_info(temp.path(), &projects, &args).unwrap(),
+            "# CI for test-project\n\n**Required checks:** CI / Test\n\n\
+             ## ../.github/workflows/ci.yml\n\nTests every pull request\n\n\
+             ## .gitlab-ci.yml\n\nDeploys from the mirror\n"
+        );
+    }
 
-pub fn clear_memories(
-    projects: &HashMap<String, ProjectData>,
-    args: &Value,
-) -> Result<String, String> {
-    let project_name = args
-        .get("project")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'project' argument")?;
+    #[test]
+    fn test_get_changelog() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        let (dir, config, ..) = projects.get_mut("test-project").unwrap();
+        *dir = temp.path().to_path_buf();
+        config
+            .commands
+            .insert("release".to_string(), "cargo release".into());
+        let args = json!({"project": "test-project"});
+        assert!(get_changelog(&projects, &args)
+            .unwrap_err()
+            .contains("has no changelog"));
 
-    let confirm = args
-        .get("confirm")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
+        std::fs::write(
+            temp.path().join("CHANGELOG.md"),
+            "# Changelog\n\n## [Unreleased]\n\n- Dark mode\n\n## [1.0.0] - 2024-05-01\n\n- Stable API\n\n## [0.9.0] - 2024-03-10\n\n- Beta\n",
+        )
+        .unwrap();
+        let config = &mut projects.get_mut("test-project").unwrap().1;
+        config.releases = Some(ReleasesConfig {
+            changelog: None,
+            versioning: Some("semver".to_string()),
+            command: Some("release".to_string()),
+        });
+        assert_eq!(
+            get_changelog(&projects, &args).unwrap(),
+            "# Changelog for test-project\n\n**File:** CHANGELOG.md\n\
+             **Current version:** 1.0.0\n**Versioning:** semver\n\
+             **Release command:** `cargo release` (`release`)\n\
+             **Versions:** Unreleased, 1.0.0 (2024-05-01), 0.9.0 (2024-03-10)\n\n\
+             ## [Unreleased]\n\n- Dark mode\n\n\
+             ## [1.0.0] - 2024-05-01\n\n- Stable API\n"
+        );
 
-    if !confirm {
-        return Err("Deletion not confirmed. Set 'confirm' to true to proceed.".to_string());
+        let version =
+            |v: &str| get_changelog(&projects, &json!({"project": "test-project", "version": v}));
+        assert_eq!(
+            version("v0.9.0").unwrap(),
+            "## [0.9.0] - 2024-03-10\n\n- Beta"
+        );
+        assert!(version("latest").unwrap().starts_with("## [1.0.0]"));
+        assert_eq!(
+            version("0.9.1").unwrap_err(),
+            "Version '0.9.1' not found, did you mean '0.9.0'?"
+        );
+
+        let secret = TempDir::new().unwrap();
+        let outside = secret.path().join("notes.md");
+        std::fs::write(&outside, "## [1.0.0]\n\n- token\n").unwrap();
+        let name = secret.path().file_name().unwrap().to_string_lossy();
+        let relative = format!("../{}/notes.md", name);
+        for changelog in [relative, outside.display().to_string()] {
+            let config = &mut projects.get_mut("test-project").unwrap().1;
+            config.releases.as_mut().unwrap().changelog = Some(changelog);
+            let err = get_changelog(&projects, &args).unwrap_err();
+            assert!(err.contains("outside the project directory"), "{}", err);
+        }
     }
 
-    let pattern = args.get("pattern").and_then(|v| v.as_str());
+    #[test]
+    fn test_get_version_history() {
+        let mut projects = create_test_projects();
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        std::fs::write(dir.join("a.txt"), "one").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "first"]);
+        git(&["tag", "-a", "test-project-v0.9.0", "-m", "Release 0.9.0"]);
+        git(&["tag", "other-v3.0.0"]);
+        std::fs::write(dir.join("a.txt"), "two").unwrap();
+        git(&["commit", "-q", "-am", "second"]);
+        projects.get_mut("test-project").unwrap().0 = dir.clone();
 
-    let (_, _, _, _, _, memory_db) = projects
-        .get(project_name)
-        .ok_or_else(|| format!("Project '{}' not found", project_name))?;
+        let args = json!({"project": "test-project"});
+        let output = get_version_history(&projects, &args).unwrap();
+        assert!(output.contains("**Configured version:** 1.0.0 (no tag yet)"));
+        assert!(output.contains("**Latest tag:** test-project-v0.9.0 ("));
+        assert!(output.contains("**Since then:** 1 commit touches this project"));
+        assert!(output.contains(": Release 0.9.0\n"));
+        assert!(!output.contains("other-v3.0.0"));
+
+        let args = json!({"project": "test-project", "pattern": "other-*"});
+        let output = get_version_history(&projects, &args).unwrap();
+        assert!(output.contains("- **other-v3.0.0** ("));
+        assert!(output.contains(": first\n"));
+
+        let args = json!({"project": "test-project", "pattern": "v*"});
+        assert!(get_version_history(&projects, &args)
+            .unwrap_err()
+            .contains("No git tags matching 'v*'"));
+    }
 
-    // Delete from database
-    let deleted_count = memory_db
-        .write(|db| {
-            if let Some(pat) = pattern {
-                let pat_lower = pat.to_lowercase();
-                let keys_to_delete: Vec<String> = db
-                    .keys()
-                    .filter(|k| k.to_lowercase().contains(&pat_lower))
-                    .cloned()
-                    .collect();
+    #[test]
+    fn test_path_styles() {
+        let projects = create_test_projects();
+        let project_dir = &projects["test-project"].0;
+        let root = project_dir.parent().unwrap();
+        let dir_name = project_dir.file_name().unwrap().to_string_lossy();
+        let related = |paths: &str| {
+            let args = json!({"project": "test-project", "query": "auth", "paths": paths});
+            get_related_files(root, &projects, &args).unwrap()
+        };
 
-                let count = keys_to_delete.len();
-                for key in keys_to_delete {
-                    db.remove(&key);
-                }
-                count
-            } else {
-                let count = db.len();
-                db.clear();
-                count
-            }
-        })
-        .map_err(|e| format!("Failed to write to memory database: {}", e))?;
+        let absolute = format!("- {}/src/auth.rs\n", project_dir.display());
+        assert!(related("absolute").contains(&absolute));
+        assert!(related("workspace").contains(&format!("- {}/src/auth.rs\n", dir_name)));
+        assert!(related("project").contains("- src/auth.rs\n"));
 
-    memory_db
-        .save()
-        .map_err(|e| format!("Failed to save memory database: {}", e))?;
+        // Projects outside the workspace keep absolute paths.
+        let args = json!({"paths": "workspace"});
+        assert_eq!(
+            path_base(Path::new("/elsewhere"), project_dir, &args).unwrap(),
+            *project_dir
+        );
+        let err = path_base(root, project_dir, &json!({"paths": "relative"})).unwrap_err();
+        assert!(err.contains("Invalid 'paths' value"));
+    }
 
-    if let Some(pat) = pattern {
-        Ok(format!(
-            "Cleared {} memor{} matching pattern '{}' for project '{}'",
-            deleted_count,
-            if deleted_count == 1 { "y" } else { "ies" },
-            pat,
-            project_name
-        ))
-    } else {
-        Ok(format!(
-            "Cleared all {} memor{} for project '{}'",
-            deleted_count,
-            if deleted_count == 1 { "y" } else { "ies" },
-            project_name
-        ))
+    #[test]
+    fn test_concept_file_matches() {
+        assert!(concept_file_matches("src/auth.rs", "src/auth.rs"));
+        assert!(concept_file_matches("src/auth/", "src/auth/jwt.rs"));
+        assert!(concept_file_matches("./src/auth", "src/auth/jwt.rs"));
+        assert!(!concept_file_matches("src/auth", "src/authz.rs"));
+        assert!(concept_file_matches(
+            "src/server.rs:120-210",
+            "src/server.rs"
+        ));
     }
-}
 
-pub fn init_project(_workspace_root: &std::path::PathBuf, args: &Value) -> Result<String, String> {
-    // Get the target directory from arguments (required)
-    let dir_str = args
-        .get("directory")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing 'directory' argument")?;
-    
-    let target_dir = std::path::PathBuf::from(dir_str);
-    
-    // Ensure the directory exists or can be created
-    if !target_dir.exists() {
-        std::fs::create_dir_all(&target_dir)
-            .map_err(|e| format!("Failed to create directory '{}': {}", target_dir.display(), e))?;
+    #[test]
+    fn test_get_conventions() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        let result = get_conventions(&projects, &args).unwrap();
+        assert!(result.contains("naming"));
+        assert!(result.contains("async"));
     }
-    
-    // Use the setup module's init function
-    match crate::setup::setup_init(&target_dir) {
-        Ok(()) => Ok(format!("Project initialized successfully in {}.", target_dir.display())),
-        Err(e) => Err(format!("Failed to initialize project: {}", e)),
+
+    #[test]
+    fn test_get_conventions_levels() {
+        let mut projects = create_test_projects();
+        let conventions = &mut projects.get_mut("test-project").unwrap().3.conventions;
+        for (name, level) in [
+            ("background", ConventionLevel::Info),
+            ("errors", ConventionLevel::Must),
+            ("style", ConventionLevel::Should),
+        ] {
+            conventions.insert(
+                name.to_string(),
+                Convention::Detailed(ConventionDetails {
+                    description: format!("{} text", name),
+                    level: Some(level),
+                }),
+            );
+        }
+        let args = json!({"project": "test-project", "category": "conventions"});
+        let result = get_conventions(&projects, &args).unwrap();
+        let order: Vec<&str> = result
+            .lines()
+            .filter_map(|line| line.strip_prefix("## "))
+            .collect();
+        assert_eq!(order, ["errors", "style", "naming", "background"]);
+        assert!(result.contains("## errors\n**[MUST]** errors text\n"));
+        assert!(result.contains("## background\n**[INFO]** background text\n"));
+        assert!(result.contains("## naming\nUse snake_case\n"));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::*;
-    use crate::memory;
-    use std::path::PathBuf;
-    use tempfile::TempDir;
+    #[test]
+    fn test_get_conventions_filtered() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project", "category": "gotchas"});
+        let result = get_conventions(&projects, &args).unwrap();
+        assert!(result.contains("async"));
+        assert!(!result.contains("naming"));
+    }
 
-    fn create_test_project() -> (String, ProjectData) {
-        let config = ProjectConfig {
-            project: ProjectInfo {
-                name: "test-project".to_string(),
-                description: "A test project".to_string(),
-                language: Some("rust".to_string()),
-                version: Some("1.0.0".to_string()),
-                repository: None,
-            },
-            commands: {
-                let mut map = HashMap::new();
-                map.insert("build".to_string(), "cargo build".to_string());
-                map.insert("test".to_string(), "cargo test".to_string());
-                map
-            },
-            entry_points: {
-                let mut map = HashMap::new();
-                map.insert("main".to_string(), "src/main.rs".to_string());
-                map
-            },
-            dependencies: Dependencies {
-                internal: vec!["shared".to_string()],
-                external: vec!["serde".to_string()],
-            },
-            related_projects: RelatedProjects {
-                upstream: vec!["core".to_string()],
-                downstream: vec![],
-            },
-            api: None,
-            concepts: {
-                let mut map = HashMap::new();
-                map.insert(
-                    "authentication".to_string(),
-                    Concept {
-                        files: vec!["src/auth.rs".to_string()],
-                        summary: "JWT auth".to_string(),
-                    },
-                );
-                map
-            },
-        };
+    #[test]
+    fn test_get_conventions_custom_category() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        let result = get_conventions(&projects, &args).unwrap();
+        assert!(result.contains("# Security for 'test-project'"));
 
-        let skills = ProjectSkills::default();
-        let conventions = ProjectConventions {
-            conventions: {
-                let mut map = HashMap::new();
-                map.insert("naming".to_string(), "Use snake_case".to_string());
-                map
-            },
-            gotchas: {
-                let mut map = HashMap::new();
-                map.insert("async".to_string(), "Avoid blocking".to_string());
-                map
-            },
-        };
-        let docs = ProjectDocs {
-            docs: {
-                let mut map = HashMap::new();
-                map.insert(
-                    "readme".to_string(),
-                    DocEntry {
-                        path: "README.md".to_string(),
-                        summary: "Project readme".to_string(),
-                    },
-                );
-                map
-            },
-        };
+        let args = json!({"project": "test-project", "category": "security"});
+        let result = get_conventions(&projects, &args).unwrap();
+        assert!(result.contains("Never log tokens"));
+        assert!(!result.contains("naming"));
 
-        // Create a temporary memory database for testing
-        let temp_dir = TempDir::new().unwrap();
-        let test_path = temp_dir.path().to_path_buf();
-        let memory_db = memory::open_or_create_memory_db(&test_path).unwrap();
+        let args = json!({"project": "test-project", "category": "securty"});
+        let err = get_conventions(&projects, &args).unwrap_err();
+        assert_eq!(
+            err,
+            "Category 'securty' not found, did you mean 'security'? Available: conventions, gotchas, security"
+        );
+    }
 
-        (
-            "test-project".to_string(),
-            (
-                test_path.clone(),
-                config,
-                skills,
-                conventions,
-                docs,
-                memory_db,
-            ),
+    #[test]
+    fn test_promote_memory() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        for (key, value) in [
+            ("migrations", "Run migrations first"),
+            ("auth", "Token checks"),
+        ] {
+            let args = json!({"project": "test-project", "key": key, "value": value});
+            store_memory(&projects, None, &args).unwrap();
+        }
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+        let conventions = temp.path().join(".jumble/conventions.toml");
+
+        let args = json!({"project": "test-project", "key": "migrations", "to": "gotcha"});
+        let preview = promote_memory(&mut projects, &args).unwrap();
+        assert!(preview.contains("[gotchas]\nmigrations = \"Run migrations first\""));
+        assert!(preview.contains("Call promote_memory again with confirm: true"));
+        assert!(preview.contains("The memory 'migrations' will then be deleted."));
+        assert!(!conventions.exists());
+
+        let mut confirmed = args.clone();
+        confirmed["confirm"] = json!(true);
+        let result = promote_memory(&mut projects, &confirmed).unwrap();
+        assert!(result.ends_with("Memory 'migrations' deleted."));
+        assert!(std::fs::read_to_string(&conventions)
+            .unwrap()
+            .contains("migrations = \"Run migrations first\""));
+        let get = json!({"project": "test-project", "key": "migrations"});
+        assert!(get_memory(&projects, &get).is_err());
+
+        std::fs::write(
+            temp.path().join(".jumble/project.toml"),
+            "[project]\nname = \"test-project\"\ndescription = \"A test project\"\n",
         )
+        .unwrap();
+        let args = json!({
+            "project": "test-project",
+            "key": "auth",
+            "to": "concept",
+            "files": ["src/auth.rs"],
+            "keep": true,
+            "confirm": true
+        });
+        let result = promote_memory(&mut projects, &args).unwrap();
+        assert!(result.ends_with("Memory 'auth' marked as promoted to concepts.auth."));
+        let (_, config, _, _, _, _) = &projects["test-project"];
+        assert_eq!(config.concepts["auth"].summary, "Token checks");
+        let get = json!({"project": "test-project", "key": "auth"});
+        assert!(get_memory(&projects, &get)
+            .unwrap()
+            .contains("**Promoted to:** concepts.auth"));
+
+        let args = json!({"project": "test-project", "key": "auth", "to": "skill"});
+        assert!(promote_memory(&mut projects, &args).is_err());
     }
 
-    fn create_test_projects() -> HashMap<String, ProjectData> {
-        let mut projects = HashMap::new();
-        let (name, data) = create_test_project();
-        projects.insert(name, data);
-        projects
+    #[test]
+    fn test_add_convention() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+        let file = temp.path().join(".jumble/conventions.toml");
+
+        let args = json!({
+            "project": "test-project",
+            "category": "gotchas",
+            "name": "migrations",
+            "description": "Run \"make migrate\" first"
+        });
+        let preview = add_convention(&mut projects, &args).unwrap();
+        assert!(preview.contains("[gotchas]\nmigrations = 'Run \"make migrate\" first'\n"));
+        assert!(!file.exists());
+
+        let mut confirmed = args.clone();
+        confirmed["confirm"] = json!(true);
+        add_convention(&mut projects, &confirmed).unwrap();
+        let written: ProjectConventions =
+            toml::from_str(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(
+            written.gotchas["migrations"],
+            "Run \"make migrate\" first".into()
+        );
+
+        // The in-memory conventions are updated without a reload.
+        let args = json!({"project": "test-project", "category": "gotchas"});
+        let result = get_conventions(&projects, &args).unwrap();
+        assert!(result.contains("make migrate"));
+
+        let err = add_convention(&mut projects, &confirmed).unwrap_err();
+        assert!(err.contains("already exists"));
+
+        let args = json!({
+            "project": "test-project",
+            "category": "conventions",
+            "name": "errors",
+            "description": "Never unwrap",
+            "level": "must",
+            "confirm": true
+        });
+        add_convention(&mut projects, &args).unwrap();
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(content.contains("errors = { description = \"Never unwrap\", level = \"must\" }\n"));
+        let written: ProjectConventions = toml::from_str(&content).unwrap();
+        assert_eq!(
+            written.conventions["errors"].level(),
+            Some(ConventionLevel::Must)
+        );
+
+        let mut args = args;
+        args["level"] = json!("critical");
+        let err = add_convention(&mut projects, &args).unwrap_err();
+        assert_eq!(
+            err,
+            "Unknown level 'critical'. Use 'must', 'should', or 'info'."
+        );
     }
 
     #[test]
-    fn test_list_projects_empty() {
-        let projects = HashMap::new();
-        let result = list_projects(&projects).unwrap();
-        assert!(result.contains("No projects found"));
+    fn test_get_docs() {
+        let projects = create_test_projects();
+        let args = json!({"project": "test-project"});
+        let result = get_docs(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("readme"));
+        assert!(result.contains("Project readme"));
     }
 
     #[test]
-    fn test_list_projects() {
+    fn test_get_docs_specific() {
         let projects = create_test_projects();
-        let result = list_projects(&projects).unwrap();
-        assert!(result.contains("test-project"));
-        assert!(result.contains("rust"));
-        assert!(result.contains("A test project"));
+        let args = json!({"project": "test-project", "topic": "readme"});
+        let result = get_docs(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("README.md"));
+
+        let args = json!({"project": "test-project", "topic": "REDME"});
+        let result = get_docs(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.starts_with("## readme\n"));
     }
 
     #[test]
-    fn test_get_project_info_not_found() {
-        let projects = create_test_projects();
-        let args = json!({"project": "nonexistent"});
-        let result = get_project_info(&projects, &args);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not found"));
+    fn test_get_docs_section() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("README.md"),
+            "# Project\n\nIntro\n\n## Install\n\ncargo install\n\n## Usage\n\nRun it.\n",
+        )
+        .unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"project": "test-project", "topic": "readme", "section": "install"});
+        let result = get_docs(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.ends_with("## Install\n\ncargo install"));
+
+        let args = json!({"project": "test-project", "topic": "readme", "section": "Usag"});
+        let err = get_docs(Path::new("/workspace"), &projects, &args).unwrap_err();
+        assert_eq!(
+            err,
+            "Section 'Usag' not found, did you mean 'Usage'? Available: Project, Install, Usage"
+        );
+
+        let args = json!({"project": "test-project", "section": "Usage"});
+        assert!(get_docs(Path::new("/workspace"), &projects, &args).is_err());
     }
 
     #[test]
-    fn test_get_project_info_full() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project"});
-        let result = get_project_info(&projects, &args).unwrap();
-        assert!(result.contains("test-project"));
-        assert!(result.contains("A test project"));
-        assert!(result.contains("rust"));
-        assert!(result.contains("1.0.0"));
+    fn test_search_docs() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("README.md"),
+            "# Project\n\nIntro\n\n## Deployment\n\nRun the deploy script.\nThen verify.\n",
+        )
+        .unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"query": "deploy"});
+        let result = search_docs(&projects, &args).unwrap();
+        assert!(result.contains("## readme (project: test-project, score 20)"));
+        assert!(result.contains("Line 5, under \"Deployment\":\n> \n> ## Deployment\n"));
+        assert!(result
+            .contains("Line 7, under \"Deployment\":\n> Run the deploy script.\n> Then verify.\n"));
+
+        // Name and summary matches count even without content hits.
+        let args = json!({"query": "readme", "project": "test-project"});
+        let result = search_docs(&projects, &args).unwrap();
+        assert!(result.contains("score 90"));
+
+        let args = json!({"query": "kubernetes"});
+        assert!(search_docs(&projects, &args)
+            .unwrap()
+            .contains("No docs matching"));
+
+        let args = json!({"query": "deploy", "project": "missing"});
+        assert!(search_docs(&projects, &args).is_err());
     }
 
     #[test]
-    fn test_get_project_info_commands_field() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project", "field": "commands"});
-        let result = get_project_info(&projects, &args).unwrap();
-        assert!(result.contains("build"));
-        assert!(result.contains("cargo build"));
+    fn test_docs_stay_in_project() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("app");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        std::fs::write(
+            temp.path().join("secret.md"),
+            "# Secret\n\n## Keys\n\ndeploy token\n",
+        )
+        .unwrap();
+        let mut projects = create_test_projects();
+        let project = projects.get_mut("test-project").unwrap();
+        project.0 = project_dir;
+        project.4.docs.insert(
+            "leak".to_string(),
+            DocEntry {
+                path: "../secret.md".to_string(),
+                summary: "Notes".to_string(),
+            },
+        );
+
+        let args = json!({"project": "test-project", "topic": "leak", "section": "Keys"});
+        let err = get_docs(Path::new("/workspace"), &projects, &args).unwrap_err();
+        assert!(err.contains("outside the project directory"), "{}", err);
+
+        let args = json!({"query": "token"});
+        assert!(search_docs(&projects, &args)
+            .unwrap()
+            .contains("No docs matching"));
     }
 
     #[test]
-    fn test_get_commands() {
-        let projects = create_test_projects();
+    fn test_record_and_list_decisions() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
         let args = json!({"project": "test-project"});
-        let result = get_commands(&projects, &args).unwrap();
-        assert!(result.contains("build"));
-        assert!(result.contains("test"));
+        assert!(list_decisions(&projects, &args)
+            .unwrap()
+            .contains("No decisions recorded"));
+
+        let args = json!({
+            "project": "test-project",
+            "title": "Use SQLite",
+            "context": "Reads dominate.",
+            "decision": "Cache in SQLite.",
+            "status": "proposed"
+        });
+        let preview = record_decision(&projects, &args).unwrap();
+        assert!(preview.contains("0001-use-sqlite.md"));
+        assert!(decisions::load_decisions(temp.path()).is_empty());
+
+        let mut confirmed = args.clone();
+        confirmed["confirm"] = json!(true);
+        record_decision(&projects, &confirmed).unwrap();
+
+        let args = json!({"project": "test-project"});
+        let result = list_decisions(&projects, &args).unwrap();
+        assert!(result.contains("**ADR-0001** [proposed]"));
+        assert!(result.contains("Use SQLite"));
+
+        let args = json!({"project": "test-project", "status": "accepted"});
+        assert!(list_decisions(&projects, &args)
+            .unwrap()
+            .contains("No accepted decisions"));
+
+        let args = json!({"project": "test-project", "number": 1});
+        let result = list_decisions(&projects, &args).unwrap();
+        assert!(result.contains("# ADR-0001: Use SQLite"));
+        assert!(result.contains("## Decision\n\nCache in SQLite."));
+
+        let mut invalid = confirmed.clone();
+        invalid["status"] = json!("maybe");
+        assert!(record_decision(&projects, &invalid).is_err());
+        invalid["confirm"] = json!(false);
+        let err = record_decision(&projects, &invalid).unwrap_err();
+        assert!(err.contains("Invalid status 'maybe'"));
     }
 
     #[test]
-    fn test_get_commands_specific() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project", "command_type": "build"});
-        let result = get_commands(&projects, &args).unwrap();
-        assert!(result.contains("cargo build"));
+    fn test_task_tools() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"project": "test-project"});
+        assert!(list_tasks(&projects, &args)
+            .unwrap()
+            .contains("No matching tasks"));
+
+        let args = json!({"project": "test-project", "title": "Write docs", "priority": "low"});
+        assert!(add_task(&projects, &args)
+            .unwrap()
+            .starts_with("Task #1 added"));
+        let args = json!({
+            "project": "test-project",
+            "title": "Fix flaky test",
+            "priority": "high",
+            "source": "agent-a",
+            "notes": "Fails on CI only"
+        });
+        assert!(add_task(&projects, &args)
+            .unwrap()
+            .starts_with("Task #2 added"));
+
+        let args = json!({"project": "test-project"});
+        let result = list_tasks(&projects, &args).unwrap();
+        let flaky = result
+            .find("- [ ] #2 (high) Fix flaky test — from agent-a")
+            .unwrap();
+        let docs = result.find("- [ ] #1 (low) Write docs").unwrap();
+        assert!(flaky < docs);
+        assert!(result.contains("  Fails on CI only\n"));
+
+        let args = json!({"project": "test-project", "id": 2});
+        assert_eq!(
+            complete_task(&projects, &args).unwrap(),
+            "Task #2 completed: Fix flaky test"
+        );
+        assert!(complete_task(&projects, &args)
+            .unwrap_err()
+            .contains("already done"));
+        let args = json!({"project": "test-project", "id": 9});
+        assert!(complete_task(&projects, &args)
+            .unwrap_err()
+            .contains("not found"));
+
+        let args = json!({"project": "test-project"});
+        assert!(!list_tasks(&projects, &args).unwrap().contains("#2"));
+        let args = json!({"project": "test-project", "status": "done"});
+        assert!(list_tasks(&projects, &args).unwrap().contains("- [x] #2"));
+
+        let args = json!({"project": "test-project", "title": "X", "priority": "urgent"});
+        assert!(add_task(&projects, &args).is_err());
     }
 
     #[test]
-    fn test_get_commands_not_found() {
+    fn test_memory_tools_regex() {
         let projects = create_test_projects();
-        let args = json!({"project": "test-project", "command_type": "deploy"});
-        let result = get_commands(&projects, &args);
-        assert!(result.is_err());
+        for (key, value) in [
+            ("api/v1/auth", "Uses JWT"),
+            ("api/v2/auth", "Uses sessions"),
+            ("apiary", "Unrelated"),
+        ] {
+            let args = json!({"project": "test-project", "key": key, "value": value});
+            store_memory(&projects, None, &args).unwrap();
+        }
+
+        let args = json!({"project": "test-project", "pattern": "api"});
+        let result = list_memories(&projects, &args).unwrap();
+        assert!(result.contains("**apiary**"));
+        let args = json!({"project": "test-project", "pattern": "^api/v\\d+/", "regex": true});
+        let result = list_memories(&projects, &args).unwrap();
+        assert!(result.contains("**api/v1/auth**"));
+        assert!(!result.contains("**apiary**"));
+
+        let args = json!({"project": "test-project", "query": "JWT|sessions", "regex": true});
+        let result = search_memories(&projects, &args).unwrap();
+        assert!(result.contains("Found 2 match(es)"));
+
+        let args = json!({"project": "test-project", "query": "(", "regex": true});
+        let err = search_memories(&projects, &args).unwrap_err();
+        assert!(err.starts_with("Invalid regex '('"));
+
+        let args = json!({
+            "project": "test-project",
+            "pattern": "^api/v1/",
+            "regex": true,
+            "confirm": true
+        });
+        let result = clear_memories(&projects, &args).unwrap();
+        assert!(result.starts_with("Cleared 1 memory matching pattern"));
     }
 
     #[test]
-    fn test_get_architecture() {
+    fn test_store_memory_quota() {
         let projects = create_test_projects();
-        let args = json!({"project": "test-project", "concept": "authentication"});
-        let result = get_architecture(&projects, &args).unwrap();
-        assert!(result.contains("authentication"));
-        assert!(result.contains("JWT auth"));
-        assert!(result.contains("src/auth.rs"));
+        let quotas = MemoryConfig {
+            max_entries_per_source: Some(5),
+            source_quotas: BTreeMap::from([("bot".to_string(), 2)]),
+        };
+        let store = |key: &str, source: Option<&str>| {
+            let args =
+                json!({"project": "test-project", "key": key, "value": "v", "source": source});
+            store_memory(&projects, Some(&quotas), &args)
+        };
+        store("a", Some("bot")).unwrap();
+        store("b", Some("bot")).unwrap();
+        // Overwriting an owned key stays allowed.
+        store("b", Some("bot")).unwrap();
+        store("c", None).unwrap();
+
+        let err = store("c", Some("bot")).unwrap_err();
+        assert!(err.starts_with(
+            "Memory quota reached: source 'bot' already stores 2 entries in project 'test-project' (limit 2)."
+        ));
+        assert!(err.contains("Consolidate related memories"));
+        assert!(err.ends_with("- a\n- b\n"));
+        // The refused store left the existing entry alone.
+        let args = json!({"project": "test-project", "key": "c"});
+        assert!(!get_memory(&projects, &args)
+            .unwrap()
+            .contains("**Source:**"));
+
+        // Other sources fall back to the default limit.
+        store("c", Some("agent")).unwrap();
+
+        // Stores without a source share the 'unknown' quota.
+        for key in ["d", "e", "f", "g", "h"] {
+            store(key, None).unwrap();
+        }
+        let err = store("i", None).unwrap_err();
+        assert!(err.starts_with("Memory quota reached: source 'unknown' already stores 5 entries"));
+        assert!(err.ends_with("- d\n- e\n- f\n- g\n- h\n"));
     }
 
     #[test]
-    fn test_get_architecture_case_insensitive() {
+    fn test_get_memories() {
         let projects = create_test_projects();
-        let args = json!({"project": "test-project", "concept": "AUTHENTICATION"});
-        let result = get_architecture(&projects, &args).unwrap();
-        assert!(result.contains("JWT auth"));
+        for key in ["auth/flow", "auth/tokens"] {
+            let args = json!({"project": "test-project", "key": key, "value": format!("notes on {}", key)});
+            store_memory(&projects, None, &args).unwrap();
+        }
+
+        let args =
+            json!({"project": "test-project", "keys": ["auth/flow", "auth/token", "auth/tokens"]});
+        let result = get_memories(&projects, &args).unwrap();
+        assert!(result.starts_with("# Memories for 'test-project' (2 of 3 found)"));
+        assert!(result.contains("Memory key 'auth/token' not found, did you mean 'auth/tokens'?"));
+        let flow = result.find("## auth/flow").unwrap();
+        assert!(flow < result.find("## auth/tokens").unwrap());
+        assert!(result.contains("**Value:** notes on auth/tokens"));
+
+        let args = json!({"project": "test-project", "keys": []});
+        assert!(get_memories(&projects, &args).is_err());
+        let args = json!({"project": "test-project", "keys": "auth/flow"});
+        assert!(get_memories(&projects, &args).is_err());
     }
 
     #[test]
-    fn test_get_architecture_partial_match() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project", "concept": "auth"});
-        let result = get_architecture(&projects, &args).unwrap();
-        assert!(result.contains("JWT auth"));
+    fn test_get_memory_audit() {
+        let mut projects = create_test_projects();
+        let temp = TempDir::new().unwrap();
+        let project = projects.get_mut("test-project").unwrap();
+        project.0 = temp.path().to_path_buf();
+        project.5 = memory::open_or_create_memory_db(temp.path()).unwrap();
+
+        let store = |value: &str| {
+            let args = json!({"project": "test-project", "key": "build", "value": value, "source": "agent-a"});
+            store_memory(&projects, None, &args).unwrap();
+        };
+        store("cargo build");
+        store("just build");
+        let args = json!({"project": "test-project", "key": "build", "source": "agent-b"});
+        delete_memory(&projects, &args).unwrap();
+
+        let output =
+            get_memory_audit(&projects, None, &json!({"project": "test-project"})).unwrap();
+        let delete = output
+            .find("delete `build` by agent-b\n  - Old: just build\n")
+            .unwrap();
+        let overwrite = output
+            .find("store `build` by agent-a\n  - Old: cargo build\n  - New: just build\n")
+            .unwrap();
+        assert!(delete < overwrite);
+        assert!(temp.path().join(".jumble/memory-audit.log").is_file());
+
+        let args = json!({"project": "test-project", "action": "store", "limit": 1});
+        let output = get_memory_audit(&projects, None, &args).unwrap();
+        assert!(output.contains("New: just build"));
+        assert!(!output.contains("New: cargo build"));
+        assert!(output.contains("1 older change(s) not shown."));
+
+        let args = json!({"project": "test-project", "key": "test"});
+        assert!(get_memory_audit(&projects, None, &args)
+            .unwrap()
+            .starts_with("No recorded memory changes"));
+        let args = json!({"project": "test-project", "action": "undo"});
+        assert!(get_memory_audit(&projects, None, &args).is_err());
     }
 
     #[test]
-    fn test_get_related_files() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project", "query": "auth"});
-        let result = get_related_files(&projects, &args).unwrap();
-        assert!(result.contains("authentication"));
-        assert!(result.contains("src/auth.rs"));
+    fn test_global_memory_tools() {
+        let temp = TempDir::new().unwrap();
+        let global = memory::open_or_create_memory_db(temp.path()).unwrap();
+
+        let args = json!({"key": "style", "value": "Prefer early returns"});
+        store_global_memory(Some(&global), None, &args).unwrap();
+        let args = json!({"key": "editor", "value": "helix", "source": "setup"});
+        store_global_memory(Some(&global), None, &args).unwrap();
+
+        let result = get_global_memory(Some(&global), &json!({"key": "editor"})).unwrap();
+        assert!(result.contains("**Value:** helix"));
+        assert!(result.contains("**Source:** setup"));
+
+        let result = get_global_memory(Some(&global), &json!({})).unwrap();
+        let editor = result.find("- **editor**: helix").unwrap();
+        assert!(editor < result.find("- **style**: Prefer early returns").unwrap());
+
+        let err = get_global_memory(Some(&global), &json!({"key": "styl"})).unwrap_err();
+        assert!(err.contains("did you mean 'style'?"));
+        assert!(get_global_memory(None, &json!({})).is_err());
     }
 
     #[test]
-    fn test_get_conventions() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project"});
-        let result = get_conventions(&projects, &args).unwrap();
-        assert!(result.contains("naming"));
-        assert!(result.contains("async"));
+    fn test_search_all_memories() {
+        let mut projects = create_test_projects();
+        let (name, data) = create_test_project();
+        projects.insert(format!("{}-billing", name), data);
+        let temp = TempDir::new().unwrap();
+        let global = memory::open_or_create_memory_db(temp.path()).unwrap();
+
+        for (project, key, value) in [
+            ("test-project", "auth", "Sessions expire after 1h"),
+            (
+                "test-project-billing",
+                "retries",
+                "Auth tokens are refreshed on 401",
+            ),
+            ("test-project-billing", "currency", "Amounts are in cents"),
+        ] {
+            let args = json!({"project": project, "key": key, "value": value});
+            store_memory(&projects, None, &args).unwrap();
+        }
+        let args = json!({"key": "style", "value": "Document auth flows"});
+        store_global_memory(Some(&global), None, &args).unwrap();
+
+        let result =
+            search_all_memories(&projects, Some(&global), &json!({"query": "auth"})).unwrap();
+        let first = result
+            .find("## Project 'test-project' (1 match(es))")
+            .unwrap();
+        let billing = result
+            .find("## Project 'test-project-billing' (1 match(es))")
+            .unwrap();
+        let global_section = result.find("## Global (1 match(es))").unwrap();
+        assert!(first < billing && billing < global_section);
+        assert!(!result.contains("currency"));
+
+        let result = search_all_memories(&projects, None, &json!({"query": "nothing"})).unwrap();
+        assert_eq!(
+            result,
+            "No memories matching query 'nothing' in any project"
+        );
     }
 
     #[test]
-    fn test_get_conventions_filtered() {
+    fn test_list_memories_sort_and_time_range() {
         let projects = create_test_projects();
-        let args = json!({"project": "test-project", "category": "gotchas"});
-        let result = get_conventions(&projects, &args).unwrap();
-        assert!(result.contains("async"));
-        assert!(!result.contains("naming"));
+        let (_, _, _, _, _, memory_db) = &projects["test-project"];
+        memory_db
+            .update(|db| {
+                for (key, timestamp) in [
+                    ("alpha", "2026-10-01T08:00:00+00:00"),
+                    ("beta", "2026-10-15T08:00:00+00:00"),
+                    ("gamma", "2026-10-10T08:00:00+00:00"),
+                ] {
+                    db.insert(
+                        key.to_string(),
+                        memory::MemoryEntry {
+                            value: key.to_string(),
+                            timestamp: timestamp.to_string(),
+                            source: None,
+                            promoted: None,
+                        },
+                    );
+                }
+            })
+            .unwrap();
+
+        let args = json!({"project": "test-project", "sort_by": "timestamp"});
+        let result = list_memories(&projects, &args).unwrap();
+        let beta = result.find("**beta**").unwrap();
+        let gamma = result.find("**gamma**").unwrap();
+        assert!(beta < gamma && gamma < result.find("**alpha**").unwrap());
+
+        let args =
+            json!({"project": "test-project", "since": "2026-10-05", "before": "2026-10-15"});
+        let result = list_memories(&projects, &args).unwrap();
+        assert!(result.contains("**gamma**"));
+        assert!(!result.contains("**alpha**") && !result.contains("**beta**"));
+
+        let args = json!({"project": "test-project", "since": "2026-11-01"});
+        let result = list_memories(&projects, &args).unwrap();
+        assert!(result.starts_with("No memories in the requested time range"));
+
+        let args = json!({"project": "test-project", "since": "last week"});
+        assert!(list_memories(&projects, &args).is_err());
+        let args = json!({"project": "test-project", "sort_by": "size"});
+        assert!(list_memories(&projects, &args).is_err());
     }
 
     #[test]
-    fn test_get_docs() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project"});
-        let result = get_docs(&projects, &args).unwrap();
-        assert!(result.contains("readme"));
-        assert!(result.contains("Project readme"));
+    fn test_find_project_for_file() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("services/api/plugins/auth/src")).unwrap();
+        let mut projects = HashMap::new();
+        for (name, dir) in [
+            ("api", "services/api"),
+            ("auth", "services/api/plugins/auth"),
+        ] {
+            let (_, mut data) = create_test_project();
+            data.0 = root.join(dir);
+            data.1.project.name = name.to_string();
+            projects.insert(name.to_string(), data);
+        }
+
+        let args = json!({"path": "services/api/plugins/auth/src/lib.rs"});
+        let result = find_project_for_file(root, &projects, &args).unwrap();
+        assert!(result.starts_with("# auth\n"));
+        assert!(result.contains("**File:** src/lib.rs"));
+
+        let absolute = root.join("services/api/./src/../src/main.rs");
+        let args = json!({"path": absolute.to_str().unwrap()});
+        let result = find_project_for_file(root, &projects, &args).unwrap();
+        assert!(result.starts_with("# api\n"));
+        assert!(result.contains("**File:** src/main.rs"));
+
+        let args = json!({"path": "services/web/index.ts"});
+        let err = find_project_for_file(root, &projects, &args).unwrap_err();
+        assert_eq!(err, "No project contains 'services/web/index.ts'");
     }
 
     #[test]
-    fn test_get_docs_specific() {
-        let projects = create_test_projects();
-        let args = json!({"project": "test-project", "topic": "readme"});
-        let result = get_docs(&projects, &args).unwrap();
-        assert!(result.contains("README.md"));
+    fn test_get_context_for_file() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        let (_, config, _, conventions, _, _) = projects.get_mut("test-project").unwrap();
+        config.concepts.clear();
+        for (name, files) in [
+            ("auth", vec!["src/auth/"]),
+            ("handlers", vec!["src/**/handler*.rs"]),
+            ("billing", vec!["src/billing.rs"]),
+        ] {
+            let concept = Concept {
+                files: files.into_iter().map(str::to_string).collect(),
+                summary: format!("{} summary", name),
+                related: Vec::new(),
+                owner: None,
+                contact: None,
+            };
+            config.concepts.insert(name.to_string(), concept);
+        }
+        conventions
+            .gotchas
+            .insert("tokens".to_string(), "Never log tokens".into());
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"path": "src/auth/handler_login.rs"});
+        let result = get_context_for_file(temp.path(), &None, &projects, &args).unwrap();
+        assert!(result.starts_with("# test-project\n"));
+        assert!(result.contains("- **auth**: auth summary"));
+        assert!(result.contains("- **handlers**: handlers summary"));
+        assert!(!result.contains("billing"));
+        let gotchas = result.find("## Gotchas").unwrap();
+        assert!(gotchas < result.find("## Conventions").unwrap());
+        assert!(result.contains("- **tokens**: Never log tokens"));
+
+        let args = json!({"path": "/elsewhere/main.rs"});
+        assert!(get_context_for_file(temp.path(), &None, &projects, &args).is_err());
+    }
+
+    #[test]
+    fn test_get_context_bundle() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        let (_, config, skills, _, _, _) = projects.get_mut("test-project").unwrap();
+        for (name, body) in [
+            ("migrations", "Run `make migrate` after adding a migration"),
+            ("release", "Tag and publish"),
+        ] {
+            let path = temp.path().join(format!("{}.md", name));
+            std::fs::write(&path, body).unwrap();
+            let info = SkillInfo {
+                path,
+                preview: body.to_string(),
+                frontmatter: None,
+                skill_dir: None,
+            };
+            skills.skills.insert(name.to_string(), info);
+        }
+        config.concepts.insert(
+            "schema".to_string(),
+            Concept {
+                files: vec!["db/schema.sql".to_string()],
+                summary: "Database schema and migration history".to_string(),
+                related: Vec::new(),
+                owner: None,
+                contact: None,
+            },
+        );
+
+        let args = json!({"project": "test-project", "task": "add a database migration"});
+        let result = get_context_bundle(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.starts_with("# Context for 'add a database migration' in test-project"));
+        assert!(result.contains("## Skill: migrations\n\nRun `make migrate`"));
+        assert!(!result.contains("Tag and publish"));
+        assert!(result.contains("### schema\n\nDatabase schema and migration history"));
+        assert!(result.contains("## Gotchas"));
+
+        let args = json!({"project": "test-project", "task": "deploy"});
+        let result = get_context_bundle(Path::new("/workspace"), &projects, &args).unwrap();
+        assert!(result.contains("_No skill matches this task._"));
+        assert!(!result.contains("## Concepts"));
+
+        let args = json!({"project": "test-project", "task": "a b"});
+        assert!(get_context_bundle(Path::new("/workspace"), &projects, &args).is_err());
     }
 
     #[test]
@@ -1665,23 +7433,223 @@ mod tests {
         assert!(result.contains("test-project"));
     }
 
+    #[test]
+    fn test_get_session_briefing() {
+        let projects = create_test_projects();
+        let (_, _, _, _, _, memory_db) = &projects["test-project"];
+        memory_db
+            .update(|db| {
+                for (key, timestamp) in [("old", "2026-01-01"), ("new", "2026-02-01")] {
+                    db.insert(
+                        key.to_string(),
+                        memory::MemoryEntry {
+                            value: format!("{} note", key),
+                            timestamp: timestamp.to_string(),
+                            source: None,
+                            promoted: None,
+                        },
+                    );
+                }
+            })
+            .unwrap();
+
+        let args = json!({"project": "test-project"});
+        let result = get_session_briefing(&None, &projects, &args).unwrap();
+        assert!(result.starts_with("# Workspace Overview"));
+        assert!(result.contains("- **test-project** (rust): A test project"));
+        assert!(result.contains("# test-project\n"));
+        let gotchas = result.find("## Gotchas").unwrap();
+        let naming = result.find("## Conventions").unwrap();
+        assert!(gotchas < naming);
+        assert!(result.find("- **new**: new note").unwrap() < result.find("- **old**").unwrap());
+        assert!(!result.contains("truncated"));
+
+        let args = json!({"project": "test-project", "max_tokens": 40});
+        let result = get_session_briefing(&None, &projects, &args).unwrap();
+        assert!(result.contains("test-project"));
+        assert!(!result.contains("## Recent Memories"));
+        assert!(result.contains("Briefing truncated to ~40 tokens"));
+
+        assert!(get_session_briefing(&None, &projects, &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_dangling_related_projects() {
+        let projects = create_test_projects();
+        let dangling = dangling_related_projects(&projects);
+        assert_eq!(
+            dangling,
+            vec![DanglingReference {
+                project: "test-project".to_string(),
+                field: "upstream",
+                target: "core".to_string(),
+            }]
+        );
+
+        let root = PathBuf::from("/workspace");
+        let overview = get_workspace_overview(&root, &None, &projects).unwrap();
+        assert!(overview.contains("## Unknown Related Projects"));
+        assert!(overview.contains("related_projects.upstream references unknown project 'core'"));
+    }
+
+    #[test]
+    fn test_get_entry_point() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        std::fs::write(
+            temp.path().join("src/main.rs"),
+            "fn main() {\n    run();\n}\n",
+        )
+        .unwrap();
+
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"project": "test-project", "name": "main"});
+        let result = get_entry_point(&projects, &args).unwrap();
+        assert!(result.starts_with("# main (src/main.rs)"));
+        assert!(result.contains("1  fn main() {\n2      run();\n3  }\n"));
+        assert!(!result.contains("Truncated"));
+
+        let args = json!({"project": "test-project", "name": "lib"});
+        let err = get_entry_point(&projects, &args).unwrap_err();
+        assert!(err.contains("Available: main"));
+
+        projects
+            .get_mut("test-project")
+            .unwrap()
+            .1
+            .entry_points
+            .insert(
+                "main".to_string(),
+                EntryPoint::Detailed {
+                    path: "src/main.rs".to_string(),
+                    description: Some("Starts the server".to_string()),
+                },
+            );
+        let args = json!({"project": "test-project", "name": "main"});
+        let result = get_entry_point(&projects, &args).unwrap();
+        assert!(result.starts_with("# main (src/main.rs)\n\nStarts the server\n\n```\n"));
+    }
+
+    #[test]
+    fn test_get_entry_point_stays_in_project() {
+        let temp = TempDir::new().unwrap();
+        let project_dir = temp.path().join("app");
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let secret = temp.path().join("secret.txt");
+        std::fs::write(&secret, "token").unwrap();
+
+        let mut projects = create_test_projects();
+        let project = projects.get_mut("test-project").unwrap();
+        project.0 = project_dir;
+        for (name, path) in [
+            ("up", "../secret.txt".to_string()),
+            ("abs", secret.display().to_string()),
+        ] {
+            project
+                .1
+                .entry_points
+                .insert(name.to_string(), EntryPoint::Path(path));
+        }
+
+        for name in ["up", "abs"] {
+            let args = json!({"project": "test-project", "name": name});
+            let err = get_entry_point(&projects, &args).unwrap_err();
+            assert!(err.contains("outside the project directory"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_get_entry_point_truncates_large_files() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("src")).unwrap();
+        let line = "x".repeat(99);
+        let content = format!("{}\n", line).repeat(MAX_ENTRY_POINT_BYTES / 100 + 10);
+        std::fs::write(temp.path().join("src/main.rs"), &content).unwrap();
+
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+
+        let args = json!({"project": "test-project", "name": "main"});
+        let result = get_entry_point(&projects, &args).unwrap();
+        assert!(result.contains(&format!(
+            "Truncated: showing {} of {} lines",
+            MAX_ENTRY_POINT_BYTES / 100,
+            MAX_ENTRY_POINT_BYTES / 100 + 10
+        )));
+    }
+
+    #[test]
+    fn test_get_dependency_graph() {
+        let projects = create_test_projects();
+
+        let mermaid = get_dependency_graph(&projects, &json!({})).unwrap();
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("[\"test-project\"]"));
+        assert!(mermaid.contains("[\"core\"]"));
+        assert!(mermaid.contains(" --> "));
+
+        let dot = get_dependency_graph(&projects, &json!({"format": "dot"})).unwrap();
+        assert!(dot.starts_with("digraph workspace {"));
+        assert!(dot.contains("\"test-project\" -> \"core\";"));
+        assert!(dot.contains("\"test-project\" -> \"shared\";"));
+
+        assert!(get_dependency_graph(&projects, &json!({"format": "png"})).is_err());
+    }
+
     #[test]
     fn test_get_workspace_overview_with_workspace() {
         let projects = create_test_projects();
         let root = PathBuf::from("/workspace");
         let workspace = Some(WorkspaceConfig {
+            schema_version: None,
             workspace: WorkspaceInfo {
                 name: Some("My Workspace".to_string()),
                 description: Some("A test workspace".to_string()),
+                ..Default::default()
             },
             conventions: HashMap::new(),
             gotchas: HashMap::new(),
+            commands: HashMap::new(),
+            skill_sources: HashMap::new(),
+            tools: Default::default(),
+            discovery: Default::default(),
+            custom_tools: Default::default(),
         });
         let result = get_workspace_overview(&root, &workspace, &projects).unwrap();
         assert!(result.contains("My Workspace"));
         assert!(result.contains("A test workspace"));
     }
 
+    #[test]
+    fn test_get_workspace_commands() {
+        assert!(get_workspace_commands(&None, &json!({})).is_err());
+        let mut config = WorkspaceConfig::default();
+        assert!(get_workspace_commands(&Some(config.clone()), &json!({}))
+            .unwrap()
+            .starts_with("No workspace commands defined"));
+
+        config
+            .commands
+            .insert("test-all".to_string(), "cargo test --workspace".into());
+        let workspace = Some(config);
+        assert_eq!(
+            get_workspace_commands(&workspace, &json!({})).unwrap(),
+            "- **test-all**: `cargo test --workspace`\n"
+        );
+        assert_eq!(
+            get_workspace_commands(&workspace, &json!({"command_type": "test-all"})).unwrap(),
+            "test-all: cargo test --workspace"
+        );
+        let err =
+            get_workspace_commands(&workspace, &json!({"command_type": "test_al"})).unwrap_err();
+        assert_eq!(
+            err,
+            "Workspace command 'test_al' not found, did you mean 'test-all'?"
+        );
+    }
+
     #[test]
     fn test_get_workspace_conventions_none() {
         let args = json!({});
@@ -1701,14 +7669,271 @@ mod tests {
         assert!(tool_names.contains(&"get_commands"));
         assert!(tool_names.contains(&"get_architecture"));
         assert!(tool_names.contains(&"get_related_files"));
+        assert!(tool_names.contains(&"who_owns"));
+        assert!(tool_names.contains(&"get_tests_for_file"));
+        assert!(tool_names.contains(&"get_coverage"));
+        assert!(tool_names.contains(&"get_environment"));
+        assert!(tool_names.contains(&"get_services"));
+        assert!(tool_names.contains(&"get_ci_info"));
+        assert!(tool_names.contains(&"get_changelog"));
+        assert!(tool_names.contains(&"get_version_history"));
+        assert!(tool_names.contains(&"get_memory_audit"));
+        assert!(tool_names.contains(&"get_recent_changes"));
         assert!(tool_names.contains(&"list_skills"));
         assert!(tool_names.contains(&"get_skill"));
+        assert!(tool_names.contains(&"search_skills"));
+        assert!(tool_names.contains(&"create_skill"));
+        assert!(tool_names.contains(&"add_concept"));
         assert!(tool_names.contains(&"get_conventions"));
+        assert!(tool_names.contains(&"record_decision"));
+        assert!(tool_names.contains(&"list_decisions"));
+        assert!(tool_names.contains(&"add_task"));
+        assert!(tool_names.contains(&"list_tasks"));
+        assert!(tool_names.contains(&"complete_task"));
+        assert!(tool_names.contains(&"get_session_briefing"));
+        assert!(tool_names.contains(&"get_usage_stats"));
+        assert!(tool_names.contains(&"get_memories"));
+        assert!(tool_names.contains(&"store_global_memory"));
+        assert!(tool_names.contains(&"get_global_memory"));
+        assert!(tool_names.contains(&"promote_memory"));
+        assert!(tool_names.contains(&"search_all_memories"));
+        assert!(tool_names.contains(&"find_project_for_file"));
+        assert!(tool_names.contains(&"get_context_for_file"));
+        assert!(tool_names.contains(&"get_context_bundle"));
+
+        for tool in tools {
+            assert_eq!(
+                tool["inputSchema"]["properties"]["max_chars"]["type"], "integer",
+                "{} should accept max_chars",
+                tool["name"]
+            );
+        }
+        assert!(tool_names.contains(&"add_convention"));
         assert!(tool_names.contains(&"get_docs"));
+        assert!(tool_names.contains(&"search_docs"));
+        assert!(tool_names.contains(&"search_context"));
         assert!(tool_names.contains(&"get_workspace_overview"));
+        assert!(tool_names.contains(&"get_entry_point"));
+        assert!(tool_names.contains(&"get_dependency_graph"));
+        assert!(tool_names.contains(&"get_workspace_commands"));
         assert!(tool_names.contains(&"get_workspace_conventions"));
         assert!(tool_names.contains(&"reload_workspace"));
         assert!(tool_names.contains(&"get_jumble_authoring_prompt"));
+        assert!(tool_names.contains(&"draft_project_config"));
+        assert!(tool_names.contains(&"get_diagnostics"));
+    }
+
+    #[test]
+    fn test_tools_list_annotations() {
+        let tools = tools_list();
+        let annotations = |name: &str| {
+            tools["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|t| t["name"] == name)
+                .unwrap()["annotations"]
+                .clone()
+        };
+        assert_eq!(
+            annotations("get_project_info"),
+            json!({"title": "Get project info", "readOnlyHint": true})
+        );
+        assert_eq!(
+            annotations("clear_memories"),
+            json!({
+                "title": "Clear memories",
+                "readOnlyHint": false,
+                "destructiveHint": true,
+                "idempotentHint": true
+            })
+        );
+        assert_eq!(annotations("add_task")["destructiveHint"], false);
+        assert_eq!(annotations("add_task")["idempotentHint"], false);
+        for tool in ["init_project", "reload_workspace", "draft_project_config"] {
+            assert_eq!(annotations(tool)["readOnlyHint"], false, "{}", tool);
+        }
+        assert_eq!(annotations("init_project")["destructiveHint"], false);
+        for tool in MUTATING_TOOLS {
+            assert!(is_builtin_tool(tool), "{} is not a tool", tool);
+        }
+    }
+
+    #[test]
+    fn test_get_skill_substitutes_variables() {
+        let (name, mut data) = create_test_project();
+        let skill_dir = TempDir::new().unwrap();
+        let skill_path = skill_dir.path().join("deploy.md");
+        std::fs::write(&skill_path, "Deploy {{project}} to {{env}} from {{root}}").unwrap();
+        data.2.skills.insert(
+            "deploy".to_string(),
+            SkillInfo {
+                path: skill_path,
+                skill_dir: None,
+                frontmatter: None,
+                preview: String::new(),
+            },
+        );
+        let root = data.0.display().to_string();
+        let mut projects = HashMap::new();
+        projects.insert(name, data);
+
+        let args = json!({
+            "project": "test-project",
+            "topic": "deploy",
+            "variables": {"env": "staging"}
+        });
+        let result = get_skill(&projects, &args).unwrap();
+        assert_eq!(
+            result,
+            format!("Deploy test-project to staging from {}", root)
+        );
+    }
+
+    #[test]
+    fn test_create_skill() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = create_test_projects();
+        projects.get_mut("test-project").unwrap().0 = temp.path().to_path_buf();
+        let file = temp.path().join(".jumble/skills/release.md");
+
+        let args = json!({
+            "project": "test-project",
+            "name": "release",
+            "description": "Cut a release: tag, build, publish",
+            "content": "# Release\n\n1. Bump the version.",
+            "tags": ["release", "ci"]
+        });
+        let preview = create_skill(&mut projects, &args).unwrap();
+        assert!(preview.contains("description: 'Cut a release: tag, build, publish'"));
+        assert!(!file.exists());
+
+        let mut confirmed = args.clone();
+        confirmed["confirm"] = json!(true);
+        create_skill(&mut projects, &confirmed).unwrap();
+        let written = std::fs::read_to_string(&file).unwrap();
+        let (frontmatter, _) = extract_skill_frontmatter_and_preview(&written);
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(
+            frontmatter.description.as_deref(),
+            Some("Cut a release: tag, build, publish")
+        );
+        assert_eq!(frontmatter.tags, ["release", "ci"]);
+
+        let args = json!({"project": "test-project", "topic": "release"});
+        assert!(get_skill(&projects, &args)
+            .unwrap()
+            .contains("Bump the version"));
+        let args = json!({"project": "test-project", "topic": "relase"});
+        assert!(get_skill(&projects, &args)
+            .unwrap()
+            .contains("Bump the version"));
+
+        let err = create_skill(&mut projects, &confirmed).unwrap_err();
+        assert!(err.contains("already exists"));
+
+        let mut bad = confirmed.clone();
+        bad["name"] = json!("../escape");
+        assert!(create_skill(&mut projects, &bad).is_err());
+    }
+
+    #[test]
+    fn test_search_skills_ranks_name_matches_first() {
+        let (name, mut data) = create_test_project();
+        let project_dir = data.0.clone();
+        data.2.skills.insert(
+            "deploy".to_string(),
+            SkillInfo {
+                path: project_dir.join(".jumble/skills/deploy.md"),
+                skill_dir: None,
+                frontmatter: Some(SkillFrontmatter {
+                    name: None,
+                    description: Some("Ship a release".to_string()),
+                    tags: vec!["release".to_string()],
+                }),
+                preview: String::new(),
+            },
+        );
+        data.2.skills.insert(
+            "debug".to_string(),
+            SkillInfo {
+                path: PathBuf::from("/home/user/.jumble/skills/debug.md"),
+                skill_dir: None,
+                frontmatter: None,
+                preview: "Check the deploy logs first".to_string(),
+            },
+        );
+        let mut projects = HashMap::new();
+        projects.insert(name, data);
+
+        let result = search_skills(&projects, &json!({"query": "deploy"})).unwrap();
+        let deploy_pos = result.find("**deploy**").unwrap();
+        let debug_pos = result.find("**debug**").unwrap();
+        assert!(deploy_pos < debug_pos);
+        assert!(result.contains("project: test-project"));
+        assert!(result.contains("global"));
+
+        let result = search_skills(&projects, &json!({"query": "nothing-here"})).unwrap();
+        assert!(result.contains("No skills matching"));
+    }
+
+    #[test]
+    fn test_search_context_groups_results() {
+        let (name, mut data) = create_test_project();
+        data.1.concepts.insert(
+            "tokens".to_string(),
+            Concept {
+                files: vec!["src/tokens.rs".to_string()],
+                summary: "Token issuing".to_string(),
+                related: Vec::new(),
+                owner: None,
+                contact: None,
+            },
+        );
+        data.3
+            .gotchas
+            .insert("logging".to_string(), "Never log tokens".into());
+        data.2.skills.insert(
+            "rotate".to_string(),
+            SkillInfo {
+                path: PathBuf::from("/skills/rotate.md"),
+                skill_dir: None,
+                frontmatter: Some(SkillFrontmatter {
+                    name: None,
+                    description: Some("Rotate signing tokens".to_string()),
+                    tags: Vec::new(),
+                }),
+                preview: String::new(),
+            },
+        );
+        let mut projects = HashMap::new();
+        projects.insert(name, data);
+        let args = json!({"project": "test-project", "key": "token-ttl", "value": "15 minutes"});
+        store_memory(&projects, None, &args).unwrap();
+
+        let args = json!({"project": "test-project", "query": "token"});
+        let result = search_context(&projects, &args).unwrap();
+        let headings: Vec<&str> = result.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(
+            headings,
+            [
+                "## Concepts (use get_architecture(project, concept))",
+                "## Memories (use get_memory(project, key))",
+                "## Conventions (use get_conventions(project))",
+                "## Gotchas (use get_conventions(project))",
+                "## Skills (use get_skill(project, topic))"
+            ]
+        );
+        assert!(result.contains("- **tokens** (score 90): Token issuing\n"));
+        assert!(result.contains("- **token-ttl** (score 60): 15 minutes\n"));
+        assert!(result.contains("- **logging** (score 30): Never log tokens\n"));
+
+        let args = json!({"project": "test-project", "query": "zebra"});
+        let result = search_context(&projects, &args).unwrap();
+        assert_eq!(
+            result,
+            "No context matching 'zebra' found in 'test-project'."
+        );
     }
 
     #[test]